@@ -1,5 +1,7 @@
 #[path = "src/args.rs"]
 mod args;
+#[path = "src/date.rs"]
+mod date;
 
 use clap::ValueEnum;
 use clap_complete::{self, Shell};
@@ -9,6 +11,8 @@ use std::error::Error;
 use std::fs::{self, File};
 use std::io::Error as IoError;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 fn build_shell_completions(out_dir: &Path) -> Result<(), IoError> {
 	fs::create_dir_all(out_dir)?;
@@ -34,8 +38,59 @@ fn build_manpage(out_dir: &Path) -> Result<(), IoError> {
 	Ok(())
 }
 
+fn command_output(cmd: &str, args: &[&str]) -> Option<String> {
+	Command::new(cmd)
+		.args(args)
+		.output()
+		.ok()
+		.filter(|output| output.status.success())
+		.map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+		.filter(|v| !v.is_empty())
+}
+
+fn build_date() -> String {
+	let secs = env::var("SOURCE_DATE_EPOCH")
+		.ok()
+		.and_then(|v| v.parse::<i64>().ok())
+		.unwrap_or_else(|| {
+			SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map(|v| v.as_secs() as i64)
+				.unwrap_or_default()
+		});
+	let (year, month, day) = date::civil_date(secs.div_euclid(86400));
+	format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn set_build_info() {
+	let rustc = env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+	let info = [
+		("KMON_RUSTC_VERSION", command_output(&rustc, &["-V"])),
+		("KMON_TARGET", env::var("TARGET").ok()),
+		(
+			"KMON_GIT_HASH",
+			command_output("git", &["rev-parse", "--short", "HEAD"]),
+		),
+		("KMON_BUILD_DATE", Some(build_date())),
+	];
+	for (key, value) in info {
+		println!(
+			"cargo:rustc-env={key}={}",
+			value.unwrap_or_else(|| String::from("unknown"))
+		);
+	}
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
 	println!("cargo:rerun-if-changed=src/args.rs");
+	/* Missing paths would make the build script run on every build. */
+	for path in [".git/HEAD", ".git/refs/heads"] {
+		if Path::new(path).exists() {
+			println!("cargo:rerun-if-changed={path}");
+		}
+	}
+	println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+	set_build_info();
 	let out_dir = match env::var_os("OUT_DIR").map(PathBuf::from) {
 		None => return Ok(()),
 		Some(v) => v
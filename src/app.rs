@@ -1,41 +1,248 @@
+use crate::clipboard::Clipboard;
+use crate::config::{Config, LayoutConfig, ScrollConfig};
 use crate::event::Event;
+use crate::i18n;
 use crate::kernel::cmd::ModuleCommand;
-use crate::kernel::lkm::KernelModules;
-use crate::kernel::log::KernelLogs;
+use crate::kernel::deps::Relation;
+use crate::kernel::lkm::{
+	self, KernelModules, ListFilter, SortType, CHANGE_DURATION,
+};
+use crate::kernel::log::{KernelLogs, LogLevel};
 use crate::kernel::Kernel;
+use crate::keys::{Action, KeyBindings};
+use crate::layout::LayoutArgs;
 use crate::style::{Style, StyledText, Symbol};
 use crate::util;
 use crate::widgets::StatefulList;
-use copypasta_ext::display::DisplayServer as ClipboardDisplayServer;
-use copypasta_ext::ClipboardProviderExt;
 use enum_iterator::Sequence;
-use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use ratatui::style::Style as TuiStyle;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin, Rect};
+use ratatui::style::{Modifier, Style as TuiStyle};
+use ratatui::symbols::border;
 use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::block::Title;
 use ratatui::widgets::{
-	Block as TuiBlock, Borders, Clear, List, ListItem, Paragraph, Row, Table, Wrap,
+	Block as TuiBlock, Borders, Cell, Clear, List, ListItem, Paragraph, Row,
+	Scrollbar, ScrollbarOrientation, ScrollbarState, Table, Wrap,
 };
 use ratatui::Frame;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt::{Debug, Display, Formatter};
+use std::mem;
+use std::path::PathBuf;
 use std::slice::Iter;
 use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
 use termion::event::Key;
 use unicode_width::UnicodeWidthStr;
 
-/* Table header of the module table */
-pub const TABLE_HEADER: &[&str] = &[" Module", "Size", "Used by"];
+/* Message identifiers of the module table header */
+pub const TABLE_HEADER: &[&str] =
+	&["header.module", "header.size", "header.used_by"];
+
+/* Duration of showing the status messages */
+const STATUS_DURATION: Duration = Duration::from_secs(2);
+
+/* Maximum interval between the repeated scrolls for accelerating */
+const SCROLL_REPEAT_INTERVAL: Duration = Duration::from_millis(150);
+
+/* Width limits of the module size bars */
+const MIN_SIZE_BAR_WIDTH: usize = 3;
+const MAX_SIZE_BAR_WIDTH: usize = 16;
+
+/* Minimum terminal size (width, height) for showing all the blocks at once */
+const FULL_LAYOUT_SIZE: (u16, u16) = (60, 16);
+
+/* Minimum terminal size (width, height) for drawing the blocks */
+const MIN_TERMINAL_SIZE: (u16, u16) = (20, 6);
+
+/* Maximum number of the shown module name completions */
+const MAX_COMPLETIONS: usize = 8;
+
+/* Maximum width of the notification popups */
+const NOTIFICATION_WIDTH: u16 = 50;
+
+/* Maximum width of the command palette */
+const PALETTE_WIDTH: u16 = 72;
+
+/* Idle duration for resetting the prefix of the type-ahead find */
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/* Maximum number of the queries in the search history */
+const HISTORY_SIZE: usize = 50;
 
 /* Available options in the module management menu */
 const OPTIONS: &[(&str, &str)] = &[
 	("unload", "Unload the module"),
+	("force-unload", "Force unload the module"),
 	("reload", "Reload the module"),
 	("blacklist", "Blacklist the module"),
+	("unblacklist", "Remove the module from blacklist"),
+	("add-boot", "Load the module at boot"),
+	("remove-boot", "Do not load the module at boot"),
 	("dependent", "Show the dependent modules"),
 	("copy", "Copy the module name"),
 	("load", "Load a kernel module"),
 	("clear", "Clear the ring buffer"),
+	("depmod", "Update the module dependencies"),
 ];
 
+/* Matching modes of the module search */
+#[derive(
+	Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, Sequence,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+	#[default]
+	Substring,
+	Regex,
+	Fuzzy,
+}
+
+impl Display for SearchMode {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", format!("{self:?}").to_lowercase())
+	}
+}
+
+/* Fields that are matched by the module search */
+#[derive(
+	Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, Sequence,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchScope {
+	#[default]
+	Name,
+	All,
+}
+
+/* Orientations of the module table and module information blocks */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+	#[default]
+	Horizontal,
+	Vertical,
+}
+
+impl TryFrom<&str> for Orientation {
+	type Error = ();
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		match s {
+			"horizontal" => Ok(Self::Horizontal),
+			"vertical" => Ok(Self::Vertical),
+			_ => Err(()),
+		}
+	}
+}
+
+impl Orientation {
+	/**
+	 * Get the direction of splitting the module table and information blocks.
+	 *
+	 * @return Direction
+	 */
+	pub fn direction(self) -> Direction {
+		match self {
+			Self::Horizontal => Direction::Horizontal,
+			Self::Vertical => Direction::Vertical,
+		}
+	}
+}
+
+/* Styles of the block borders */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderStyle {
+	#[default]
+	Plain,
+	Rounded,
+	Double,
+	Thick,
+	None,
+}
+
+impl TryFrom<&str> for BorderStyle {
+	type Error = ();
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		match s {
+			"plain" => Ok(Self::Plain),
+			"rounded" => Ok(Self::Rounded),
+			"double" => Ok(Self::Double),
+			"thick" => Ok(Self::Thick),
+			"none" => Ok(Self::None),
+			_ => Err(()),
+		}
+	}
+}
+
+impl BorderStyle {
+	/**
+	 * Get the symbols of the borders.
+	 *
+	 * Borders are drawn as blank lines for keeping the blocks separated
+	 * if they are disabled.
+	 *
+	 * @return Set
+	 */
+	pub fn symbols(self) -> border::Set {
+		match self {
+			Self::Plain => border::PLAIN,
+			Self::Rounded => border::ROUNDED,
+			Self::Double => border::DOUBLE,
+			Self::Thick => border::THICK,
+			Self::None => border::Set {
+				top_left: " ",
+				top_right: " ",
+				bottom_left: " ",
+				bottom_right: " ",
+				vertical_left: " ",
+				vertical_right: " ",
+				horizontal_top: " ",
+				horizontal_bottom: " ",
+			},
+		}
+	}
+}
+
+/* Columns of the module table */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+	Name,
+	Size,
+	UsedBy,
+}
+
+impl Column {
+	/* Columns in their default order */
+	pub const ALL: [Self; 3] = [Self::Name, Self::Size, Self::UsedBy];
+
+	/**
+	 * Get the index of the column on the module rows.
+	 *
+	 * @return usize
+	 */
+	pub fn index(self) -> usize {
+		self as usize
+	}
+
+	/**
+	 * Get the share of the column from the width of the module table.
+	 *
+	 * @return u32
+	 */
+	fn weight(self) -> u32 {
+		match self {
+			Self::Name => 3,
+			Self::Size => 2,
+			Self::UsedBy => 5,
+		}
+	}
+}
+
 /* Supported directions of scrolling */
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ScrollDirection {
@@ -68,8 +275,10 @@ impl ScrollDirection {
 }
 
 /* Main blocks of the terminal */
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Sequence)]
+#[derive(
+	Clone, Copy, Debug, PartialEq, Eq, Hash, Sequence, Deserialize, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
 pub enum Block {
 	UserInput,
 	ModuleTable,
@@ -77,7 +286,24 @@ pub enum Block {
 	Activities,
 }
 
+impl Block {
+	/**
+	 * Get the name of the block that is used in the configuration file.
+	 *
+	 * @return str
+	 */
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::UserInput => "user-input",
+			Self::ModuleTable => "module-table",
+			Self::ModuleInfo => "module-info",
+			Self::Activities => "activities",
+		}
+	}
+}
+
 /* Sizes of the terminal blocks */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct BlockSize {
 	pub input: u16,
 	pub info: u16,
@@ -95,12 +321,28 @@ impl Default for BlockSize {
 	}
 }
 
+/* Block sizes from the layout configuration */
+impl From<&LayoutConfig> for BlockSize {
+	fn from(layout: &LayoutConfig) -> Self {
+		Self {
+			input: layout.input,
+			info: layout.info,
+			activities: layout.activities,
+		}
+	}
+}
+
 /* User input mode */
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Sequence)]
 pub enum InputMode {
 	None,
 	Search,
 	Load,
+	LoadFile,
+	LogSearch,
+	Parameter,
+	SizeFilter,
+	Confirm,
 }
 
 impl InputMode {
@@ -112,6 +354,16 @@ impl InputMode {
 	pub fn is_none(self) -> bool {
 		self == Self::None
 	}
+
+	/**
+	 * Check if the input mode can be selected by switching between the modes.
+	 *
+	 * @return bool
+	 */
+	pub fn is_switchable(self) -> bool {
+		!self.is_none()
+			&& !matches!(self, Self::Parameter | Self::SizeFilter | Self::Confirm)
+	}
 }
 
 /* Implementation of Display for using InputMode members as string */
@@ -128,18 +380,198 @@ impl Display for InputMode {
 	}
 }
 
+/* Severity levels of the notifications */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+	Success,
+	Error,
+}
+
+/* Notification that is shown as a popup until its expiry tick */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+	pub text: String,
+	pub severity: Severity,
+	pub expiry: u64,
+}
+
+/* Previous queries of a search input that can be recalled like a shell */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputHistory {
+	entries: Vec<String>,
+	index: Option<usize>,
+	draft: String,
+}
+
+impl InputHistory {
+	/**
+	 * Create a new history from the given queries (oldest first).
+	 *
+	 * @param  entries
+	 * @return InputHistory
+	 */
+	pub fn new(entries: &[String]) -> Self {
+		let mut history = Self::default();
+		for query in entries {
+			history.push(query);
+		}
+		history
+	}
+
+	/**
+	 * Get the queries of the history (oldest first).
+	 *
+	 * @return entries
+	 */
+	pub fn entries(&self) -> &[String] {
+		&self.entries
+	}
+
+	/**
+	 * Check if the history is empty.
+	 *
+	 * @return bool
+	 */
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/**
+	 * Add the query to the history and stop recalling.
+	 *
+	 * Empty queries and the repeats of the last query are skipped.
+	 *
+	 * @param query
+	 */
+	pub fn push(&mut self, query: &str) {
+		self.reset();
+		if query.is_empty() || self.entries.last().is_some_and(|v| v == query) {
+			return;
+		}
+		self.entries.push(query.to_string());
+		if self.entries.len() > HISTORY_SIZE {
+			self.entries.remove(0);
+		}
+	}
+
+	/* Stop recalling the queries. */
+	pub fn reset(&mut self) {
+		self.index = None;
+		self.draft = String::new();
+	}
+
+	/**
+	 * Recall the previous query.
+	 *
+	 * The given query is kept for returning to it after the newest query.
+	 *
+	 * @param  query
+	 * @return query (Option)
+	 */
+	pub fn older(&mut self, query: &str) -> Option<String> {
+		let index = match self.index {
+			Some(index) => index.saturating_sub(1),
+			None => {
+				self.draft = query.to_string();
+				self.entries.len().checked_sub(1)?
+			}
+		};
+		self.index = Some(index);
+		self.entries.get(index).cloned()
+	}
+
+	/**
+	 * Recall the next query or the query that was being typed.
+	 *
+	 * @return query (Option)
+	 */
+	pub fn newer(&mut self) -> Option<String> {
+		match self.index {
+			Some(index) if index + 1 < self.entries.len() => {
+				self.index = Some(index + 1);
+				self.entries.get(index + 1).cloned()
+			}
+			Some(_) => {
+				let draft = self.draft.clone();
+				self.reset();
+				Some(draft)
+			}
+			None => None,
+		}
+	}
+}
+
+/* Command palette for finding and running the actions */
+#[derive(Debug)]
+pub struct Palette {
+	pub query: String,
+	pub entries: StatefulList<(Action, String)>,
+}
+
 /* Application settings and related methods  */
 pub struct App {
 	pub selected_block: Block,
 	pub default_block: Block,
 	pub block_size: BlockSize,
+	default_block_size: BlockSize,
+	pub orientation: Orientation,
+	pub border: BorderStyle,
+	max_list_height: Option<u16>,
+	modules_title: String,
+	activities_title: String,
 	pub block_index: u8,
+	pub no_log: bool,
 	pub input_mode: InputMode,
 	pub input_query: String,
+	pub search_history: InputHistory,
+	pub log_search_history: InputHistory,
+	type_ahead: bool,
+	size_bar: bool,
+	updated_time: bool,
+	pub last_refresh: Instant,
+	out_of_tree_marker: bool,
+	change_highlight: bool,
+	boot_marker: bool,
+	pub columns: Vec<Column>,
+	column_order: Vec<Column>,
+	type_ahead_prefix: String,
+	type_ahead_time: Instant,
+	pub completions: Vec<String>,
 	pub options: StatefulList<(String, String)>,
 	pub show_options: bool,
+	pub palette: Option<Palette>,
+	pub pending_action: Option<Action>,
+	pub show_help: bool,
+	help_scroll: u16,
+	help_height: u16,
+	pub search_mode: SearchMode,
+	pub search_scope: SearchScope,
+	search_matches: BTreeMap<String, &'static str>,
+	search_query: String,
+	pending_query: String,
+	pub saved_query: Option<String>,
+	pub confirm_action: Option<Action>,
+	query_time: Instant,
+	search_debounce: Duration,
+	pub case_sensitive: bool,
+	pub read_only: bool,
+	remote: Option<String>,
+	pub confirm_quit: bool,
+	pub quit_pending: bool,
+	pub banner: Option<String>,
+	pub paused: bool,
+	pub refresh_pending: bool,
+	pub block_areas: Vec<(Block, Rect)>,
+	pub key_bindings: KeyBindings,
+	pub scroll: ScrollConfig,
+	scroll_repeat: Option<(Action, Instant, usize)>,
 	style: Style,
-	clipboard: Option<Box<dyn ClipboardProviderExt>>,
+	clipboard: Clipboard,
+	status: Option<(String, Instant)>,
+	pub notifications: VecDeque<Notification>,
+	notification_ticks: u64,
+	tickrate: Duration,
+	tick: u64,
 }
 
 impl App {
@@ -148,40 +580,110 @@ impl App {
 	 *
 	 * @param  Block
 	 * @param  Style
+	 * @param  Config
 	 * @return App
 	 */
-	pub fn new(block: Block, style: Style) -> Self {
+	pub fn new(block: Block, style: Style, config: &Config) -> Self {
 		Self {
 			selected_block: block,
 			default_block: block,
-			block_size: BlockSize::default(),
+			block_size: BlockSize::from(&config.layout),
+			default_block_size: BlockSize::from(&config.layout),
+			orientation: config.layout.orientation,
+			border: config.layout.border,
+			max_list_height: config.layout.max_list_height,
+			modules_title: config
+				.layout
+				.modules_title
+				.clone()
+				.unwrap_or_else(|| String::from(i18n::text("title.modules"))),
+			activities_title: config
+				.layout
+				.activities_title
+				.clone()
+				.unwrap_or_else(|| String::from(i18n::text("title.activities"))),
 			block_index: 0,
+			no_log: config.no_log,
 			input_mode: InputMode::None,
 			input_query: String::new(),
+			search_history: InputHistory::new(&config.search.history),
+			log_search_history: InputHistory::new(&config.search.log_history),
+			type_ahead: config.type_ahead,
+			size_bar: config.size_bar,
+			updated_time: config.updated_time,
+			last_refresh: Instant::now(),
+			out_of_tree_marker: config.out_of_tree_marker,
+			change_highlight: config.change_highlight,
+			boot_marker: config.boot.marker,
+			columns: config.columns.clone(),
+			column_order: config.columns.iter().chain(Column::ALL.iter()).fold(
+				Vec::new(),
+				|mut columns, column| {
+					if !columns.contains(column) {
+						columns.push(*column);
+					}
+					columns
+				},
+			),
+			type_ahead_prefix: String::new(),
+			type_ahead_time: Instant::now(),
+			completions: Vec::new(),
 			options: StatefulList::with_items(
 				OPTIONS
 					.iter()
+					/* Hide the module commands in read-only mode. */
+					.filter(|(option, _)| {
+						!config.read_only
+							|| ModuleCommand::try_from(option.to_string()).is_err()
+					})
 					.map(|(option, text)| {
 						(String::from(*option), String::from(*text))
 					})
 					.collect(),
 			),
 			show_options: false,
+			palette: None,
+			pending_action: None,
+			show_help: false,
+			help_scroll: 0,
+			help_height: 0,
+			search_mode: config.search.mode(),
+			search_scope: config.search.scope,
+			search_matches: BTreeMap::new(),
+			search_query: String::new(),
+			pending_query: String::new(),
+			saved_query: None,
+			confirm_action: None,
+			query_time: Instant::now(),
+			search_debounce: Duration::from_millis(config.search.debounce),
+			case_sensitive: false,
+			read_only: config.read_only,
+			remote: config.remote.clone(),
+			confirm_quit: config.confirm_quit,
+			quit_pending: false,
+			banner: None,
+			paused: false,
+			refresh_pending: false,
+			block_areas: Vec::new(),
+			key_bindings: config.keybindings.clone(),
+			scroll: config.scroll,
+			scroll_repeat: None,
 			style,
-			clipboard: match ClipboardDisplayServer::select().try_context() {
-				None => {
-					eprintln!("failed to initialize clipboard, no suitable clipboard provider found");
-					None
-				}
-				clipboard => clipboard,
-			},
+			clipboard: Clipboard::new(),
+			status: None,
+			notifications: VecDeque::new(),
+			notification_ticks: (STATUS_DURATION.as_millis() as u64
+				/ config.tickrate.max(1))
+			.max(1),
+			tickrate: Duration::from_millis(config.tickrate),
+			tick: 0,
 		}
 	}
 
 	/* Reset app properties to default. */
 	pub fn refresh(&mut self) {
 		self.selected_block = self.default_block;
-		self.block_size = BlockSize::default();
+		self.block_size = self.default_block_size;
 		self.block_index = 0;
 		self.input_mode = InputMode::None;
 		self.input_query = String::new();
@@ -192,6 +694,8 @@ impl App {
 	/**
 	 * Get style depending on the selected state of the block.
 	 *
+	 * Border of the selected block is bold while the others are dimmed.
+	 *
 	 * @param  block
 	 * @return TuiStyle
 	 */
@@ -199,9 +703,51 @@ impl App {
 		if self.show_options {
 			self.style.colored
 		} else if block == self.selected_block {
-			self.style.default
+			self.style.default.add_modifier(Modifier::BOLD)
 		} else {
-			self.style.colored
+			self.style.colored.add_modifier(Modifier::DIM)
+		}
+	}
+
+	/**
+	 * Check if the block is hidden (the kernel activities are disabled).
+	 *
+	 * @param  block
+	 * @return bool
+	 */
+	pub fn is_hidden(&self, block: Block) -> bool {
+		self.no_log && block == Block::Activities
+	}
+
+	/**
+	 * Get the number of the blocks that change their positions.
+	 *
+	 * @return u8
+	 */
+	pub fn block_count(&self) -> u8 {
+		if self.no_log {
+			2
+		} else {
+			3
+		}
+	}
+
+	/**
+	 * Select the next (or previous) block that is not hidden.
+	 *
+	 * @param forward
+	 */
+	pub fn select_next_block(&mut self, forward: bool) {
+		loop {
+			self.selected_block = if forward {
+				self.selected_block.next().or_else(Block::first)
+			} else {
+				self.selected_block.previous().or_else(Block::last)
+			}
+			.unwrap_or(Block::ModuleTable);
+			if !self.is_hidden(self.selected_block) {
+				break;
+			}
 		}
 	}
 
@@ -224,166 +770,1294 @@ impl App {
 	 * @return contents
 	 */
 	pub fn get_clipboard_contents(&mut self) -> String {
-		if let Some(clipboard) = self.clipboard.as_mut() {
-			if let Ok(contents) = clipboard.get_contents() {
-				return contents;
-			}
-		}
-		String::new()
+		self.clipboard.get_contents()
 	}
 
 	/**
-	 * Set clipboard contents.
+	 * Set clipboard contents and show the result on the status.
 	 *
 	 * @param contents
 	 */
 	pub fn set_clipboard_contents(&mut self, contents: &str) {
-		if let Some(clipboard) = self.clipboard.as_mut() {
-			if let Err(e) = clipboard.set_contents(contents.to_string()) {
-				eprintln!("{e}");
+		let status = match self.clipboard.set_contents(contents) {
+			Ok(()) if contents.lines().count() > 1 => {
+				format!("copied {} lines", contents.lines().count())
 			}
+			Ok(()) => format!("copied {contents}"),
+			Err(e) => e,
+		};
+		self.set_status(status);
+	}
+
+	/**
+	 * Set a status message that is shown for a short time.
+	 *
+	 * @param message
+	 */
+	/**
+	 * Show or hide the given column of the module table.
+	 *
+	 * Shown columns are placed in their configured order.
+	 *
+	 * @param  column
+	 * @return visible
+	 */
+	pub fn toggle_column(&mut self, column: Column) -> bool {
+		if self.columns.contains(&column) {
+			self.columns.retain(|v| *v != column);
+			false
+		} else {
+			self.columns = self
+				.column_order
+				.iter()
+				.copied()
+				.filter(|v| *v == column || self.columns.contains(v))
+				.collect();
+			true
 		}
 	}
 
+	pub fn set_status(&mut self, message: String) {
+		self.status = Some((message, Instant::now()));
+	}
+
 	/**
-	 * Show help message on the information block.
+	 * Get the number of rows to scroll the module list for the given action.
 	 *
-	 * @param kernel_modules
+	 * The step is doubled at every 5 repeated scrolls (up to 8 times)
+	 * if the acceleration is enabled.
+	 *
+	 * @param  action
+	 * @return step
 	 */
-	pub fn show_help_message(&mut self, kernel_modules: &mut KernelModules<'_>) {
-		let key_bindings: Vec<(&str, &str)> = util::KEY_BINDINGS.to_vec();
-		let mut help_text = Vec::new();
-		let mut help_text_raw = Vec::new();
-		for (key, desc) in &key_bindings {
-			help_text.push(Line::from(Span::styled(
-				format!("{}:", &key),
-				self.style.colored,
-			)));
-			help_text_raw.push(format!("{key}:"));
-			help_text.push(Line::from(Span::styled(
-				format!("{}{}", self.style.unicode.get(Symbol::Blank), &desc),
-				self.style.default,
-			)));
-			help_text_raw.push(format!(" {}", &desc));
+	pub fn scroll_step(&mut self, action: Action) -> usize {
+		if !self.scroll.acceleration {
+			return self.scroll.step;
 		}
-		kernel_modules.info_scroll_offset = 0;
-		kernel_modules.command = ModuleCommand::None;
-		kernel_modules.current_name =
-			format!("!Help{}", self.style.unicode.get(Symbol::Helmet));
-		kernel_modules
-			.current_info
-			.set(Text::from(help_text), help_text_raw.join("\n"));
+		let repeat = match self.scroll_repeat {
+			Some((last_action, time, count))
+				if last_action == action
+					&& time.elapsed() < SCROLL_REPEAT_INTERVAL =>
+			{
+				count + 1
+			}
+			_ => 0,
+		};
+		self.scroll_repeat = Some((action, Instant::now(), repeat));
+		self.scroll.step * (1 << (repeat / 5).min(3))
 	}
 
 	/**
-	 * Show dependent modules on the information block.
+	 * Get the history of the search input that is being used.
 	 *
-	 * @param kernel_modules
+	 * @return InputHistory (Option)
 	 */
-	#[allow(clippy::nonminimal_bool)]
-	pub fn show_dependent_modules(
-		&mut self,
-		kernel_modules: &mut KernelModules<'_>,
-	) {
-		let dependent_modules_list = kernel_modules.default_list
-			[kernel_modules.index][2]
-			.split(' ')
-			.last()
-			.unwrap_or("-")
-			.split(',')
-			.collect::<Vec<&str>>();
-		if !(dependent_modules_list[0] == "-"
-			|| kernel_modules.current_name.contains("Dependent modules"))
-			|| cfg!(test)
+	pub fn input_history(&mut self) -> Option<&mut InputHistory> {
+		match self.input_mode {
+			InputMode::Search => Some(&mut self.search_history),
+			InputMode::LogSearch => Some(&mut self.log_search_history),
+			_ => None,
+		}
+	}
+
+	/**
+	 * Update the prefix of the type-ahead find with the given key.
+	 *
+	 * Module name characters are appended to the prefix and backspace
+	 * shortens it while the module list is selected. Digits are only used
+	 * after the first character for keeping the number keys available.
+	 * The prefix is reset after a short idle time.
+	 *
+	 * @param  key
+	 * @return prefix (Option) if the key is used
+	 */
+	pub fn type_ahead(&mut self, key: Key) -> Option<&str> {
+		if !self.type_ahead
+			|| self.selected_block != Block::ModuleTable
+			|| !self.input_mode.is_none()
 		{
-			kernel_modules.info_scroll_offset = 0;
-			kernel_modules.command = ModuleCommand::None;
-			kernel_modules.current_name = format!(
-				"!Dependent modules of {}{}",
-				kernel_modules.current_name,
-				self.style.unicode.get(Symbol::HistoricSite)
+			return None;
+		}
+		if self.type_ahead_time.elapsed() > TYPE_AHEAD_TIMEOUT {
+			self.type_ahead_prefix.clear();
+		}
+		match key {
+			Key::Char(c)
+				if c.is_ascii_alphabetic()
+					|| c == '_' || (!self.type_ahead_prefix.is_empty()
+					&& (c.is_ascii_digit() || c == '-')) =>
+			{
+				self.type_ahead_prefix.push(c)
+			}
+			Key::Backspace if !self.type_ahead_prefix.is_empty() => {
+				self.type_ahead_prefix.pop();
+			}
+			_ => return None,
+		}
+		self.type_ahead_time = Instant::now();
+		Some(&self.type_ahead_prefix)
+	}
+
+	/**
+	 * Replace the input query with a query from the search history.
+	 *
+	 * @param  previous
+	 * @return bool (if a query is recalled)
+	 */
+	pub fn recall_query(&mut self, previous: bool) -> bool {
+		let query = self.input_query.clone();
+		let recalled = self.input_history().and_then(|history| {
+			if previous {
+				history.older(&query)
+			} else {
+				history.newer()
+			}
+		});
+		match recalled {
+			Some(query) => {
+				self.input_query = query;
+				true
+			}
+			None => false,
+		}
+	}
+
+	/* Show that the command is disabled in read-only mode. */
+	pub fn show_read_only_status(&mut self) {
+		self.set_status(String::from(i18n::text("status.read_only")));
+	}
+
+	/**
+	 * Get the status message if it is not expired.
+	 *
+	 * @return message (Option)
+	 */
+	pub fn status(&self) -> Option<&str> {
+		self.status
+			.as_ref()
+			.filter(|(_, time)| time.elapsed() < STATUS_DURATION)
+			.map(|(message, _)| message.as_str())
+	}
+
+	/* Clear the status message. */
+	pub fn clear_status(&mut self) {
+		self.status = None;
+	}
+
+	/**
+	 * Push a notification to the queue.
+	 *
+	 * @param text
+	 * @param severity
+	 */
+	pub fn notify(&mut self, text: String, severity: Severity) {
+		self.notifications.push_back(Notification {
+			text,
+			severity,
+			expiry: self.tick + self.notification_ticks,
+		});
+	}
+
+	/**
+	 * Show the result of an operation on the status bar and as a notification.
+	 *
+	 * @param result
+	 */
+	pub fn show_result(&mut self, result: Result<String, String>) {
+		let (message, severity) = match result {
+			Ok(message) => (message, Severity::Success),
+			Err(e) => (format!("error: {e}"), Severity::Error),
+		};
+		self.set_status(message.to_string());
+		self.notify(message, severity);
+	}
+
+	/* Advance the tick counter and dismiss the expired notifications. */
+	pub fn on_tick(&mut self) {
+		self.tick += 1;
+		let tick = self.tick;
+		self.notifications.retain(|v| v.expiry > tick);
+		if self.status().is_none() {
+			self.status = None;
+		}
+	}
+
+	/**
+	 * Check if the status message expires until the next tick.
+	 *
+	 * @return bool
+	 */
+	fn is_status_expiring(&self) -> bool {
+		self.status.as_ref().is_some_and(|(_, time)| {
+			time.elapsed() + self.tickrate >= STATUS_DURATION
+		})
+	}
+
+	/**
+	 * Check if the drawn frame changes over time without any events.
+	 *
+	 * Spinners, countdowns and the messages or highlights that expire are
+	 * animated on each tick while they are shown. The status message is
+	 * only animated on the last tick before it is cleared.
+	 *
+	 * @param  kernel
+	 * @return bool
+	 */
+	pub fn is_animating(&self, kernel: &Kernel) -> bool {
+		self.is_status_expiring()
+			|| !self.notifications.is_empty()
+			|| self.updated_time
+			|| (self.input_mode == InputMode::Search
+				&& self.input_query != self.search_query)
+			|| kernel.modules.running.is_some()
+			|| kernel.modules.confirmation_remaining().is_some()
+			|| (self.change_highlight && kernel.modules.recent_changes().is_some())
+			|| kernel.logs.is_highlighting()
+	}
+
+	/**
+	 * Get the text that shows how long ago the data was last updated.
+	 *
+	 * @return String (Option)
+	 */
+	pub fn updated_text(&self) -> Option<String> {
+		self.updated_time.then(|| {
+			i18n::format(
+				"status.updated",
+				&util::format_duration(self.last_refresh.elapsed()),
+			)
+		})
+	}
+
+	/**
+	 * Get the summary of the module list for the status bar.
+	 *
+	 * Less important parts are omitted if the summary does not fit in the given width.
+	 *
+	 * @param  kernel_modules
+	 * @param  max_width
+	 * @return String
+	 */
+	pub fn module_summary(
+		kernel_modules: &KernelModules,
+		max_width: usize,
+	) -> String {
+		let totals = format!(
+			"{} loaded, {} in use, {} unused, {} blacklisted | {}",
+			kernel_modules.loaded_count,
+			kernel_modules.used_count,
+			kernel_modules.loaded_count - kernel_modules.used_count,
+			kernel_modules.blacklisted_count,
+			kernel_modules
+				.args
+				.size_format
+				.format(kernel_modules.loaded_size)
+		);
+		let shown = format!("{} shown", kernel_modules.list.len());
+		let sort = format!(
+			"sort: {}",
+			kernel_modules
+				.args
+				.sort
+				.description(kernel_modules.args.reverse)
+				.unwrap_or_else(|| String::from("none"))
+		);
+		let compact = format!(
+			"{}/{}/{}/{} | {}",
+			kernel_modules.loaded_count,
+			kernel_modules.used_count,
+			kernel_modules.loaded_count - kernel_modules.used_count,
+			kernel_modules.blacklisted_count,
+			kernel_modules
+				.args
+				.size_format
+				.format(kernel_modules.loaded_size)
+		);
+		let candidates = [
+			format!("{totals} | {shown} | {sort}"),
+			format!("{totals} | {shown}"),
+			totals,
+			compact.clone(),
+		];
+		match candidates.iter().find(|v| v.width() <= max_width) {
+			Some(summary) => summary.to_string(),
+			None => util::truncate_width(&compact, max_width),
+		}
+	}
+
+	/**
+	 * Fit the dependent modules on the used by column into the given width.
+	 *
+	 * Dependent modules are replaced with their count if they do not fit
+	 * and the count is truncated with '...' if it does not fit either.
+	 *
+	 * @param  used_by
+	 * @param  width
+	 * @return String
+	 */
+	pub fn compact_dependents(used_by: &str, width: usize) -> String {
+		if used_by.width() <= width {
+			return used_by.to_string();
+		}
+		let used_by = match used_by.split_once(' ') {
+			Some((refcount, dependents)) if dependents != "-" => {
+				format!("{refcount} ({} modules)", dependents.split(',').count())
+			}
+			_ => used_by.to_string(),
+		};
+		util::truncate_width(&used_by, width)
+	}
+
+	/**
+	 * Get the header text with the kernel release and loaded module totals.
+	 *
+	 * The count of the available modules is omitted if it cannot be read.
+	 *
+	 * @param  release
+	 * @param  kernel_modules
+	 * @return String
+	 */
+	pub fn header_text(release: &str, kernel_modules: &KernelModules) -> String {
+		format!(
+			"Linux {release} | {} modules loaded{} ({})",
+			kernel_modules.loaded_count,
+			kernel_modules
+				.available_count()
+				.map(|v| format!(" / {v} available"))
+				.unwrap_or_default(),
+			kernel_modules
+				.args
+				.size_format
+				.format(kernel_modules.loaded_size)
+		)
+	}
+
+	/**
+	 * Get the actions that match the given query of the command palette.
+	 *
+	 * Actions are listed with their keys in the order of the key bindings
+	 * and sorted by the fuzzy match scores of their descriptions and names
+	 * if a query is given.
+	 *
+	 * @param  query
+	 * @return Vec
+	 */
+	pub fn palette_entries(&self, query: &str) -> Vec<(Action, String)> {
+		let mut entries = self
+			.key_bindings
+			.entries()
+			.into_iter()
+			.filter(|(action, _)| *action != Action::Palette)
+			.filter_map(|(action, keys)| {
+				[action.description(), action.name()]
+					.into_iter()
+					.filter_map(|text| util::fuzzy_score(query, text))
+					.max()
+					.map(|score| (score, action, keys))
+			})
+			.collect::<Vec<(i64, Action, String)>>();
+		if !query.is_empty() {
+			entries.sort_by_key(|(score, _, _)| Reverse(*score));
+		}
+		entries
+			.into_iter()
+			.map(|(_, action, keys)| (action, keys))
+			.collect()
+	}
+
+	/* Show the command palette with all the actions. */
+	pub fn show_palette(&mut self) {
+		self.palette = Some(Palette {
+			query: String::new(),
+			entries: StatefulList::with_items(self.palette_entries("")),
+		});
+	}
+
+	/**
+	 * Handle the key input while the command palette is shown.
+	 *
+	 * Typed characters filter the actions and the selected action is returned
+	 * for running it after closing the palette with enter.
+	 *
+	 * @param  key
+	 * @return Action (Option)
+	 */
+	pub fn handle_palette_key(&mut self, key: Key) -> Option<Action> {
+		let palette = self.palette.as_mut()?;
+		match key {
+			Key::Char('\n') => {
+				let action = palette.entries.selected().map(|(action, _)| *action);
+				self.palette = None;
+				return action;
+			}
+			Key::Esc => self.palette = None,
+			Key::Up | Key::BackTab if !palette.entries.items.is_empty() => {
+				palette.entries.previous()
+			}
+			Key::Down | Key::Char('\t') if !palette.entries.items.is_empty() => {
+				palette.entries.next()
+			}
+			Key::Backspace | Key::Char(_) => {
+				match key {
+					Key::Char(c) => palette.query.push(c),
+					_ => {
+						palette.query.pop();
+					}
+				}
+				let query = palette.query.clone();
+				let entries = self.palette_entries(&query);
+				if let Some(palette) = self.palette.as_mut() {
+					palette.entries = StatefulList::with_items(entries);
+				}
+			}
+			_ => {}
+		}
+		None
+	}
+
+	/* Show the help popup from its first line. */
+	pub fn show_help_message(&mut self) {
+		self.show_help = true;
+		self.help_scroll = 0;
+	}
+
+	/**
+	 * Scroll the help popup.
+	 *
+	 * @param direction
+	 * @param page
+	 */
+	pub fn scroll_help(&mut self, direction: ScrollDirection, page: bool) {
+		let amount = if page {
+			self.help_height.saturating_sub(1).max(1)
+		} else {
+			1
+		};
+		match direction {
+			ScrollDirection::Up => {
+				self.help_scroll = self.help_scroll.saturating_sub(amount)
+			}
+			ScrollDirection::Down => {
+				self.help_scroll = self.help_scroll.saturating_add(amount)
+			}
+			ScrollDirection::Top => self.help_scroll = 0,
+			ScrollDirection::Bottom => self.help_scroll = u16::MAX,
+			_ => {}
+		}
+	}
+
+	/**
+	 * Get the lines of the help message.
+	 *
+	 * @return Vec
+	 */
+	fn help_text(&self) -> Vec<Line<'static>> {
+		let mut key_bindings = self.key_bindings.help();
+		key_bindings.push((String::from("1..9"), i18n::text("help.dependent")));
+		let mut help_text = Vec::new();
+		for (key, desc) in &key_bindings {
+			help_text.push(Line::from(Span::styled(
+				format!("{}:", &key),
+				self.style.colored,
+			)));
+			help_text.push(Line::from(Span::styled(
+				format!("{}{}", self.style.unicode.get(Symbol::Blank), &desc),
+				self.style.default,
+			)));
+		}
+		help_text
+	}
+
+	/**
+	 * Draw the help message as a scrollable popup.
+	 *
+	 * Scroll offset is limited with the popup height that is
+	 * recomputed on each draw for handling the terminal resizes.
+	 *
+	 * @param frame
+	 * @param area
+	 */
+	pub fn draw_help_popup(&mut self, frame: &mut Frame, area: Rect) {
+		let help_text = self.help_text();
+		/* Use the whole area on the small terminals. */
+		let popup_rect = if Self::is_compact(area) {
+			area
+		} else {
+			Rect {
+				x: area.x + area.width / 10,
+				y: area.y + area.height / 10,
+				width: area.width - area.width / 5,
+				height: area.height - area.height / 5,
+			}
+		};
+		self.help_height = popup_rect.height.saturating_sub(2);
+		let line_count = help_text.len() as u16;
+		self.help_scroll = self
+			.help_scroll
+			.min(line_count.saturating_sub(self.help_height));
+		let scroll_indicator = if line_count > self.help_height {
+			format!(
+				" {}{}-{}/{}{}",
+				self.style.unicode.get(Symbol::LeftBracket),
+				self.help_scroll + 1,
+				(self.help_scroll + self.help_height).min(line_count),
+				line_count,
+				self.style.unicode.get(Symbol::RightBracket),
+			)
+		} else {
+			String::new()
+		};
+		frame.render_widget(Clear, popup_rect);
+		frame.render_widget(
+			Paragraph::new(Text::from(help_text))
+				.block(
+					self.bordered_block()
+						.title(Span::styled(
+							format!(
+								"{}{}{}",
+								i18n::text("title.help"),
+								self.style.unicode.get(Symbol::Helmet),
+								scroll_indicator
+							),
+							self.style.bold,
+						))
+						.title_alignment(Alignment::Center)
+						.style(self.style.default),
+				)
+				.style(self.style.colored)
+				.scroll((self.help_scroll, 0)),
+			popup_rect,
+		);
+	}
+
+	/**
+	 * Show the result of saving the kernel activities on the information block.
+	 *
+	 * @param kernel_modules
+	 * @param result
+	 */
+	pub fn show_saved_logs(
+		&mut self,
+		kernel_modules: &mut KernelModules<'_>,
+		result: Result<PathBuf, String>,
+	) {
+		let (title, message) = match result {
+			Ok(path) => (
+				format!("!Saved logs{}", self.style.unicode.get(Symbol::FuelPump)),
+				format!("Saved the kernel activities to '{}'", path.display()),
+			),
+			Err(e) => (
+				format!("!Error{}", self.style.unicode.get(Symbol::NoEntry)),
+				e,
+			),
+		};
+		kernel_modules.info_scroll_offset = 0;
+		kernel_modules.command = ModuleCommand::None;
+		kernel_modules.current_name = title;
+		kernel_modules
+			.current_info
+			.set(Text::styled(message.clone(), self.style.default), message);
+	}
+
+	/**
+	 * Show dependent modules on the information block.
+	 *
+	 * @param kernel_modules
+	 */
+	pub fn show_dependent_modules(
+		&mut self,
+		kernel_modules: &mut KernelModules<'_>,
+	) {
+		let Some(module_name) = kernel_modules
+			.list
+			.get(kernel_modules.index)
+			.and_then(|module| module[0].split_whitespace().next())
+			.map(String::from)
+		else {
+			return;
+		};
+		let dependents = kernel_modules
+			.get_dependent_modules(&module_name)
+			.unwrap_or_default();
+		if (!dependents.is_empty() && !kernel_modules.is_showing_dependents())
+			|| cfg!(test)
+		{
+			kernel_modules.info_scroll_offset = 0;
+			kernel_modules.command = ModuleCommand::None;
+			kernel_modules.current_name = format!(
+				"!Dependent modules of {}{}",
+				module_name,
+				self.style.unicode.get(Symbol::HistoricSite)
+			);
+			kernel_modules.dependents = dependents;
+			kernel_modules.dependent_index = 0;
+			kernel_modules.render_dependents();
+		}
+	}
+
+	/**
+	 * Show the dependency tree of the module on the information block.
+	 *
+	 * @param kernel_modules
+	 */
+	pub fn show_dependency_tree(&mut self, kernel_modules: &mut KernelModules<'_>) {
+		if kernel_modules.current_name.starts_with('!') {
+			return;
+		}
+		let module_name = kernel_modules.current_name.clone();
+		let graph = kernel_modules.get_dependency_graph(&module_name);
+		let mut tree_text = Vec::new();
+		let mut tree_text_raw = Vec::new();
+		for (relation, title) in [
+			(Relation::Depends, "Depends on:"),
+			(Relation::Dependents, "Used by:"),
+		] {
+			if !tree_text.is_empty() {
+				tree_text.push(Line::default());
+				tree_text_raw.push(String::new());
+			}
+			tree_text.push(Line::from(Span::styled(title, self.style.colored)));
+			tree_text_raw.push(title.to_string());
+			for line in graph.tree(&module_name, relation) {
+				let cycle = if line.cycle { " (cycle)" } else { "" };
+				tree_text.push(Line::from(vec![
+					Span::styled(line.prefix.to_string(), self.style.colored),
+					Span::styled(line.name.to_string(), self.style.default),
+					Span::styled(cycle, self.style.colored),
+				]));
+				tree_text_raw.push(format!("{}{}{cycle}", line.prefix, line.name));
+			}
+		}
+		kernel_modules.info_scroll_offset = 0;
+		kernel_modules.command = ModuleCommand::None;
+		kernel_modules.current_name = format!(
+			"!Dependency tree of {}{}",
+			module_name,
+			self.style.unicode.get(Symbol::HistoricSite)
+		);
+		kernel_modules
+			.current_info
+			.set(Text::from(tree_text), tree_text_raw.join("\n"));
+	}
+
+	/**
+	 * Show the hardware aliases of the module on the information block.
+	 *
+	 * Aliases are parsed from the cached 'modinfo' output of the module.
+	 *
+	 * @param kernel_modules
+	 */
+	pub fn show_module_aliases(&mut self, kernel_modules: &mut KernelModules<'_>) {
+		kernel_modules.select_module(kernel_modules.index);
+		if kernel_modules.current_name.starts_with('!') {
+			return;
+		}
+		let module_name = kernel_modules.current_name.clone();
+		let aliases = lkm::parse_aliases(&kernel_modules.current_info.raw_text);
+		let mut aliases_text = vec![Line::from(Span::styled(
+			format!("Aliases ({}):", aliases.len()),
+			self.style.colored,
+		))];
+		if aliases.is_empty() {
+			aliases_text.push(Line::from(Span::styled(
+				"no aliases available",
+				self.style.default,
+			)));
+		}
+		for alias in &aliases {
+			aliases_text.push(Line::from(Span::styled(
+				alias.to_string(),
+				self.style.default,
+			)));
+		}
+		kernel_modules.info_scroll_offset = 0;
+		kernel_modules.command = ModuleCommand::None;
+		kernel_modules.current_name = format!(
+			"!Aliases of {}{}",
+			module_name,
+			self.style.unicode.get(Symbol::Magnifier)
+		);
+		kernel_modules.current_info.set(
+			Text::from(aliases_text),
+			format!("Aliases ({}):\n{}", aliases.len(), aliases.join("\n")),
+		);
+	}
+
+	/**
+	 * Show the runtime parameters of the module on the information block.
+	 *
+	 * @param kernel_modules
+	 */
+	pub fn show_module_parameters(
+		&mut self,
+		kernel_modules: &mut KernelModules<'_>,
+	) {
+		let Some(module_name) = kernel_modules
+			.list
+			.get(kernel_modules.index)
+			.and_then(|module| module[0].split_whitespace().next())
+			.map(String::from)
+		else {
+			return;
+		};
+		/* Keep the selection while re-reading the parameters of the same module. */
+		if kernel_modules.parameters_module != module_name {
+			kernel_modules.parameter_index = 0;
+		}
+		kernel_modules.parameters = kernel_modules.get_parameters(&module_name);
+		kernel_modules.parameter_info =
+			kernel_modules.get_parameter_info(&module_name);
+		kernel_modules.parameter_index = kernel_modules
+			.parameter_index
+			.min(kernel_modules.parameters.len().saturating_sub(1));
+		kernel_modules.parameters_module.clone_from(&module_name);
+		kernel_modules.info_scroll_offset = 0;
+		kernel_modules.command = ModuleCommand::None;
+		kernel_modules.current_name = format!(
+			"!Parameters of {}{}",
+			module_name,
+			self.style.unicode.get(Symbol::Gear)
+		);
+		kernel_modules.render_parameters();
+	}
+
+	/**
+	 * Check if the area is too small for showing all the blocks at once.
+	 *
+	 * @param  area
+	 * @return bool
+	 */
+	pub fn is_compact(area: Rect) -> bool {
+		area.width < FULL_LAYOUT_SIZE.0 || area.height < FULL_LAYOUT_SIZE.1
+	}
+
+	/**
+	 * Get the properties of the block layout.
+	 *
+	 * @return LayoutArgs
+	 */
+	pub fn layout_args(&self) -> LayoutArgs {
+		LayoutArgs {
+			block_size: self.block_size,
+			orientation: self.orientation,
+			max_list_height: self.max_list_height,
+			no_log: self.no_log,
+		}
+	}
+
+	/**
+	 * Check if the area is too small for drawing the blocks.
+	 *
+	 * @param  area
+	 * @return bool
+	 */
+	pub fn is_too_small(area: Rect) -> bool {
+		area.width < MIN_TERMINAL_SIZE.0 || area.height < MIN_TERMINAL_SIZE.1
+	}
+
+	/**
+	 * Draw only the selected block on the given area for the small terminals.
+	 *
+	 * User input is shown above the module list while it is selected and
+	 * the module information is shown while a command is waiting for confirmation.
+	 *
+	 * @param frame
+	 * @param area
+	 * @param kernel
+	 * @param tx
+	 */
+	pub fn draw_compact_layout(
+		&mut self,
+		frame: &mut Frame,
+		area: Rect,
+		kernel: &mut Kernel,
+		tx: &Sender<Event<Key>>,
+	) {
+		let area = if self.selected_block == Block::UserInput
+			|| !self.input_mode.is_none()
+		{
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints([Constraint::Length(3), Constraint::Min(0)])
+				.split(area);
+			self.block_areas.push((Block::UserInput, chunks[0]));
+			self.draw_user_input(frame, chunks[0], tx);
+			chunks[1]
+		} else {
+			area
+		};
+		let block = match self.selected_block {
+			_ if !kernel.modules.command.is_none() => Block::ModuleInfo,
+			Block::UserInput => Block::ModuleTable,
+			block => block,
+		};
+		self.block_areas.push((block, area));
+		match block {
+			Block::ModuleInfo => {
+				self.draw_module_info(frame, area, &mut kernel.modules)
+			}
+			Block::Activities => {
+				self.draw_kernel_activities(frame, area, &mut kernel.logs)
+			}
+			_ => self.draw_kernel_modules(frame, area, &mut kernel.modules),
+		}
+	}
+
+	/**
+	 * Draw the message about the terminal size on the center of the given area.
+	 *
+	 * @param frame
+	 * @param area
+	 */
+	pub fn draw_size_warning(&self, frame: &mut Frame, area: Rect) {
+		let text = format!(
+			"{} ({}x{})",
+			i18n::text("status.too_small"),
+			area.width,
+			area.height
+		);
+		frame.render_widget(
+			Paragraph::new(Span::styled(text, self.style.bold))
+				.alignment(Alignment::Center)
+				.wrap(Wrap { trim: true }),
+			Rect {
+				y: area.y + area.height.saturating_sub(1) / 2,
+				height: area.height.min(2),
+				..area
+			},
+		);
+	}
+
+	/**
+	 * Draw a block according to the index.
+	 *
+	 * @param frame
+	 * @param area
+	 * @param kernel
+	 */
+	pub fn draw_dynamic_block(
+		&mut self,
+		frame: &mut Frame,
+		area: Rect,
+		kernel: &mut Kernel,
+	) {
+		let last_index = self.block_count() - 1;
+		let block = match self.block_index.min(last_index) {
+			0 => Block::ModuleTable,
+			1 => Block::ModuleInfo,
+			_ => Block::Activities,
+		};
+		self.block_areas.push((block, area));
+		match block {
+			Block::ModuleInfo => {
+				self.draw_module_info(frame, area, &mut kernel.modules)
+			}
+			Block::Activities => {
+				self.draw_kernel_activities(frame, area, &mut kernel.logs)
+			}
+			_ => self.draw_kernel_modules(frame, area, &mut kernel.modules),
+		}
+		if self.block_index < last_index {
+			self.block_index += 1;
+		} else {
+			self.block_index = 0;
+		}
+	}
+
+	/**
+	 * Draw a paragraph widget for using as user input.
+	 *
+	 * @param frame
+	 * @param area
+	 * @param tx
+	 */
+	pub fn draw_user_input(
+		&self,
+		frame: &mut Frame,
+		area: Rect,
+		tx: &Sender<Event<Key>>,
+	) {
+		frame.render_widget(
+			Paragraph::new(Span::raw(self.input_query.to_string()))
+				.block(
+					self.bordered_block()
+						.border_style({
+							if self.selected_block == Block::UserInput
+								&& self.input_mode.is_none()
+							{
+								tx.send(Event::Input(Key::Char('\n'))).unwrap();
+							}
+							self.block_style(Block::UserInput)
+						})
+						.title(Span::styled(
+							format!(
+								"{}{}{}",
+								self.input_mode,
+								match self.search_regex(&self.input_query) {
+									_ if matches!(
+										self.input_mode,
+										InputMode::Load
+											| InputMode::LoadFile | InputMode::Parameter
+											| InputMode::SizeFilter | InputMode::Confirm
+									) =>
+										String::new(),
+									_ if self.input_mode == InputMode::LogSearch
+										&& self.case_sensitive =>
+										String::from(" (case-sensitive)"),
+									_ if self.input_mode == InputMode::LogSearch =>
+										String::new(),
+									Some(Err(_)) => String::from(" (invalid regex)"),
+									_ if self.search_scope == SearchScope::All =>
+										format!(
+											" ({}, all fields)",
+											self.search_mode
+										),
+									_ => format!(" ({})", self.search_mode),
+								},
+								match self.input_mode {
+									InputMode::Load | InputMode::LoadFile =>
+										self.style.unicode.get(Symbol::Anchor),
+									InputMode::Parameter =>
+										self.style.unicode.get(Symbol::Gear),
+									InputMode::Confirm =>
+										self.style.unicode.get(Symbol::NoEntry),
+									_ => self.style.unicode.get(Symbol::Magnifier),
+								},
+							),
+							self.style.bold,
+						))
+						.title(
+							Title::from(Span::styled(
+								if self.read_only { "READ ONLY" } else { "" },
+								self.style.bold,
+							))
+							.alignment(Alignment::Right),
+						),
+				)
+				.alignment(Alignment::Left),
+			area,
+		);
+	}
+
+	/**
+	 * Complete the module name on the input query.
+	 *
+	 * The query is extended to the common prefix of the candidates
+	 * and the candidates are listed if there are more than one.
+	 *
+	 * @param kernel_modules
+	 */
+	pub fn complete_module_name(&mut self, kernel_modules: &mut KernelModules<'_>) {
+		let candidates = kernel_modules.get_completions(&self.input_query);
+		let prefix = util::common_prefix(&candidates);
+		if prefix.len() > self.input_query.len() {
+			self.input_query = prefix;
+		}
+		self.completions = if candidates.len() > 1 {
+			candidates
+		} else {
+			Vec::new()
+		};
+	}
+
+	/**
+	 * Draw the module name completions below the input area.
+	 *
+	 * @param frame
+	 */
+	pub fn draw_completions(&self, frame: &mut Frame) {
+		let Some((_, area)) = self
+			.block_areas
+			.iter()
+			.find(|(block, _)| *block == Block::UserInput)
+		else {
+			return;
+		};
+		let count = self.completions.len().min(MAX_COMPLETIONS);
+		let popup_rect = Rect {
+			x: area.x,
+			y: area.bottom(),
+			width: area.width,
+			height: (count as u16 + 2)
+				.min(frame.size().bottom().saturating_sub(area.bottom())),
+		};
+		let mut items = self
+			.completions
+			.iter()
+			.take(count)
+			.map(|v| ListItem::new(Span::styled(v.to_string(), self.style.default)))
+			.collect::<Vec<ListItem<'_>>>();
+		if self.completions.len() > count {
+			items.pop();
+			items.push(ListItem::new(Span::styled(
+				format!("+{} more", self.completions.len() - count + 1),
+				self.style.colored,
+			)));
+		}
+		frame.render_widget(Clear, popup_rect);
+		frame.render_widget(
+			List::new(items).block(
+				self.bordered_block()
+					.border_style(self.style.colored)
+					.title(Span::styled(
+						format!("Completions ({})", self.completions.len()),
+						self.style.bold,
+					)),
+			),
+			popup_rect,
+		);
+	}
+
+	/**
+	 * Draw the command palette with the matching actions and their keys.
+	 *
+	 * @param frame
+	 * @param area
+	 */
+	pub fn draw_palette(&mut self, frame: &mut Frame, area: Rect) {
+		let Some(mut palette) = self.palette.take() else {
+			return;
+		};
+		let width = area.width.min(PALETTE_WIDTH);
+		let height = area
+			.height
+			.min(((palette.entries.items.len() as u16).max(1)).saturating_add(3))
+			.min(area.height.saturating_sub(area.height / 5).max(4));
+		let popup_rect = Rect {
+			x: area.x + (area.width - width) / 2,
+			y: area.y + (area.height - height) / 2,
+			width,
+			height,
+		};
+		let key_width = palette
+			.entries
+			.items
+			.iter()
+			.map(|(_, keys)| keys.width())
+			.max()
+			.unwrap_or_default();
+		let desc_width = (width as usize).saturating_sub(key_width + 4);
+		let items = palette
+			.entries
+			.items
+			.iter()
+			.map(|(action, keys)| {
+				ListItem::new(Line::from(vec![
+					Span::styled(
+						format!(" {:<desc_width$}", action.description()),
+						self.style.default,
+					),
+					Span::styled(format!("{keys:>key_width$}"), self.style.colored),
+				]))
+			})
+			.collect::<Vec<ListItem<'_>>>();
+		let block = self
+			.bordered_block()
+			.border_style(self.style.colored)
+			.title(Span::styled(
+				format!(
+					"{} ({})",
+					i18n::text("title.palette"),
+					palette.entries.items.len()
+				),
+				self.style.bold,
+			))
+			.title_alignment(Alignment::Center)
+			.style(self.style.default);
+		let chunks = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints([Constraint::Length(1), Constraint::Min(0)])
+			.split(block.inner(popup_rect));
+		frame.render_widget(Clear, popup_rect);
+		frame.render_widget(block, popup_rect);
+		frame.render_widget(
+			Paragraph::new(Span::styled(
+				format!(":{}", palette.query),
+				self.style.bold,
+			)),
+			chunks[0],
+		);
+		frame.render_stateful_widget(
+			List::new(items).highlight_style(self.style.selected),
+			chunks[1],
+			&mut palette.entries.state,
+		);
+		frame
+			.set_cursor(chunks[0].x + 1 + palette.query.width() as u16, chunks[0].y);
+		self.palette = Some(palette);
+	}
+
+	/**
+	 * Draw the quit confirmation dialog on the center of the given area.
+	 *
+	 * @param frame
+	 * @param area
+	 */
+	pub fn draw_quit_dialog(&self, frame: &mut Frame, area: Rect) {
+		let message = i18n::text("prompt.quit");
+		let width = area.width.min(message.width() as u16 + 4);
+		let height = area.height.min(3);
+		let popup_rect = Rect {
+			x: area.x + (area.width - width) / 2,
+			y: area.y + (area.height - height) / 2,
+			width,
+			height,
+		};
+		frame.render_widget(Clear, popup_rect);
+		frame.render_widget(
+			Paragraph::new(Span::styled(message, self.style.bold))
+				.alignment(Alignment::Center)
+				.block(self.bordered_block().style(self.style.default)),
+			popup_rect,
+		);
+	}
+
+	/**
+	 * Draw the notifications as stacked popups on the top right corner.
+	 *
+	 * @param frame
+	 * @param area
+	 */
+	pub fn draw_notifications(&self, frame: &mut Frame, area: Rect) {
+		let width = area.width.min(NOTIFICATION_WIDTH);
+		for (i, notification) in self
+			.notifications
+			.iter()
+			.take((area.height / 3) as usize)
+			.enumerate()
+		{
+			let popup_rect = Rect {
+				x: area.right() - width,
+				y: area.y + i as u16 * 3,
+				width,
+				height: 3,
+			};
+			let style = match notification.severity {
+				Severity::Success => self.style.success,
+				Severity::Error => self.style.error,
+			};
+			frame.render_widget(Clear, popup_rect);
+			frame.render_widget(
+				Paragraph::new(Span::styled(notification.text.to_string(), style))
+					.block(self.bordered_block().style(style)),
+				popup_rect,
 			);
-			let mut dependent_modules = Vec::new();
-			for module in &dependent_modules_list {
-				dependent_modules.push(Line::from(vec![
-					Span::styled("-", self.style.colored),
-					Span::styled(format!(" {module}"), self.style.default),
-				]));
-			}
-			kernel_modules.current_info.set(
-				Text::from(dependent_modules),
-				kernel_modules.current_name.clone(),
+		}
+	}
+
+	/**
+	 * Draw the startup banner over the header line until it is dismissed.
+	 *
+	 * @param frame
+	 * @param area
+	 */
+	pub fn draw_banner(&self, frame: &mut Frame, area: Rect) {
+		if let Some(banner) = &self.banner {
+			frame.render_widget(Clear, area);
+			frame.render_widget(
+				Paragraph::new(Span::styled(banner.as_str(), self.style.warning))
+					.alignment(Alignment::Center),
+				area,
 			);
 		}
 	}
 
 	/**
-	 * Draw a block according to the index.
+	 * Draw the header line with the kernel release and loaded module totals.
+	 *
+	 * The header is prefixed with the remote host and with 'PAUSED' while
+	 * the auto-refresh is paused, and the time since the last update is
+	 * appended if it is enabled.
 	 *
 	 * @param frame
 	 * @param area
-	 * @param kernel
+	 * @param release
+	 * @param kernel_modules
 	 */
-	pub fn draw_dynamic_block(
-		&mut self,
+	pub fn draw_header(
+		&self,
 		frame: &mut Frame,
 		area: Rect,
-		kernel: &mut Kernel,
+		release: &str,
+		kernel_modules: &KernelModules,
 	) {
-		match self.block_index {
-			0 => self.draw_kernel_modules(frame, area, &mut kernel.modules),
-			1 => self.draw_module_info(frame, area, &mut kernel.modules),
-			_ => self.draw_kernel_activities(frame, area, &mut kernel.logs),
+		let mut header = Self::header_text(release, kernel_modules);
+		if let Some(updated) = self.updated_text() {
+			header = format!("{header} | {updated}");
 		}
-		if self.block_index < 2 {
-			self.block_index += 1;
-		} else {
-			self.block_index = 0;
+		if let Some(remote) = &self.remote {
+			header = format!("remote: {remote} | {header}");
 		}
+		frame.render_widget(
+			Paragraph::new(Span::styled(
+				if self.paused {
+					format!("PAUSED | {header}")
+				} else {
+					header
+				},
+				self.style.bold,
+			))
+			.alignment(Alignment::Center),
+			area,
+		);
 	}
 
 	/**
-	 * Draw a paragraph widget for using as user input.
+	 * Draw the status bar with the last status message and module list summary.
 	 *
 	 * @param frame
 	 * @param area
-	 * @param tx
+	 * @param kernel_modules
 	 */
-	pub fn draw_user_input(
+	pub fn draw_status_bar(
 		&self,
 		frame: &mut Frame,
 		area: Rect,
-		tx: &Sender<Event<Key>>,
+		kernel_modules: &KernelModules,
 	) {
+		/* Animate a spinner while a module command is running. */
+		let running = kernel_modules.running.as_ref().map(|(command, name, _)| {
+			format!(
+				"{} {}...",
+				self.style.unicode.spinner(self.tick),
+				command.progress_message(name)
+			)
+		});
+		/* Show the progress of indexing the modules for searching all fields. */
+		let indexing = kernel_modules
+			.indexing
+			.filter(|_| self.search_scope == SearchScope::All)
+			.map(|(indexed, total)| {
+				format!("indexing modules ({indexed}/{total})...")
+			});
+		let status = running
+			.as_deref()
+			.or_else(|| self.status())
+			.or(indexing.as_deref())
+			.unwrap_or_default();
+		let summary = Self::module_summary(
+			kernel_modules,
+			(area.width as usize).saturating_sub(if status.is_empty() {
+				1
+			} else {
+				status.width() + 3
+			}),
+		);
+		let chunks = Layout::default()
+			.direction(Direction::Horizontal)
+			.constraints([
+				Constraint::Min(0),
+				Constraint::Length(summary.width() as u16 + 1),
+			])
+			.split(area);
 		frame.render_widget(
-			Paragraph::new(Span::raw(self.input_query.to_string()))
-				.block(
-					TuiBlock::default()
-						.border_style(match self.selected_block {
-							Block::UserInput => {
-								if self.input_mode.is_none() {
-									tx.send(Event::Input(Key::Char('\n'))).unwrap();
-								}
-								self.style.default
-							}
-							_ => self.style.colored,
-						})
-						.borders(Borders::ALL)
-						.title(Span::styled(
-							format!(
-								"{}{}",
-								self.input_mode,
-								match self.input_mode {
-									InputMode::Load =>
-										self.style.unicode.get(Symbol::Anchor),
-									_ => self.style.unicode.get(Symbol::Magnifier),
-								}
-							),
-							self.style.bold,
-						)),
-				)
-				.alignment(Alignment::Left),
-			area,
+			Paragraph::new(Span::styled(format!(" {status}"), self.style.default)),
+			chunks[0],
+		);
+		frame.render_widget(
+			Paragraph::new(Span::styled(summary, self.style.colored))
+				.alignment(Alignment::Right),
+			chunks[1],
 		);
 	}
 
@@ -398,11 +2072,10 @@ impl App {
 		frame.render_widget(
 			Paragraph::new(Span::raw(&info[1]))
 				.block(
-					TuiBlock::default()
+					self.bordered_block()
 						.border_style(self.style.colored)
-						.borders(Borders::ALL)
 						.title(Span::styled(
-							&format!(
+							format!(
 								"{}{}",
 								info[0],
 								self.style.unicode.get(Symbol::Gear)
@@ -432,31 +2105,132 @@ impl App {
 	) {
 		/* Filter the module list depending on the input query. */
 		let mut kernel_module_list = kernel_modules.default_list.clone();
-		if (self.input_mode == InputMode::None
-			|| self.input_mode == InputMode::Search)
-			&& !self.input_query.is_empty()
-		{
-			kernel_module_list.retain(|module| {
-				module[0]
-					.to_lowercase()
-					.contains(&self.input_query.to_lowercase())
-			});
+		let mut search_matches = BTreeMap::new();
+		let search_query = Some(self.debounced_query()).filter(|query| {
+			(self.input_mode == InputMode::None
+				|| self.input_mode == InputMode::Search)
+				&& !query.is_empty()
+		});
+		/* Fall back to substring matching if the regex is not valid. */
+		let regex = search_query
+			.as_deref()
+			.and_then(|query| self.search_regex(query))
+			.and_then(Result::ok);
+		if let Some(search_query) = &search_query {
+			let query = search_query.to_lowercase();
+			/* Match the indexed descriptions and aliases of the modules. */
+			let matched_field = |name: &str| {
+				if self.search_scope != SearchScope::All {
+					return None;
+				}
+				kernel_modules
+					.search_index
+					.get(name)?
+					.matched_field(|text| match &regex {
+						Some(regex) => regex.is_match(text),
+						None => text.to_lowercase().contains(&query),
+					})
+			};
+			if self.search_mode == SearchMode::Fuzzy {
+				/* Order the modules by their match scores. */
+				let mut scored_list = kernel_module_list
+					.into_iter()
+					.filter_map(|module| {
+						let name =
+							module[0].split_whitespace().next().unwrap_or_default();
+						match util::fuzzy_score(search_query, name) {
+							Some(score) => Some((score, module)),
+							None => {
+								search_matches
+									.insert(name.to_string(), matched_field(name)?);
+								Some((i64::MIN, module))
+							}
+						}
+					})
+					.collect::<Vec<(i64, Vec<String>)>>();
+				scored_list.sort_by_key(|(score, _)| Reverse(*score));
+				kernel_module_list =
+					scored_list.into_iter().map(|(_, module)| module).collect();
+			} else {
+				kernel_module_list.retain(|module| {
+					let name =
+						module[0].split_whitespace().next().unwrap_or_default();
+					let name_matched = match &regex {
+						Some(regex) => regex.is_match(name),
+						None => module[0].to_lowercase().contains(&query),
+					};
+					name_matched
+						|| matched_field(name)
+							.map(|field| {
+								search_matches.insert(name.to_string(), field)
+							})
+							.is_some()
+				});
+			}
 		}
-		/* Append '...' if dependent modules exceed the block width. */
-		let dependent_width = (area.width / 2).saturating_sub(7) as usize;
+		self.search_matches = search_matches;
+		kernel_module_list = kernel_modules.group_rows(kernel_module_list);
+		/* Share the width of the table between the visible columns. */
+		let total_weight = self.columns.iter().map(|v| v.weight()).sum::<u32>();
+		let column_width = |column: Column| {
+			(area.width.saturating_sub(2) as u32 * column.weight())
+				.checked_div(total_weight)
+				.unwrap_or_default() as usize
+		};
+		/* Show the count of dependent modules if they exceed the block width. */
+		let dependent_width = column_width(Column::UsedBy).saturating_sub(6);
 		for module in &mut kernel_module_list {
-			if module[2].len() > dependent_width {
-				module[2].truncate(dependent_width);
-				module[2] = format!("{}...", module[2]);
-			}
+			module[2] = Self::compact_dependents(&module[2], dependent_width);
 		}
 		kernel_modules.list = kernel_module_list;
+		kernel_modules.select_pending();
+		/* Fit the size bars next to the sizes unless the size column is too narrow. */
+		let size_width = kernel_modules
+			.list
+			.iter()
+			.map(|v| v[1].width())
+			.max()
+			.unwrap_or_default();
+		let bar_width = column_width(Column::Size)
+			.saturating_sub(size_width + 2)
+			.min(MAX_SIZE_BAR_WIDTH);
+		let max_size = kernel_modules
+			.modules
+			.iter()
+			.map(|v| v.size)
+			.max()
+			.unwrap_or_default();
+		let size_bar = |name: &str| {
+			if !self.size_bar || bar_width < MIN_SIZE_BAR_WIDTH || max_size == 0 {
+				return None;
+			}
+			let size = kernel_modules.modules.iter().find(|v| v.name == name)?.size;
+			Some(
+				self.style
+					.unicode
+					.bar(size as f64 / max_size as f64, bar_width),
+			)
+		};
+		/* Highlight the modules that are changed since the last refresh. */
+		let changes = kernel_modules
+			.recent_changes()
+			.filter(|_| self.change_highlight)
+			.cloned();
+		let change_style = |name: &str| {
+			let changes = changes.as_ref().filter(|v| v.added.contains(name))?;
+			/* Fade out the highlight in the second half of the duration. */
+			Some(if changes.time.elapsed() < CHANGE_DURATION / 2 {
+				self.style.success.add_modifier(Modifier::BOLD)
+			} else {
+				self.style.success
+			})
+		};
+		/* Leave out the spacing between the columns from the width of the names. */
+		let name_width =
+			column_width(Column::Name).saturating_sub(self.columns.len());
 		/* Set the scroll offset for modules. */
-		let modules_scroll_offset = area
-			.height
-			.checked_sub(5)
-			.and_then(|height| kernel_modules.index.checked_sub(height as usize))
-			.unwrap_or(0);
+		let modules_scroll_offset =
+			Self::modules_scroll_offset(area, kernel_modules.index);
 		/* Set selected state of the modules and render the table widget. */
 		frame.render_widget(
 			Table::new(
@@ -466,49 +2240,201 @@ impl App {
 					.skip(modules_scroll_offset)
 					.enumerate()
 					.map(|(i, item)| {
-						let item = item.iter().map(|v| v.to_string());
+						let mut item = item.to_vec();
+						let name = item[0]
+							.split_whitespace()
+							.next()
+							.unwrap_or_default()
+							.to_string();
+						/* Mark the modules that are selected for the batch commands. */
+						if kernel_modules.marked.contains(&name) {
+							item[0].replace_range(..1, "*");
+						}
+						/* Show the field that is matched instead of the name. */
+						if let Some(field) = self.search_matches.get(&name) {
+							item[0] = format!("{} ({field})", item[0]);
+						}
+						if let Some(bar) = size_bar(&name) {
+							item[1] = format!("{:<size_width$} {bar}", item[1]);
+						}
+						if kernel_modules.is_bookmarked(&name) {
+							item[0] = format!(
+								"{} {}",
+								item[0],
+								self.style.unicode.get(Symbol::Star)
+							);
+						}
+						/* Mark the modules that are loaded at boot. */
+						if self.boot_marker
+							&& !kernel_modules.boot_files(&name).is_empty()
+						{
+							item[0] = format!("{} (boot)", item[0]);
+						}
+						/* Mark the third-party modules for diagnosing issues. */
+						if self.out_of_tree_marker
+							&& kernel_modules.is_out_of_tree(&name)
+						{
+							item[0] = format!("{} (out-of-tree)", item[0]);
+						}
+						/* Truncate the names that do not fit into the column. */
+						let name_text = util::truncate_width(&item[0], name_width);
+						let visible_chars = if name_text == item[0] {
+							name_text.chars().count()
+						} else {
+							name_text
+								.chars()
+								.count()
+								.saturating_sub(util::ELLIPSIS.len())
+						};
+						item[0] = name_text;
+						/* Highlight the characters of the name that match the query. */
+						let mut matched = search_query
+							.as_deref()
+							.map(|query| {
+								self.matched_chars(&name, query, regex.as_ref())
+							})
+							.unwrap_or_default();
+						matched.truncate(visible_chars.saturating_sub(1));
+						let item = self
+							.columns
+							.iter()
+							.map(|v| {
+								let text = mem::take(&mut item[v.index()]);
+								if *v == Column::Name && matched.contains(&true) {
+									Cell::from(self.highlight_name(text, &matched))
+								} else {
+									Cell::from(text)
+								}
+							})
+							.collect::<Vec<Cell>>();
 						if Some(i)
 							== kernel_modules
 								.index
 								.checked_sub(modules_scroll_offset)
 						{
-							Row::new(item).style(self.style.default)
+							Row::new(item).style(self.style.selected)
+						} else if kernel_modules.group_header(&name).is_some() {
+							/* Show the headers of the collapsed groups in bold. */
+							Row::new(item).style(self.style.bold)
+						} else if let Some(style) = change_style(&name) {
+							Row::new(item).style(style)
+						} else if kernel_modules.is_builtin(&name) {
+							/* Show the built-in modules in italic. */
+							Row::new(item).style(
+								self.style.colored.add_modifier(Modifier::ITALIC),
+							)
+						} else if !kernel_modules.is_live(&name) {
+							/* Show the modules that are initializing or being removed. */
+							Row::new(item).style(
+								self.style.warning.add_modifier(Modifier::ITALIC),
+							)
+						} else if kernel_modules.is_blacklisted(&name) {
+							/* Dim the modules that are blacklisted. */
+							Row::new(item).style(
+								self.style.colored.add_modifier(Modifier::DIM),
+							)
+						} else if kernel_modules.is_in_use(&name) {
+							/* Show the modules that are in use in bold. */
+							Row::new(item).style(
+								self.style.colored.add_modifier(Modifier::BOLD),
+							)
 						} else {
 							Row::new(item).style(self.style.colored)
 						}
 					}),
-				&[
-					Constraint::Percentage(30),
-					Constraint::Percentage(20),
-					Constraint::Percentage(50),
-				],
+				self.columns
+					.iter()
+					.map(|v| Constraint::Ratio(v.weight(), total_weight)),
 			)
 			.header(
-				Row::new(TABLE_HEADER.iter().map(|v| v.to_string()))
-					.style(self.style.bold),
+				Row::new(self.columns.iter().map(|v| TABLE_HEADER[v.index()]).map(
+					|v| match v {
+						"header.module" => format!(" {}", i18n::text(v)),
+						"header.size" => format!(
+							"{} ({})",
+							i18n::text(v),
+							kernel_modules.args.size_format
+						),
+						_ => i18n::text(v).to_string(),
+					},
+				))
+				.style(self.style.bold),
 			)
 			.block(
-				TuiBlock::default()
+				self.bordered_block()
 					.border_style(self.block_style(Block::ModuleTable))
-					.borders(Borders::ALL)
 					.title(Span::styled(
 						format!(
-							"Loaded Kernel Modules {}{}/{}{} {}{}%{}",
-							self.style.unicode.get(Symbol::LeftBracket),
-							match kernel_modules.list.len() {
-								0 => kernel_modules.index,
-								_ => kernel_modules.index + 1,
+							"{} {}{}{}{}{}{}{}{}{}",
+							self.modules_title,
+							match kernel_modules.args.filter {
+								ListFilter::All => String::new(),
+								filter => format!("({filter}) "),
+							},
+							match kernel_modules.args.size_filter {
+								Some(size_filter) =>
+									format!("(size {size_filter}) "),
+								None => String::new(),
+							},
+							if kernel_modules.args.hide_used {
+								"(in-use hidden) "
+							} else {
+								""
+							},
+							if kernel_modules.args.bookmarked {
+								"(bookmarks) "
+							} else {
+								""
 							},
-							kernel_modules.list.len(),
-							self.style.unicode.get(Symbol::RightBracket),
-							self.style.unicode.get(Symbol::LeftBracket),
-							if !kernel_modules.list.is_empty() {
-								((kernel_modules.index + 1) as f64
-									/ kernel_modules.list.len() as f64 * 100.0) as u64
+							match kernel_modules
+								.args
+								.sort
+								.description(kernel_modules.args.reverse)
+							{
+								/* Show the direction of the sorting with an arrow. */
+								Some(sort)
+									if kernel_modules.args.sort
+										!= SortType::None =>
+								{
+									format!(
+										"({sort} {}) ",
+										self.style.unicode.get(
+											if kernel_modules
+												.args
+												.sort
+												.is_descending(
+													kernel_modules.args.reverse
+												) {
+												Symbol::DownArrow
+											} else {
+												Symbol::UpArrow
+											}
+										)
+									)
+								}
+								Some(sort) => format!("({sort}) "),
+								None => String::new(),
+							},
+							match kernel_modules.marked.len() {
+								0 => String::new(),
+								count => format!("({count} selected) "),
+							},
+							match &changes {
+								Some(changes) => format!("({}) ", changes.summary()),
+								None => String::new(),
+							},
+							self.scroll_indicator(
+								match kernel_modules.list.len() {
+									0 => kernel_modules.index,
+									_ => kernel_modules.index + 1,
+								},
+								kernel_modules.list.len()
+							),
+							if kernel_modules.refreshing {
+								" refreshing..."
 							} else {
-								0
+								""
 							},
-							self.style.unicode.get(Symbol::RightBracket),
 						),
 						self.style.bold,
 					)),
@@ -520,6 +2446,234 @@ impl App {
 		}
 	}
 
+	/**
+	 * Get the search query that is applied on the module list.
+	 *
+	 * Changes of the query are applied after the input is unchanged for
+	 * the debounce interval while searching and immediately otherwise.
+	 *
+	 * @return String
+	 */
+	fn debounced_query(&mut self) -> String {
+		if self.input_query != self.pending_query {
+			self.pending_query.clone_from(&self.input_query);
+			self.query_time = Instant::now();
+		}
+		if self.input_mode != InputMode::Search
+			|| self.query_time.elapsed() >= self.search_debounce
+		{
+			self.search_query.clone_from(&self.pending_query);
+		}
+		self.search_query.to_string()
+	}
+
+	/**
+	 * Build a case-insensitive regex from the search query.
+	 *
+	 * @return Option
+	 */
+	fn search_regex(&self, query: &str) -> Option<Result<Regex, regex::Error>> {
+		if self.search_mode == SearchMode::Regex && !query.is_empty() {
+			Some(RegexBuilder::new(query).case_insensitive(true).build())
+		} else {
+			None
+		}
+	}
+
+	/**
+	 * Get the style of the kernel activities with the given log level.
+	 *
+	 * @param  level
+	 * @return TuiStyle (Option)
+	 */
+	fn log_style(&self, level: Option<LogLevel>) -> Option<TuiStyle> {
+		match level? {
+			LogLevel::Emerg | LogLevel::Alert | LogLevel::Crit | LogLevel::Err => {
+				Some(self.style.error)
+			}
+			LogLevel::Warn => Some(self.style.warning),
+			LogLevel::Debug => Some(TuiStyle::default().add_modifier(Modifier::DIM)),
+			LogLevel::Notice | LogLevel::Info => None,
+		}
+	}
+
+	/**
+	 * Get the characters of the module name that match the search query.
+	 *
+	 * @param  name
+	 * @param  query
+	 * @param  regex
+	 * @return Vec
+	 */
+	fn matched_chars(
+		&self,
+		name: &str,
+		query: &str,
+		regex: Option<&Regex>,
+	) -> Vec<bool> {
+		let mut matched = vec![false; name.chars().count()];
+		let mut mark = |start: usize, end: usize| {
+			let start = name[..start].chars().count();
+			let end = name[..end].chars().count();
+			matched[start..end].iter_mut().for_each(|v| *v = true);
+		};
+		match (self.search_mode, regex) {
+			(SearchMode::Fuzzy, _) => {
+				for index in util::fuzzy_indices(query, name).unwrap_or_default() {
+					matched[index] = true;
+				}
+			}
+			(_, Some(regex)) => {
+				for range in regex.find_iter(name) {
+					mark(range.start(), range.end());
+				}
+			}
+			_ => {
+				let lowercase_name = name.to_lowercase();
+				if lowercase_name.len() == name.len() {
+					for (start, v) in
+						lowercase_name.match_indices(&query.to_lowercase())
+					{
+						mark(start, start + v.len());
+					}
+				}
+			}
+		}
+		matched
+	}
+
+	/**
+	 * Split the name cell into the spans of the matched and unmatched characters.
+	 *
+	 * Name starts after the marker character of the row.
+	 *
+	 * @param  text
+	 * @param  matched
+	 * @return Line
+	 */
+	fn highlight_name(&self, text: String, matched: &[bool]) -> Line<'static> {
+		let style = TuiStyle::default()
+			.fg(self.style.default.fg.unwrap_or_default())
+			.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+		let mut spans = Vec::<Span>::new();
+		let mut current = (String::new(), false);
+		for (i, c) in text.chars().enumerate() {
+			let is_matched =
+				i.checked_sub(1).and_then(|i| matched.get(i)) == Some(&true);
+			if is_matched != current.1 && !current.0.is_empty() {
+				spans.push(Self::name_span(
+					mem::take(&mut current.0),
+					current.1,
+					style,
+				));
+			}
+			current.0.push(c);
+			current.1 = is_matched;
+		}
+		spans.push(Self::name_span(current.0, current.1, style));
+		Line::from(spans)
+	}
+
+	/**
+	 * Create a span of the module name with the highlight style if it is matched.
+	 *
+	 * @param  text
+	 * @param  matched
+	 * @param  style
+	 * @return Span
+	 */
+	fn name_span(text: String, matched: bool, style: TuiStyle) -> Span<'static> {
+		if matched {
+			Span::styled(text, style)
+		} else {
+			Span::raw(text)
+		}
+	}
+
+	/**
+	 * Create a block with the borders of the configured style.
+	 *
+	 * @return TuiBlock
+	 */
+	fn bordered_block(&self) -> TuiBlock<'static> {
+		TuiBlock::default()
+			.borders(Borders::ALL)
+			.border_set(self.border.symbols())
+	}
+
+	/**
+	 * Get the indicator of the scroll position as line number and percentage.
+	 *
+	 * @param  line
+	 * @param  line_count
+	 * @return String
+	 */
+	fn scroll_indicator(&self, line: usize, line_count: usize) -> String {
+		format!(
+			"{}{line}/{line_count}{} {}{}%{}",
+			self.style.unicode.get(Symbol::LeftBracket),
+			self.style.unicode.get(Symbol::RightBracket),
+			self.style.unicode.get(Symbol::LeftBracket),
+			if line_count != 0 {
+				(line as f64 / line_count as f64 * 100.0) as u64
+			} else {
+				0
+			},
+			self.style.unicode.get(Symbol::RightBracket),
+		)
+	}
+
+	/**
+	 * Get the scroll offset of the module table for the selected index.
+	 *
+	 * @param  area
+	 * @param  index
+	 * @return usize
+	 */
+	fn modules_scroll_offset(area: Rect, index: usize) -> usize {
+		area.height
+			.checked_sub(5)
+			.and_then(|height| index.checked_sub(height as usize))
+			.unwrap_or(0)
+	}
+
+	/**
+	 * Get the block and its area at the given terminal position.
+	 *
+	 * @param  x
+	 * @param  y
+	 * @return Option
+	 */
+	pub fn block_at(&self, x: u16, y: u16) -> Option<(Block, Rect)> {
+		self.block_areas.iter().copied().find(|(_, area)| {
+			(area.left()..area.right()).contains(&x)
+				&& (area.top()..area.bottom()).contains(&y)
+		})
+	}
+
+	/**
+	 * Get the index of the module at the given row of the module table.
+	 *
+	 * @param  area
+	 * @param  y
+	 * @param  kernel_modules
+	 * @return Option
+	 */
+	pub fn module_at(
+		area: Rect,
+		y: u16,
+		kernel_modules: &KernelModules<'_>,
+	) -> Option<usize> {
+		/* Skip the top border and the table header. */
+		let row = y.checked_sub(area.top() + 2)?;
+		if row >= area.height.saturating_sub(3) {
+			return None;
+		}
+		let index =
+			Self::modules_scroll_offset(area, kernel_modules.index) + row as usize;
+		(index < kernel_modules.list.len()).then_some(index)
+	}
+
 	/**
 	 * Draws the options menu as a popup.
 	 *
@@ -590,14 +2744,13 @@ impl App {
 		frame.render_stateful_widget(
 			List::new(items)
 				.block(
-					TuiBlock::default()
+					self.bordered_block()
 						.title(Span::styled(block_title, self.style.bold))
 						.title_alignment(Alignment::Center)
-						.style(self.style.default)
-						.borders(Borders::ALL),
+						.style(self.style.default),
 				)
 				.style(self.style.colored)
-				.highlight_style(self.style.default),
+				.highlight_style(self.style.selected),
 			popup_rect,
 			&mut self.options.state,
 		);
@@ -616,19 +2769,53 @@ impl App {
 		area: Rect,
 		kernel_modules: &mut KernelModules<'_>,
 	) {
+		let height = area.height.saturating_sub(2).max(1) as usize;
+		let line_count = kernel_modules
+			.current_info
+			.wrapped_lines(area.width.saturating_sub(2));
+		/* Keep the selected entry visible. */
+		if let Some(line) = kernel_modules.selected_info_line() {
+			kernel_modules.info_scroll_offset = kernel_modules
+				.info_scroll_offset
+				.clamp((line + 1).saturating_sub(height), line);
+		}
+		/* Do not scroll past the end of the information. */
+		let max_offset = line_count.saturating_sub(height);
+		kernel_modules.info_scroll_offset =
+			kernel_modules.info_scroll_offset.min(max_offset);
 		frame.render_widget(
 			Paragraph::new(kernel_modules.current_info.get())
 				.block(
-					TuiBlock::default()
+					self.bordered_block()
 						.border_style(self.block_style(Block::ModuleInfo))
-						.borders(Borders::ALL)
 						.title(Span::styled(
 							format!(
-								"{}{}",
+								"{}{}{}{}",
 								kernel_modules.get_current_command().title,
 								self.style.unicode.get(
 									kernel_modules.get_current_command().symbol
-								)
+								),
+								/* Show the countdown of the confirmation timeout. */
+								kernel_modules
+									.confirmation_remaining()
+									.map(|v| format!(
+										" (cancel in {}s)",
+										v.as_secs()
+											+ u64::from(v.subsec_nanos() > 0)
+									))
+									.unwrap_or_default(),
+								/* Show the position if the information is scrollable. */
+								if line_count > height {
+									format!(
+										" {}",
+										self.scroll_indicator(
+											kernel_modules.info_scroll_offset + 1,
+											line_count
+										)
+									)
+								} else {
+									String::new()
+								}
 							),
 							self.style.bold,
 						)),
@@ -649,6 +2836,22 @@ impl App {
 				.scroll((kernel_modules.info_scroll_offset as u16, 0)),
 			area,
 		);
+		/* Show the scrollbar if the information does not fit into the block. */
+		if line_count > height {
+			frame.render_stateful_widget(
+				Scrollbar::new(ScrollbarOrientation::VerticalRight)
+					.symbols(self.style.unicode.scrollbar())
+					.begin_symbol(None)
+					.end_symbol(None)
+					.thumb_style(self.block_style(Block::ModuleInfo)),
+				area.inner(&Margin {
+					vertical: 1,
+					horizontal: 0,
+				}),
+				&mut ScrollbarState::new(max_offset + 1)
+					.position(kernel_modules.info_scroll_offset),
+			);
+		}
 	}
 
 	/**
@@ -664,25 +2867,89 @@ impl App {
 		area: Rect,
 		kernel_logs: &mut KernelLogs,
 	) {
+		let selected_output = kernel_logs.select(area.height, 2).to_string();
+		/* Truncate the lines that do not fit into the block. */
+		let visible_output = selected_output
+			.lines()
+			.map(|line| {
+				util::truncate_width(line, area.width.saturating_sub(2).into())
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+		let mut text = StyledText::default().stylize_data(
+			&visible_output,
+			"] ",
+			Style {
+				default: self.style.log,
+				..self.style.clone()
+			},
+		);
+		for (i, ((line, raw_line), level)) in text
+			.lines
+			.iter_mut()
+			.zip(selected_output.lines())
+			.zip(&kernel_logs.selected_levels)
+			.enumerate()
+		{
+			/* Color the lines depending on their log level. */
+			if let Some(style) = self.log_style(*level) {
+				for span in line.spans.iter_mut() {
+					span.style = span.style.patch(style);
+				}
+			}
+			/* Highlight the lines that match the search query. */
+			if kernel_logs.is_match(raw_line) {
+				line.style = line.style.add_modifier(Modifier::REVERSED);
+			}
+			/* Underline the lines that are new since the last refresh. */
+			if kernel_logs.selected_new.contains(&i) {
+				line.style = line.style.add_modifier(Modifier::UNDERLINED);
+			}
+		}
 		frame.render_widget(
-			Paragraph::new(StyledText::default().stylize_data(
-				kernel_logs.select(area.height, 2),
-				"] ",
-				self.style.clone(),
-			))
-			.block(
-				TuiBlock::default()
-					.border_style(self.block_style(Block::Activities))
-					.borders(Borders::ALL)
-					.title(Span::styled(
-						format!(
-							"Kernel Activities{}",
-							self.style.unicode.get(Symbol::HighVoltage)
-						),
-						self.style.bold,
-					)),
-			)
-			.alignment(Alignment::Left),
+			Paragraph::new(text)
+				.block(
+					self.bordered_block()
+						.border_style(self.block_style(Block::Activities))
+						.title(Span::styled(
+							format!(
+								"{}{} {}{}{} {}{}{}{}{}",
+								self.activities_title,
+								self.style.unicode.get(Symbol::HighVoltage),
+								self.style.unicode.get(Symbol::LeftBracket),
+								kernel_logs.level,
+								self.style.unicode.get(Symbol::RightBracket),
+								{
+									let (line, line_count) =
+										kernel_logs.scroll_position();
+									self.scroll_indicator(line, line_count)
+								},
+								if kernel_logs.reverse {
+									" (newest first)"
+								} else {
+									""
+								},
+								if kernel_logs.follow { " (follow)" } else { "" },
+								match kernel_logs.new_line_count() {
+									0 => String::new(),
+									count => format!(" (+{count} new)"),
+								},
+								if kernel_logs.query.is_empty() {
+									String::new()
+								} else {
+									format!(
+										" {}/{} ({} matches){}",
+										self.style.unicode.get(Symbol::LeftBracket),
+										kernel_logs.query,
+										kernel_logs.match_count(),
+										self.style.unicode.get(Symbol::RightBracket),
+									)
+								},
+							),
+							self.style.bold,
+						)),
+				)
+				.alignment(Alignment::Left),
 			area,
 		);
 	}
@@ -691,22 +2958,184 @@ impl App {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::config::Config;
 	use crate::event::Events;
+	use crate::kernel::cmd::CommandArgs;
 	use crate::kernel::info;
 	use crate::kernel::lkm::ListArgs;
-	use clap::ArgMatches;
 	use ratatui::backend::TestBackend;
 	use ratatui::Terminal;
 	#[test]
 	fn test_app() {
-		let args = ArgMatches::default();
-		let mut kernel_modules =
-			KernelModules::new(ListArgs::new(&args), Style::new(&args));
-		let mut app = App::new(Block::ModuleTable, kernel_modules.style.clone());
+		let config = Config::default();
+		let mut kernel_modules = KernelModules::new(
+			ListArgs::new(&config),
+			CommandArgs::new(&config),
+			Style::new(&config),
+		);
+		let mut app =
+			App::new(Block::ModuleTable, kernel_modules.style.clone(), &config);
+		assert!(!app.toggle_column(Column::Size));
+		assert_eq!(vec![Column::Name, Column::UsedBy], app.columns);
+		assert!(app.toggle_column(Column::Size));
+		assert_eq!(Column::ALL.to_vec(), app.columns);
+		assert_eq!(None, app.type_ahead(Key::Char('e')));
+		app.type_ahead = true;
+		assert_eq!(None, app.type_ahead(Key::Char('1')));
+		assert_eq!(Some("e"), app.type_ahead(Key::Char('e')));
+		assert_eq!(Some("e1"), app.type_ahead(Key::Char('1')));
+		assert_eq!(Some("e"), app.type_ahead(Key::Backspace));
+		assert_eq!(None, app.type_ahead(Key::Ctrl('e')));
+		app.type_ahead_time -= TYPE_AHEAD_TIMEOUT * 2;
+		assert_eq!(Some("x"), app.type_ahead(Key::Char('x')));
+		assert_eq!(1, app.scroll_step(Action::ScrollDown));
+		app.scroll.acceleration = true;
+		let steps = (0..20)
+			.map(|_| app.scroll_step(Action::ScrollDown))
+			.collect::<Vec<usize>>();
+		assert_eq!((1, 8), (steps[0], steps[19]));
+		assert_eq!(1, app.scroll_step(Action::ScrollUp));
 		app.set_clipboard_contents("test");
 		assert_ne!("x", app.get_clipboard_contents());
-		assert_eq!(app.style.default, app.block_style(Block::ModuleTable));
-		assert_eq!(app.style.colored, app.block_style(Block::Activities));
+		assert!(app.status().is_some());
+		app.clear_status();
+		assert!(app.status().is_none());
+		app.show_result(Ok(String::from("loaded test")));
+		app.show_result(Err(String::from("failed")));
+		assert_eq!(Some("error: failed"), app.status());
+		assert_eq!(
+			vec![Severity::Success, Severity::Error],
+			app.notifications
+				.iter()
+				.map(|v| v.severity)
+				.collect::<Vec<Severity>>()
+		);
+		for _ in 0..app.notification_ticks {
+			assert_eq!(2, app.notifications.len());
+			app.on_tick();
+		}
+		assert!(app.notifications.is_empty());
+		let kernel = Kernel::new(&config);
+		assert!(app.status().is_some());
+		assert!(!app.is_animating(&kernel));
+		app.status = Some((String::from("test"), Instant::now() - STATUS_DURATION));
+		assert!(app.is_animating(&kernel));
+		app.on_tick();
+		assert!(app.status.is_none());
+		assert!(!app.is_animating(&kernel));
+		assert_eq!(
+			format!(
+				"{} loaded, {} in use, {} unused, {} blacklisted | {} | {} shown | sort: none",
+				kernel_modules.loaded_count,
+				kernel_modules.used_count,
+				kernel_modules.loaded_count - kernel_modules.used_count,
+				kernel_modules.blacklisted_count,
+				util::format_bytes(kernel_modules.loaded_size),
+				kernel_modules.list.len()
+			),
+			App::module_summary(&kernel_modules, usize::MAX)
+		);
+		assert!(App::module_summary(&kernel_modules, 30).width() <= 30);
+		assert_eq!(
+			format!(
+				"{}/{}/{}/{} | {}",
+				kernel_modules.loaded_count,
+				kernel_modules.used_count,
+				kernel_modules.loaded_count - kernel_modules.used_count,
+				kernel_modules.blacklisted_count,
+				util::format_bytes(kernel_modules.loaded_size),
+			),
+			App::module_summary(&kernel_modules, 30)
+		);
+		assert_eq!("", App::module_summary(&kernel_modules, 0));
+		assert_eq!(
+			format!(
+				"Linux 6.1.0 | {} modules loaded{} ({})",
+				kernel_modules.loaded_count,
+				kernel_modules
+					.available_count()
+					.map(|v| format!(" / {v} available"))
+					.unwrap_or_default(),
+				util::format_bytes(
+					kernel_modules.modules.iter().map(|v| v.size).sum()
+				)
+			),
+			App::header_text("6.1.0", &kernel_modules)
+		);
+		app.block_size.info = 100;
+		app.refresh();
+		assert_eq!(BlockSize::default(), app.block_size);
+		assert_eq!(3, app.block_count());
+		app.selected_block = Block::ModuleInfo;
+		app.select_next_block(true);
+		assert_eq!(Block::Activities, app.selected_block);
+		app.no_log = true;
+		assert!(app.is_hidden(Block::Activities));
+		assert!(!app.is_hidden(Block::ModuleInfo));
+		assert_eq!(2, app.block_count());
+		app.selected_block = Block::ModuleInfo;
+		app.select_next_block(true);
+		assert_eq!(Block::UserInput, app.selected_block);
+		app.select_next_block(false);
+		assert_eq!(Block::ModuleInfo, app.selected_block);
+		app.no_log = false;
+		app.selected_block = Block::ModuleTable;
+		assert!(app.scroll_indicator(5, 10).contains("5/10"));
+		assert!(app.scroll_indicator(5, 10).ends_with("50%)"));
+		assert!(app.scroll_indicator(0, 0).contains("0%"));
+		assert_eq!(Some(app.style.error), app.log_style(Some(LogLevel::Crit)));
+		assert_eq!(Some(app.style.warning), app.log_style(Some(LogLevel::Warn)));
+		assert_eq!(None, app.log_style(Some(LogLevel::Info)));
+		assert_eq!(None, app.log_style(None));
+		assert_eq!(
+			app.style.default.add_modifier(Modifier::BOLD),
+			app.block_style(Block::ModuleTable)
+		);
+		assert_eq!(
+			app.style.colored.add_modifier(Modifier::DIM),
+			app.block_style(Block::Activities)
+		);
+		app.block_areas
+			.push((Block::ModuleInfo, Rect::new(10, 0, 10, 10)));
+		assert_eq!(Some(Block::ModuleInfo), app.block_at(12, 5).map(|(b, _)| b));
+		assert_eq!(None, app.block_at(5, 5));
+		let read_only_app = App::new(
+			Block::ModuleTable,
+			kernel_modules.style.clone(),
+			&Config {
+				read_only: true,
+				..Config::default()
+			},
+		);
+		assert_eq!(
+			vec!["dependent", "copy"],
+			read_only_app
+				.options
+				.items
+				.iter()
+				.map(|(v, _)| v.as_str())
+				.collect::<Vec<&str>>()
+		);
+		app.show_dependency_tree(&mut kernel_modules);
+		assert!(kernel_modules.current_info.raw_text.contains("Used by:"));
+		app.show_module_parameters(&mut kernel_modules);
+		assert!(kernel_modules.current_name.starts_with("!Parameters of"));
+		app.show_module_aliases(&mut kernel_modules);
+		assert!(kernel_modules.current_name.starts_with("!Aliases of"));
+		assert!(kernel_modules
+			.current_info
+			.raw_text
+			.starts_with("Aliases ("));
+		app.input_query = String::from("^(nv");
+		assert!(app.search_regex(&app.input_query).is_none());
+		app.search_mode = SearchMode::Regex;
+		assert!(matches!(app.search_regex(&app.input_query), Some(Err(_))));
+		app.input_query = String::from("^NV|ext4");
+		assert!(app
+			.search_regex(&app.input_query)
+			.unwrap()
+			.unwrap()
+			.is_match("nvidia"));
 		let mut kernel_logs = KernelLogs::default();
 		let backend = TestBackend::new(20, 10);
 		let mut terminal = Terminal::new(backend).unwrap();
@@ -714,14 +3143,175 @@ mod tests {
 			.draw(|f| {
 				let size = f.size();
 				app.selected_block = Block::UserInput;
-				app.draw_user_input(f, size, &Events::new(100, &kernel_logs).tx);
+				app.draw_user_input(
+					f,
+					size,
+					&Events::new(100, None, &kernel_logs).tx,
+				);
 				app.draw_kernel_info(f, size, &info::KernelInfo::new().current_info);
 				app.input_query = String::from("a");
 				app.draw_kernel_modules(f, size, &mut kernel_modules);
 				app.draw_module_info(f, size, &mut kernel_modules);
 				app.draw_kernel_activities(f, size, &mut kernel_logs);
+				app.show_help_message();
+				app.scroll_help(ScrollDirection::Bottom, false);
+				app.draw_help_popup(f, size);
+				app.notify(String::from("test"), Severity::Success);
+				app.draw_notifications(f, size);
+				app.draw_quit_dialog(f, size);
+				app.show_palette();
+				app.draw_palette(f, size);
+				app.palette = None;
+				app.banner = Some(i18n::text("status.unprivileged").to_string());
+				app.draw_banner(f, size);
+				app.block_areas
+					.push((Block::UserInput, Rect::new(0, 0, 10, 3)));
+				app.completions = (0..10).map(|i| format!("m{i}")).collect();
+				app.draw_completions(f);
 			})
 			.unwrap();
+		assert_eq!(8, app.help_height);
+		assert_eq!(app.help_text().len() as u16 - 8, app.help_scroll);
+		app.scroll_help(ScrollDirection::Up, true);
+		assert_eq!(app.help_text().len() as u16 - 15, app.help_scroll);
+		assert!(App::is_compact(Rect::new(0, 0, 80, 10)));
+		assert_eq!(None, app.updated_text());
+		app.updated_time = true;
+		assert_eq!(Some(String::from("updated ~0s ago")), app.updated_text());
+		app.updated_time = false;
+		assert_eq!("2 a,b", App::compact_dependents("2 a,b", 5));
+		assert_eq!(
+			"3 (3 modules)",
+			App::compact_dependents("3 abcdef,ghijkl,mnopqr", 15)
+		);
+		assert_eq!("3 (3...", App::compact_dependents("3 abc,def,ghi", 7));
+		assert_eq!("3 (3 mo...", App::compact_dependents("3 abc,def,ghi", 10));
+		assert_eq!(".", App::compact_dependents("0 -", 1));
+		kernel_logs.set_output(String::from("日本語のカーネルログ\nabc"));
+		let mut terminal = Terminal::new(TestBackend::new(12, 5)).unwrap();
+		terminal
+			.draw(|f| app.draw_kernel_activities(f, f.size(), &mut kernel_logs))
+			.unwrap();
+		let row = (1..11)
+			.map(|x| terminal.backend().buffer().get(x, 1).symbol())
+			.collect::<String>();
+		assert!(row.contains("語") && row.contains("...") && !row.contains("の"));
+		assert_eq!("│", terminal.backend().buffer().get(11, 1).symbol());
+		app.show_palette();
+		assert_eq!(
+			app.key_bindings.entries().len() - 1,
+			app.palette.as_ref().unwrap().entries.items.len()
+		);
+		for c in "unload".chars() {
+			assert_eq!(None, app.handle_palette_key(Key::Char(c)));
+		}
+		assert_eq!(
+			Some(&(Action::Unload, String::from("-, u, backspace, ctrl-h"))),
+			app.palette.as_ref().unwrap().entries.selected()
+		);
+		app.handle_palette_key(Key::Down);
+		app.handle_palette_key(Key::Up);
+		assert_eq!(
+			Some(Action::Unload),
+			app.handle_palette_key(Key::Char('\n'))
+		);
+		assert!(app.palette.is_none());
+		app.show_palette();
+		for c in "~~".chars() {
+			app.handle_palette_key(Key::Char(c));
+		}
+		app.handle_palette_key(Key::Down);
+		assert_eq!(None, app.handle_palette_key(Key::Char('\n')));
+		app.show_palette();
+		app.handle_palette_key(Key::Esc);
+		assert!(app.palette.is_none());
+		app.max_list_height = Some(20);
+		assert_eq!(Some(20), app.layout_args().max_list_height);
+		app.max_list_height = None;
+		assert_eq!(LayoutArgs::new(&Config::default()), app.layout_args());
+		assert!(!App::is_compact(Rect::new(0, 0, 80, 24)));
+		assert!(!App::is_too_small(Rect::new(0, 0, 20, 10)));
+		assert!(App::is_too_small(Rect::new(0, 0, 80, 5)));
+		assert_eq!(Ok(BorderStyle::Thick), BorderStyle::try_from("thick"));
+		assert_eq!(border::ROUNDED, BorderStyle::Rounded.symbols());
+		assert_eq!(" ", BorderStyle::None.symbols().vertical_left);
+		kernel_modules.default_list = ["e1000e", "ext4", "e100"]
+			.iter()
+			.map(|name| vec![format!(" {name}"), String::new(), String::new()])
+			.collect();
+		app.search_mode = SearchMode::Fuzzy;
+		app.input_mode = InputMode::Search;
+		app.input_query = String::from("e100");
+		assert_eq!("a", app.debounced_query());
+		app.search_debounce = Duration::ZERO;
+		terminal
+			.draw(|f| app.draw_kernel_modules(f, f.size(), &mut kernel_modules))
+			.unwrap();
+		assert_eq!(
+			vec![" e100", " e1000e"],
+			kernel_modules
+				.list
+				.iter()
+				.map(|module| module[0].as_str())
+				.collect::<Vec<&str>>()
+		);
+		assert_eq!("fuzzy", SearchMode::Fuzzy.to_string());
+		kernel_modules.search_index.insert(
+			String::from("ext4"),
+			lkm::SearchEntry {
+				description: String::from("Fourth Extended Filesystem"),
+				aliases: vec![String::from("fs-ext4")],
+			},
+		);
+		app.search_scope = SearchScope::All;
+		app.search_mode = SearchMode::Substring;
+		app.input_query = String::from("filesystem");
+		terminal
+			.draw(|f| app.draw_kernel_modules(f, f.size(), &mut kernel_modules))
+			.unwrap();
+		assert_eq!(1, kernel_modules.list.len());
+		assert_eq!(Some(&"description"), app.search_matches.get("ext4"));
+		app.search_mode = SearchMode::Fuzzy;
+		app.input_query = String::from("fs-ext");
+		terminal
+			.draw(|f| app.draw_kernel_modules(f, f.size(), &mut kernel_modules))
+			.unwrap();
+		assert_eq!(Some(&"alias"), app.search_matches.get("ext4"));
+		assert_eq!(
+			vec![true, false, true, false, false],
+			app.matched_chars("e1000", "e0", None)
+		);
+		let line = app.highlight_name(
+			String::from(" e1000 (boot)"),
+			&app.matched_chars("e1000", "e0", None),
+		);
+		assert_eq!(
+			vec![" ", "e", "1", "0", "00 (boot)"],
+			line.spans
+				.iter()
+				.map(|span| span.content.as_ref())
+				.collect::<Vec<&str>>()
+		);
+		assert!(line.spans[1]
+			.style
+			.add_modifier
+			.contains(Modifier::UNDERLINED));
+		app.search_mode = SearchMode::Substring;
+		assert_eq!(
+			vec![false, true, true, false, false],
+			app.matched_chars("e1000", "10", None)
+		);
+		assert_eq!(
+			vec![false, false, true, true, true],
+			app.matched_chars("e1000", "0", Regex::new("0+$").ok().as_ref())
+		);
+		app.search_mode = SearchMode::Fuzzy;
+		app.search_scope = SearchScope::Name;
+		app.search_mode = SearchMode::Substring;
+		terminal
+			.draw(|f| app.draw_kernel_modules(f, f.size(), &mut kernel_modules))
+			.unwrap();
+		assert!(kernel_modules.list.is_empty());
 	}
 	#[test]
 	fn test_input_mode() {
@@ -730,5 +3320,36 @@ mod tests {
 		assert!(input_mode.to_string().contains("Load"));
 		input_mode = InputMode::None;
 		assert!(input_mode.to_string().contains("Search"));
+		let mut history = InputHistory::new(&[
+			String::from("snd"),
+			String::from("snd"),
+			String::new(),
+			String::from("usb"),
+		]);
+		assert_eq!(vec!["snd", "usb"], history.entries());
+		assert_eq!(None, history.newer());
+		assert_eq!(Some(String::from("usb")), history.older("e1"));
+		assert_eq!(Some(String::from("snd")), history.older("e1"));
+		assert_eq!(Some(String::from("snd")), history.older("e1"));
+		assert_eq!(Some(String::from("usb")), history.newer());
+		assert_eq!(Some(String::from("e1")), history.newer());
+		assert_eq!(None, history.newer());
+		for i in 0..HISTORY_SIZE {
+			history.push(&i.to_string());
+		}
+		assert_eq!(HISTORY_SIZE, history.entries().len());
+		assert_eq!(Some(String::from("0")), history.entries().first().cloned());
+		let config = Config::default();
+		let mut app = App::new(Block::ModuleTable, Style::new(&config), &config);
+		assert!(app.input_history().is_none());
+		app.input_mode = InputMode::Search;
+		app.search_history.push("i915");
+		app.input_query = String::from("amd");
+		assert!(app.recall_query(true));
+		assert_eq!("i915", app.input_query);
+		assert!(app.recall_query(false));
+		assert_eq!("amd", app.input_query);
+		app.input_mode = InputMode::LogSearch;
+		assert!(!app.recall_query(true));
 	}
 }
@@ -1,4 +1,5 @@
-use clap::{Arg, ArgAction, Command as App};
+use clap::{value_parser, Arg, ArgAction, ArgGroup, Command as App};
+use std::path::PathBuf;
 
 /* ASCII format of the project logo */
 const ASCII_LOGO: &str = "
@@ -28,12 +29,26 @@ pub fn get_args() -> App {
 			"Press '?' while running the terminal UI to see key bindings."
 		))
 		.before_help(ASCII_LOGO)
+		.disable_version_flag(true)
+		.arg(
+			Arg::new("version")
+				.short('V')
+				.long("version")
+				.help("Print version information")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("verbose")
+				.long("verbose")
+				.help("Print the build information along with the version")
+				.requires("version")
+				.action(ArgAction::SetTrue),
+		)
 		.arg(
 			Arg::new("accent-color")
 				.short('a')
 				.long("accent-color")
 				.value_name("COLOR")
-				.default_value("white")
 				.help("Set the accent color using hex or color name")
 				.num_args(1),
 		)
@@ -42,10 +57,108 @@ pub fn get_args() -> App {
 				.short('c')
 				.long("color")
 				.value_name("COLOR")
-				.default_value("darkgray")
 				.help("Set the main color using hex or color name")
 				.num_args(1),
 		)
+		.arg(
+			Arg::new("config")
+				.long("config")
+				.value_name("PATH")
+				.value_parser(value_parser!(PathBuf))
+				.help("Set the path of the configuration file")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("profile")
+				.long("profile")
+				.value_name("NAME")
+				.help("Apply the named profile of the configuration file")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("log-file")
+				.long("log-file")
+				.value_name("PATH")
+				.value_parser(value_parser!(PathBuf))
+				.help("Set the path of the file for saving the kernel activities")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("log")
+				.long("log")
+				.value_name("PATH")
+				.value_parser(value_parser!(PathBuf))
+				.help("Append the executed commands to the given file")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("log-verbose")
+				.long("log-verbose")
+				.help("Log the commands that read information as well")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("layout")
+				.long("layout")
+				.value_name("ORIENTATION")
+				.value_parser(["horizontal", "vertical"])
+				.help("Set the orientation of the module list and information blocks")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("border")
+				.long("border")
+				.value_name("STYLE")
+				.value_parser(["plain", "rounded", "double", "thick", "none"])
+				.help("Set the style of the block borders")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("kernel-log")
+				.long("kernel-log")
+				.value_name("SOURCE")
+				.value_parser(["dmesg", "kmsg", "journal"])
+				.help("Set the source of the kernel activities")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("log-time")
+				.long("log-time")
+				.value_name("FORMAT")
+				.value_parser(["raw", "iso", "relative"])
+				.help("Set the format of the kernel activity timestamps")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("log-lines")
+				.long("log-lines")
+				.value_name("N")
+				.value_parser(value_parser!(usize))
+				.help("Set the number of recent kernel activities to keep")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("no-log")
+				.long("no-log")
+				.help("Hide the kernel activities and do not read the kernel logs")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("theme")
+				.long("theme")
+				.value_name("THEME")
+				.help(
+					"Set the color theme (default, dark, light, solarized, matrix, high-contrast)",
+				)
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("lang")
+				.long("lang")
+				.value_name("LANG")
+				.help("Set the language of the user interface (en, tr)")
+				.num_args(1),
+		)
 		.arg(
 			Arg::new("rate")
 				.short('t')
@@ -55,6 +168,155 @@ pub fn get_args() -> App {
 				.help("Set the refresh rate of the terminal")
 				.num_args(1),
 		)
+		.arg(
+			Arg::new("refresh-interval")
+				.long("refresh-interval")
+				.value_name("MS")
+				.value_parser(value_parser!(u64))
+				.help("Set the interval of reading the kernel activities (0 for manual)")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("loader")
+				.long("loader")
+				.value_name("TOOL")
+				.value_parser([
+					"modprobe",
+					"insmod",
+					#[cfg(target_os = "freebsd")]
+					"kld",
+				])
+				.help("Set the tool for loading and unloading modules")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("privilege-cmd")
+				.long("privilege-cmd")
+				.value_name("TOOL")
+				.value_parser(["sudo", "doas", "pkexec", "none"])
+				.help("Set the tool for running the module commands as root")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("mouse")
+				.long("mouse")
+				.help("Enable mouse support for selecting and scrolling blocks")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("size-bar")
+				.long("size-bar")
+				.help("Show the module sizes as bars relative to the largest module")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("updated-time")
+				.long("updated-time")
+				.help("Show the time since the data was last updated on the header")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("no-highlight")
+				.long("no-highlight")
+				.help("Do not highlight the modules that are changed since the last refresh")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("type-ahead")
+				.long("type-ahead")
+				.help("Select the modules by typing their names on the module list")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("watch")
+				.long("watch")
+				.help("Refresh the modules when they are changed by other processes")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("builtin")
+				.long("builtin")
+				.help("Show the built-in modules in the module list")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("dry-run")
+				.long("dry-run")
+				.help("Show the module commands instead of executing them")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("demo")
+				.long("demo")
+				.help("Show fabricated modules and logs for the demos")
+				.hide(true)
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("read-only")
+				.long("read-only")
+				.help("Disable the commands that modify the kernel modules")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("remote")
+				.long("remote")
+				.value_name("TARGET")
+				.help("Inspect the kernel modules of a remote host over SSH")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("remote-write")
+				.long("remote-write")
+				.help("Allow the module commands on the remote host")
+				.requires("remote")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("confirm-quit")
+				.long("confirm-quit")
+				.help("Ask for confirmation before quitting")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("stop-on-error")
+				.long("stop-on-error")
+				.help("Stop the batch commands after the first failure")
+				.action(ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("load-file")
+				.long("load-file")
+				.value_name("PATH")
+				.value_parser(value_parser!(PathBuf))
+				.help("Load the modules that are listed in the given file and exit")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("output")
+				.short('o')
+				.long("output")
+				.value_name("FORMAT")
+				.value_parser(["json", "csv"])
+				.help("Print the kernel module list in the given format and exit")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("snapshot")
+				.long("snapshot")
+				.value_name("PATH")
+				.value_parser(value_parser!(PathBuf))
+				.help("Save a snapshot of the loaded modules to the given file and exit")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("size-format")
+				.long("size-format")
+				.value_name("FORMAT")
+				.value_parser(["raw", "human"])
+				.help("Set the display format of the module sizes")
+				.num_args(1),
+		)
 		.arg(
 			Arg::new("reverse")
 				.short('r')
@@ -92,8 +354,117 @@ pub fn get_args() -> App {
 						.long("dependent")
 						.help("Sort modules by their dependent modules")
 						.action(ArgAction::SetTrue),
+				)
+				.arg(
+					Arg::new("refcount")
+						.short('c')
+						.long("refcount")
+						.help("Sort modules by their reference counts")
+						.action(ArgAction::SetTrue),
+				)
+				.group(
+					ArgGroup::new("sort-type")
+						.args(["size", "name", "dependent", "refcount"])
+						.multiple(false),
+				),
+		)
+		.subcommand(
+			App::new("diff")
+				.about("Compare two module snapshots and exit")
+				.arg(
+					Arg::new("old")
+						.value_name("OLD")
+						.value_parser(value_parser!(PathBuf))
+						.help("Path of the old snapshot")
+						.required(true),
+				)
+				.arg(
+					Arg::new("new")
+						.value_name("NEW")
+						.value_parser(value_parser!(PathBuf))
+						.help("Path of the new snapshot")
+						.required(true),
 				),
 		)
+		.subcommand(
+			App::new("report")
+				.about("Print the Markdown report of a module and exit")
+				.arg(
+					Arg::new("module")
+						.value_name("MODULE")
+						.help("Name of the module")
+						.required(true),
+				),
+		)
+		.subcommand(
+			App::new("info")
+				.about("Print the information of modules and exit")
+				.arg(
+					Arg::new("module")
+						.value_name("MODULE")
+						.help("Names of the modules ('-' for reading them from stdin)")
+						.num_args(1..)
+						.required(true),
+				),
+		)
+		.subcommand(
+			App::new("graph")
+				.about("Print the module dependency graph and exit")
+				.arg(
+					Arg::new("format")
+						.long("format")
+						.value_name("FORMAT")
+						.value_parser(["dot"])
+						.default_value("dot")
+						.help("Set the output format of the graph"),
+				)
+				.arg(
+					Arg::new("root")
+						.long("root")
+						.value_name("MODULE")
+						.help("Print only the dependencies of the given module")
+						.num_args(1),
+				),
+		)
+		.subcommand(
+			App::new("config")
+				.about("Print the configuration as TOML and exit")
+				.arg(
+					Arg::new("dump")
+						.long("dump")
+						.help("Print the effective configuration")
+						.action(ArgAction::SetTrue),
+				)
+				.arg(
+					Arg::new("defaults")
+						.long("defaults")
+						.help("Print the default configuration")
+						.action(ArgAction::SetTrue),
+				)
+				.group(
+					ArgGroup::new("config-output")
+						.args(["dump", "defaults"])
+						.required(true)
+						.multiple(false),
+				),
+		)
+		.subcommand(
+			App::new("completions")
+				.about("Print the shell completions and exit")
+				.hide(true)
+				.arg(
+					Arg::new("shell")
+						.value_name("SHELL")
+						.value_parser(["bash", "zsh", "fish", "elvish"])
+						.help("Shell to generate the completions for")
+						.required(true),
+				),
+		)
+		.subcommand(
+			App::new("mangen")
+				.about("Print the man page and exit")
+				.hide(true),
+		)
 }
 
 #[cfg(test)]
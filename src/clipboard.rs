@@ -0,0 +1,147 @@
+use crate::util;
+use enum_iterator::Sequence;
+use std::env;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/* External tools for accessing the system clipboard */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Sequence)]
+pub enum ClipboardTool {
+	WlCopy,
+	Xclip,
+	Xsel,
+}
+
+impl ClipboardTool {
+	/**
+	 * Get the environment variable of the required display server.
+	 *
+	 * @return str
+	 */
+	fn display_var(self) -> &'static str {
+		match self {
+			Self::WlCopy => "WAYLAND_DISPLAY",
+			Self::Xclip | Self::Xsel => "DISPLAY",
+		}
+	}
+
+	/**
+	 * Get the command for copying the standard input to the clipboard.
+	 *
+	 * @return (command, arguments)
+	 */
+	pub fn copy_cmd(self) -> (&'static str, &'static [&'static str]) {
+		match self {
+			Self::WlCopy => ("wl-copy", &[]),
+			Self::Xclip => ("xclip", &["-selection", "clipboard", "-in"]),
+			Self::Xsel => ("xsel", &["--clipboard", "--input"]),
+		}
+	}
+
+	/**
+	 * Get the command for printing the clipboard contents.
+	 *
+	 * @return (command, arguments)
+	 */
+	pub fn paste_cmd(self) -> (&'static str, &'static [&'static str]) {
+		match self {
+			Self::WlCopy => ("wl-paste", &["--no-newline"]),
+			Self::Xclip => ("xclip", &["-selection", "clipboard", "-out"]),
+			Self::Xsel => ("xsel", &["--clipboard", "--output"]),
+		}
+	}
+
+	/**
+	 * Check if the tool is installed and its display server is running.
+	 *
+	 * @return bool
+	 */
+	fn is_available(self) -> bool {
+		env::var_os(self.display_var()).is_some()
+			&& util::is_executable_in_path(self.copy_cmd().0)
+	}
+}
+
+/* System clipboard with a fallback to the internal buffer */
+#[derive(Clone, Debug, Default)]
+pub struct Clipboard {
+	tool: Option<ClipboardTool>,
+	buffer: String,
+}
+
+impl Clipboard {
+	/**
+	 * Create a new clipboard using the first available tool.
+	 *
+	 * @return Clipboard
+	 */
+	pub fn new() -> Self {
+		Self {
+			tool: enum_iterator::all::<ClipboardTool>().find(|v| v.is_available()),
+			buffer: String::new(),
+		}
+	}
+
+	/**
+	 * Set the clipboard contents.
+	 *
+	 * The contents are kept in the internal buffer even if the
+	 * system clipboard cannot be accessed.
+	 *
+	 * @param  contents
+	 * @return Result
+	 */
+	pub fn set_contents(&mut self, contents: &str) -> Result<(), String> {
+		self.buffer = contents.to_string();
+		let Some(tool) = self.tool else {
+			return Ok(());
+		};
+		let (cmd, args) = tool.copy_cmd();
+		let mut child = Command::new(cmd)
+			.args(args)
+			.stdin(Stdio::piped())
+			.stdout(Stdio::null())
+			.stderr(Stdio::null())
+			.spawn()
+			.map_err(|e| format!("failed to run '{cmd}': {e}"))?;
+		if let Some(mut stdin) = child.stdin.take() {
+			stdin
+				.write_all(contents.as_bytes())
+				.map_err(|e| format!("failed to write to '{cmd}': {e}"))?;
+		}
+		match child.wait() {
+			Ok(status) if status.success() => Ok(()),
+			Ok(status) => Err(format!("'{cmd}' failed with {status}")),
+			Err(e) => Err(format!("failed to wait for '{cmd}': {e}")),
+		}
+	}
+
+	/**
+	 * Get the clipboard contents.
+	 *
+	 * @return contents
+	 */
+	pub fn get_contents(&self) -> String {
+		self.tool
+			.and_then(|tool| {
+				let (cmd, args) = tool.paste_cmd();
+				util::exec_local_cmd(cmd, args).ok()
+			})
+			.unwrap_or_else(|| self.buffer.clone())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	#[test]
+	fn test_clipboard() {
+		let mut clipboard = Clipboard::default();
+		assert_eq!(Ok(()), clipboard.set_contents("snd_hda_intel"));
+		assert_eq!("snd_hda_intel", clipboard.get_contents());
+		for tool in enum_iterator::all::<ClipboardTool>() {
+			assert_ne!(tool.copy_cmd(), tool.paste_cmd());
+		}
+		assert_eq!("wl-paste", ClipboardTool::WlCopy.paste_cmd().0);
+	}
+}
@@ -0,0 +1,1424 @@
+use crate::app::{
+	Block, BlockSize, BorderStyle, Column, Orientation, SearchMode, SearchScope,
+};
+use crate::i18n::Lang;
+use crate::kernel::blacklist::{self, BLACKLIST_FILE};
+use crate::kernel::boot::BOOT_FILE;
+use crate::kernel::cmd::{Loader, PrivilegeCmd};
+use crate::kernel::lkm::{ListFilter, SizeFormat, SortType};
+use crate::kernel::log::{LogSource, LogTime};
+use crate::keys::KeyBindings;
+use crate::state::State;
+use crate::style::{Style, Theme};
+use crate::util;
+use clap::parser::ValueSource;
+use clap::ArgMatches;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/* Name of the configuration file */
+const CONFIG_FILE: &str = "kmon.toml";
+
+/* Environment variable for the path of the configuration file */
+pub const CONFIG_ENV: &str = "KMON_CONFIG";
+
+/* Table of the named profiles in the configuration file */
+const PROFILES_TABLE: &str = "profiles";
+
+/* Error message of the invalid tick rates */
+const TICKRATE_ERROR: &str = "tickrate must be a positive integer (milliseconds)";
+
+/* Error message of the invalid log line limits */
+const LOG_LINES_ERROR: &str = "log_lines must be a positive integer";
+
+/* Error message of the invalid confirmation timeout */
+const CONFIRM_TIMEOUT_ERROR: &str =
+	"confirmation timeout must be a positive integer";
+
+/* Error message of the invalid scroll amounts */
+const SCROLL_ERROR: &str = "scroll step and page must be positive integers";
+
+/* Error message of the invalid module table columns */
+const COLUMNS_ERROR: &str = "columns must include \"name\" and each column once";
+
+/* Error message of the invalid block sizes */
+const LAYOUT_ERROR: &str = "layout sizes must be between 1 and 99 (percent)";
+
+/* Error message of the invalid module list height */
+const LIST_HEIGHT_ERROR: &str = "maximum list height must be a positive integer";
+
+/* Modules that require typing their name for confirming the unload */
+pub const PROTECTED_MODULES: &[&str] = &[
+	/* Filesystems */
+	"ext2",
+	"ext3",
+	"ext4",
+	"jbd2",
+	"mbcache",
+	"xfs",
+	"btrfs",
+	"f2fs",
+	"zfs",
+	"vfat",
+	"fat",
+	/* Storage controllers */
+	"ahci",
+	"libahci",
+	"libata",
+	"nvme",
+	"nvme_core",
+	"sd_mod",
+	"scsi_mod",
+	"virtio_blk",
+	"virtio_scsi",
+	"dm_*",
+	"md_mod",
+	"raid*",
+	/* Automounter of systemd */
+	"autofs4",
+];
+
+/* Application configuration */
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+	pub theme: Option<String>,
+	pub lang: Option<String>,
+	pub accent_color: Option<String>,
+	pub color: Option<String>,
+	pub tickrate: u64,
+	pub refresh_interval: Option<u64>,
+	pub reverse: bool,
+	pub unicode: bool,
+	pub mouse: bool,
+	pub type_ahead: bool,
+	pub size_bar: bool,
+	pub updated_time: bool,
+	pub out_of_tree_marker: bool,
+	pub change_highlight: bool,
+	pub detect_background: bool,
+	pub docs_url: Option<String>,
+	pub columns: Vec<Column>,
+	pub watch: bool,
+	pub builtin: bool,
+	pub dry_run: bool,
+	pub read_only: bool,
+	pub remote: Option<String>,
+	pub remote_write: bool,
+	pub stop_on_error: bool,
+	pub confirm_quit: bool,
+	pub enter_action: EnterAction,
+	pub refresh_after_op: Vec<RefreshTarget>,
+	pub select_after_load: bool,
+	pub log_file: Option<PathBuf>,
+	pub kernel_log: LogSource,
+	pub log_lines: Option<usize>,
+	pub log_time: Option<LogTime>,
+	pub no_log: bool,
+	pub loader: Loader,
+	pub privilege_cmd: Option<PrivilegeCmd>,
+	pub sort: SortType,
+	pub size_format: SizeFormat,
+	pub search: SearchConfig,
+	pub blacklist: BlacklistConfig,
+	pub boot: BootConfig,
+	pub groups: GroupsConfig,
+	pub hooks: HooksConfig,
+	pub confirm: ConfirmConfig,
+	pub command_log: CommandLogConfig,
+	pub layout: LayoutConfig,
+	pub scroll: ScrollConfig,
+	pub keybindings: KeyBindings,
+	#[serde(skip_serializing)]
+	pub profiles: BTreeMap<String, toml::Table>,
+	#[serde(skip)]
+	pub filter: ListFilter,
+	#[serde(skip)]
+	pub monochrome: bool,
+	#[serde(skip)]
+	pub block: Option<Block>,
+	#[serde(skip)]
+	pub demo: bool,
+	#[serde(skip)]
+	pub detected_theme: Option<String>,
+}
+
+/* Settings of the module search */
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchConfig {
+	pub regex: bool,
+	pub mode: Option<SearchMode>,
+	pub scope: SearchScope,
+	pub debounce: u64,
+	pub save_history: bool,
+	#[serde(skip)]
+	pub history: Vec<String>,
+	#[serde(skip)]
+	pub log_history: Vec<String>,
+}
+
+/* Default initialization values for SearchConfig */
+impl Default for SearchConfig {
+	fn default() -> Self {
+		Self {
+			regex: false,
+			mode: None,
+			scope: SearchScope::default(),
+			debounce: 150,
+			save_history: false,
+			history: Vec::new(),
+			log_history: Vec::new(),
+		}
+	}
+}
+
+impl SearchConfig {
+	/**
+	 * Get the search mode that is used at startup.
+	 *
+	 * 'regex' option is used if the mode is not specified.
+	 *
+	 * @return SearchMode
+	 */
+	pub fn mode(&self) -> SearchMode {
+		match self.mode {
+			Some(mode) => mode,
+			None if self.regex => SearchMode::Regex,
+			None => SearchMode::Substring,
+		}
+	}
+}
+
+/* Data that is read again after executing a module command */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RefreshTarget {
+	Modules,
+	Logs,
+	Info,
+}
+
+/* Actions of the enter key on the selected module */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnterAction {
+	#[default]
+	Info,
+	Search,
+	Reload,
+	Copy,
+}
+
+/* Module groups that can be collapsed on the module list */
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GroupsConfig {
+	pub prefixes: Vec<String>,
+	pub collapsed: bool,
+}
+
+/* Settings of the module blacklist */
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BlacklistConfig {
+	pub path: PathBuf,
+	pub remove_all: bool,
+	pub hard: bool,
+}
+
+/* Default initialization values for BlacklistConfig */
+impl Default for BlacklistConfig {
+	fn default() -> Self {
+		Self {
+			path: PathBuf::from(BLACKLIST_FILE),
+			remove_all: false,
+			hard: true,
+		}
+	}
+}
+
+/* Settings of the modules that are loaded at boot */
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BootConfig {
+	pub path: PathBuf,
+	pub marker: bool,
+}
+
+impl Default for BootConfig {
+	fn default() -> Self {
+		Self {
+			path: PathBuf::from(BOOT_FILE),
+			marker: false,
+		}
+	}
+}
+
+/* Settings of the executed command log */
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommandLogConfig {
+	pub path: Option<PathBuf>,
+	pub verbose: bool,
+}
+
+/* Commands that are executed after the module commands */
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+	pub post_load: Option<String>,
+	pub post_unload: Option<String>,
+	pub depmod: bool,
+}
+
+/* Choices of the command confirmation that are selected with enter */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfirmDefault {
+	#[serde(alias = "y")]
+	Yes,
+	#[default]
+	#[serde(alias = "n")]
+	No,
+}
+
+/* Settings of the module command confirmation */
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfirmConfig {
+	pub prompt: Option<String>,
+	pub timeout: Option<u64>,
+	pub default: ConfirmDefault,
+	pub skip_reload: bool,
+	pub protected: Vec<String>,
+}
+
+impl ConfirmConfig {
+	/**
+	 * Get the confirmation prompt along with the available choices.
+	 *
+	 * The default choice is shown in uppercase.
+	 *
+	 * @param  default_prompt
+	 * @param  recursive
+	 * @return String
+	 */
+	pub fn prompt(&self, default_prompt: &str, recursive: bool) -> String {
+		format!(
+			"{} [{}{}]:",
+			self.prompt.as_deref().unwrap_or(default_prompt),
+			match self.default {
+				ConfirmDefault::Yes => "Y/n",
+				ConfirmDefault::No => "y/N",
+			},
+			if recursive { "/a" } else { "" }
+		)
+	}
+
+	/**
+	 * Get the duration for cancelling the unanswered confirmation.
+	 *
+	 * @return Duration (Option)
+	 */
+	pub fn timeout(&self) -> Option<Duration> {
+		self.timeout.map(Duration::from_secs)
+	}
+
+	/**
+	 * Check if the module matches one of the protected module patterns.
+	 *
+	 * Configured patterns extend the default ones, which include the
+	 * filesystem of the root mount point.
+	 *
+	 * @param  module_name
+	 * @param  root_fs
+	 * @return bool
+	 */
+	pub fn is_protected(&self, module_name: &str, root_fs: Option<&str>) -> bool {
+		let module_name = blacklist::normalize_name(module_name);
+		PROTECTED_MODULES
+			.iter()
+			.copied()
+			.chain(self.protected.iter().map(String::as_str))
+			.chain(root_fs)
+			.any(|pattern| {
+				util::glob_match(&blacklist::normalize_name(pattern), &module_name)
+			})
+	}
+
+	/**
+	 * Check if the timeout is positive.
+	 *
+	 * @return Result
+	 */
+	pub fn validate(&self) -> Result<(), String> {
+		if self.timeout == Some(0) {
+			Err(String::from(CONFIRM_TIMEOUT_ERROR))
+		} else {
+			Ok(())
+		}
+	}
+}
+
+/* Scroll amounts of the module list and kernel activities */
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScrollConfig {
+	pub step: usize,
+	pub page: Option<usize>,
+	pub acceleration: bool,
+}
+
+/* Default initialization values for ScrollConfig */
+impl Default for ScrollConfig {
+	fn default() -> Self {
+		Self {
+			step: 1,
+			page: None,
+			acceleration: false,
+		}
+	}
+}
+
+impl ScrollConfig {
+	/**
+	 * Check if the scroll amounts are positive.
+	 *
+	 * @return Result
+	 */
+	pub fn validate(&self) -> Result<(), String> {
+		if self.step > 0 && self.page != Some(0) {
+			Ok(())
+		} else {
+			Err(String::from(SCROLL_ERROR))
+		}
+	}
+}
+
+/* Settings of the block layout */
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutConfig {
+	pub orientation: Orientation,
+	pub border: BorderStyle,
+	pub input: u16,
+	pub info: u16,
+	pub activities: u16,
+	pub max_list_height: Option<u16>,
+	pub modules_title: Option<String>,
+	pub activities_title: Option<String>,
+}
+
+/* Default initialization values for LayoutConfig */
+impl Default for LayoutConfig {
+	fn default() -> Self {
+		let block_size = BlockSize::default();
+		Self {
+			orientation: Orientation::Horizontal,
+			border: BorderStyle::Plain,
+			input: block_size.input,
+			info: block_size.info,
+			activities: block_size.activities,
+			max_list_height: None,
+			modules_title: None,
+			activities_title: None,
+		}
+	}
+}
+
+impl LayoutConfig {
+	/**
+	 * Check if the block sizes are valid percentages and the maximum
+	 * height of the module list is positive.
+	 *
+	 * @return Result
+	 */
+	pub fn validate(&self) -> Result<(), String> {
+		if ![self.input, self.info, self.activities]
+			.iter()
+			.all(|v| (1..100).contains(v))
+		{
+			Err(String::from(LAYOUT_ERROR))
+		} else if self.max_list_height == Some(0) {
+			Err(String::from(LIST_HEIGHT_ERROR))
+		} else {
+			Ok(())
+		}
+	}
+}
+
+/**
+ * Merge the values of the given table into the base table recursively.
+ *
+ * @param base
+ * @param table
+ */
+fn merge_tables(base: &mut toml::Table, table: toml::Table) {
+	for (key, value) in table {
+		match value {
+			toml::Value::Table(table)
+				if base.get(&key).is_some_and(toml::Value::is_table) =>
+			{
+				if let Some(toml::Value::Table(base)) = base.get_mut(&key) {
+					merge_tables(base, table);
+				}
+			}
+			value => {
+				base.insert(key, value);
+			}
+		}
+	}
+}
+
+/* Default initialization values for Config */
+impl Default for Config {
+	fn default() -> Self {
+		Self {
+			theme: None,
+			lang: None,
+			accent_color: None,
+			color: None,
+			tickrate: 250,
+			refresh_interval: None,
+			reverse: false,
+			unicode: false,
+			mouse: false,
+			type_ahead: false,
+			size_bar: false,
+			updated_time: false,
+			out_of_tree_marker: false,
+			change_highlight: true,
+			detect_background: true,
+			docs_url: None,
+			columns: Column::ALL.to_vec(),
+			watch: false,
+			builtin: false,
+			dry_run: false,
+			read_only: false,
+			remote: None,
+			remote_write: false,
+			stop_on_error: false,
+			confirm_quit: false,
+			enter_action: EnterAction::Info,
+			refresh_after_op: vec![
+				RefreshTarget::Modules,
+				RefreshTarget::Logs,
+				RefreshTarget::Info,
+			],
+			select_after_load: true,
+			log_file: None,
+			kernel_log: LogSource::Dmesg,
+			log_lines: None,
+			log_time: None,
+			no_log: false,
+			loader: Loader::default(),
+			privilege_cmd: None,
+			sort: SortType::None,
+			size_format: SizeFormat::Human,
+			search: SearchConfig::default(),
+			blacklist: BlacklistConfig::default(),
+			boot: BootConfig::default(),
+			groups: GroupsConfig::default(),
+			hooks: HooksConfig::default(),
+			confirm: ConfirmConfig::default(),
+			command_log: CommandLogConfig::default(),
+			layout: LayoutConfig::default(),
+			scroll: ScrollConfig::default(),
+			keybindings: KeyBindings::default(),
+			profiles: BTreeMap::new(),
+			filter: ListFilter::All,
+			monochrome: false,
+			block: None,
+			demo: false,
+			detected_theme: None,
+		}
+	}
+}
+
+impl Config {
+	/**
+	 * Create a new configuration from the config file, session state and given arguments.
+	 *
+	 * Values that are given as command line arguments take precedence
+	 * over the ones in the session state and the configuration file.
+	 *
+	 * @param  args
+	 * @param  state
+	 * @return Result
+	 */
+	pub fn new(args: &ArgMatches, state: &State) -> Result<Self, String> {
+		let path = Self::path(
+			args.try_get_one::<PathBuf>("config")
+				.ok()
+				.flatten()
+				.map(PathBuf::as_path),
+			env::var_os(CONFIG_ENV).map(PathBuf::from).as_deref(),
+		);
+		let mut config = Self::load(
+			path.as_deref(),
+			args.try_get_one::<String>("profile")
+				.ok()
+				.flatten()
+				.map(String::as_str),
+		)?;
+		config.apply_state(state);
+		config.apply_args(args)?;
+		if config.tickrate == 0 {
+			return Err(String::from(TICKRATE_ERROR));
+		}
+		if config.log_lines == Some(0) {
+			return Err(String::from(LOG_LINES_ERROR));
+		}
+		config.layout.validate()?;
+		config.scroll.validate()?;
+		config.validate_columns()?;
+		config.confirm.validate()?;
+		/* Modules of the remote host are read-only unless writing is allowed. */
+		if config.remote.is_some() {
+			config.read_only |= !config.remote_write;
+			config.watch = false;
+			if config.kernel_log == LogSource::Kmsg {
+				config.kernel_log = LogSource::Dmesg;
+			}
+		}
+		if let Some(theme) = &config.theme {
+			Theme::from_name(theme)?;
+		}
+		if let Some(lang) = &config.lang {
+			Lang::try_from(lang.as_str())?;
+		}
+		for color in [&config.accent_color, &config.color].into_iter().flatten() {
+			Style::parse_color(color)?;
+		}
+		Ok(config)
+	}
+
+	/**
+	 * Load the configuration file from the given path or default locations.
+	 *
+	 * Default values are returned if there is no configuration file.
+	 * The given profile of the file is applied over the base values.
+	 *
+	 * @param  path
+	 * @param  profile
+	 * @return Result
+	 */
+	pub fn load(path: Option<&Path>, profile: Option<&str>) -> Result<Self, String> {
+		let path = match path {
+			Some(v) => v.to_path_buf(),
+			None => match Self::default_path() {
+				Some(v) => v,
+				None => return Self::parse_profile("", profile),
+			},
+		};
+		let contents = fs::read_to_string(&path).map_err(|e| {
+			format!("failed to read the config file ({}): {e}", path.display())
+		})?;
+		Self::parse_profile(&contents, profile).map_err(|e| {
+			format!("failed to parse the config file ({}):\n{e}", path.display())
+		})
+	}
+
+	/**
+	 * Get the configuration as a TOML string.
+	 *
+	 * @return Result
+	 */
+	pub fn to_toml(&self) -> Result<String, String> {
+		toml::to_string(self).map_err(|e| e.to_string())
+	}
+
+	/**
+	 * Parse the configuration from the given TOML string.
+	 *
+	 * @param  contents
+	 * @return Result
+	 */
+	pub fn parse(contents: &str) -> Result<Self, String> {
+		toml::from_str(contents).map_err(|e| e.to_string())
+	}
+
+	/**
+	 * Parse the configuration and apply the given profile over it.
+	 *
+	 * Profiles are the tables under '[profiles]' (e.g. '[profiles.audit]').
+	 * Tables of the profile are merged into the base tables and its other
+	 * values replace the base values.
+	 *
+	 * @param  contents
+	 * @param  profile
+	 * @return Result
+	 */
+	pub fn parse_profile(
+		contents: &str,
+		profile: Option<&str>,
+	) -> Result<Self, String> {
+		let config = Self::parse(contents)?;
+		let Some(name) = profile else {
+			return Ok(config);
+		};
+		let Some(values) = config.profiles.get(name) else {
+			return Err(if config.profiles.is_empty() {
+				format!("unknown profile: '{name}' (no profiles are defined)")
+			} else {
+				format!(
+					"unknown profile: '{name}' (available: {})",
+					config
+						.profiles
+						.keys()
+						.map(String::as_str)
+						.collect::<Vec<&str>>()
+						.join(", ")
+				)
+			});
+		};
+		let mut table =
+			contents.parse::<toml::Table>().map_err(|e| e.to_string())?;
+		table.remove(PROFILES_TABLE);
+		merge_tables(&mut table, values.clone());
+		toml::Value::Table(table)
+			.try_into()
+			.map_err(|e: toml::de::Error| format!("profile '{name}': {e}"))
+	}
+
+	/**
+	 * Get the path of the configuration file to load.
+	 *
+	 * The path given with the argument takes precedence over the path in
+	 * the environment variable, which is ignored with a warning if the file
+	 * does not exist. None means the default locations are used.
+	 *
+	 * @param  arg_path
+	 * @param  env_path
+	 * @return PathBuf
+	 */
+	fn path(arg_path: Option<&Path>, env_path: Option<&Path>) -> Option<PathBuf> {
+		if let Some(path) = arg_path {
+			return Some(path.to_path_buf());
+		}
+		let path = env_path.filter(|v| !v.as_os_str().is_empty())?;
+		if path.is_file() {
+			Some(path.to_path_buf())
+		} else {
+			eprintln!(
+				"warning: the config file in ${CONFIG_ENV} is not found ({}), \
+				using the default locations",
+				path.display()
+			);
+			None
+		}
+	}
+
+	/**
+	 * Get the path of the first existing configuration file.
+	 *
+	 * Checks '$XDG_CONFIG_HOME/kmon/kmon.toml' and '~/.config/kmon/kmon.toml'.
+	 *
+	 * @return PathBuf
+	 */
+	fn default_path() -> Option<PathBuf> {
+		[
+			env::var_os("XDG_CONFIG_HOME").map(PathBuf::from),
+			env::var_os("HOME").map(|v| PathBuf::from(v).join(".config")),
+		]
+		.into_iter()
+		.flatten()
+		.map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(CONFIG_FILE))
+		.find(|path| path.is_file())
+	}
+
+	/**
+	 * Check if the module table columns include the name column once.
+	 *
+	 * @return Result
+	 */
+	pub fn validate_columns(&self) -> Result<(), String> {
+		if !self.columns.contains(&Column::Name)
+			|| Column::ALL.iter().any(|column| {
+				self.columns.iter().filter(|v| *v == column).count() > 1
+			}) {
+			return Err(String::from(COLUMNS_ERROR));
+		}
+		Ok(())
+	}
+
+	/**
+	 * Check if the background color of the terminal is queried for
+	 * choosing the theme, which is done unless the colors are set.
+	 *
+	 * @return bool
+	 */
+	pub fn detects_background(&self) -> bool {
+		self.detect_background
+			&& !self.monochrome
+			&& self.theme.is_none()
+			&& self.accent_color.is_none()
+			&& self.color.is_none()
+	}
+
+	/**
+	 * Get the interval of reading the kernel activities in the background.
+	 *
+	 * Defaults to ten ticks and 0 disables the automatic reading.
+	 *
+	 * @return Option
+	 */
+	pub fn refresh_interval(&self) -> Option<Duration> {
+		match self.refresh_interval {
+			Some(0) => None,
+			Some(v) => Some(Duration::from_millis(v)),
+			None => Some(Duration::from_millis(self.tickrate * 10)),
+		}
+	}
+
+	/**
+	 * Parse the tick rate in milliseconds.
+	 *
+	 * @param  value
+	 * @return Result
+	 */
+	pub fn parse_tickrate(value: &str) -> Result<u64, String> {
+		match value.trim().parse::<u64>() {
+			Ok(v) if v > 0 => Ok(v),
+			_ => Err(String::from(TICKRATE_ERROR)),
+		}
+	}
+
+	/**
+	 * Restore the view state of the last session.
+	 *
+	 * @param state
+	 */
+	fn apply_state(&mut self, state: &State) {
+		if let Some(sort) = state.sort {
+			self.sort = sort;
+		}
+		if let Some(reverse) = state.reverse {
+			self.reverse = reverse;
+		}
+		if let Some(filter) = state.filter {
+			self.filter = filter;
+		}
+		/* Themes that are removed from the presets are ignored. */
+		if let Some(theme) =
+			state.theme.as_ref().filter(|v| Theme::from_name(v).is_ok())
+		{
+			self.theme = Some(theme.to_string());
+		}
+		self.block = state.block.filter(|v| *v != Block::UserInput);
+		if self.search.save_history {
+			self.search.history.clone_from(&state.search_history);
+			self.search
+				.log_history
+				.clone_from(&state.log_search_history);
+		}
+	}
+
+	/**
+	 * Override the configuration values with the given arguments.
+	 *
+	 * @param  args
+	 * @return Result
+	 */
+	pub(crate) fn apply_args(&mut self, args: &ArgMatches) -> Result<(), String> {
+		let is_present = |id: &str| {
+			matches!(args.try_contains_id(id), Ok(true))
+				&& args.value_source(id) == Some(ValueSource::CommandLine)
+		};
+		if is_present("theme") {
+			self.theme = args.get_one::<String>("theme").cloned();
+		}
+		if is_present("lang") {
+			self.lang = args.get_one::<String>("lang").cloned();
+		}
+		if is_present("accent-color") {
+			self.accent_color = args.get_one::<String>("accent-color").cloned();
+		}
+		if is_present("color") {
+			self.color = args.get_one::<String>("color").cloned();
+		}
+		/* Disable the colors unless they are given on the command line. */
+		self.monochrome = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+			&& !["theme", "accent-color", "color"]
+				.into_iter()
+				.any(is_present);
+		if is_present("log-file") {
+			self.log_file = args.get_one::<PathBuf>("log-file").cloned();
+		}
+		if is_present("log") {
+			self.command_log.path = args.get_one::<PathBuf>("log").cloned();
+		}
+		if is_present("log-verbose") {
+			self.command_log.verbose = args.get_flag("log-verbose");
+		}
+		if is_present("layout") {
+			if let Some(v) = args
+				.get_one::<String>("layout")
+				.and_then(|v| Orientation::try_from(v.as_str()).ok())
+			{
+				self.layout.orientation = v;
+			}
+		}
+		if is_present("border") {
+			if let Some(v) = args
+				.get_one::<String>("border")
+				.and_then(|v| BorderStyle::try_from(v.as_str()).ok())
+			{
+				self.layout.border = v;
+			}
+		}
+		if is_present("kernel-log") {
+			if let Some(v) = args
+				.get_one::<String>("kernel-log")
+				.and_then(|v| LogSource::try_from(v.as_str()).ok())
+			{
+				self.kernel_log = v;
+			}
+		}
+		if is_present("log-lines") {
+			self.log_lines = args.get_one::<usize>("log-lines").copied();
+		}
+		if is_present("no-log") {
+			self.no_log = args.get_flag("no-log");
+		}
+		if is_present("log-time") {
+			self.log_time = args
+				.get_one::<String>("log-time")
+				.and_then(|v| LogTime::try_from(v.as_str()).ok());
+		}
+		if is_present("rate") {
+			if let Some(v) = args.get_one::<String>("rate") {
+				self.tickrate = Self::parse_tickrate(v)?;
+			}
+		}
+		if is_present("refresh-interval") {
+			self.refresh_interval = args.get_one::<u64>("refresh-interval").copied();
+		}
+		if is_present("loader") {
+			if let Some(v) = args
+				.get_one::<String>("loader")
+				.and_then(|v| Loader::try_from(v.as_str()).ok())
+			{
+				self.loader = v;
+			}
+		}
+		if is_present("privilege-cmd") {
+			if let Some(v) = args
+				.get_one::<String>("privilege-cmd")
+				.and_then(|v| PrivilegeCmd::try_from(v.as_str()).ok())
+			{
+				self.privilege_cmd = Some(v);
+			}
+		}
+		if is_present("size-format") {
+			if let Some(v) = args
+				.get_one::<String>("size-format")
+				.and_then(|v| SizeFormat::try_from(v.as_str()).ok())
+			{
+				self.size_format = v;
+			}
+		}
+		if is_present("reverse") {
+			self.reverse = args.get_flag("reverse");
+		}
+		if is_present("unicode") {
+			self.unicode = args.get_flag("unicode");
+		}
+		if is_present("mouse") {
+			self.mouse = args.get_flag("mouse");
+		}
+		if is_present("size-bar") {
+			self.size_bar = args.get_flag("size-bar");
+		}
+		if is_present("no-highlight") {
+			self.change_highlight = !args.get_flag("no-highlight");
+		}
+		if is_present("updated-time") {
+			self.updated_time = args.get_flag("updated-time");
+		}
+		if is_present("type-ahead") {
+			self.type_ahead = args.get_flag("type-ahead");
+		}
+		if is_present("watch") {
+			self.watch = args.get_flag("watch");
+		}
+		if is_present("builtin") {
+			self.builtin = args.get_flag("builtin");
+		}
+		if is_present("dry-run") {
+			self.dry_run = args.get_flag("dry-run");
+		}
+		if is_present("read-only") {
+			self.read_only = args.get_flag("read-only");
+		}
+		if is_present("demo") {
+			self.demo = args.get_flag("demo");
+		}
+		if is_present("remote") {
+			self.remote = args.get_one::<String>("remote").cloned();
+		}
+		if is_present("remote-write") {
+			self.remote_write = args.get_flag("remote-write");
+		}
+		if is_present("stop-on-error") {
+			self.stop_on_error = args.get_flag("stop-on-error");
+		}
+		if is_present("confirm-quit") {
+			self.confirm_quit = args.get_flag("confirm-quit");
+		}
+		if let Some(("sort", matches)) = args.subcommand() {
+			self.sort = sort_type(matches);
+		}
+		Ok(())
+	}
+}
+
+/**
+ * Resolve the sort type from the flags of the sort subcommand.
+ *
+ * The flags are mutually exclusive and the modules are sorted
+ * by their names if none of them is given.
+ *
+ * @param  matches
+ * @return SortType
+ */
+fn sort_type(matches: &ArgMatches) -> SortType {
+	[
+		("size", SortType::Size),
+		("name", SortType::Name),
+		("dependent", SortType::Dependent),
+		("refcount", SortType::Refcount),
+	]
+	.into_iter()
+	.find(|(id, _)| matches.get_flag(id))
+	.map_or(SortType::Name, |(_, sort)| sort)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::args;
+	use crate::keys::Action;
+	use termion::event::Key;
+	#[test]
+	fn test_config() {
+		let config = Config::parse(
+			r#"
+			color = "red"
+			tickrate = 500
+			reverse = true
+			sort = "size"
+			loader = "insmod"
+			privilege_cmd = "doas"
+			kernel_log = "kmsg"
+			refresh_after_op = ["modules"]
+			columns = ["name", "used_by"]
+			select_after_load = false
+			enter_action = "reload"
+			out_of_tree_marker = true
+			docs_url = "https://wiki.archlinux.org/?search={module}"
+
+			[search]
+			regex = true
+
+			[blacklist]
+			path = "/etc/modprobe.d/blacklist.conf"
+			remove_all = true
+			hard = false
+
+			[boot]
+			marker = true
+
+			[hooks]
+			post_load = "logger loaded {module}"
+			depmod = true
+
+			[layout]
+			info = 30
+			border = "rounded"
+			max_list_height = 20
+			modules_title = "Modules"
+
+			[confirm]
+			prompt = "Run it?"
+			timeout = 10
+			default = "y"
+			protected = ["i915", "snd-*"]
+
+			[scroll]
+			page = 10
+			acceleration = true
+
+			[keybindings]
+			quit = ["q", "ctrl-q"]
+			unload = "u"
+
+			[keybindings.activities]
+			follow = "F"
+			"#,
+		)
+		.unwrap();
+		assert_eq!(Some(String::from("red")), config.color);
+		assert_eq!(None, config.accent_color);
+		assert_eq!(500, config.tickrate);
+		assert!(config.reverse);
+		assert_eq!(SortType::Size, config.sort);
+		assert_eq!(Loader::Insmod, config.loader);
+		assert_eq!(Some(PrivilegeCmd::Doas), config.privilege_cmd);
+		assert_eq!(LogSource::Kmsg, config.kernel_log);
+		assert_eq!(vec![RefreshTarget::Modules], config.refresh_after_op);
+		assert!(!config.select_after_load);
+		assert_eq!(EnterAction::Reload, config.enter_action);
+		assert!(config.out_of_tree_marker);
+		assert_eq!(
+			Some("https://wiki.archlinux.org/?search={module}"),
+			config.docs_url.as_deref()
+		);
+		assert_eq!(EnterAction::Info, Config::default().enter_action);
+		assert!(Config::parse("enter_action = \"unload\"").is_err());
+		assert_eq!(vec![Column::Name, Column::UsedBy], config.columns);
+		assert!(config.validate_columns().is_ok());
+		assert!(Config::default().validate_columns().is_ok());
+		for columns in ["[\"size\"]", "[\"name\", \"size\", \"name\"]"] {
+			assert!(Config::parse(&format!("columns = {columns}"))
+				.unwrap()
+				.validate_columns()
+				.is_err());
+		}
+		assert_eq!(3, Config::default().refresh_after_op.len());
+		assert!(Config::parse("refresh_after_op = [\"header\"]").is_err());
+		assert!(config.search.regex);
+		assert_eq!(SearchMode::Regex, config.search.mode());
+		assert_eq!(
+			SearchMode::Fuzzy,
+			Config::parse("[search]\nregex = true\nmode = \"fuzzy\"")
+				.unwrap()
+				.search
+				.mode()
+		);
+		assert!(config.blacklist.remove_all);
+		assert!(!config.blacklist.hard);
+		assert!(Config::default().blacklist.hard);
+		assert!(config.boot.marker);
+		assert_eq!(PathBuf::from(BOOT_FILE), config.boot.path);
+		assert_eq!(
+			Some(String::from("logger loaded {module}")),
+			config.hooks.post_load
+		);
+		assert_eq!(None, config.hooks.post_unload);
+		assert!(config.hooks.depmod);
+		assert_eq!(30, config.layout.info);
+		assert_eq!(25, config.layout.activities);
+		assert_eq!(Some(String::from("Modules")), config.layout.modules_title);
+		assert_eq!(BorderStyle::Rounded, config.layout.border);
+		assert_eq!(Some(20), config.layout.max_list_height);
+		assert!(Config::parse("[layout]\nmax_list_height = 0")
+			.unwrap()
+			.layout
+			.validate()
+			.is_err());
+		assert!(Config::parse("[layout]\nborder = \"dotted\"")
+			.unwrap_err()
+			.to_string()
+			.contains("expected one of `plain`"));
+		assert!(config.layout.validate().is_ok());
+		assert!(Config::parse("[layout]\nactivities = 100")
+			.unwrap()
+			.layout
+			.validate()
+			.is_err());
+		assert_eq!("Run it? [Y/n/a]:", config.confirm.prompt("Execute?", true));
+		assert_eq!(Some(Duration::from_secs(10)), config.confirm.timeout());
+		assert_eq!(
+			"Execute? [y/N]:",
+			ConfirmConfig::default().prompt("Execute?", false)
+		);
+		assert!(Config::parse("[confirm]\ntimeout = 0")
+			.unwrap()
+			.confirm
+			.validate()
+			.is_err());
+		assert!(config.confirm.is_protected("ext4", None));
+		assert!(config.confirm.is_protected("dm-crypt", None));
+		assert!(config.confirm.is_protected("snd_hda_intel", None));
+		assert!(config.confirm.is_protected("i915", None));
+		assert!(config.confirm.is_protected("nilfs2", Some("nilfs2")));
+		assert!(!config.confirm.is_protected("nilfs2", None));
+		assert!(!Config::default().confirm.is_protected("i915", Some("ext4")));
+		assert_eq!(1, config.scroll.step);
+		assert_eq!(Some(10), config.scroll.page);
+		assert!(config.scroll.acceleration);
+		assert!(Config::parse("[scroll]\nstep = 0")
+			.unwrap()
+			.scroll
+			.validate()
+			.is_err());
+		assert_eq!(
+			PathBuf::from("/etc/modprobe.d/blacklist.conf"),
+			config.blacklist.path
+		);
+		assert_eq!(
+			PathBuf::from(BLACKLIST_FILE),
+			Config::default().blacklist.path
+		);
+		assert_eq!(Some(Action::Quit), config.keybindings.get(Key::Ctrl('q')));
+		assert_eq!(None, config.keybindings.get(Key::Char('-')));
+		assert_eq!(
+			Some(Action::Filter),
+			config.keybindings.get_in(Block::Activities, Key::Char('f'))
+		);
+		assert!(Config::parse("[keybindings.popup]\nquit = \"u\"").is_err());
+		assert!(Config::parse("[keybindings]\nquit = \"u\"").is_err());
+		assert!(Config::parse("tickrate = \"fast\"").is_err());
+		assert!(Config::parse("unknown = true").is_err());
+		let contents = config.to_toml().unwrap();
+		assert!(contents.contains("color = \"red\""));
+		assert!(contents.contains("[keybindings.activities]"));
+		assert_eq!(
+			contents,
+			Config::parse(&contents).unwrap().to_toml().unwrap()
+		);
+		let contents = Config::default().to_toml().unwrap();
+		assert!(contents.contains("tickrate = 250"));
+		assert_eq!(
+			contents,
+			Config::parse(&contents).unwrap().to_toml().unwrap()
+		);
+		assert!(
+			Config::load(Some(Path::new("/nonexistent/kmon.toml")), None).is_err()
+		);
+		let contents = "tickrate = 300\nno_log = true\n[layout]\ninput = 40\n\
+			[profiles.audit]\nno_log = false\n[profiles.dev]\ntickrate = 100\n\
+			[profiles.dev.layout]\ninfo = 30\n";
+		let config = Config::parse_profile(contents, None).unwrap();
+		assert_eq!(300, config.tickrate);
+		assert_eq!(2, config.profiles.len());
+		assert!(
+			!Config::parse_profile(contents, Some("audit"))
+				.unwrap()
+				.no_log
+		);
+		let config = Config::parse_profile(contents, Some("dev")).unwrap();
+		assert_eq!(100, config.tickrate);
+		assert!(config.no_log);
+		assert_eq!(40, config.layout.input);
+		assert_eq!(30, config.layout.info);
+		assert!(config.profiles.is_empty());
+		assert_eq!(
+			Err(String::from(
+				"unknown profile: 'ci' (available: audit, dev)"
+			)),
+			Config::parse_profile(contents, Some("ci")).map(|_| ())
+		);
+		assert_eq!(
+			Err(String::from(
+				"unknown profile: 'ci' (no profiles are defined)"
+			)),
+			Config::parse_profile("", Some("ci")).map(|_| ())
+		);
+		assert!(Config::parse_profile(
+			"[profiles.dev]\ntickrate = \"fast\"",
+			Some("dev")
+		)
+		.is_err());
+		let env_path = std::env::temp_dir().join("kmon-test-env.toml");
+		fs::write(&env_path, "tickrate = 300").unwrap();
+		let arg_path = Path::new("/nonexistent/kmon.toml");
+		assert_eq!(
+			Some(arg_path.to_path_buf()),
+			Config::path(Some(arg_path), Some(&env_path))
+		);
+		assert_eq!(Some(env_path.clone()), Config::path(None, Some(&env_path)));
+		assert_eq!(None, Config::path(None, Some(arg_path)));
+		assert_eq!(None, Config::path(None, Some(Path::new(""))));
+		assert_eq!(None, Config::path(None, None));
+		assert_eq!(
+			300,
+			Config::load(Config::path(None, Some(&env_path)).as_deref(), None)
+				.unwrap()
+				.tickrate
+		);
+		fs::remove_file(env_path).unwrap();
+	}
+	#[test]
+	fn test_config_args() {
+		let mut config = Config::parse("color = \"red\"\ntickrate = 500").unwrap();
+		let args = args::get_args().get_matches_from([
+			"kmon",
+			"-t",
+			"100",
+			"--mouse",
+			"--type-ahead",
+			"--size-bar",
+			"--updated-time",
+			"--no-highlight",
+			"--watch",
+			"--builtin",
+			"--dry-run",
+			"--demo",
+			"--read-only",
+			"--stop-on-error",
+			"--confirm-quit",
+			"--log-file",
+			"/tmp/kmon.log",
+			"--log",
+			"/tmp/kmon-commands.log",
+			"--log-verbose",
+			"--kernel-log",
+			"journal",
+			"--layout",
+			"vertical",
+			"--border",
+			"none",
+			"--log-lines",
+			"1000",
+			"--refresh-interval",
+			"0",
+			"--log-time",
+			"iso",
+			"--no-log",
+			"--size-format",
+			"raw",
+			"--privilege-cmd",
+			"pkexec",
+			"--theme",
+			"solarized",
+			"--lang",
+			"tr",
+			"sort",
+			"-d",
+		]);
+		config.apply_args(&args).unwrap();
+		assert_eq!(Some(String::from("red")), config.color);
+		assert_eq!(Some(String::from("solarized")), config.theme);
+		assert_eq!(Some(String::from("tr")), config.lang);
+		assert_eq!(100, config.tickrate);
+		assert_eq!(SizeFormat::Raw, config.size_format);
+		assert_eq!(Some(PrivilegeCmd::Pkexec), config.privilege_cmd);
+		assert!(config.mouse);
+		assert!(config.type_ahead);
+		assert!(config.size_bar);
+		assert!(config.updated_time);
+		assert!(!config.change_highlight);
+		assert!(config.watch);
+		assert!(config.builtin);
+		assert!(config.dry_run);
+		assert!(config.demo);
+		assert!(config.read_only);
+		assert!(config.stop_on_error);
+		assert!(config.confirm_quit);
+		assert_eq!(Some(PathBuf::from("/tmp/kmon.log")), config.log_file);
+		assert_eq!(
+			Some(PathBuf::from("/tmp/kmon-commands.log")),
+			config.command_log.path
+		);
+		assert!(config.command_log.verbose);
+		assert_eq!(LogSource::Journal, config.kernel_log);
+		assert_eq!(Orientation::Vertical, config.layout.orientation);
+		assert_eq!(BorderStyle::None, config.layout.border);
+		assert_eq!(Some(1000), config.log_lines);
+		assert_eq!(None, config.refresh_interval());
+		config.refresh_interval = None;
+		assert_eq!(Some(Duration::from_secs(1)), config.refresh_interval());
+		assert_eq!(Some(LogTime::Iso), config.log_time);
+		assert!(config.no_log);
+		assert_eq!(SortType::Dependent, config.sort);
+		assert_eq!(Ok(500), Config::parse_tickrate("500"));
+		assert_eq!(
+			Err(String::from(TICKRATE_ERROR)),
+			Config::parse_tickrate("abc")
+		);
+		assert_eq!(
+			Err(String::from(TICKRATE_ERROR)),
+			Config::parse_tickrate("0")
+		);
+		assert!(config
+			.apply_args(&args::get_args().get_matches_from(["kmon", "-t", "abc"]))
+			.is_err());
+		assert!(Config::new(
+			&args::get_args().get_matches_from(["kmon", "-t", "0"]),
+			&State::default()
+		)
+		.is_err());
+		let config = Config::new(
+			&args::get_args().get_matches_from(["kmon", "--remote", "admin@srv1"]),
+			&State::default(),
+		)
+		.unwrap();
+		assert_eq!(Some(String::from("admin@srv1")), config.remote);
+		assert!(config.read_only);
+		assert!(!config.watch);
+		assert!(
+			!Config::new(
+				&args::get_args().get_matches_from([
+					"kmon",
+					"--remote",
+					"admin@srv1",
+					"--remote-write"
+				]),
+				&State::default(),
+			)
+			.unwrap()
+			.read_only
+		);
+		assert!(args::get_args()
+			.try_get_matches_from(["kmon", "--remote-write"])
+			.is_err());
+		let mut config = Config::default();
+		config.apply_state(&State {
+			sort: Some(SortType::Name),
+			reverse: Some(true),
+			filter: Some(ListFilter::Unused),
+			theme: Some(String::from("~")),
+			block: Some(Block::UserInput),
+			..State::default()
+		});
+		assert_eq!(None, config.block);
+		assert_eq!(SortType::Name, config.sort);
+		assert!(config.reverse);
+		assert_eq!(ListFilter::Unused, config.filter);
+		assert_eq!(None, config.theme);
+		assert!(Config::default().detects_background());
+		assert!(!Config {
+			accent_color: Some(String::from("red")),
+			..Config::default()
+		}
+		.detects_background());
+		assert!(!Config {
+			detect_background: false,
+			..Config::default()
+		}
+		.detects_background());
+		let state = State {
+			search_history: vec![String::from("snd")],
+			..State::default()
+		};
+		config.apply_state(&state);
+		assert!(config.search.history.is_empty());
+		config.search.save_history = true;
+		config.apply_state(&state);
+		assert_eq!(state.search_history, config.search.history);
+		config
+			.apply_args(&args::get_args().get_matches_from(["kmon", "sort", "-s"]))
+			.unwrap();
+		assert_eq!(SortType::Size, config.sort);
+		for (flags, sort) in [
+			(vec![], SortType::Name),
+			(vec!["-n"], SortType::Name),
+			(vec!["--dependent"], SortType::Dependent),
+			(vec!["-c"], SortType::Refcount),
+		] {
+			let args = args::get_args()
+				.get_matches_from([vec!["kmon", "sort"], flags].concat());
+			assert_eq!(Some(sort), args.subcommand_matches("sort").map(sort_type));
+		}
+		assert!(args::get_args()
+			.try_get_matches_from(["kmon", "sort", "--size", "--name"])
+			.is_err());
+	}
+}
@@ -0,0 +1,21 @@
+/**
+ * Convert the days since the Unix epoch to a civil date in the proleptic
+ * Gregorian calendar.
+ *
+ * This is shared with the build script for the build date.
+ *
+ * @param  days
+ * @return (year, month, day)
+ */
+pub fn civil_date(days: i64) -> (i64, i64, i64) {
+	let z = days + 719468;
+	let era = z.div_euclid(146097);
+	let doe = z.rem_euclid(146097);
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + i64::from(month <= 2);
+	(year, month, day)
+}
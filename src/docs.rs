@@ -0,0 +1,175 @@
+use crate::kernel::blacklist;
+use crate::util;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/* Default URL template of the module documentation */
+pub const DOCS_URL: &str =
+	"https://www.kernel.org/doc/html/latest/search.html?q={module}";
+
+/* Directory of the locally installed documentation */
+pub const DOCS_DIR: &str = "/usr/share/doc";
+
+/* Prefixes of the kernel documentation packages */
+const KERNEL_DOCS_PREFIXES: &[&str] = &["linux-doc", "kernel-doc"];
+
+/* Maximum depth of the kernel documentation that is searched */
+const MAX_SEARCH_DEPTH: usize = 8;
+
+/**
+ * Get the URL of the module documentation from the given template.
+ *
+ * @param  template
+ * @param  module_name
+ * @return String
+ */
+pub fn docs_url(template: &str, module_name: &str) -> String {
+	template.replace("{module}", module_name)
+}
+
+/**
+ * Find the local documentation of the module in the given directory.
+ *
+ * The documentation directory of a package with the module name is
+ * preferred over the files of the kernel documentation packages.
+ *
+ * @param  dir
+ * @param  module_name
+ * @return PathBuf (Option)
+ */
+pub fn find_local_docs(dir: &Path, module_name: &str) -> Option<PathBuf> {
+	let names = [module_name.to_string(), module_name.replace('_', "-")];
+	if let Some(path) = names.iter().map(|v| dir.join(v)).find(|v| v.is_dir()) {
+		return Some(path);
+	}
+	let module_name = blacklist::normalize_name(module_name);
+	fs::read_dir(dir)
+		.ok()?
+		.flatten()
+		.map(|entry| entry.path())
+		.filter(|path| {
+			path.file_name().is_some_and(|v| {
+				KERNEL_DOCS_PREFIXES
+					.iter()
+					.any(|prefix| v.to_string_lossy().starts_with(prefix))
+			})
+		})
+		.find_map(|path| find_doc_file(&path, &module_name, MAX_SEARCH_DEPTH))
+}
+
+/**
+ * Find the documentation file that is named after the module recursively.
+ *
+ * @param  dir
+ * @param  module_name
+ * @param  depth
+ * @return PathBuf (Option)
+ */
+fn find_doc_file(dir: &Path, module_name: &str, depth: usize) -> Option<PathBuf> {
+	let mut entries = fs::read_dir(dir)
+		.ok()?
+		.flatten()
+		.map(|entry| entry.path())
+		.collect::<Vec<PathBuf>>();
+	entries.sort();
+	if let Some(path) = entries.iter().find(|path| {
+		path.is_file()
+			&& path.file_name().is_some_and(|v| {
+				v.to_string_lossy()
+					.split_once('.')
+					.is_some_and(|(name, ext)| {
+						blacklist::normalize_name(name) == module_name
+							&& ["rst", "txt", "rst.gz", "txt.gz"].contains(&ext)
+					})
+			})
+	}) {
+		return Some(path.to_path_buf());
+	}
+	if depth == 0 {
+		return None;
+	}
+	entries
+		.iter()
+		.filter(|path| path.is_dir())
+		.find_map(|path| find_doc_file(path, module_name, depth - 1))
+}
+
+/**
+ * Open the given file or URL with 'xdg-open' in the background.
+ *
+ * Opening is skipped without a display server since a text mode browser
+ * would take over the terminal.
+ *
+ * @param  target
+ * @return Result
+ */
+pub fn open(target: &str) -> Result<(), String> {
+	if !["DISPLAY", "WAYLAND_DISPLAY"]
+		.iter()
+		.any(|v| env::var_os(v).is_some_and(|v| !v.is_empty()))
+		|| !util::is_executable_in_path("xdg-open")
+	{
+		return Err(String::from("no browser is available"));
+	}
+	let mut child = Command::new("xdg-open")
+		.arg(target)
+		.stdin(Stdio::null())
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.spawn()
+		.map_err(|e| format!("failed to run 'xdg-open': {e}"))?;
+	thread::spawn(move || child.wait());
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	#[test]
+	fn test_docs() {
+		assert_eq!(
+			"https://www.kernel.org/doc/html/latest/search.html?q=e1000e",
+			docs_url(DOCS_URL, "e1000e")
+		);
+		let dir = env::temp_dir().join("kmon-test-docs");
+		let kernel_docs = dir.join("linux-doc-6.1").join("Documentation");
+		fs::create_dir_all(kernel_docs.join("networking").join("device_drivers"))
+			.unwrap();
+		fs::create_dir_all(dir.join("zfs-dkms")).unwrap();
+		fs::create_dir_all(dir.join("v4l2-loopback")).unwrap();
+		fs::write(
+			kernel_docs
+				.join("networking")
+				.join("device_drivers")
+				.join("e1000e.rst.gz"),
+			"",
+		)
+		.unwrap();
+		fs::write(kernel_docs.join("snd-hda-intel.txt"), "").unwrap();
+		fs::write(kernel_docs.join("loop.html"), "").unwrap();
+		assert_eq!(
+			Some(dir.join("v4l2-loopback")),
+			find_local_docs(&dir, "v4l2_loopback")
+		);
+		assert_eq!(
+			Some(
+				kernel_docs
+					.join("networking")
+					.join("device_drivers")
+					.join("e1000e.rst.gz")
+			),
+			find_local_docs(&dir, "e1000e")
+		);
+		assert_eq!(
+			Some(kernel_docs.join("snd-hda-intel.txt")),
+			find_local_docs(&dir, "snd_hda_intel")
+		);
+		assert_eq!(None, find_local_docs(&dir, "loop"));
+		assert_eq!(None, find_local_docs(&dir, "zfs"));
+		assert_eq!(None, find_local_docs(&dir.join("nonexistent"), "loop"));
+		fs::remove_dir_all(dir).unwrap();
+	}
+}
@@ -1,15 +1,28 @@
+use crate::kernel::cmd::ExecutionResult;
+use crate::kernel::lkm::{Module, SearchEntry};
 use crate::kernel::log::KernelLogs;
+use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
-use termion::event::Key;
+use termion::event::{Event as TermEvent, Key, MouseEvent};
 use termion::input::TermRead;
 
+/* Polling interval of the watched files */
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
 /* Terminal event methods */
 pub enum Event<I> {
 	Input(I),
+	Mouse(MouseEvent),
 	Kernel(String),
+	Modules(Result<Vec<Module>, String>),
+	SearchIndex(Vec<(String, SearchEntry)>),
+	Execution(ExecutionResult),
+	Snapshot(Result<PathBuf, String>),
+	ModulesChanged,
 	Tick,
 }
 
@@ -27,11 +40,18 @@ impl Events {
 	/**
 	 * Create a new events instance.
 	 *
+	 * Kernel logs are only read in the background if the interval is given.
+	 *
 	 * @param  refresh_rate
+	 * @param  refresh_interval
 	 * @param  kernel_logs
 	 * @return Events
 	 */
-	pub fn new(refresh_rate: u64, kernel_logs: &KernelLogs) -> Self {
+	pub fn new(
+		refresh_rate: u64,
+		refresh_interval: Option<Duration>,
+		kernel_logs: &KernelLogs,
+	) -> Self {
 		/* Convert refresh rate to Duration from milliseconds. */
 		let refresh_rate = Duration::from_millis(refresh_rate);
 		/* Create a new asynchronous channel. */
@@ -41,8 +61,14 @@ impl Events {
 			let tx = tx.clone();
 			thread::spawn(move || {
 				let stdin = io::stdin();
-				for key in stdin.keys().flatten() {
-					tx.send(Event::Input(key)).unwrap();
+				for event in stdin.events().flatten() {
+					match event {
+						TermEvent::Key(key) => tx.send(Event::Input(key)).unwrap(),
+						TermEvent::Mouse(mouse) => {
+							tx.send(Event::Mouse(mouse)).unwrap()
+						}
+						TermEvent::Unsupported(_) => {}
+					}
 				}
 			})
 		};
@@ -50,12 +76,17 @@ impl Events {
 		let kernel_handler = {
 			let tx = tx.clone();
 			let mut kernel_logs = kernel_logs.clone();
-			thread::spawn(move || loop {
-				if kernel_logs.update() {
-					tx.send(Event::Kernel(kernel_logs.output.to_string()))
-						.unwrap_or_default();
+			thread::spawn(move || {
+				let Some(refresh_interval) = refresh_interval else {
+					return;
+				};
+				loop {
+					if kernel_logs.update() {
+						tx.send(Event::Kernel(kernel_logs.output.to_string()))
+							.unwrap_or_default();
+					}
+					thread::sleep(refresh_interval);
 				}
-				thread::sleep(refresh_rate * 10);
 			})
 		};
 		/* Create a loop for handling events. */
@@ -75,6 +106,42 @@ impl Events {
 			tick_handler,
 		}
 	}
+
+	/**
+	 * Watch the given file for the changes of the loaded modules.
+	 *
+	 * Since procfs does not emit inotify events, the file is polled
+	 * and the kernel logs are updated along with the change event.
+	 *
+	 * @param  path
+	 * @param  kernel_logs
+	 * @return bool
+	 */
+	pub fn watch(&self, path: &Path, kernel_logs: &KernelLogs) -> bool {
+		let Ok(mut contents) = fs::read_to_string(path) else {
+			return false;
+		};
+		let tx = self.tx.clone();
+		let path = path.to_path_buf();
+		let mut kernel_logs = kernel_logs.clone();
+		thread::spawn(move || loop {
+			thread::sleep(WATCH_INTERVAL);
+			let Ok(new_contents) = fs::read_to_string(&path) else {
+				break;
+			};
+			if new_contents != contents {
+				contents = new_contents;
+				if tx.send(Event::ModulesChanged).is_err() {
+					break;
+				}
+				if kernel_logs.update() {
+					tx.send(Event::Kernel(kernel_logs.output.to_string()))
+						.unwrap_or_default();
+				}
+			}
+		});
+		true
+	}
 }
 
 #[cfg(test)]
@@ -84,7 +151,14 @@ mod tests {
 	#[test]
 	fn test_events() -> Result<(), Box<dyn Error>> {
 		let kernel_logs = KernelLogs::default();
-		let events = Events::new(100, &kernel_logs);
+		let events = Events::new(100, Some(Duration::from_secs(1)), &kernel_logs);
+		let path = std::env::temp_dir().join("kmon-test-watch");
+		fs::write(&path, "snd 1")?;
+		assert!(events.watch(&path, &kernel_logs));
+		assert!(!events.watch(Path::new("/nonexistent"), &kernel_logs));
+		fs::write(&path, "snd 2")?;
+		while !matches!(events.rx.recv()?, Event::ModulesChanged) {}
+		fs::remove_file(path)?;
 		let mut i = 0;
 		loop {
 			let tx = events.tx.clone();
@@ -100,6 +174,12 @@ mod tests {
 						break;
 					}
 				}
+				Event::Mouse(_)
+				| Event::Modules(_)
+				| Event::SearchIndex(_)
+				| Event::Execution(_)
+				| Event::Snapshot(_)
+				| Event::ModulesChanged => {}
 				Event::Tick => thread::sleep(Duration::from_millis(100)),
 				Event::Kernel(log) => assert!(!log.is_empty()),
 			}
@@ -0,0 +1,476 @@
+use std::env;
+use std::sync::OnceLock;
+
+/* Language of the user interface */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Lang {
+	#[default]
+	En,
+	Tr,
+}
+
+impl TryFrom<&str> for Lang {
+	type Error = String;
+	/**
+	 * Parse the language from its code or locale name (e.g. 'tr_TR.UTF-8').
+	 *
+	 * @param  value
+	 * @return Result
+	 */
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		let code = value
+			.split(['_', '.', '@', '-'])
+			.next()
+			.unwrap_or_default()
+			.to_lowercase();
+		match code.as_str() {
+			"en" | "c" | "posix" => Ok(Self::En),
+			"tr" => Ok(Self::Tr),
+			_ => Err(format!("unsupported language: {value}")),
+		}
+	}
+}
+
+impl Lang {
+	/**
+	 * Detect the language from the locale environment variables.
+	 *
+	 * @return Lang
+	 */
+	pub fn from_env() -> Self {
+		["LC_ALL", "LC_MESSAGES", "LANG"]
+			.iter()
+			.filter_map(|v| env::var(v).ok())
+			.find(|v| !v.is_empty())
+			.and_then(|v| Self::try_from(v.as_str()).ok())
+			.unwrap_or_default()
+	}
+
+	/**
+	 * Get the message catalog of the language.
+	 *
+	 * @return catalog
+	 */
+	fn catalog(self) -> &'static [(&'static str, &'static str)] {
+		match self {
+			Self::En => EN,
+			Self::Tr => TR,
+		}
+	}
+}
+
+/* Language that is selected for the user interface */
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/**
+ * Set the language of the user interface.
+ *
+ * @param lang
+ */
+pub fn set_lang(lang: Lang) {
+	let _ = LANG.set(lang);
+}
+
+/**
+ * Get the language of the user interface.
+ *
+ * @return Lang
+ */
+pub fn lang() -> Lang {
+	LANG.get().copied().unwrap_or_default()
+}
+
+/**
+ * Get the message with the given identifier in the given language.
+ *
+ * Falls back to English if the message is not translated.
+ *
+ * @param  lang
+ * @param  id
+ * @return message
+ */
+pub fn text_in(lang: Lang, id: &str) -> &'static str {
+	[lang.catalog(), EN]
+		.iter()
+		.find_map(|catalog| {
+			catalog.iter().find(|(key, _)| *key == id).map(|(_, v)| *v)
+		})
+		.unwrap_or_default()
+}
+
+/**
+ * Get the message with the given identifier in the selected language.
+ *
+ * @param  id
+ * @return message
+ */
+pub fn text(id: &str) -> &'static str {
+	text_in(lang(), id)
+}
+
+/**
+ * Get the message with the given identifier in the given language and
+ * replace its placeholder ('{}') with the given value.
+ *
+ * @param  lang
+ * @param  id
+ * @param  value
+ * @return message
+ */
+pub fn format_in(lang: Lang, id: &str, value: &str) -> String {
+	text_in(lang, id).replace("{}", value)
+}
+
+/**
+ * Get the message with the given identifier in the selected language and
+ * replace its placeholder with the given value.
+ *
+ * @param  id
+ * @param  value
+ * @return message
+ */
+pub fn format(id: &str, value: &str) -> String {
+	format_in(lang(), id, value)
+}
+
+/* English messages that are used as a fallback for the other languages */
+const EN: &[(&str, &str)] = &[
+	("title.modules", "Loaded Kernel Modules"),
+	("title.activities", "Kernel Activities"),
+	("title.help", "Help"),
+	("title.palette", "Command Palette"),
+	("header.module", "Module"),
+	("header.size", "Size"),
+	("header.used_by", "Used by"),
+	("help.dependent", "jump to the dependent module"),
+	("prompt.execute", "Execute the following command?"),
+	("prompt.execute_batch", "Execute the following commands?"),
+	("prompt.quit", "Quit kmon? (y/n)"),
+	("status.read_only", "read-only mode"),
+	("status.too_small", "Terminal is too small"),
+	(
+		"status.unprivileged",
+		"Not running as root: loading, unloading and blacklisting modules require privileges (press any key to dismiss)",
+	),
+	("status.find", "find: {}"),
+	("status.find_no_match", "find: {} (no match)"),
+	("status.not_in_group", "module is not in a group"),
+	("status.no_command", "no command to copy"),
+	("status.cancelled", "command is cancelled (timeout)"),
+	("status.updated", "updated {} ago"),
+	("info.execution_failed", "Failed to execute command:"),
+	("result.load", "loaded {}"),
+	("result.unload", "unloaded {}"),
+	("result.force-unload", "force unloaded {}"),
+	("result.reload", "reloaded {}"),
+	("result.blacklist", "blacklisted {}"),
+	("result.unblacklist", "unblacklisted {}"),
+	("result.clear", "cleared the ring buffer"),
+	("result.depmod", "updated the module dependencies"),
+	("result.add-boot", "added {} to the boot modules"),
+	("result.remove-boot", "removed {} from the boot modules"),
+	("progress.load", "loading {}"),
+	("progress.unload", "unloading {}"),
+	("progress.force-unload", "force unloading {}"),
+	("progress.reload", "reloading {}"),
+	("progress.blacklist", "blacklisting {}"),
+	("progress.unblacklist", "unblacklisting {}"),
+	("progress.clear", "clearing the ring buffer"),
+	("progress.depmod", "updating the module dependencies"),
+	("progress.add-boot", "adding {} to the boot modules"),
+	("progress.remove-boot", "removing {} from the boot modules"),
+	("action.help", "help"),
+	("action.previous_block", "switch to the previous block"),
+	("action.next_block", "switch to the next block"),
+	(
+		"action.switch_focus",
+		"switch between the module list and kernel activities",
+	),
+	("action.scroll_up", "scroll up [selected block]"),
+	("action.scroll_down", "scroll down [selected block]"),
+	(
+		"action.smooth_scroll_up",
+		"scroll up smoothly [selected block]",
+	),
+	(
+		"action.smooth_scroll_down",
+		"scroll down smoothly [selected block]",
+	),
+	("action.scroll_log_up", "scroll up [kernel activities]"),
+	("action.scroll_log_down", "scroll down [kernel activities]"),
+	("action.scroll_log_left", "scroll left [kernel activities]"),
+	(
+		"action.scroll_log_right",
+		"scroll right [kernel activities]",
+	),
+	("action.scroll_info_up", "scroll up [module information]"),
+	(
+		"action.scroll_info_down",
+		"scroll down [module information]",
+	),
+	("action.scroll_top", "scroll to top [module list]"),
+	("action.scroll_bottom", "scroll to bottom [module list]"),
+	("action.expand_block", "expand the selected block"),
+	("action.shrink_block", "shrink the selected block"),
+	("action.block_position", "change the block position"),
+	("action.clear", "clear the kernel ring buffer"),
+	("action.dependent", "show the dependent modules"),
+	("action.next_info", "show the next kernel information"),
+	("action.search", "search a kernel module"),
+	("action.next_match", "jump to the next match"),
+	("action.previous_match", "jump to the previous match"),
+	("action.filter", "filter the modules (all/in-use/unused)"),
+	("action.size_filter", "filter the modules by size (e.g. >1M)"),
+	("action.builtin", "show/hide the built-in modules"),
+	("action.hide_used", "show/hide the modules in use"),
+	("action.bookmark", "bookmark the module"),
+	("action.bookmarks", "show only the bookmarked modules"),
+	("action.group", "expand/collapse the module group"),
+	("action.groups", "expand/collapse all the module groups"),
+	("action.size_column", "show/hide the size column"),
+	("action.used_by_column", "show/hide the used by column"),
+	("action.sort", "sort the modules (name/size/refcount)"),
+	("action.sort_direction", "toggle the sort direction"),
+	("action.size_format", "toggle the module size format"),
+	("action.log_level", "cycle the minimum log level"),
+	("action.save_logs", "save the kernel activities to a file"),
+	("action.follow", "follow the new kernel activities"),
+	("action.reverse_logs", "show the newest kernel activities first"),
+	("action.snapshot", "save a snapshot of the loaded modules"),
+	("action.load", "load a kernel module"),
+	(
+		"action.load_file",
+		"load the kernel modules listed in a file",
+	),
+	("action.mark", "select the module for batch commands"),
+	("action.unload", "unload the kernel module"),
+	("action.force_unload", "force unload the kernel module"),
+	("action.blacklist", "blacklist the kernel module"),
+	(
+		"action.unblacklist",
+		"remove the kernel module from blacklist",
+	),
+	("action.add_boot", "load the kernel module at boot"),
+	(
+		"action.remove_boot",
+		"do not load the kernel module at boot",
+	),
+	("action.reload", "reload the kernel module"),
+	("action.depmod", "update the module dependencies"),
+	("action.undo", "undo the last module command"),
+	("action.options", "show the options menu"),
+	("action.execute", "execute the command"),
+	(
+		"action.execute_recursive",
+		"unload with the dependent modules",
+	),
+	("action.cancel", "cancel the command"),
+	("action.copy", "copy"),
+	(
+		"action.copy_info",
+		"copy the module information as markdown",
+	),
+	(
+		"action.copy_command",
+		"copy the command of the current action",
+	),
+	("action.open_docs", "open the module documentation"),
+	("action.paste", "paste"),
+	("action.refresh", "refresh"),
+	("action.pause", "pause/resume the auto-refresh"),
+	("action.palette", "show the command palette"),
+	("action.quit", "quit"),
+];
+
+/* Turkish messages */
+const TR: &[(&str, &str)] = &[
+	("title.modules", "Yüklü Çekirdek Modülleri"),
+	("title.activities", "Çekirdek Etkinlikleri"),
+	("title.help", "Yardım"),
+	("title.palette", "Komut Paleti"),
+	("header.module", "Modül"),
+	("header.size", "Boyut"),
+	("header.used_by", "Kullanan"),
+	("help.dependent", "bağımlı modüle git"),
+	("prompt.execute", "Aşağıdaki komut çalıştırılsın mı?"),
+	(
+		"prompt.execute_batch",
+		"Aşağıdaki komutlar çalıştırılsın mı?",
+	),
+	("prompt.quit", "kmon kapatılsın mı? (y/n)"),
+	("status.read_only", "salt okunur kip"),
+	("status.too_small", "Uçbirim çok küçük"),
+	(
+		"status.unprivileged",
+		"Root olarak çalışılmıyor: modülleri yüklemek, kaldırmak ve kara listeye almak yetki gerektirir (kapatmak için bir tuşa basın)",
+	),
+	("status.find", "bul: {}"),
+	("status.find_no_match", "bul: {} (eşleşme yok)"),
+	("status.not_in_group", "modül bir grupta değil"),
+	("status.no_command", "kopyalanacak komut yok"),
+	("status.cancelled", "komut iptal edildi (zaman aşımı)"),
+	("status.updated", "{} önce güncellendi"),
+	("info.execution_failed", "Komut çalıştırılamadı:"),
+	("result.load", "{} yüklendi"),
+	("result.unload", "{} kaldırıldı"),
+	("result.force-unload", "{} zorla kaldırıldı"),
+	("result.reload", "{} yeniden yüklendi"),
+	("result.blacklist", "{} kara listeye alındı"),
+	("result.unblacklist", "{} kara listeden çıkarıldı"),
+	("result.clear", "halka arabelleği temizlendi"),
+	("result.depmod", "modül bağımlılıkları güncellendi"),
+	("result.add-boot", "{} açılış modüllerine eklendi"),
+	("result.remove-boot", "{} açılış modüllerinden çıkarıldı"),
+	("progress.load", "{} yükleniyor"),
+	("progress.unload", "{} kaldırılıyor"),
+	("progress.force-unload", "{} zorla kaldırılıyor"),
+	("progress.reload", "{} yeniden yükleniyor"),
+	("progress.blacklist", "{} kara listeye alınıyor"),
+	("progress.unblacklist", "{} kara listeden çıkarılıyor"),
+	("progress.clear", "halka arabelleği temizleniyor"),
+	("progress.depmod", "modül bağımlılıkları güncelleniyor"),
+	("progress.add-boot", "{} açılış modüllerine ekleniyor"),
+	("progress.remove-boot", "{} açılış modüllerinden çıkarılıyor"),
+	("action.help", "yardım"),
+	("action.previous_block", "önceki bloğa geç"),
+	("action.next_block", "sonraki bloğa geç"),
+	(
+		"action.switch_focus",
+		"modül listesi ve çekirdek etkinlikleri arasında geç",
+	),
+	("action.scroll_up", "yukarı kaydır [seçili blok]"),
+	("action.scroll_down", "aşağı kaydır [seçili blok]"),
+	(
+		"action.smooth_scroll_up",
+		"yumuşak yukarı kaydır [seçili blok]",
+	),
+	(
+		"action.smooth_scroll_down",
+		"yumuşak aşağı kaydır [seçili blok]",
+	),
+	(
+		"action.scroll_log_up",
+		"yukarı kaydır [çekirdek etkinlikleri]",
+	),
+	(
+		"action.scroll_log_down",
+		"aşağı kaydır [çekirdek etkinlikleri]",
+	),
+	(
+		"action.scroll_log_left",
+		"sola kaydır [çekirdek etkinlikleri]",
+	),
+	(
+		"action.scroll_log_right",
+		"sağa kaydır [çekirdek etkinlikleri]",
+	),
+	("action.scroll_info_up", "yukarı kaydır [modül bilgisi]"),
+	("action.scroll_info_down", "aşağı kaydır [modül bilgisi]"),
+	("action.scroll_top", "en üste git [modül listesi]"),
+	("action.scroll_bottom", "en alta git [modül listesi]"),
+	("action.expand_block", "seçili bloğu genişlet"),
+	("action.shrink_block", "seçili bloğu daralt"),
+	("action.block_position", "blok konumunu değiştir"),
+	("action.clear", "çekirdek halka arabelleğini temizle"),
+	("action.dependent", "bağımlı modülleri göster"),
+	("action.next_info", "sonraki çekirdek bilgisini göster"),
+	("action.search", "çekirdek modülü ara"),
+	("action.next_match", "sonraki eşleşmeye git"),
+	("action.previous_match", "önceki eşleşmeye git"),
+	(
+		"action.filter",
+		"modülleri süz (hepsi/kullanımda/kullanılmayan)",
+	),
+	("action.size_filter", "modülleri boyuta göre süz (örn. >1M)"),
+	("action.builtin", "yerleşik modülleri göster/gizle"),
+	("action.hide_used", "kullanımdaki modülleri göster/gizle"),
+	("action.bookmark", "modülü yer imlerine ekle"),
+	("action.bookmarks", "yalnızca yer imli modülleri göster"),
+	("action.group", "modül grubunu genişlet/daralt"),
+	("action.groups", "tüm modül gruplarını genişlet/daralt"),
+	("action.size_column", "boyut sütununu göster/gizle"),
+	("action.used_by_column", "kullanan sütununu göster/gizle"),
+	("action.sort", "modülleri sırala (ad/boyut/referans)"),
+	("action.sort_direction", "sıralama yönünü değiştir"),
+	("action.size_format", "modül boyutu biçimini değiştir"),
+	("action.log_level", "en düşük günlük seviyesini değiştir"),
+	("action.save_logs", "çekirdek etkinliklerini dosyaya kaydet"),
+	("action.follow", "yeni çekirdek etkinliklerini takip et"),
+	("action.reverse_logs", "en yeni çekirdek etkinliklerini önce göster"),
+	(
+		"action.snapshot",
+		"yüklü modüllerin anlık görüntüsünü kaydet",
+	),
+	("action.load", "çekirdek modülü yükle"),
+	(
+		"action.load_file",
+		"dosyada listelenen çekirdek modüllerini yükle",
+	),
+	("action.mark", "modülü toplu komutlar için seç"),
+	("action.unload", "çekirdek modülünü kaldır"),
+	("action.force_unload", "çekirdek modülünü zorla kaldır"),
+	("action.blacklist", "çekirdek modülünü kara listeye al"),
+	(
+		"action.unblacklist",
+		"çekirdek modülünü kara listeden çıkar",
+	),
+	("action.add_boot", "çekirdek modülünü açılışta yükle"),
+	("action.remove_boot", "çekirdek modülünü açılışta yükleme"),
+	("action.reload", "çekirdek modülünü yeniden yükle"),
+	("action.depmod", "modül bağımlılıklarını güncelle"),
+	("action.undo", "son modül komutunu geri al"),
+	("action.options", "seçenekler menüsünü göster"),
+	("action.execute", "komutu çalıştır"),
+	(
+		"action.execute_recursive",
+		"bağımlı modüllerle birlikte kaldır",
+	),
+	("action.cancel", "komutu iptal et"),
+	("action.copy", "kopyala"),
+	(
+		"action.copy_info",
+		"modül bilgisini markdown olarak kopyala",
+	),
+	("action.copy_command", "geçerli eylemin komutunu kopyala"),
+	("action.open_docs", "modül belgelerini aç"),
+	("action.paste", "yapıştır"),
+	("action.refresh", "yenile"),
+	("action.pause", "otomatik yenilemeyi duraklat/sürdür"),
+	("action.palette", "komut paletini göster"),
+	("action.quit", "çık"),
+];
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::keys::Action;
+	use enum_iterator::all;
+	#[test]
+	fn test_i18n() {
+		assert_eq!(Ok(Lang::Tr), Lang::try_from("tr_TR.UTF-8"));
+		assert_eq!(Ok(Lang::En), Lang::try_from("en_US"));
+		assert_eq!(Ok(Lang::En), Lang::try_from("C"));
+		assert!(Lang::try_from("xx").is_err());
+		assert_eq!("Yardım", text_in(Lang::Tr, "title.help"));
+		assert_eq!("Help", text_in(Lang::En, "title.help"));
+		assert_eq!("", text_in(Lang::Tr, "unknown"));
+		assert_eq!(
+			"bul: snd (eşleşme yok)",
+			format_in(Lang::Tr, "status.find_no_match", "snd")
+		);
+		assert_eq!("find: snd", format_in(Lang::En, "status.find", "snd"));
+		assert_eq!(
+			"5s önce güncellendi",
+			format_in(Lang::Tr, "status.updated", "5s")
+		);
+		for action in all::<Action>() {
+			let id = format!("action.{}", action.name());
+			assert!(!text_in(Lang::En, &id).is_empty());
+			assert!(!text_in(Lang::Tr, &id).is_empty());
+		}
+		for (id, _) in TR {
+			assert!(EN.iter().any(|(key, _)| key == id));
+		}
+		assert_eq!(EN.len(), TR.len());
+	}
+}
@@ -0,0 +1,120 @@
+use crate::util;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/* Blacklist file that is managed by kmon */
+pub const BLACKLIST_FILE: &str = "/etc/modprobe.d/kmon-blacklist.conf";
+
+/* Directories of the modprobe configuration files */
+pub const MODPROBE_DIRS: &[&str] = &["/etc/modprobe.d", "/run/modprobe.d"];
+
+/* Location of a 'blacklist' directive */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlacklistEntry {
+	pub path: PathBuf,
+	pub line: usize,
+}
+
+impl Display for BlacklistEntry {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{}", self.path.display(), self.line)
+	}
+}
+
+/**
+ * Normalize the module name for comparing it with the listed modules.
+ *
+ * Dashes and underscores are interchangeable in the module names.
+ *
+ * @param  module_name
+ * @return String
+ */
+pub fn normalize_name(module_name: &str) -> String {
+	module_name.replace('-', "_")
+}
+
+/**
+ * Read the blacklisted modules from the configuration files in given directories.
+ *
+ * @param  dirs
+ * @return BTreeMap
+ */
+pub fn read_blacklist<P: AsRef<Path>>(
+	dirs: &[P],
+) -> BTreeMap<String, Vec<BlacklistEntry>> {
+	let mut blacklist = BTreeMap::<String, Vec<BlacklistEntry>>::new();
+	for dir in dirs {
+		let mut paths = util::read_dir(dir)
+			.map(|paths| {
+				paths
+					.into_iter()
+					.filter(|path| path.extension().is_some_and(|v| v == "conf"))
+					.collect::<Vec<PathBuf>>()
+			})
+			.unwrap_or_default();
+		paths.sort();
+		for path in paths {
+			let Ok(contents) = util::read_file(&path) else {
+				continue;
+			};
+			for (i, line) in contents.lines().enumerate() {
+				let mut values = line.split_whitespace();
+				if let (Some("blacklist"), Some(module_name)) =
+					(values.next(), values.next())
+				{
+					blacklist
+						.entry(normalize_name(module_name))
+						.or_default()
+						.push(BlacklistEntry {
+							path: path.to_path_buf(),
+							line: i + 1,
+						});
+				}
+			}
+		}
+	}
+	blacklist
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs;
+	#[test]
+	fn test_blacklist() {
+		let dir = std::env::temp_dir().join("kmon-test-blacklist");
+		fs::create_dir_all(&dir).unwrap();
+		fs::write(
+			dir.join("a.conf"),
+			"# blacklist pcspkr\nblacklist pcspkr\ninstall pcspkr /bin/false\n",
+		)
+		.unwrap();
+		fs::write(dir.join("b.conf"), "blacklist snd-pcsp\nblacklist pcspkr")
+			.unwrap();
+		fs::write(dir.join("c.txt"), "blacklist nouveau\n").unwrap();
+		let blacklist = read_blacklist(&[&dir, &dir.join("nonexistent")]);
+		assert_eq!(
+			vec!["pcspkr", "snd_pcsp"],
+			blacklist.keys().collect::<Vec<&String>>()
+		);
+		assert_eq!(
+			vec![
+				BlacklistEntry {
+					path: dir.join("a.conf"),
+					line: 2,
+				},
+				BlacklistEntry {
+					path: dir.join("b.conf"),
+					line: 2,
+				},
+			],
+			blacklist["pcspkr"]
+		);
+		assert_eq!(
+			format!("{}:1", dir.join("b.conf").display()),
+			blacklist["snd_pcsp"][0].to_string()
+		);
+		fs::remove_dir_all(dir).unwrap();
+	}
+}
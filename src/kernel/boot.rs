@@ -0,0 +1,123 @@
+use crate::kernel::blacklist::normalize_name;
+use crate::util;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/* File of the modules that are loaded at boot and managed by kmon */
+pub const BOOT_FILE: &str = "/etc/modules-load.d/kmon.conf";
+
+/* Directories of the module lists that are loaded at boot (in priority order) */
+pub const MODULES_LOAD_DIRS: &[&str] = &[
+	"/etc/modules-load.d",
+	"/run/modules-load.d",
+	"/usr/lib/modules-load.d",
+];
+
+/* Module list of the Debian based distributions that is loaded at boot */
+pub const ETC_MODULES: &str = "/etc/modules";
+
+/**
+ * Parse the module names from the contents of a module list.
+ *
+ * Empty lines and the lines starting with '#' or ';' are ignored. Module
+ * parameters that follow the name (in '/etc/modules') are skipped.
+ *
+ * @param  contents
+ * @return Vec
+ */
+pub fn parse_module_names(contents: &str) -> Vec<String> {
+	contents
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.starts_with('#') && !line.starts_with(';'))
+		.filter_map(|line| line.split_whitespace().next())
+		.map(normalize_name)
+		.collect()
+}
+
+/**
+ * Read the modules that are loaded at boot from the given directories and files.
+ *
+ * Files that have the same name in a directory with a higher priority
+ * override the ones in the following directories.
+ *
+ * @param  dirs
+ * @param  files
+ * @return BTreeMap
+ */
+pub fn read_boot_modules<P: AsRef<Path>>(
+	dirs: &[P],
+	files: &[P],
+) -> BTreeMap<String, Vec<PathBuf>> {
+	let mut paths = BTreeMap::<String, PathBuf>::new();
+	for dir in dirs {
+		for path in util::read_dir(dir)
+			.unwrap_or_default()
+			.into_iter()
+			.filter(|path| path.extension().is_some_and(|v| v == "conf"))
+		{
+			if let Some(file_name) = path.file_name() {
+				paths
+					.entry(file_name.to_string_lossy().to_string())
+					.or_insert(path);
+			}
+		}
+	}
+	let mut boot_modules = BTreeMap::<String, Vec<PathBuf>>::new();
+	for path in paths
+		.into_values()
+		.chain(files.iter().map(|v| v.as_ref().to_path_buf()))
+	{
+		let Ok(contents) = util::read_file(&path) else {
+			continue;
+		};
+		for module_name in parse_module_names(&contents) {
+			let entries = boot_modules.entry(module_name).or_default();
+			if !entries.contains(&path) {
+				entries.push(path.to_path_buf());
+			}
+		}
+	}
+	boot_modules
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs;
+	#[test]
+	fn test_boot() {
+		assert_eq!(
+			vec!["snd_pcsp", "loop", "i2c_dev"],
+			parse_module_names(
+				"# comment\n; comment\n\nsnd-pcsp\n  loop max_loop=8\ni2c_dev\n"
+			)
+		);
+		let dir = std::env::temp_dir().join("kmon-test-boot");
+		for sub_dir in ["etc", "lib"] {
+			fs::create_dir_all(dir.join(sub_dir)).unwrap();
+		}
+		fs::write(dir.join("etc").join("a.conf"), "loop\n").unwrap();
+		fs::write(dir.join("lib").join("a.conf"), "nvidia\n").unwrap();
+		fs::write(dir.join("lib").join("b.conf"), "loop\nuinput\n").unwrap();
+		fs::write(dir.join("lib").join("c.txt"), "nouveau\n").unwrap();
+		fs::write(dir.join("modules"), "# comment\nloop\n").unwrap();
+		let boot_modules = read_boot_modules(
+			&[dir.join("etc"), dir.join("lib"), dir.join("nonexistent")],
+			&[dir.join("modules")],
+		);
+		assert_eq!(
+			vec!["loop", "uinput"],
+			boot_modules.keys().collect::<Vec<&String>>()
+		);
+		assert_eq!(
+			vec![
+				dir.join("etc").join("a.conf"),
+				dir.join("lib").join("b.conf"),
+				dir.join("modules")
+			],
+			boot_modules["loop"]
+		);
+		fs::remove_dir_all(dir).unwrap();
+	}
+}
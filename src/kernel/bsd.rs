@@ -0,0 +1,94 @@
+use crate::kernel::info::KernelInfo;
+use crate::kernel::lkm::{ListArgs, Module};
+use crate::kernel::log::LogSource;
+use crate::kernel::source::{self, ModuleSource};
+use crate::util::{self, CmdError};
+use std::error::Error;
+
+/**
+ * Parse the kernel modules from the output of 'kldstat'.
+ *
+ * Columns are the file id, reference count, load address, size (in hex)
+ * and the file name. The kernel itself is listed as the first file.
+ *
+ * @param  output
+ * @return Vec
+ */
+pub fn parse_kldstat(output: &str) -> Vec<Module> {
+	output
+		.lines()
+		.filter_map(|line| {
+			let columns = line.split_whitespace().collect::<Vec<&str>>();
+			let [id, refs, _, size, name] = columns[..] else {
+				return None;
+			};
+			id.parse::<u64>().ok()?;
+			Some(Module {
+				name: name.trim_end_matches(".ko").to_string(),
+				size: u64::from_str_radix(size, 16).ok()?,
+				refcount: refs.parse().ok()?,
+				..Module::default()
+			})
+		})
+		.collect()
+}
+
+/* Kernel modules and logs of FreeBSD that are read with 'kldstat' */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BsdSource;
+
+impl ModuleSource for BsdSource {
+	fn list_modules(&self, args: &ListArgs) -> Result<Vec<Module>, Box<dyn Error>> {
+		Ok(source::apply_list_args(
+			parse_kldstat(&util::exec_cmd("kldstat", &[])?),
+			args,
+		))
+	}
+
+	fn module_info(&self, module_name: &str) -> Result<String, CmdError> {
+		util::exec_cmd("kldstat", &["-v", "-n", module_name])
+	}
+
+	fn holders(&self, _: &str) -> Option<Vec<String>> {
+		None
+	}
+
+	fn parameters(&self, _: &str) -> Vec<(String, Option<String>)> {
+		Vec::new()
+	}
+
+	fn kernel_log(&self, _: LogSource, _: bool) -> String {
+		util::exec_cmd("dmesg", &[])
+			.unwrap_or_else(|_| String::from("failed to retrieve dmesg output"))
+	}
+
+	fn kernel_info(&self) -> Vec<Vec<String>> {
+		KernelInfo::uname_infos()
+	}
+
+	fn kernel_release(&self) -> Option<String> {
+		util::exec_cmd("uname", &["-r"])
+			.ok()
+			.map(|v| v.trim().to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	#[test]
+	fn test_bsd() {
+		let modules = parse_kldstat(
+			"Id Refs Address                Size Name
+			 1   23 0xffffffff80200000  1f3e2d8 kernel
+			 2    1 0xffffffff82120000     3390 accf_http.ko
+			invalid line\n",
+		);
+		assert_eq!(2, modules.len());
+		assert_eq!("kernel", modules[0].name);
+		assert_eq!(23, modules[0].refcount);
+		assert_eq!("accf_http", modules[1].name);
+		assert_eq!(0x3390, modules[1].size);
+		assert_eq!("live", modules[1].initstate());
+	}
+}
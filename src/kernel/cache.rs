@@ -0,0 +1,108 @@
+use std::collections::{HashMap, VecDeque};
+
+/* Bounded cache that evicts the least recently used entries */
+#[derive(Clone, Debug)]
+pub struct LruCache<V> {
+	entries: HashMap<String, V>,
+	order: VecDeque<String>,
+	capacity: usize,
+}
+
+impl<V: Clone> LruCache<V> {
+	/**
+	 * Create a new cache with the given capacity.
+	 *
+	 * @param  capacity
+	 * @return LruCache
+	 */
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			entries: HashMap::new(),
+			order: VecDeque::new(),
+			capacity: capacity.max(1),
+		}
+	}
+
+	/**
+	 * Get the cached value or insert the value that is returned from closure.
+	 *
+	 * @param  key
+	 * @param  f
+	 * @return value
+	 */
+	pub fn get_or_insert_with<F>(&mut self, key: &str, f: F) -> V
+	where
+		F: FnOnce() -> V,
+	{
+		if let Some(value) = self.entries.get(key) {
+			let value = value.clone();
+			if let Some(pos) = self.order.iter().position(|k| k == key) {
+				if let Some(k) = self.order.remove(pos) {
+					self.order.push_back(k);
+				}
+			}
+			return value;
+		}
+		let value = f();
+		if self.entries.len() >= self.capacity {
+			if let Some(k) = self.order.pop_front() {
+				self.entries.remove(&k);
+			}
+		}
+		self.entries.insert(key.to_string(), value.clone());
+		self.order.push_back(key.to_string());
+		value
+	}
+
+	/* Remove all the entries from the cache. */
+	pub fn clear(&mut self) {
+		self.entries.clear();
+		self.order.clear();
+	}
+
+	/**
+	 * Get the number of the cached entries.
+	 *
+	 * @return usize
+	 */
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/**
+	 * Check if the cache is empty.
+	 *
+	 * @return bool
+	 */
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	#[test]
+	fn test_lru_cache() {
+		let mut cache = LruCache::new(2);
+		let mut calls = 0;
+		for _ in 0..2 {
+			assert_eq!(
+				"a1",
+				cache.get_or_insert_with("a", || {
+					calls += 1;
+					String::from("a1")
+				})
+			);
+		}
+		assert_eq!(1, calls);
+		cache.get_or_insert_with("b", || String::from("b1"));
+		cache.get_or_insert_with("a", || String::from("a2"));
+		cache.get_or_insert_with("c", || String::from("c1"));
+		assert_eq!(2, cache.len());
+		assert_eq!("a1", cache.get_or_insert_with("a", || String::from("a3")));
+		assert_eq!("b2", cache.get_or_insert_with("b", || String::from("b2")));
+		cache.clear();
+		assert!(cache.is_empty());
+	}
+}
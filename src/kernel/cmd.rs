@@ -1,4 +1,18 @@
+use crate::config::{
+	BlacklistConfig, BootConfig, Config, ConfirmConfig, HooksConfig,
+};
+use crate::i18n::{self, Lang};
+use crate::kernel::demo;
 use crate::style::Symbol;
+use crate::util::{self, CmdError, CmdOutput};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::Duration;
+
+/* Timeout of the module management commands */
+const EXEC_TIMEOUT: Duration = Duration::from_secs(60);
 
 /* Kernel module related command */
 #[derive(Debug)]
@@ -42,15 +56,481 @@ impl Command {
 	}
 }
 
+/* Tools that are used for loading and unloading modules */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Loader {
+	#[cfg_attr(not(target_os = "freebsd"), default)]
+	Modprobe,
+	Insmod,
+	#[cfg(target_os = "freebsd")]
+	#[default]
+	Kld,
+}
+
+impl TryFrom<&str> for Loader {
+	type Error = ();
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		match s {
+			"modprobe" => Ok(Self::Modprobe),
+			"insmod" => Ok(Self::Insmod),
+			#[cfg(target_os = "freebsd")]
+			"kld" => Ok(Self::Kld),
+			_ => Err(()),
+		}
+	}
+}
+
+/* Tools that are used for running the commands with root privileges */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivilegeCmd {
+	#[default]
+	None,
+	Sudo,
+	Doas,
+	Pkexec,
+}
+
+impl TryFrom<&str> for PrivilegeCmd {
+	type Error = ();
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		match s {
+			"none" => Ok(Self::None),
+			"sudo" => Ok(Self::Sudo),
+			"doas" => Ok(Self::Doas),
+			"pkexec" => Ok(Self::Pkexec),
+			_ => Err(()),
+		}
+	}
+}
+
+impl PrivilegeCmd {
+	/**
+	 * Get the executable of the tool.
+	 *
+	 * @return Option
+	 */
+	pub fn executable(self) -> Option<&'static str> {
+		match self {
+			Self::None => None,
+			Self::Sudo => Some("sudo"),
+			Self::Doas => Some("doas"),
+			Self::Pkexec => Some("pkexec"),
+		}
+	}
+
+	/**
+	 * Detect the tool to use if kmon is not running as root.
+	 *
+	 * 'sudo' is preferred over 'doas' if both of them are installed.
+	 *
+	 * @return PrivilegeCmd
+	 */
+	pub fn detect() -> Self {
+		if Self::is_root() {
+			return Self::None;
+		}
+		[Self::Sudo, Self::Doas]
+			.into_iter()
+			.find(|v| v.is_available())
+			.unwrap_or_default()
+	}
+
+	/**
+	 * Get the installed tool for retrying a command that is not permitted.
+	 *
+	 * @return PrivilegeCmd (Option)
+	 */
+	pub fn escalation() -> Option<Self> {
+		if Self::is_root() {
+			return None;
+		}
+		[Self::Sudo, Self::Doas, Self::Pkexec]
+			.into_iter()
+			.find(|v| v.is_available())
+	}
+
+	/**
+	 * Check if the commands that modify the modules cannot gain root privileges.
+	 *
+	 * @return bool
+	 */
+	pub fn is_unprivileged(self) -> bool {
+		self == Self::None && !Self::is_root() && Self::escalation().is_none()
+	}
+
+	/**
+	 * Check if kmon is running as root.
+	 *
+	 * The effective UID is read from '/proc/self/status' and 'id' is used
+	 * as a fallback if it is not available.
+	 *
+	 * @return bool
+	 */
+	fn is_root() -> bool {
+		match util::read_file("/proc/self/status")
+			.ok()
+			.and_then(|status| parse_effective_uid(&status))
+		{
+			Some(uid) => uid == 0,
+			None => util::exec_cmd("id", &["-u"]).is_ok_and(|uid| uid.trim() == "0"),
+		}
+	}
+
+	/**
+	 * Check if the tool is installed.
+	 *
+	 * @return bool
+	 */
+	pub fn is_available(self) -> bool {
+		self.executable().is_none_or(|cmd| match util::remote() {
+			Some(_) => {
+				util::exec_cmd("sh", &["-c", &format!("command -v {cmd}")]).is_ok()
+			}
+			None => util::is_executable_in_path(cmd),
+		})
+	}
+
+	/**
+	 * Prefix the given shell command with the tool.
+	 *
+	 * Password prompts are disabled since the terminal is used by the interface.
+	 *
+	 * @param  cmd
+	 * @return String
+	 */
+	pub fn wrap(self, cmd: &str) -> String {
+		let prefix = match self {
+			_ if cmd.is_empty() => return cmd.to_string(),
+			Self::None => return cmd.to_string(),
+			Self::Sudo => "sudo -n",
+			Self::Doas => "doas -n",
+			Self::Pkexec => "pkexec",
+		};
+		format!("{prefix} sh -c {}", ModuleCommand::quote(cmd))
+	}
+}
+
+/* Properties of the module management commands */
+#[derive(Clone, Debug, Default)]
+pub struct CommandArgs {
+	pub loader: Loader,
+	pub privilege: PrivilegeCmd,
+	pub blacklist: BlacklistConfig,
+	pub boot: BootConfig,
+	pub hooks: HooksConfig,
+	pub confirm: ConfirmConfig,
+	pub dry_run: bool,
+	pub demo: bool,
+	pub read_only: bool,
+	pub stop_on_error: bool,
+	pub select_after_load: bool,
+}
+
+impl CommandArgs {
+	/**
+	 * Create a new command arguments instance.
+	 *
+	 * @param  Config
+	 * @return CommandArgs
+	 */
+	pub fn new(config: &Config) -> Self {
+		Self {
+			loader: config.loader,
+			privilege: config.privilege_cmd.unwrap_or_else(PrivilegeCmd::detect),
+			blacklist: config.blacklist.clone(),
+			boot: config.boot.clone(),
+			hooks: config.hooks.clone(),
+			confirm: config.confirm.clone(),
+			dry_run: config.dry_run,
+			demo: config.demo,
+			read_only: config.read_only,
+			stop_on_error: config.stop_on_error,
+			select_after_load: config.select_after_load,
+		}
+	}
+
+	/**
+	 * Run the given shell command unless dry run is enabled.
+	 *
+	 * Commands are not executed and reported as successful in dry run.
+	 * Results are simulated in demo mode.
+	 *
+	 * @param  cmd
+	 * @return Result
+	 */
+	pub fn run(&self, cmd: &str) -> Result<CmdOutput, CmdError> {
+		if self.demo {
+			Ok(demo::run(cmd))
+		} else if self.dry_run {
+			Ok(CmdOutput {
+				code: Some(0),
+				..CmdOutput::default()
+			})
+		} else {
+			util::exec_cmd_status("sh", &["-c", cmd], EXEC_TIMEOUT)
+		}
+	}
+
+	/**
+	 * Get the hooks of the given command for the changed modules.
+	 *
+	 * The module dependencies are updated once after all the hooks.
+	 *
+	 * @param  command
+	 * @param  module_names
+	 * @return Vec
+	 */
+	pub fn hook_cmds(
+		&self,
+		command: ModuleCommand,
+		module_names: &[&str],
+	) -> Vec<String> {
+		let mut cmds = module_names
+			.iter()
+			.flat_map(|v| command.hook_cmds(v, &self.hooks))
+			.collect::<Vec<String>>();
+		if self.hooks.depmod
+			&& !module_names.is_empty()
+			&& matches!(
+				command,
+				ModuleCommand::Load
+					| ModuleCommand::Unload
+					| ModuleCommand::ForceUnload
+					| ModuleCommand::Reload
+			) {
+			cmds.push(ModuleCommand::Depmod.get_with_params("", &[], self).cmd);
+		}
+		cmds
+	}
+
+	/**
+	 * Run the hooks of the given command after it is executed successfully.
+	 *
+	 * Remaining hooks are still run after a failure and all the failures
+	 * are reported together, but the executed command is not reverted.
+	 *
+	 * @param  command
+	 * @param  module_names
+	 * @return Result
+	 */
+	pub fn run_hooks(
+		&self,
+		command: ModuleCommand,
+		module_names: &[&str],
+	) -> Result<(), String> {
+		let errors = self
+			.hook_cmds(command, module_names)
+			.into_iter()
+			.filter_map(|cmd| match self.run(&cmd) {
+				Ok(output) if output.success() => None,
+				Ok(output) => Some(format!(
+					"hook '{cmd}' failed: {}",
+					output
+						.stderr
+						.lines()
+						.next()
+						.map(String::from)
+						.unwrap_or_else(|| format!("exit code: {:?}", output.code))
+				)),
+				Err(e) => Some(format!("hook '{cmd}' failed: {e}")),
+			})
+			.collect::<Vec<String>>();
+		if errors.is_empty() {
+			Ok(())
+		} else {
+			Err(errors.join(", "))
+		}
+	}
+}
+
+/* Status of a module command in a batch */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchStatus {
+	Done,
+	Failed(CmdError),
+	Skipped,
+}
+
+/* Result of a module command and its hooks that are executed in the background */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecutionResult {
+	Single(Result<CmdOutput, CmdError>, Result<(), String>),
+	Batch(Vec<BatchResult>, Result<(), String>),
+}
+
+impl ExecutionResult {
+	/**
+	 * Execute the module command and run the hooks of the changed modules.
+	 *
+	 * The batch command is executed for each module if it is not empty.
+	 *
+	 * @param  command
+	 * @param  module_name
+	 * @param  cmd
+	 * @param  batch
+	 * @param  args
+	 * @return ExecutionResult
+	 */
+	pub fn execute(
+		command: ModuleCommand,
+		module_name: &str,
+		cmd: &str,
+		batch: &[String],
+		args: &CommandArgs,
+	) -> Self {
+		if batch.is_empty() {
+			let result = args.run(cmd);
+			let hooks = match &result {
+				Ok(output) if output.success() && !args.dry_run => {
+					args.run_hooks(command, &[module_name])
+				}
+				_ => Ok(()),
+			};
+			Self::Single(result, hooks)
+		} else {
+			let results = command.run_batch(batch, args);
+			let hooks = args.run_hooks(
+				command,
+				&results
+					.iter()
+					.filter(|v| v.status == BatchStatus::Done)
+					.map(|v| v.name.as_str())
+					.collect::<Vec<&str>>(),
+			);
+			Self::Batch(results, hooks)
+		}
+	}
+}
+
+/* Result of a module command in a batch */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchResult {
+	pub name: String,
+	pub cmd: String,
+	pub status: BatchStatus,
+}
+
+impl BatchResult {
+	/**
+	 * Describe the result for showing it to the user.
+	 *
+	 * Commands are shown instead of their status in dry run.
+	 *
+	 * @param  dry_run
+	 * @return String
+	 */
+	pub fn describe(&self, dry_run: bool) -> String {
+		match &self.status {
+			BatchStatus::Done if dry_run => format!("{}: {}", self.name, self.cmd),
+			BatchStatus::Done => format!("{}: done", self.name),
+			BatchStatus::Failed(CmdError::Status { code, stderr })
+				if stderr.trim().is_empty() =>
+			{
+				format!("{}: failed (exit code: {code:?})", self.name)
+			}
+			BatchStatus::Failed(e) => format!(
+				"{}: failed ({})",
+				self.name,
+				e.to_string().lines().next().unwrap_or_default()
+			),
+			BatchStatus::Skipped => format!("{}: skipped", self.name),
+		}
+	}
+
+	/**
+	 * Summarize the given batch results.
+	 *
+	 * @param  results
+	 * @return String
+	 */
+	pub fn summary(results: &[Self]) -> String {
+		let count = |f: fn(&BatchStatus) -> bool| {
+			results.iter().filter(|v| f(&v.status)).count()
+		};
+		format!(
+			"{} done, {} failed, {} skipped",
+			count(|v| *v == BatchStatus::Done),
+			count(|v| matches!(v, BatchStatus::Failed(_))),
+			count(|v| *v == BatchStatus::Skipped)
+		)
+	}
+}
+
+/**
+ * Parse the module names from the contents of a module list file.
+ *
+ * Module names are given one per line and '#' starts a comment.
+ * Each line is either a module name or the path of an existing '.ko' file
+ * since the names are used in the commands that are executed as root.
+ *
+ * @param  contents
+ * @return Result
+ */
+pub fn parse_module_list(contents: &str) -> Result<Vec<String>, String> {
+	let mut names = Vec::new();
+	for (i, line) in contents.lines().enumerate() {
+		let line = line.split('#').next().unwrap_or_default().trim();
+		if line.is_empty() {
+			continue;
+		}
+		let is_name = line
+			.chars()
+			.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+		let is_file =
+			ModuleCommand::is_module_filename(line) && Path::new(line).is_file();
+		if !(is_name || is_file) {
+			return Err(format!("invalid module name at line {}: '{line}'", i + 1));
+		}
+		names.push(line.to_string());
+	}
+	Ok(names)
+}
+
+/**
+ * Read the module names from the given module list file.
+ *
+ * @param  path
+ * @return Result
+ */
+pub fn read_module_list(path: &Path) -> Result<Vec<String>, String> {
+	fs::read_to_string(path)
+		.map_err(|e| e.to_string())
+		.and_then(|contents| parse_module_list(&contents))
+		.map_err(|e| {
+			format!("failed to read the module list ({}): {e}", path.display())
+		})
+}
+
+/**
+ * Parse the effective user ID from the contents of '/proc/<pid>/status'.
+ *
+ * @param  status
+ * @return uid (Option)
+ */
+fn parse_effective_uid(status: &str) -> Option<u32> {
+	status
+		.lines()
+		.find_map(|line| line.strip_prefix("Uid:"))
+		.and_then(|uids| uids.split_whitespace().nth(1))
+		.and_then(|uid| uid.parse().ok())
+}
+
 /* Kernel module management commands */
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ModuleCommand {
 	None,
 	Load,
 	Unload,
+	ForceUnload,
 	Reload,
 	Blacklist,
+	Unblacklist,
 	Clear,
+	Depmod,
+	AddBoot,
+	RemoveBoot,
 }
 
 impl TryFrom<String> for ModuleCommand {
@@ -59,15 +539,97 @@ impl TryFrom<String> for ModuleCommand {
 		match s.as_ref() {
 			"load" => Ok(Self::Load),
 			"unload" => Ok(Self::Unload),
+			"force-unload" => Ok(Self::ForceUnload),
 			"reload" => Ok(Self::Reload),
 			"blacklist" => Ok(Self::Blacklist),
+			"unblacklist" => Ok(Self::Unblacklist),
 			"clear" => Ok(Self::Clear),
+			"depmod" => Ok(Self::Depmod),
+			"add-boot" => Ok(Self::AddBoot),
+			"remove-boot" => Ok(Self::RemoveBoot),
 			_ => Err(()),
 		}
 	}
 }
 
 impl ModuleCommand {
+	/**
+	 * Get the name of the command that is used in the options menu.
+	 *
+	 * @return str
+	 */
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::None => "none",
+			Self::Load => "load",
+			Self::Unload => "unload",
+			Self::ForceUnload => "force-unload",
+			Self::Reload => "reload",
+			Self::Blacklist => "blacklist",
+			Self::Unblacklist => "unblacklist",
+			Self::Clear => "clear",
+			Self::Depmod => "depmod",
+			Self::AddBoot => "add-boot",
+			Self::RemoveBoot => "remove-boot",
+		}
+	}
+
+	/**
+	 * Get the message that describes the successful execution of the command.
+	 *
+	 * @param  module_name
+	 * @return String
+	 */
+	pub fn result_message(self, module_name: &str) -> String {
+		self.message(i18n::lang(), "result", module_name)
+	}
+
+	/**
+	 * Get the message that describes the command while it is running.
+	 *
+	 * @param  module_name
+	 * @return String
+	 */
+	pub fn progress_message(self, module_name: &str) -> String {
+		self.message(i18n::lang(), "progress", module_name)
+	}
+
+	/**
+	 * Get the message of the given kind (e.g. 'result') in the given language.
+	 *
+	 * @param  lang
+	 * @param  kind
+	 * @param  module_name
+	 * @return String
+	 */
+	fn message(self, lang: Lang, kind: &str, module_name: &str) -> String {
+		match self {
+			Self::None => String::new(),
+			_ => i18n::format_in(
+				lang,
+				&format!("{kind}.{}", self.name()),
+				module_name,
+			),
+		}
+	}
+
+	/**
+	 * Get the command that reverts the effect of the command.
+	 *
+	 * @return ModuleCommand (Option)
+	 */
+	pub fn inverse(self) -> Option<Self> {
+		match self {
+			Self::Load => Some(Self::Unload),
+			Self::Unload | Self::ForceUnload => Some(Self::Load),
+			Self::Blacklist => Some(Self::Unblacklist),
+			Self::Unblacklist => Some(Self::Blacklist),
+			Self::AddBoot => Some(Self::RemoveBoot),
+			Self::RemoveBoot => Some(Self::AddBoot),
+			_ => None,
+		}
+	}
+
 	/**
 	 * Get Command struct from a enum element.
 	 *
@@ -75,19 +637,56 @@ impl ModuleCommand {
 	 * @return Command
 	 */
 	pub fn get(self, module_name: &str) -> Command {
-		match self {
+		self.get_with_params(module_name, &[], &CommandArgs::default())
+	}
+
+	/**
+	 * Get Command struct from a enum element with the given module parameters.
+	 *
+	 * Parameters are the configuration files for the unblacklist command.
+	 * Commands are prefixed with the tool for acquiring root privileges.
+	 *
+	 * @param  module_name
+	 * @param  params
+	 * @param  CommandArgs
+	 * @return Command
+	 */
+	pub fn get_with_params(
+		self,
+		module_name: &str,
+		params: &[String],
+		args: &CommandArgs,
+	) -> Command {
+		self.get_with_prerequisites(module_name, params, &[], args)
+	}
+
+	/**
+	 * Get Command struct that inserts the given module files before loading.
+	 *
+	 * Prerequisites are only used for the load command.
+	 *
+	 * @param  module_name
+	 * @param  params
+	 * @param  prerequisites
+	 * @param  CommandArgs
+	 * @return Command
+	 */
+	pub fn get_with_prerequisites(
+		self,
+		module_name: &str,
+		params: &[String],
+		prerequisites: &[String],
+		args: &CommandArgs,
+	) -> Command {
+		let mut command = match self {
             Self::None => Command::new(String::from(""), "", format!("Module: {module_name}"), Symbol::None),
             Self::Load => Command::new(
-                if Self::is_module_filename(module_name) {
-					format!("insmod {}", &module_name)
-				} else {
-					format!("modprobe {0} || insmod {0}.ko", &module_name)
-				},
+                Self::load_cmd(module_name, params, args.loader),
                 "Add and remove modules from the Linux Kernel\n
                 This command inserts a module to the kernel.",
                 format!("Load: {module_name}"), Symbol::Anchor),
             Self::Unload => Command::new(
-                format!("modprobe -r {0} || rmmod {0}", &module_name),
+                Self::unload_cmd(module_name, false, args.loader),
                 "modprobe/rmmod: Add and remove modules from the Linux Kernel
                 modprobe -r, --remove or rmmod\n
                 This option causes modprobe to remove rather than insert a module. \
@@ -98,18 +697,24 @@ impl ModuleCommand {
                 modules require it. Your distribution kernel may not have been \
                 built to support removal of modules at all.",
                 format!("Remove: {module_name}"), Symbol::CircleX),
+            Self::ForceUnload => Command::new(
+                Self::unload_cmd(module_name, true, args.loader),
+                "modprobe/rmmod: Add and remove modules from the Linux Kernel
+                option: -f, --force\n
+                This option can be extremely dangerous: it has no effect unless \
+                your kernel was compiled with CONFIG_MODULE_FORCE_UNLOAD. \
+                Forcing the removal of a module that is still in use can make \
+                the related devices unusable or crash the system.",
+                format!("Force Remove: {module_name}"), Symbol::CircleX),
             Self::Reload => Command::new(
                 format!("{} && {}",
-                    ModuleCommand::Unload.get(module_name).cmd,
-                    ModuleCommand::Load.get(module_name).cmd),
+                    Self::group(Self::unload_cmd(module_name, false, args.loader)),
+                    Self::group(Self::load_cmd(module_name, params, args.loader))),
                 "modprobe/insmod/rmmod: Add and remove modules from the Linux Kernel\n
                 This command reloads a module, removes and inserts to the kernel.",
                 format!("Reload: {module_name}"), Symbol::FuelPump),
-			Self::Blacklist => Command::new(
-				format!("if ! grep -q {module} /etc/modprobe.d/blacklist.conf; then
-				  echo 'blacklist {module}' >> /etc/modprobe.d/blacklist.conf
-				  echo 'install {module} /bin/false' >> /etc/modprobe.d/blacklist.conf
-				fi", module = &module_name),
+			Self::Blacklist if args.blacklist.hard => Command::new(
+				Self::blacklist_cmd(module_name, &args.blacklist.path.to_string_lossy(), true),
 				"This command blacklists a module and any other module that depends on it.\n
 				Blacklisting is a mechanism to prevent the kernel module from loading. \
 				This could be useful if, for example, the associated hardware is not needed, \
@@ -119,15 +724,506 @@ impl ModuleCommand {
 				depends on it or if it is loaded manually. However, there is a workaround for \
 				this behaviour; the install command instructs modprobe to run a custom command \
 				instead of inserting the module in the kernel as normal, so the module will \
-				always fail to load.",
+				always fail to load (hard blacklisting).",
+				format!("Blacklist: {module_name}"), Symbol::SquareX),
+			Self::Blacklist => Command::new(
+				Self::blacklist_cmd(module_name, &args.blacklist.path.to_string_lossy(), false),
+				"This command blacklists a module so that it is not loaded automatically.\n
+				Blacklisting is a mechanism to prevent the kernel module from loading. \
+				This could be useful if, for example, the associated hardware is not needed, \
+				or if loading that module causes problems.
+				Only the blacklist entry is written (soft blacklisting), so the module may \
+				still be loaded if another non-blacklisted module depends on it or if it \
+				is loaded manually. Set 'hard' in the [blacklist] section of the \
+				configuration file for adding an install entry that always fails the \
+				loading of the module.",
 				format!("Blacklist: {module_name}"), Symbol::SquareX),
+			Self::Unblacklist => Command::new(
+				Self::unblacklist_cmd(module_name, params),
+				"This command removes the module from the blacklist.\n
+				The 'blacklist' and 'install' entries of the module are deleted \
+				from the given modprobe configuration files, so that the module \
+				can be loaded automatically again.",
+				format!("Unblacklist: {module_name}"), Symbol::Anchor),
 			Self::Clear => Command::new(
 				String::from("dmesg --clear"),
 				"dmesg: Print or control the kernel ring buffer
 				option: -C, --clear\n
 				Clear the ring buffer.",
 				String::from("Clear"), Symbol::Cloud),
-        }
+			Self::Depmod => Command::new(
+				String::from("depmod -a"),
+				"depmod: Generate modules.dep and map files
+				option: -a, --all\n
+				Probe all modules in the module directory of the running kernel \
+				and update the dependency database that is used by modprobe. \
+				This is needed after installing or removing the module files, \
+				e.g. for loading an out-of-tree module by its name.",
+				String::from("Depmod"), Symbol::Gear),
+			Self::AddBoot => Command::new(
+				Self::add_boot_cmd(module_name, &args.boot.path),
+				"modules-load.d: Configure kernel modules to load at boot\n
+				This command adds the module to the module list that is managed by kmon, \
+				so that systemd-modules-load (or the init scripts that read the files of \
+				/etc/modules-load.d) loads the module statically at boot. \
+				The directory of the file is created if it does not exist.",
+				format!("Load at Boot: {module_name}"), Symbol::Anchor),
+			Self::RemoveBoot => Command::new(
+				Self::remove_boot_cmd(module_name, &args.boot.path),
+				"modules-load.d: Configure kernel modules to load at boot\n
+				This command removes the module from the module list that is managed by kmon, \
+				so that it is not loaded at boot anymore unless it is listed in another \
+				file or loaded automatically for a device.",
+				format!("Remove from Boot: {module_name}"), Symbol::CircleX),
+        };
+		if self == Self::Load && !prerequisites.is_empty() {
+			command.cmd = format!(
+				"{} && {}",
+				prerequisites
+					.iter()
+					.map(|path| format!("insmod {path}"))
+					.collect::<Vec<String>>()
+					.join(" && "),
+				command.cmd
+			);
+		}
+		command.cmd = args.privilege.wrap(&command.cmd);
+		command
+	}
+
+	/**
+	 * Get the command for loading the given module with parameters.
+	 *
+	 * @param  module_name
+	 * @param  params
+	 * @param  loader
+	 * @return String
+	 */
+	fn load_cmd(module_name: &str, params: &[String], loader: Loader) -> String {
+		/* Parameters are set as the kernel environment variables on FreeBSD. */
+		#[cfg(target_os = "freebsd")]
+		if loader == Loader::Kld {
+			return params
+				.iter()
+				.map(|param| format!("kenv {} && ", Self::quote_param(param)))
+				.chain([format!("kldload {}", Self::quote_name(module_name))])
+				.collect();
+		}
+		let params = params
+			.iter()
+			.map(|param| format!(" {}", Self::quote_param(param)))
+			.collect::<String>();
+		if Self::is_module_filename(module_name) {
+			format!("insmod {}{params}", Self::quote_name(module_name))
+		} else if loader == Loader::Insmod {
+			format!("modprobe {}{params}", Self::quote_name(module_name))
+		} else {
+			format!(
+				"modprobe {}{params} || insmod {}{params}",
+				Self::quote_name(module_name),
+				Self::quote_name(&format!("{module_name}.ko"))
+			)
+		}
+	}
+
+	/**
+	 * Quote the module name or path for using it in shell.
+	 *
+	 * Names that consist of the safe characters are kept as is for keeping
+	 * the commands readable.
+	 *
+	 * @param  module_name
+	 * @return String
+	 */
+	fn quote_name(module_name: &str) -> String {
+		if !module_name.is_empty()
+			&& module_name
+				.chars()
+				.all(|c| c.is_ascii_alphanumeric() || "_-+.,:/".contains(c))
+		{
+			module_name.to_string()
+		} else {
+			Self::quote(module_name)
+		}
+	}
+
+	/**
+	 * Quote the value of a module parameter for using it in shell.
+	 *
+	 * @param  param
+	 * @return String
+	 */
+	fn quote_param(param: &str) -> String {
+		match param.split_once('=') {
+			Some((key, value))
+				if !value
+					.chars()
+					.all(|c| c.is_ascii_alphanumeric() || "_-+.,:/".contains(c)) =>
+			{
+				format!("{key}={}", Self::quote(value))
+			}
+			_ => param.to_string(),
+		}
+	}
+
+	/**
+	 * Quote the given value for using it in shell.
+	 *
+	 * @param  value
+	 * @return String
+	 */
+	fn quote(value: &str) -> String {
+		util::shell_quote(value)
+	}
+
+	/**
+	 * Parse the module name and parameters from the input of load command.
+	 *
+	 * Parameters are given after the module name in 'param=value' format.
+	 *
+	 * @param  input
+	 * @return Result
+	 */
+	pub fn parse_load_input(input: &str) -> Result<(String, Vec<String>), String> {
+		let mut values = input.split_whitespace();
+		let module_name = values.next().unwrap_or_default().to_string();
+		let params = values
+			.map(|param| match param.split_once('=') {
+				Some((key, _))
+					if !key.is_empty()
+						&& key.chars().all(|c| {
+							c.is_ascii_alphanumeric() || "_-.".contains(c)
+						}) =>
+				{
+					Ok(param.to_string())
+				}
+				_ => Err(format!(
+					"invalid parameter '{param}' (expected 'param=value')"
+				)),
+			})
+			.collect::<Result<Vec<String>, String>>()?;
+		Ok((module_name, params))
+	}
+
+	/**
+	 * Get the command for unloading the given module.
+	 *
+	 * @param  module_name
+	 * @param  force
+	 * @param  loader
+	 * @return String
+	 */
+	fn unload_cmd(module_name: &str, force: bool, loader: Loader) -> String {
+		let force = if force { " -f" } else { "" };
+		let module_name = Self::quote_name(module_name);
+		match loader {
+			Loader::Modprobe => {
+				format!("modprobe -r{1} {0} || rmmod{1} {0}", module_name, force)
+			}
+			Loader::Insmod => format!("rmmod{force} {module_name}"),
+			#[cfg(target_os = "freebsd")]
+			Loader::Kld => format!("kldunload{force} {module_name}"),
+		}
+	}
+
+	/**
+	 * Group the alternatives of the given command with parentheses.
+	 *
+	 * This keeps the alternatives of loading from running after a failed unload.
+	 *
+	 * @param  cmd
+	 * @return String
+	 */
+	fn group(cmd: String) -> String {
+		if cmd.contains(" || ") {
+			format!("({cmd})")
+		} else {
+			cmd
+		}
+	}
+
+	/**
+	 * Get the command for adding the given module to the blacklist file.
+	 *
+	 * File is created if it does not exist and existing entries are not duplicated.
+	 * An 'install' entry is also written for preventing the manual loads if
+	 * the hard blacklisting is enabled.
+	 *
+	 * @param  module_name
+	 * @param  file
+	 * @param  hard
+	 * @return String
+	 */
+	fn blacklist_cmd(module_name: &str, file: &str, hard: bool) -> String {
+		let file = Self::quote(file);
+		let mut lines = vec![format!("blacklist {module_name}")];
+		if hard {
+			lines.push(format!("install {module_name} /bin/true"));
+		}
+		lines
+			.iter()
+			.map(|line| {
+				let line = Self::quote(line);
+				format!("(grep -qsxF {line} {file} || echo {line} >> {file})")
+			})
+			.collect::<Vec<String>>()
+			.join(" && ")
+	}
+
+	/**
+	 * Get the pattern that matches the given module name in the sed commands.
+	 *
+	 * Dashes and underscores are interchangeable in the module names.
+	 *
+	 * @param  module_name
+	 * @return String
+	 */
+	fn name_pattern(module_name: &str) -> String {
+		module_name
+			.chars()
+			.map(|c| match c {
+				'-' | '_' => String::from("[-_]"),
+				c if c.is_ascii_alphanumeric() => c.to_string(),
+				c => format!("\\{c}"),
+			})
+			.collect()
+	}
+
+	/**
+	 * Get the command for removing the given module from the blacklist files.
+	 *
+	 * @param  module_name
+	 * @param  files
+	 * @return String
+	 */
+	fn unblacklist_cmd(module_name: &str, files: &[String]) -> String {
+		let name = Self::name_pattern(module_name);
+		format!(
+			"sed -E -i \
+			-e '/^[[:space:]]*blacklist[[:space:]]+{name}[[:space:]]*(#.*)?$/d' \
+			-e '/^[[:space:]]*install[[:space:]]+{name}[[:space:]]+\\/bin\\/(false|true)/d'{}",
+			files
+				.iter()
+				.map(|file| format!(" {}", Self::quote(file)))
+				.collect::<String>()
+		)
+	}
+
+	/**
+	 * Get the command for adding the given module to the boot module list.
+	 *
+	 * @param  module_name
+	 * @param  file
+	 * @return String
+	 */
+	fn add_boot_cmd(module_name: &str, file: &Path) -> String {
+		let dir = Self::quote(
+			&file
+				.parent()
+				.map(|v| v.to_string_lossy().to_string())
+				.unwrap_or_else(|| String::from(".")),
+		);
+		let file = Self::quote(&file.to_string_lossy());
+		let line = Self::quote(module_name);
+		format!(
+			"mkdir -p {dir} && (grep -qsxF {line} {file} || echo {line} >> {file})"
+		)
+	}
+
+	/**
+	 * Get the command for removing the given module from the boot module list.
+	 *
+	 * @param  module_name
+	 * @param  file
+	 * @return String
+	 */
+	fn remove_boot_cmd(module_name: &str, file: &Path) -> String {
+		format!(
+			"sed -E -i '/^[[:space:]]*{}[[:space:]]*(#.*)?$/d' {}",
+			Self::name_pattern(module_name),
+			Self::quote(&file.to_string_lossy())
+		)
+	}
+
+	/**
+	 * Check if the executed command failed because the module is in use.
+	 *
+	 * @param  error
+	 * @return bool
+	 */
+	pub fn is_busy_error(self, error: &CmdError) -> bool {
+		match error {
+			CmdError::Status { stderr, .. } => {
+				let stderr = stderr.to_lowercase();
+				matches!(self, Self::Unload | Self::ForceUnload | Self::Reload)
+					&& (stderr.contains("in use")
+						|| stderr.contains("resource busy"))
+			}
+			_ => false,
+		}
+	}
+
+	/**
+	 * Check if the command failed since it is not permitted (EPERM).
+	 *
+	 * @param  error
+	 * @return bool
+	 */
+	pub fn is_permission_error(self, error: &CmdError) -> bool {
+		match error {
+			CmdError::Spawn {
+				kind: ErrorKind::PermissionDenied,
+				..
+			} => true,
+			CmdError::Status { stderr, .. } => {
+				let stderr = stderr.to_lowercase();
+				stderr.contains("operation not permitted")
+					|| stderr.contains("permission denied")
+			}
+			_ => false,
+		}
+	}
+
+	/**
+	 * Check if the command modifies the configuration files instead of the kernel.
+	 *
+	 * @return bool
+	 */
+	fn writes_config(self) -> bool {
+		matches!(
+			self,
+			Self::Blacklist | Self::Unblacklist | Self::AddBoot | Self::RemoveBoot
+		)
+	}
+
+	/**
+	 * Get a friendly explanation of the failure of the executed command.
+	 *
+	 * @param  error
+	 * @return Option
+	 */
+	pub fn get_error_hint(self, error: &CmdError) -> Option<&'static str> {
+		let stderr = match error {
+			CmdError::Status { stderr, .. } => stderr.to_lowercase(),
+			_ => String::new(),
+		};
+		let unload = matches!(self, Self::Unload | Self::ForceUnload | Self::Reload);
+		match error {
+			CmdError::Spawn {
+				kind: ErrorKind::NotFound,
+				..
+			}
+			| CmdError::Status {
+				code: Some(126 | 127),
+				..
+			} => Some("Command not found, make sure kmod is installed."),
+			CmdError::Timeout { .. } => Some(
+				"Command did not finish in time, the privilege command might be \
+				waiting for a password or the module might be stuck in its \
+				initialization.",
+			),
+			CmdError::Status { .. }
+				if stderr.contains("password is required")
+					|| stderr.contains("authentication required")
+					|| stderr.contains("not authorized") =>
+			{
+				Some(
+					"Root privileges could not be acquired without a password, \
+					cache the credentials (e.g. 'sudo -v') before running kmon.",
+				)
+			}
+			_ if self.is_permission_error(error) && self.writes_config() => Some(
+				"Writing the configuration files of modprobe and systemd requires \
+					root privileges, try running kmon with sudo or setting the \
+					privilege command.",
+			),
+			_ if self.is_permission_error(error) => Some(
+				"Managing kernel modules requires root privileges \
+					(CAP_SYS_MODULE), try running kmon with sudo \
+					or setting the privilege command.",
+			),
+			_ if self.is_busy_error(error) => Some(
+				"Module is in use by other modules or processes, unload them first \
+				or force unload the module.",
+			),
+			CmdError::Status { .. }
+				if unload && stderr.contains("not currently loaded") =>
+			{
+				Some("Module is not loaded.")
+			}
+			CmdError::Status { .. } if stderr.contains("not found") => {
+				Some("Module not found for the running kernel.")
+			}
+			_ => None,
+		}
+	}
+
+	/**
+	 * Run the command for each of the given modules in order.
+	 *
+	 * Remaining modules are skipped after a failure if 'stop_on_error' is set.
+	 *
+	 * @param  names
+	 * @param  CommandArgs
+	 * @return results
+	 */
+	pub fn run_batch(
+		self,
+		names: &[String],
+		args: &CommandArgs,
+	) -> Vec<BatchResult> {
+		let mut failed = false;
+		names
+			.iter()
+			.map(|name| {
+				let cmd = self.get_with_params(name, &[], args).cmd;
+				let status = if failed && args.stop_on_error {
+					BatchStatus::Skipped
+				} else {
+					match args.run(&cmd).and_then(CmdOutput::check) {
+						Ok(_) => BatchStatus::Done,
+						Err(e) => BatchStatus::Failed(e),
+					}
+				};
+				failed |= matches!(status, BatchStatus::Failed(_));
+				BatchResult {
+					name: name.to_string(),
+					cmd,
+					status,
+				}
+			})
+			.collect()
+	}
+
+	/**
+	 * Get the hook commands of the module command with the module name.
+	 *
+	 * '{module}' in the hook templates is replaced with the module name,
+	 * which is appended as an argument if the template does not contain it.
+	 *
+	 * @param  module_name
+	 * @param  hooks
+	 * @return Vec
+	 */
+	pub fn hook_cmds(self, module_name: &str, hooks: &HooksConfig) -> Vec<String> {
+		let templates = match self {
+			Self::Load => vec![&hooks.post_load],
+			Self::Unload | Self::ForceUnload => vec![&hooks.post_unload],
+			Self::Reload => vec![&hooks.post_unload, &hooks.post_load],
+			_ => Vec::new(),
+		};
+		let module_name = Self::quote(module_name);
+		templates
+			.into_iter()
+			.flatten()
+			.map(|template| {
+				if template.contains("{module}") {
+					template.replace("{module}", &module_name)
+				} else {
+					format!("{template} {module_name}")
+				}
+			})
+			.collect()
 	}
 
 	/**
@@ -155,8 +1251,13 @@ impl ModuleCommand {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use std::path::PathBuf;
 	#[test]
 	fn test_module_command() {
+		let mut args = CommandArgs {
+			loader: Loader::Insmod,
+			..CommandArgs::default()
+		};
 		let module_command = ModuleCommand::None;
 		assert!(module_command == ModuleCommand::None);
 
@@ -174,10 +1275,216 @@ mod tests {
 			ModuleCommand::Load.get("test-module.ko").cmd
 		);
 
+		assert_eq!(
+			"modprobe test dyndbg=+p || insmod test.ko dyndbg=+p",
+			ModuleCommand::Load
+				.get_with_params(
+					"test",
+					&[String::from("dyndbg=+p")],
+					&CommandArgs::default()
+				)
+				.cmd
+		);
+		assert_eq!(
+			"insmod test.ko a='x y' b='\'\\'\''",
+			ModuleCommand::Load
+				.get_with_params(
+					"test.ko",
+					&[String::from("a=x y"), String::from("b='")],
+					&args
+				)
+				.cmd
+		);
+		assert_eq!(
+			"modprobe test",
+			ModuleCommand::Load.get_with_params("test", &[], &args).cmd
+		);
+		assert_eq!(
+			"rmmod -f test",
+			ModuleCommand::ForceUnload
+				.get_with_params("test", &[], &args)
+				.cmd
+		);
+		assert_eq!(
+			"insmod /a.ko && insmod /b.ko && insmod test.ko",
+			ModuleCommand::Load
+				.get_with_prerequisites(
+					"test.ko",
+					&[],
+					&[String::from("/a.ko"), String::from("/b.ko")],
+					&args
+				)
+				.cmd
+		);
+		assert_eq!(
+			"rmmod test",
+			ModuleCommand::Unload
+				.get_with_prerequisites("test", &[], &[String::from("/a.ko")], &args)
+				.cmd
+		);
+		assert_eq!(Ok(Loader::Insmod), Loader::try_from("insmod"));
+		#[cfg(target_os = "freebsd")]
+		{
+			args.loader = Loader::Kld;
+			assert_eq!(
+				"kenv hw.ath.debug=1 && kldload if_ath.ko",
+				ModuleCommand::Load
+					.get_with_prerequisites(
+						"if_ath.ko",
+						&[String::from("hw.ath.debug=1")],
+						&[],
+						&args
+					)
+					.cmd
+			);
+			assert_eq!(
+				"kldunload -f if_ath",
+				ModuleCommand::ForceUnload
+					.get_with_prerequisites("if_ath", &[], &[], &args)
+					.cmd
+			);
+		}
+		#[cfg(not(target_os = "freebsd"))]
+		assert_eq!(Err(()), Loader::try_from("kld"));
+		args.loader = Loader::Insmod;
+		assert_eq!(Ok(PrivilegeCmd::Doas), PrivilegeCmd::try_from("doas"));
+		assert_eq!(
+			"sudo -n sh -c 'rmmod '\\''test'\\'''",
+			PrivilegeCmd::Sudo.wrap("rmmod 'test'")
+		);
+		assert_eq!("", PrivilegeCmd::Pkexec.wrap(""));
+		assert!(PrivilegeCmd::None.is_available());
+		args.privilege = PrivilegeCmd::Doas;
+		assert_eq!(
+			"doas -n sh -c 'rmmod test && modprobe test'",
+			ModuleCommand::Reload
+				.get_with_params("test", &[], &args)
+				.cmd
+		);
+		args.privilege = PrivilegeCmd::None;
+		args.blacklist.path = PathBuf::from("/tmp/kmon's.conf");
+		assert_eq!(
+			"(grep -qsxF 'blacklist test' '/tmp/kmon'\\''s.conf' || \
+			echo 'blacklist test' >> '/tmp/kmon'\\''s.conf') && \
+			(grep -qsxF 'install test /bin/true' '/tmp/kmon'\\''s.conf' || \
+			echo 'install test /bin/true' >> '/tmp/kmon'\\''s.conf')",
+			ModuleCommand::Blacklist
+				.get_with_params("test", &[], &args)
+				.cmd
+		);
+		args.blacklist.hard = false;
+		assert_eq!(
+			"(grep -qsxF 'blacklist test' '/tmp/kmon'\\''s.conf' || \
+			echo 'blacklist test' >> '/tmp/kmon'\\''s.conf')",
+			ModuleCommand::Blacklist
+				.get_with_params("test", &[], &args)
+				.cmd
+		);
+		assert!(ModuleCommand::Blacklist
+			.get_with_params("test", &[], &args)
+			.desc
+			.contains("soft"));
+		let status_error = |code: i32, stderr: &str| CmdError::Status {
+			code: Some(code),
+			stderr: stderr.to_string(),
+		};
+		let error =
+			status_error(1, "rmmod: ERROR: Module snd is in use by: snd_pcm");
+		assert!(ModuleCommand::Unload
+			.get_error_hint(&error)
+			.unwrap()
+			.contains("in use"));
+		assert_eq!(None, ModuleCommand::Load.get_error_hint(&error));
+		assert!(ModuleCommand::Reload.is_busy_error(&error));
+		assert!(!ModuleCommand::Load.is_busy_error(&error));
+		let error =
+			status_error(1, "modprobe: FATAL: Module x not found in directory");
+		assert!(ModuleCommand::Load
+			.get_error_hint(&error)
+			.unwrap()
+			.contains("not found"));
+		assert!(ModuleCommand::Load
+			.get_error_hint(&status_error(127, ""))
+			.unwrap()
+			.contains("kmod"));
+		let error = CmdError::Spawn {
+			program: String::from("sh"),
+			kind: ErrorKind::NotFound,
+			message: String::new(),
+		};
+		assert!(ModuleCommand::Load
+			.get_error_hint(&error)
+			.unwrap()
+			.contains("kmod"));
+		let error = CmdError::Timeout {
+			cmd: String::from("sh"),
+			timeout: EXEC_TIMEOUT,
+		};
+		assert!(ModuleCommand::Load
+			.get_error_hint(&error)
+			.unwrap()
+			.contains("in time"));
+		assert!(!ModuleCommand::Unload.is_busy_error(&error));
+		assert_eq!(
+			Ok((String::from("test"), vec![String::from("debug=1")])),
+			ModuleCommand::parse_load_input(" test  debug=1 ")
+		);
+		assert_eq!(
+			Ok((String::new(), Vec::new())),
+			ModuleCommand::parse_load_input("")
+		);
+		assert!(ModuleCommand::parse_load_input("test module").is_err());
+		assert!(ModuleCommand::parse_load_input("test =1").is_err());
+
+		assert_eq!(
+			"sed -E -i \
+			-e '/^[[:space:]]*blacklist[[:space:]]+snd[-_]pcsp[[:space:]]*(#.*)?$/d' \
+			-e '/^[[:space:]]*install[[:space:]]+snd[-_]pcsp[[:space:]]+\\/bin\\/(false|true)/d' \
+			'/etc/modprobe.d/a.conf' '/tmp/it'\\''s.conf'",
+			ModuleCommand::Unblacklist
+				.get_with_params(
+					"snd-pcsp",
+					&[
+						String::from("/etc/modprobe.d/a.conf"),
+						String::from("/tmp/it's.conf")
+					],
+					&CommandArgs::default()
+				)
+				.cmd
+		);
+		let error = status_error(1, "sed: couldn't open file: Permission denied");
+		assert!(ModuleCommand::Unblacklist
+			.get_error_hint(&error)
+			.unwrap()
+			.contains("configuration files"));
+		assert!(ModuleCommand::Load
+			.get_error_hint(&error)
+			.unwrap()
+			.contains("CAP_SYS_MODULE"));
+		assert!(ModuleCommand::Load.is_permission_error(&error));
+		assert!(!ModuleCommand::Load.is_permission_error(&status_error(1, "")));
+		assert!(ModuleCommand::Load.is_permission_error(&CmdError::Spawn {
+			program: String::from("sh"),
+			kind: ErrorKind::PermissionDenied,
+			message: String::new(),
+		}));
+		assert_ne!(Some(PrivilegeCmd::None), PrivilegeCmd::escalation());
+		assert!(!PrivilegeCmd::Sudo.is_unprivileged());
+		assert_eq!(
+			Some(0),
+			parse_effective_uid(
+				"Name:\tkmon\nUid:\t1000\t0\t0\t0\nGid:\t0\t0\t0\t0"
+			)
+		);
+		assert_eq!(None, parse_effective_uid("Name:\tkmon\nUid:\t1000"));
 		assert_eq!(
 			"modprobe -r test-module || rmmod test-module",
 			ModuleCommand::Unload.get("test-module").cmd
 		);
+		assert_eq!(
+			"modprobe -r -f test-module || rmmod -f test-module",
+			ModuleCommand::ForceUnload.get("test-module").cmd
+		);
 		assert_eq!(
 			"modprobe -r test-module.ko || rmmod test-module.ko",
 			ModuleCommand::Unload.get("test-module.ko").cmd
@@ -185,7 +1492,7 @@ mod tests {
 
 		assert_eq!(
 			format!(
-				"{} && {}",
+				"({}) && ({})",
 				ModuleCommand::Unload.get("test-module").cmd,
 				ModuleCommand::Load.get("test-module").cmd
 			),
@@ -194,11 +1501,193 @@ mod tests {
 
 		assert_eq!(
 			format!(
-				"{} && {}",
+				"({}) && {}",
 				ModuleCommand::Unload.get("test-module.ko").cmd,
 				ModuleCommand::Load.get("test-module.ko").cmd
 			),
 			ModuleCommand::Reload.get("test-module.ko").cmd,
 		);
+
+		for command in [
+			ModuleCommand::Load,
+			ModuleCommand::ForceUnload,
+			ModuleCommand::Blacklist,
+			ModuleCommand::Clear,
+			ModuleCommand::Depmod,
+			ModuleCommand::AddBoot,
+			ModuleCommand::RemoveBoot,
+		] {
+			assert_eq!(
+				Ok(command),
+				ModuleCommand::try_from(command.name().to_string())
+			);
+		}
+		assert_eq!(Some(ModuleCommand::Unload), ModuleCommand::Load.inverse());
+		assert_eq!(
+			Some(ModuleCommand::Load),
+			ModuleCommand::ForceUnload.inverse()
+		);
+		assert_eq!(
+			Some(ModuleCommand::Unblacklist),
+			ModuleCommand::Blacklist.inverse()
+		);
+		assert_eq!(None, ModuleCommand::Reload.inverse());
+		assert_eq!("loaded snd", ModuleCommand::Load.result_message("snd"));
+		assert_eq!(
+			"cleared the ring buffer",
+			ModuleCommand::Clear.result_message("snd")
+		);
+		assert_eq!("loading snd", ModuleCommand::Load.progress_message("snd"));
+		assert_eq!(
+			"snd yükleniyor",
+			ModuleCommand::Load.message(Lang::Tr, "progress", "snd")
+		);
+		assert_eq!(
+			"snd açılış modüllerinden çıkarıldı",
+			ModuleCommand::RemoveBoot.message(Lang::Tr, "result", "snd")
+		);
+		for command in [
+			ModuleCommand::Load,
+			ModuleCommand::Unload,
+			ModuleCommand::ForceUnload,
+			ModuleCommand::Reload,
+			ModuleCommand::Blacklist,
+			ModuleCommand::Unblacklist,
+			ModuleCommand::Clear,
+			ModuleCommand::Depmod,
+			ModuleCommand::AddBoot,
+			ModuleCommand::RemoveBoot,
+		] {
+			assert!(!command.message(Lang::Tr, "result", "snd").is_empty());
+			assert!(!command.message(Lang::Tr, "progress", "snd").is_empty());
+		}
+		assert_eq!("depmod -a", ModuleCommand::Depmod.get("snd").cmd);
+		assert_eq!(
+			"mkdir -p '/etc/modules-load.d' && \
+			(grep -qsxF 'snd-pcsp' '/etc/modules-load.d/kmon.conf' || \
+			echo 'snd-pcsp' >> '/etc/modules-load.d/kmon.conf')",
+			ModuleCommand::AddBoot.get("snd-pcsp").cmd
+		);
+		assert_eq!(
+			"sed -E -i '/^[[:space:]]*snd[-_]pcsp[[:space:]]*(#.*)?$/d' \
+			'/etc/modules-load.d/kmon.conf'",
+			ModuleCommand::RemoveBoot.get("snd-pcsp").cmd
+		);
+		assert_eq!(
+			Some(ModuleCommand::AddBoot),
+			ModuleCommand::RemoveBoot.inverse()
+		);
+
+		assert_eq!(
+			Ok(vec![String::from("snd"), String::from("e1000e")]),
+			parse_module_list("# modules\nsnd\n\n  e1000e # network\n")
+		);
+		assert!(parse_module_list("snd\nsnd index=1\n").is_err());
+		for line in ["foo;reboot", "$(reboot)", "`id`", "/nonexistent/a.ko"] {
+			assert_eq!(
+				Err(format!("invalid module name at line 2: '{line}'")),
+				parse_module_list(&format!("snd\n{line}\n"))
+			);
+		}
+		let module_path = std::env::temp_dir().join("kmon-test-module.ko");
+		fs::write(&module_path, "").unwrap();
+		assert_eq!(
+			Ok(vec![module_path.display().to_string()]),
+			parse_module_list(&module_path.display().to_string())
+		);
+		fs::remove_file(module_path).unwrap();
+		assert_eq!(
+			"modprobe 'a;b' || insmod 'a;b.ko'",
+			ModuleCommand::load_cmd("a;b", &[], Loader::Modprobe)
+		);
+		assert_eq!(
+			"rmmod '$(id)'",
+			ModuleCommand::unload_cmd("$(id)", false, Loader::Insmod)
+		);
+		assert!(read_module_list(Path::new("/nonexistent/modules.txt")).is_err());
+		let names = [String::from("~a"), String::from("~b")];
+		let mut args = CommandArgs {
+			privilege: PrivilegeCmd::None,
+			..CommandArgs::default()
+		};
+		let results = ModuleCommand::Load.run_batch(&names, &args);
+		assert!(matches!(
+			results[1].status,
+			BatchStatus::Failed(CmdError::Status { .. })
+		));
+		assert_eq!(
+			"~a: failed (exit code: Some(1))",
+			BatchResult {
+				status: BatchStatus::Failed(status_error(1, "")),
+				..results[0].clone()
+			}
+			.describe(false)
+		);
+		assert_eq!(
+			"0 done, 2 failed, 0 skipped",
+			BatchResult::summary(&results)
+		);
+		args.stop_on_error = true;
+		let results = ModuleCommand::Load.run_batch(&names, &args);
+		assert_eq!(BatchStatus::Skipped, results[1].status);
+		assert_eq!("~b: skipped", results[1].describe(false));
+		args.dry_run = true;
+		let results = ModuleCommand::Load.run_batch(&names, &args);
+		assert_eq!(
+			"2 done, 0 failed, 0 skipped",
+			BatchResult::summary(&results)
+		);
+		assert_eq!(format!("~a: {}", results[0].cmd), results[0].describe(true));
+		assert_eq!("~a: done", results[0].describe(false));
+		args.hooks = HooksConfig {
+			post_load: Some(String::from("echo {module} >/dev/null")),
+			post_unload: Some(String::from("false")),
+			..HooksConfig::default()
+		};
+		assert_eq!(
+			vec!["false 'snd'", "echo 'snd' >/dev/null"],
+			ModuleCommand::Reload.hook_cmds("snd", &args.hooks)
+		);
+		assert!(ModuleCommand::Blacklist
+			.hook_cmds("snd", &args.hooks)
+			.is_empty());
+		args.hooks.depmod = true;
+		let cmds = args.hook_cmds(ModuleCommand::Unload, &["snd", "loop"]);
+		assert_eq!(3, cmds.len());
+		assert_eq!(1, cmds.iter().filter(|v| v.contains("depmod")).count());
+		assert!(cmds[2].contains("depmod -a"));
+		assert!(args.hook_cmds(ModuleCommand::Unload, &[]).is_empty());
+		assert!(args.run_hooks(ModuleCommand::Unload, &["snd"]).is_ok());
+		args.dry_run = false;
+		args.hooks.depmod = false;
+		assert!(args.run_hooks(ModuleCommand::Load, &["snd"]).is_ok());
+		assert_eq!(
+			Err(String::from(
+				"hook 'false 'snd'' failed: exit code: Some(1)"
+			)),
+			args.run_hooks(ModuleCommand::Unload, &["snd"])
+		);
+		assert_eq!(
+			Err(String::from(
+				"hook 'false 'snd'' failed: exit code: Some(1), \
+				hook 'false 'loop'' failed: exit code: Some(1)"
+			)),
+			args.run_hooks(ModuleCommand::Unload, &["snd", "loop"])
+		);
+		assert!(args.run_hooks(ModuleCommand::Unload, &[]).is_ok());
+		assert!(matches!(
+			ExecutionResult::execute(ModuleCommand::Unload, "snd", "true", &[], &args),
+			ExecutionResult::Single(Ok(_), Err(e)) if e.starts_with("hook 'false 'snd''")
+		));
+		assert!(matches!(
+			ExecutionResult::execute(
+				ModuleCommand::Unload,
+				"snd",
+				"false",
+				&[],
+				&args
+			),
+			ExecutionResult::Single(Ok(_), Ok(()))
+		));
 	}
 }
@@ -0,0 +1,304 @@
+use crate::kernel::lkm;
+use crate::kernel::source::MockSource;
+use crate::util::CmdOutput;
+use std::collections::BTreeMap;
+
+/* Fabricated contents of '/proc/modules' for the demo mode */
+const DEMO_MODULES: &str = "\
+nvidia_drm 61440 4 - Live 0xffffffffc1d2c000 (POE)
+nvidia_modeset 1212416 8 nvidia_drm, Live 0xffffffffc1a00000 (POE)
+nvidia 56807424 412 nvidia_modeset, Live 0xffffffffbe200000 (POE)
+snd_hda_intel 57344 3 - Live 0xffffffffc0f8a000
+snd_hda_codec 188416 1 snd_hda_intel, Live 0xffffffffc0e2c000
+snd_pcm 155648 2 snd_hda_intel,snd_hda_codec, Live 0xffffffffc0d9a000
+snd 126976 4 snd_hda_intel,snd_hda_codec,snd_pcm, Live 0xffffffffc0d5e000
+iwlwifi 483328 0 - Live 0xffffffffc0c84000
+cfg80211 1122304 1 iwlwifi, Live 0xffffffffc0a48000
+ext4 1056768 2 - Live 0xffffffffc08d2000
+jbd2 192512 1 ext4, Live 0xffffffffc0894000
+mbcache 16384 1 ext4, Live 0xffffffffc088c000
+e1000e 327680 0 - Live 0xffffffffc0822000
+uinput 20480 0 - Live 0xffffffffc081a000
+loop 40960 0 - Live 0xffffffffc080c000
+";
+
+/* Runtime parameters of the demo modules and their values */
+const DEMO_PARAMETERS: &[(&str, &[(&str, &str)])] = &[
+	("loop", &[("max_loop", "0"), ("max_part", "0")]),
+	(
+		"snd_hda_intel",
+		&[("power_save", "1"), ("probe_mask", "-1")],
+	),
+	("e1000e", &[("copybreak", "256"), ("debug", "-1")]),
+];
+
+/* Descriptions, authors and licenses of the demo modules */
+const DEMO_INFO: &[(&str, &str, &str, &str)] = &[
+	(
+		"nvidia_drm",
+		"NVIDIA DRM driver",
+		"NVIDIA Corporation",
+		"Dual MIT/GPL",
+	),
+	(
+		"nvidia_modeset",
+		"NVIDIA modeset",
+		"NVIDIA Corporation",
+		"NVIDIA",
+	),
+	("nvidia", "NVIDIA driver", "NVIDIA Corporation", "NVIDIA"),
+	("snd_hda_intel", "Intel HDA driver", "Takashi Iwai", "GPL"),
+	("snd_hda_codec", "HDA codec core", "Takashi Iwai", "GPL"),
+	(
+		"snd_pcm",
+		"Midlevel PCM code for ALSA.",
+		"Jaroslav Kysela",
+		"GPL",
+	),
+	(
+		"snd",
+		"Advanced Linux Sound Architecture driver",
+		"Jaroslav Kysela",
+		"GPL",
+	),
+	(
+		"iwlwifi",
+		"Intel(R) Wireless WiFi driver for Linux",
+		"Intel Corporation",
+		"GPL",
+	),
+	(
+		"cfg80211",
+		"wireless configuration support",
+		"Johannes Berg",
+		"GPL",
+	),
+	(
+		"ext4",
+		"Fourth Extended Filesystem",
+		"Remy Card, Theodore Ts'o",
+		"GPL",
+	),
+	(
+		"jbd2",
+		"Generic filesystem journal-writing module",
+		"",
+		"GPL",
+	),
+	(
+		"mbcache",
+		"Meta block cache (for extended attributes)",
+		"Andreas Gruenbacher",
+		"GPL",
+	),
+	(
+		"e1000e",
+		"Intel(R) PRO/1000 Network Driver",
+		"Intel Corporation",
+		"GPL v2",
+	),
+	(
+		"uinput",
+		"User level driver support for input subsystem",
+		"Aristeu Sergio Rozanski Filho",
+		"GPL",
+	),
+	("loop", "Loopback device support", "", "GPL"),
+];
+
+/* Fabricated kernel activities for the demo mode */
+const DEMO_LOG: &str = "\
+kern  :notice: [    0.000000] Linux version 6.9.7-arch1-1 (linux@archlinux) (gcc (GCC) 14.1.1) #1 SMP PREEMPT_DYNAMIC
+kern  :info  : [    0.000000] Command line: BOOT_IMAGE=/vmlinuz-linux root=UUID=0a3f rw loglevel=3 quiet
+kern  :info  : [    0.042113] ACPI: Early table checksum verification disabled
+kern  :info  : [    1.203771] EXT4-fs (nvme0n1p2): mounted filesystem with ordered data mode. Quota mode: none.
+kern  :warn  : [    2.118304] nvidia: loading out-of-tree module taints kernel.
+kern  :warn  : [    2.118311] nvidia: module license 'NVIDIA' taints kernel.
+kern  :info  : [    2.364852] nvidia-modeset: Loading NVIDIA Kernel Mode Setting Driver for UNIX platforms
+kern  :info  : [    2.901557] e1000e 0000:00:1f.6 eth0: Intel(R) PRO/1000 Network Connection
+kern  :info  : [    3.472306] iwlwifi 0000:00:14.3: loaded firmware version 89.202a2f7b.0
+kern  :err   : [    3.518020] snd_hda_intel 0000:00:1f.3: no codecs found!
+kern  :info  : [    4.007761] loop0: detected capacity change from 0 to 8
+kern  :notice: [   12.650124] wlan0: associated
+";
+
+/**
+ * Create the data source of the demo mode with the fabricated modules and logs.
+ *
+ * @return MockSource
+ */
+pub fn source() -> MockSource {
+	MockSource {
+		modules: lkm::parse_proc_modules(DEMO_MODULES),
+		module_info: DEMO_INFO
+			.iter()
+			.map(|(name, description, author, license)| {
+				(
+					name.to_string(),
+					format!(
+						"filename:       /lib/modules/6.9.7-arch1-1/kernel/{name}.ko.zst\n\
+						license:        {license}\n\
+						author:         {author}\n\
+						description:    {description}\n\
+						srcversion:     5F6B1A55E3F1C6D2E8A07B4\n\
+						vermagic:       6.9.7-arch1-1 SMP preempt mod_unload\n",
+					),
+				)
+			})
+			.collect(),
+		kernel_log: DEMO_LOG.to_string(),
+		holders: BTreeMap::new(),
+		parameters: DEMO_PARAMETERS
+			.iter()
+			.map(|(name, parameters)| {
+				(
+					name.to_string(),
+					parameters
+						.iter()
+						.map(|(param, value)| {
+							(param.to_string(), Some(value.to_string()))
+						})
+						.collect(),
+				)
+			})
+			.collect(),
+		kernel_info: vec![
+			vec![
+				String::from("Kernel Release"),
+				String::from("Linux kmon-demo 6.9.7-arch1-1"),
+			],
+			vec![
+				String::from("Kernel Version"),
+				String::from(
+					"#1 SMP PREEMPT_DYNAMIC Fri, 28 Jun 2024 04:32:50 +0000",
+				),
+			],
+			vec![
+				String::from("Kernel Platform"),
+				String::from("x86_64 GNU/Linux"),
+			],
+		],
+		kernel_release: Some(String::from("6.9.7-arch1-1")),
+	}
+}
+
+/**
+ * Simulate the result of a module command in demo mode.
+ *
+ * Unloading a module that is in use fails
+ * and loading a module that is not in the demo list is not found.
+ *
+ * @param  cmd
+ * @return CmdOutput
+ */
+pub fn run(cmd: &str) -> CmdOutput {
+	let modules = lkm::parse_proc_modules(DEMO_MODULES);
+	let words = cmd
+		.split(|c: char| c.is_whitespace() || c == '\'' || c == '(' || c == ')')
+		.collect::<Vec<&str>>();
+	let unload = cmd.contains("modprobe -r") || cmd.contains("rmmod");
+	let stderr = if let Some(module) = modules.iter().find(|module| {
+		module.refcount > 0 && unload && words.contains(&module.name.as_str())
+	}) {
+		match module.dependents.as_slice() {
+			[] => format!("rmmod: ERROR: Module {} is in use", module.name),
+			dependents => format!(
+				"rmmod: ERROR: Module {} is in use by: {}",
+				module.name,
+				dependents.join(" ")
+			),
+		}
+	} else if let Some(module_name) = words
+		.iter()
+		.skip_while(|v| !["modprobe", "insmod"].contains(v))
+		.nth(1)
+		.filter(|v| !unload && !modules.iter().any(|m| m.name == **v))
+	{
+		format!("modprobe: FATAL: Module {module_name} not found in directory")
+	} else {
+		String::new()
+	};
+	CmdOutput {
+		code: Some(i32::from(!stderr.is_empty())),
+		stderr,
+		..CmdOutput::default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::Config;
+	use crate::kernel::deps::Relation;
+	use crate::kernel::lkm::ListArgs;
+	use crate::kernel::source::{DataSource, ModuleSource};
+	use crate::kernel::Kernel;
+	use crate::output;
+	use crate::util;
+	use std::cell::Cell;
+	#[test]
+	fn test_demo() {
+		let source = source();
+		assert_eq!(15, source.modules.len());
+		assert_eq!(DEMO_INFO.len(), source.module_info.len());
+		assert!(source
+			.module_info("ext4")
+			.unwrap()
+			.contains("Fourth Extended Filesystem"));
+		assert_eq!(
+			15,
+			source
+				.list_modules(&ListArgs::new(&Default::default()))
+				.unwrap()
+				.len()
+		);
+		assert!(run("modprobe -r loop || rmmod loop").success());
+		assert!(run("modprobe -r jbd2 || rmmod jbd2")
+			.stderr
+			.ends_with("in use by: ext4"));
+		assert!(run("modprobe -r ext4 || rmmod ext4")
+			.stderr
+			.ends_with("ext4 is in use"));
+		assert!(run("modprobe uinput || insmod uinput.ko").success());
+		assert!(!run("modprobe nonexistent || insmod nonexistent.ko").success());
+		assert!(run("(modprobe -r loop || rmmod loop) && (modprobe loop)").success());
+		let config = Config {
+			demo: true,
+			..Config::default()
+		};
+		let spawned = util::SPAWN_COUNT.with(Cell::get);
+		let mut kernel =
+			Kernel::with_source(&config, DataSource::new(super::source()));
+		kernel.modules.select_unfiltered("ext4");
+		assert!(kernel
+			.modules
+			.get_dependency_graph("ext4")
+			.get("ext4", Relation::Depends)
+			.contains(&String::from("jbd2")));
+		assert!(kernel
+			.modules
+			.snapshot()
+			.unwrap()
+			.modules
+			.contains_key("ext4"));
+		assert!(kernel.modules.module_report().is_some());
+		kernel.modules.parameters = kernel.modules.get_parameters("loop");
+		kernel.modules.parameters_module = String::from("loop");
+		assert_eq!(
+			Ok(String::from("0")),
+			kernel.modules.get_editable_parameter()
+		);
+		assert_eq!(
+			Ok(String::from("max_loop = 8")),
+			kernel.modules.set_parameter("8")
+		);
+		assert_eq!(Some(String::from("8")), kernel.modules.parameters[0].1);
+		kernel.modules.cmd_args.read_only = true;
+		assert!(kernel.modules.set_parameter("1").is_err());
+		assert_eq!(
+			output::EXIT_SUCCESS,
+			output::print_module_info(&source, &[String::from("ext4")])
+		);
+		assert_eq!(spawned, util::SPAWN_COUNT.with(Cell::get));
+	}
+}
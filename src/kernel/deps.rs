@@ -0,0 +1,276 @@
+use std::collections::{BTreeSet, HashMap};
+
+/* Direction of the dependency relation */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+	Depends,
+	Dependents,
+}
+
+/* Line of a rendered dependency tree */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeLine {
+	pub prefix: String,
+	pub name: String,
+	pub cycle: bool,
+}
+
+/* Dependency graph of the kernel modules */
+#[derive(Clone, Debug, Default)]
+pub struct DependencyGraph {
+	depends: HashMap<String, BTreeSet<String>>,
+	dependents: HashMap<String, BTreeSet<String>>,
+}
+
+impl DependencyGraph {
+	/**
+	 * Create a new dependency graph from the modules and their dependents.
+	 *
+	 * @param  modules
+	 * @return DependencyGraph
+	 */
+	pub fn new(modules: &[(String, Vec<String>)]) -> Self {
+		let mut graph = Self::default();
+		for (name, dependents) in modules {
+			for dependent in dependents {
+				graph.add_dependency(dependent, name);
+			}
+		}
+		graph
+	}
+
+	/**
+	 * Add a dependency between the given modules.
+	 *
+	 * @param module
+	 * @param dependency
+	 */
+	pub fn add_dependency(&mut self, module: &str, dependency: &str) {
+		self.depends
+			.entry(module.to_string())
+			.or_default()
+			.insert(dependency.to_string());
+		self.dependents
+			.entry(dependency.to_string())
+			.or_default()
+			.insert(module.to_string());
+	}
+
+	/**
+	 * Get the modules that are related to the given module.
+	 *
+	 * @param  module
+	 * @param  relation
+	 * @return Vec
+	 */
+	pub fn get(&self, module: &str, relation: Relation) -> Vec<String> {
+		match relation {
+			Relation::Depends => &self.depends,
+			Relation::Dependents => &self.dependents,
+		}
+		.get(module)
+		.map(|v| v.iter().cloned().collect())
+		.unwrap_or_default()
+	}
+
+	/**
+	 * Get the order for unloading the given module with its transitive dependents.
+	 *
+	 * Dependent modules come before the modules that they are using.
+	 *
+	 * @param  module
+	 * @return Vec
+	 */
+	pub fn unload_order(&self, module: &str) -> Vec<String> {
+		let mut order = Vec::new();
+		self.visit_dependents(module, &mut BTreeSet::new(), &mut order);
+		order
+	}
+
+	/**
+	 * Append the dependents of the given module and then the module itself.
+	 *
+	 * @param module
+	 * @param visited
+	 * @param order
+	 */
+	fn visit_dependents(
+		&self,
+		module: &str,
+		visited: &mut BTreeSet<String>,
+		order: &mut Vec<String>,
+	) {
+		if !visited.insert(module.to_string()) {
+			return;
+		}
+		for dependent in self.get(module, Relation::Dependents) {
+			self.visit_dependents(&dependent, visited, order);
+		}
+		order.push(module.to_string());
+	}
+
+	/**
+	 * Get the given module and the modules that are transitively related to it.
+	 *
+	 * @param  module
+	 * @param  relation
+	 * @return BTreeSet
+	 */
+	pub fn reachable(&self, module: &str, relation: Relation) -> BTreeSet<String> {
+		let mut visited = BTreeSet::new();
+		let mut modules = vec![module.to_string()];
+		while let Some(module) = modules.pop() {
+			if visited.insert(module.to_string()) {
+				modules.extend(self.get(&module, relation));
+			}
+		}
+		visited
+	}
+
+	/**
+	 * Render the dependencies between the given modules in Graphviz DOT format.
+	 *
+	 * Edges point from the modules to the modules that they depend on.
+	 *
+	 * @param  modules
+	 * @return String
+	 */
+	pub fn to_dot(&self, modules: &BTreeSet<String>) -> String {
+		let quote = |name: &str| format!("\"{}\"", name.replace('"', "\\\""));
+		let mut dot = String::from("digraph modules {\n");
+		for module in modules {
+			dot += &format!("\t{};\n", quote(module));
+		}
+		for module in modules {
+			for dependency in self.get(module, Relation::Depends) {
+				if modules.contains(&dependency) {
+					dot +=
+						&format!("\t{} -> {};\n", quote(module), quote(&dependency));
+				}
+			}
+		}
+		dot + "}\n"
+	}
+
+	/**
+	 * Render the transitive relations of the given module as a tree.
+	 *
+	 * Modules that are already on the current path are marked as cycles.
+	 *
+	 * @param  module
+	 * @param  relation
+	 * @return Vec
+	 */
+	pub fn tree(&self, module: &str, relation: Relation) -> Vec<TreeLine> {
+		let mut lines = vec![TreeLine {
+			prefix: String::new(),
+			name: module.to_string(),
+			cycle: false,
+		}];
+		self.walk(
+			module,
+			relation,
+			"",
+			&mut vec![module.to_string()],
+			&mut lines,
+		);
+		lines
+	}
+
+	/**
+	 * Append the children of the given module to the tree lines.
+	 *
+	 * @param module
+	 * @param relation
+	 * @param indent
+	 * @param path
+	 * @param lines
+	 */
+	fn walk(
+		&self,
+		module: &str,
+		relation: Relation,
+		indent: &str,
+		path: &mut Vec<String>,
+		lines: &mut Vec<TreeLine>,
+	) {
+		let children = self.get(module, relation);
+		for (i, child) in children.iter().enumerate() {
+			let last = i == children.len() - 1;
+			let cycle = path.contains(child);
+			lines.push(TreeLine {
+				prefix: format!("{indent}{}", if last { "└─ " } else { "├─ " }),
+				name: child.to_string(),
+				cycle,
+			});
+			if !cycle {
+				path.push(child.to_string());
+				self.walk(
+					child,
+					relation,
+					&format!("{indent}{}", if last { "   " } else { "│  " }),
+					path,
+					lines,
+				);
+				path.pop();
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	#[test]
+	fn test_dependency_graph() {
+		let mut graph = DependencyGraph::new(&[
+			(
+				String::from("snd"),
+				vec![String::from("snd_pcm"), String::from("snd_hda_codec")],
+			),
+			(String::from("snd_pcm"), vec![String::from("snd_hda_codec")]),
+		]);
+		assert_eq!(vec!["snd"], graph.get("snd_pcm", Relation::Depends));
+		assert_eq!(
+			vec!["snd_hda_codec", "snd_pcm"],
+			graph.get("snd", Relation::Dependents)
+		);
+		assert_eq!(
+			vec![
+				"snd",
+				"├─ snd_hda_codec",
+				"└─ snd_pcm",
+				"   └─ snd_hda_codec"
+			],
+			graph
+				.tree("snd", Relation::Dependents)
+				.iter()
+				.map(|line| format!("{}{}", line.prefix, line.name))
+				.collect::<Vec<String>>()
+		);
+		assert_eq!(
+			vec!["snd_hda_codec", "snd_pcm", "snd"],
+			graph.unload_order("snd")
+		);
+		assert_eq!(vec!["snd_hda_codec"], graph.unload_order("snd_hda_codec"));
+		graph.add_dependency("snd", "snd_hda_codec");
+		assert_eq!(3, graph.unload_order("snd").len());
+		let tree = graph.tree("snd", Relation::Depends);
+		assert_eq!(5, tree.len());
+		assert!(tree[2].cycle && tree[4].cycle);
+		assert_eq!("snd", tree[2].name);
+		assert!(!tree[3].cycle);
+		let modules = graph.reachable("snd_pcm", Relation::Depends);
+		assert_eq!(
+			vec!["snd", "snd_hda_codec", "snd_pcm"],
+			Vec::from_iter(modules)
+		);
+		assert_eq!(
+			"digraph modules {\n\t\"snd\";\n\t\"snd_pcm\";\n\t\"snd_pcm\" -> \"snd\";\n}\n",
+			graph.to_dot(&BTreeSet::from([
+				String::from("snd"),
+				String::from("snd_pcm")
+			]))
+		);
+	}
+}
@@ -1,10 +1,30 @@
+use crate::kernel::source::DataSource;
 use crate::util;
+use std::env;
+use std::path::Path;
 use std::vec::IntoIter;
 
+/* Directory of the kernel parameters in procfs */
+const PROC_KERNEL_DIR: &str = "/proc/sys/kernel";
+
+/**
+ * Read the release of the running kernel from procfs.
+ *
+ * @return release (Option)
+ */
+pub fn kernel_release() -> Option<String> {
+	util::read_file(Path::new(PROC_KERNEL_DIR).join("osrelease"))
+		.ok()
+		.map(|v| v.trim().to_string())
+		.filter(|v| !v.is_empty())
+}
+
 /* Kernel and system information */
 pub struct KernelInfo {
 	pub current_info: Vec<String>,
+	pub release: String,
 	uname_output: IntoIter<Vec<String>>,
+	source: DataSource,
 }
 
 impl Default for KernelInfo {
@@ -20,9 +40,21 @@ impl KernelInfo {
 	 * @return KernelInfo
 	 */
 	pub fn new() -> Self {
+		Self::with_source(DataSource::default())
+	}
+
+	/**
+	 * Create a new kernel info instance that reads from the given source.
+	 *
+	 * @param  DataSource
+	 * @return KernelInfo
+	 */
+	pub fn with_source(source: DataSource) -> Self {
 		let mut kernel_info = Self {
 			current_info: Vec::new(),
+			release: String::new(),
 			uname_output: Vec::new().into_iter(),
+			source,
 		};
 		kernel_info.refresh();
 		kernel_info
@@ -30,8 +62,14 @@ impl KernelInfo {
 
 	/* Refresh the kernel information fields. */
 	pub fn refresh(&mut self) {
-		self.uname_output = KernelInfo::get_infos();
-		self.next();
+		self.uname_output = self.source.kernel_info().into_iter();
+		self.release = self
+			.source
+			.kernel_release()
+			.unwrap_or_else(|| String::from("unknown"));
+		self.current_info = self.uname_output.next().unwrap_or_else(|| {
+			vec![String::from("Kernel Release"), String::from("unknown")]
+		});
 	}
 
 	/**
@@ -45,29 +83,46 @@ impl KernelInfo {
 	}
 
 	/**
-	 * Execute 'uname' command and return its output along with its description.
+	 * Execute 'uname' command and return its outputs along with their descriptions.
 	 *
-	 * @return Iterator
+	 * @return Vec
 	 */
-	fn get_infos() -> IntoIter<Vec<String>> {
+	pub fn uname_infos() -> Vec<Vec<String>> {
 		vec![
-			vec![
-				String::from("Kernel Release"),
-				util::exec_cmd("uname", &["-srn"])
-					.unwrap_or_else(|_| String::from("?")),
-			],
-			vec![
-				String::from("Kernel Version"),
-				util::exec_cmd("uname", &["-v"])
-					.unwrap_or_else(|_| String::from("?")),
-			],
-			vec![
-				String::from("Kernel Platform"),
-				util::exec_cmd("uname", &["-om"])
-					.unwrap_or_else(|_| String::from("?")),
-			],
+			vec![String::from("Kernel Release"), Self::uname("-srn")],
+			vec![String::from("Kernel Version"), Self::uname("-v")],
+			vec![String::from("Kernel Platform"), Self::uname("-om")],
 		]
-		.into_iter()
+	}
+
+	/**
+	 * Get the output of 'uname' with the given argument.
+	 *
+	 * Values are read from procfs if 'uname' is not available.
+	 *
+	 * @param  arg
+	 * @return String
+	 */
+	fn uname(arg: &str) -> String {
+		util::exec_cmd("uname", &[arg]).unwrap_or_else(|_| {
+			let read = |name: &str| {
+				util::read_file(Path::new(PROC_KERNEL_DIR).join(name))
+					.map(|v| v.trim().to_string())
+					.unwrap_or_else(|_| String::from("?"))
+			};
+			match arg {
+				"-srn" => {
+					format!(
+						"{} {} {}",
+						read("ostype"),
+						read("hostname"),
+						read("osrelease")
+					)
+				}
+				"-v" => read("version"),
+				_ => format!("{} {}", env::consts::ARCH, read("ostype")),
+			}
+		})
 	}
 }
 
@@ -85,5 +140,10 @@ mod tests {
 			util::exec_cmd("uname", &["-srn"]).unwrap(),
 			kernel_info.current_info[1]
 		);
+		assert!(KernelInfo::uname("--kmon").starts_with(env::consts::ARCH));
+		assert_eq!(
+			util::exec_cmd("uname", &["-r"]).unwrap(),
+			kernel_info.release
+		);
 	}
 }
@@ -1,20 +1,50 @@
 use crate::app::ScrollDirection;
-use crate::kernel::cmd::{Command, ModuleCommand};
+use crate::config::{Config, ConfirmConfig, GroupsConfig};
+use crate::event::Event;
+use crate::i18n;
+use crate::kernel::blacklist::{self, BlacklistEntry, MODPROBE_DIRS};
+use crate::kernel::boot::{self, ETC_MODULES, MODULES_LOAD_DIRS};
+use crate::kernel::cache::LruCache;
+use crate::kernel::cmd::{
+	self, BatchResult, Command, CommandArgs, ExecutionResult, Loader, ModuleCommand,
+	PrivilegeCmd,
+};
+use crate::kernel::deps::{DependencyGraph, Relation};
+use crate::kernel::info;
+use crate::kernel::snapshot::Snapshot;
+use crate::kernel::source::{self, DataSource, ModuleSource};
 use crate::style::{Style, StyledText, Symbol};
-use crate::util;
-use bytesize::ByteSize;
-use clap::ArgMatches;
+use crate::util::{self, CmdError, CmdOutput};
+use enum_iterator::Sequence;
 use ratatui::text::{Line, Span, Text};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
+use std::fmt::{Display, Formatter, Write as _};
+use std::fs;
+use std::io::ErrorKind;
+use std::mem;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::slice::Iter;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use termion::event::Key;
 
 /* Type of the sorting of module list */
-#[derive(Clone, Copy, Debug)]
-enum SortType {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortType {
 	None,
 	Size,
 	Name,
 	Dependent,
+	Refcount,
 }
 
 impl SortType {
@@ -30,215 +60,3460 @@ impl SortType {
 			SortType::Size,
 			SortType::Name,
 			SortType::Dependent,
+			SortType::Refcount,
 		]
 		.iter()
 	}
+
+	/**
+	 * Get the next sort type for cycling them in the application.
+	 *
+	 * @return SortType
+	 */
+	pub fn next(self) -> Self {
+		match self {
+			Self::None => Self::Name,
+			Self::Name => Self::Size,
+			Self::Size => Self::Refcount,
+			Self::Refcount | Self::Dependent => Self::None,
+		}
+	}
+
+	/**
+	 * Get the description of the sort order.
+	 *
+	 * @param  reverse
+	 * @return String (Option)
+	 */
+	pub fn description(self, reverse: bool) -> Option<String> {
+		let name = match self {
+			Self::None if reverse => return Some(String::from("reversed")),
+			Self::None => return None,
+			Self::Size => "size",
+			Self::Name => "name",
+			Self::Dependent => "dependent",
+			Self::Refcount => "refcount",
+		};
+		Some(format!(
+			"{name} {}",
+			if self.is_descending(reverse) {
+				"desc"
+			} else {
+				"asc"
+			}
+		))
+	}
+
+	/**
+	 * Check if the modules are sorted in descending order.
+	 *
+	 * Numeric values are sorted in descending order by default.
+	 *
+	 * @param  reverse
+	 * @return bool
+	 */
+	pub fn is_descending(self, reverse: bool) -> bool {
+		(self != Self::Name) != reverse
+	}
+
+	/**
+	 * Compare the given modules for sorting them.
+	 *
+	 * Numeric values are sorted in descending order and
+	 * the ties are broken by the module names.
+	 *
+	 * @param  a
+	 * @param  b
+	 * @return Ordering
+	 */
+	pub fn compare(self, a: &Module, b: &Module) -> Ordering {
+		match self {
+			Self::None => Ordering::Equal,
+			Self::Size => b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)),
+			Self::Name => a.name.cmp(&b.name),
+			Self::Dependent => b
+				.dependents
+				.len()
+				.cmp(&a.dependents.len())
+				.then_with(|| a.name.cmp(&b.name)),
+			Self::Refcount => b
+				.refcount
+				.cmp(&a.refcount)
+				.then_with(|| a.name.cmp(&b.name)),
+		}
+	}
+}
+
+/* Display format of the module sizes */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeFormat {
+	Raw,
+	#[default]
+	Human,
+}
+
+impl TryFrom<&str> for SizeFormat {
+	type Error = ();
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		match s {
+			"raw" => Ok(Self::Raw),
+			"human" => Ok(Self::Human),
+			_ => Err(()),
+		}
+	}
+}
+
+impl SizeFormat {
+	/**
+	 * Format the given size.
+	 *
+	 * @param  size
+	 * @return String
+	 */
+	pub fn format(self, size: u64) -> String {
+		match self {
+			Self::Raw => size.to_string(),
+			Self::Human => util::format_bytes(size),
+		}
+	}
+
+	/**
+	 * Get the other size format.
+	 *
+	 * @return SizeFormat
+	 */
+	pub fn toggle(self) -> Self {
+		match self {
+			Self::Raw => Self::Human,
+			Self::Human => Self::Raw,
+		}
+	}
+}
+
+impl Display for SizeFormat {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Raw => "raw",
+				Self::Human => "human",
+			}
+		)
+	}
+}
+
+/* Filter of the module list */
+#[derive(
+	Clone, Copy, Debug, Default, PartialEq, Eq, Sequence, Deserialize, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum ListFilter {
+	#[default]
+	All,
+	InUse,
+	Unused,
+}
+
+impl ListFilter {
+	/**
+	 * Check if the given module passes the filter.
+	 *
+	 * @param  module
+	 * @return bool
+	 */
+	pub fn matches(self, module: &Module) -> bool {
+		match self {
+			Self::All => true,
+			Self::InUse => module.refcount > 0,
+			Self::Unused => module.refcount == 0,
+		}
+	}
+}
+
+impl Display for ListFilter {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::All => "all",
+				Self::InUse => "in-use",
+				Self::Unused => "unused",
+			}
+		)
+	}
+}
+
+/* Size threshold of the listed modules */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SizeFilter {
+	pub bytes: u64,
+	pub below: bool,
+}
+
+impl TryFrom<&str> for SizeFilter {
+	type Error = String;
+	/**
+	 * Parse the size filter from an expression such as '>1M' or '<512K'.
+	 *
+	 * Sizes without a '<' prefix are used as the minimum size. Units are
+	 * binary (K, M, G) and the size is given in bytes without a unit.
+	 *
+	 * @param  value
+	 * @return Result
+	 */
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		let value = value.trim();
+		let (expr, below) = match value.strip_prefix('<') {
+			Some(v) => (v, true),
+			None => (
+				value
+					.strip_prefix(">=")
+					.or_else(|| value.strip_prefix('>'))
+					.unwrap_or(value),
+				false,
+			),
+		};
+		let expr = expr.trim().to_lowercase();
+		let number = expr.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+		let multiplier = match expr[number.len()..].trim_end_matches('b') {
+			"" => 1,
+			"k" | "ki" => 1 << 10,
+			"m" | "mi" => 1 << 20,
+			"g" | "gi" => 1 << 30,
+			_ => return Err(format!("invalid size unit: {value}")),
+		};
+		match number.trim().parse::<f64>() {
+			Ok(size) if size >= 0.0 => Ok(Self {
+				bytes: (size * multiplier as f64) as u64,
+				below,
+			}),
+			_ => Err(format!("invalid size: {value}")),
+		}
+	}
+}
+
+impl SizeFilter {
+	/**
+	 * Check if the size of the given module passes the filter.
+	 *
+	 * @param  module
+	 * @return bool
+	 */
+	pub fn matches(self, module: &Module) -> bool {
+		if self.below {
+			module.size < self.bytes
+		} else {
+			module.size >= self.bytes
+		}
+	}
+}
+
+impl Display for SizeFilter {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let unit =
+			["G", "M", "K"]
+				.into_iter()
+				.zip([30, 20, 10])
+				.find(|(_, shift)| {
+					self.bytes != 0 && self.bytes.is_multiple_of(1 << shift)
+				});
+		write!(
+			f,
+			"{}{}",
+			if self.below { "<" } else { ">" },
+			match unit {
+				Some((unit, shift)) => format!("{}{unit}", self.bytes >> shift),
+				None => self.bytes.to_string(),
+			}
+		)
+	}
 }
 
 /* Listing properties of module list */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ListArgs {
-	sort: SortType,
-	reverse: bool,
+	pub sort: SortType,
+	pub reverse: bool,
+	pub filter: ListFilter,
+	pub size_filter: Option<SizeFilter>,
+	pub size_format: SizeFormat,
+	pub builtin: bool,
+	pub hide_used: bool,
+	pub bookmarked: bool,
 }
 
 impl ListArgs {
 	/**
 	 * Create a new list arguments instance.
 	 *
-	 * @param  ArgMatches
+	 * @param  Config
 	 * @return ListArgs
 	 */
-	pub fn new(args: &ArgMatches) -> Self {
-		let mut sort_type = SortType::None;
-		if let Some(("sort", matches)) = args.subcommand() {
-			if matches.get_flag("size") {
-				sort_type = SortType::Size;
-			} else if matches.get_flag("dependent") {
-				sort_type = SortType::Dependent;
-			} else {
-				sort_type = SortType::Name;
-			}
-		}
+	pub fn new(config: &Config) -> Self {
 		Self {
-			sort: sort_type,
-			reverse: args.try_get_one::<bool>("reverse").ok().flatten()
-				== Some(&true),
+			sort: config.sort,
+			reverse: config.reverse,
+			filter: config.filter,
+			size_filter: None,
+			size_format: config.size_format,
+			builtin: false,
+			hide_used: false,
+			bookmarked: false,
 		}
 	}
 }
 
-/* Loadable kernel modules */
-pub struct KernelModules<'a> {
-	pub default_list: Vec<Vec<String>>,
-	pub list: Vec<Vec<String>>,
-	pub current_name: String,
-	pub current_info: StyledText<'a>,
-	pub command: ModuleCommand,
-	pub index: usize,
-	pub info_scroll_offset: usize,
-	pub style: Style,
-	pub args: ListArgs,
+/* Kernel module entry parsed from '/proc/modules' */
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Module {
+	pub name: String,
+	pub size: u64,
+	pub refcount: u64,
+	pub dependents: Vec<String>,
+	#[serde(skip)]
+	pub taint: Option<String>,
+	#[serde(skip)]
+	pub initstate: Option<String>,
+	#[serde(skip)]
+	pub offset: Option<u64>,
+	#[serde(skip)]
+	pub builtin: bool,
 }
 
-impl KernelModules<'_> {
+impl Module {
 	/**
-	 * Create a new kernel modules instance.
+	 * Parse a module entry from a line of '/proc/modules'.
 	 *
-	 * @param  ListArgs
-	 * @param  Style
-	 * @return KernelModules
+	 * Columns are the name, size, reference count, dependent modules, state,
+	 * memory offset and taint flags. Reference count is '-' if unloading is
+	 * not supported and the offset is omitted on some kernels.
+	 *
+	 * @param  line
+	 * @return Module
 	 */
-	pub fn new(args: ListArgs, style: Style) -> Self {
-		let mut kernel_modules = Self {
-			default_list: Vec::new(),
-			list: Vec::new(),
-			current_name: String::new(),
-			current_info: StyledText::default(),
-			command: ModuleCommand::None,
-			index: 0,
-			info_scroll_offset: 0,
-			args,
-			style,
+	pub fn parse(line: &str) -> Option<Self> {
+		let mut columns = line.split_whitespace();
+		let name = columns.next()?.to_string();
+		let size = columns.next()?.parse().ok()?;
+		let refcount = match columns.next()? {
+			"-" => 0,
+			v => v.parse().ok()?,
 		};
-		if let Err(e) = kernel_modules.refresh() {
-			eprintln!("{e}");
+		let dependents = columns
+			.next()?
+			.split(',')
+			.filter(|v| !v.is_empty() && *v != "-")
+			.map(String::from)
+			.collect();
+		let initstate = columns.next().map(|v| match v {
+			"Live" => String::from("live"),
+			"Loading" => String::from("coming"),
+			"Unloading" => String::from("going"),
+			v => v.to_lowercase(),
+		});
+		let (mut offset, mut taint) = (None, None);
+		for column in columns {
+			if let Some(v) = column.strip_prefix("0x") {
+				offset = u64::from_str_radix(v, 16).ok();
+			} else if column.starts_with('(') {
+				taint = Some(column.to_string());
+			}
 		}
-		kernel_modules
+		Some(Self {
+			name,
+			size,
+			refcount,
+			dependents,
+			taint,
+			initstate,
+			offset,
+			builtin: false,
+		})
 	}
 
-	/* Parse kernel modules from '/proc/modules'. */
-	pub fn refresh(&mut self) -> Result<(), Box<dyn Error>> {
-		let mut module_list: Vec<Vec<String>> = Vec::new();
-		/* Set the command for reading kernel modules and execute it. */
-		let mut module_read_cmd = String::from("cat /proc/modules");
-		match self.args.sort {
-			SortType::Size => module_read_cmd += " | sort -n -r -t ' ' -k2",
-			SortType::Name => module_read_cmd += " | sort -t ' ' -k1",
-			SortType::Dependent => module_read_cmd += " | sort -n -r -t ' ' -k3",
-			_ => {}
+	/**
+	 * Get the initialization state of the module (live, coming or going).
+	 *
+	 * Modules without a known state (e.g. built-in modules) are live.
+	 *
+	 * @return str
+	 */
+	pub fn initstate(&self) -> &str {
+		self.initstate.as_deref().unwrap_or("live")
+	}
+
+	/**
+	 * Check if the module is initialized and not being removed.
+	 *
+	 * @return bool
+	 */
+	pub fn is_live(&self) -> bool {
+		self.initstate() == "live"
+	}
+
+	/**
+	 * Get the row of the module table.
+	 *
+	 * @param  size_format
+	 * @return row
+	 */
+	pub fn row(&self, size_format: SizeFormat) -> Vec<String> {
+		if self.builtin {
+			return vec![
+				format!(" {}", self.name),
+				String::from("-"),
+				String::from("built-in"),
+			];
+		}
+		let mut module_name = format!(" {}", self.name);
+		if let Some(taint) = &self.taint {
+			module_name = format!("{module_name} {taint}");
 		}
-		let modules_content = util::exec_cmd("sh", &["-c", &module_read_cmd])?;
+		if !self.is_live() {
+			module_name = format!("{module_name} ({})", self.initstate());
+		}
+		let used_modules = format!(
+			"{} {}",
+			self.refcount,
+			if self.dependents.is_empty() {
+				String::from("-")
+			} else {
+				self.dependents.join(",")
+			}
+		);
+		vec![module_name, size_format.format(self.size), used_modules]
+	}
+}
+
+/* Maximum number of the cached module information */
+const INFO_CACHE_SIZE: usize = 64;
+
+/* Duration of highlighting the modules that are changed since the last refresh */
+pub const CHANGE_DURATION: Duration = Duration::from_secs(3);
+
+/* Maximum number of the unloaded module names that are shown */
+const MAX_REMOVED_NAMES: usize = 3;
+
+/* Maximum number of 'modinfo' processes that are executed at once for indexing */
+const INDEX_THREADS: usize = 8;
+
+/* Number of the indexed modules that are sent together */
+const INDEX_BATCH: usize = 16;
+
+/* List of the loaded kernel modules in procfs */
+pub const PROC_MODULES: &str = "/proc/modules";
+
+/* Directory of the loaded kernel modules in sysfs */
+pub const SYS_MODULE_DIR: &str = "/sys/module";
 
-		/* Parse content for module name, size and related information. */
-		for line in modules_content.lines() {
+/* Paths, names and mount commands of the required pseudo-filesystems */
+const REQUIRED_FILESYSTEMS: &[(&str, &str, &str)] = &[
+	(PROC_MODULES, "procfs", "mount -t proc proc /proc"),
+	(SYS_MODULE_DIR, "sysfs", "mount -t sysfs sysfs /sys"),
+];
+
+/* Reason of the disabled commands for the built-in modules */
+const BUILTIN_ERROR: &str =
+	"Built-in modules cannot be loaded, unloaded or blacklisted.";
+
+/* Directory of the buses that contain the present devices in sysfs */
+const SYS_BUS_DIR: &str = "/sys/bus";
+
+/* Directory of the installed kernel modules */
+const LIB_MODULES_DIR: &str = "/lib/modules";
+
+/* File of the mounted filesystems */
+const PROC_MOUNTS: &str = "/proc/mounts";
+
+/**
+ * Parse the kernel modules from the contents of '/proc/modules'.
+ *
+ * @param  contents
+ * @return Vec
+ */
+pub fn parse_proc_modules(contents: &str) -> Vec<Module> {
+	contents.lines().filter_map(Module::parse).collect()
+}
+
+/**
+ * Parse the kernel modules from the output of 'lsmod'.
+ *
+ * 'lsmod' prints a header line and omits the dependent modules column
+ * if the module is not used by any other modules.
+ *
+ * @param  output
+ * @return Vec
+ */
+pub fn parse_lsmod(output: &str) -> Vec<Module> {
+	output
+		.lines()
+		.skip(1)
+		.filter_map(|line| {
 			let columns: Vec<&str> = line.split_whitespace().collect();
-			let mut module_name = format!(" {}", columns[0]);
-			if columns.len() >= 7 {
-				module_name = format!("{} {}", module_name, columns[6]);
+			match columns[..] {
+				[name, size, refcount, ..] => Some(Module {
+					name: name.to_string(),
+					size: size.parse().ok()?,
+					refcount: refcount.parse().ok()?,
+					dependents: columns
+						.get(3)
+						.map(|v| {
+							v.split(',')
+								.filter(|v| !v.is_empty())
+								.map(String::from)
+								.collect()
+						})
+						.unwrap_or_default(),
+					taint: None,
+					initstate: None,
+					offset: None,
+					builtin: false,
+				}),
+				_ => None,
 			}
-			let mut used_modules = format!("{} {}", columns[2], columns[3]);
-			if used_modules.ends_with(',') {
-				used_modules.pop();
+		})
+		.collect()
+}
+
+/**
+ * Parse the built-in kernel modules from the contents of 'modules.builtin'.
+ *
+ * Dashes in the file names are replaced as in the names of loaded modules.
+ *
+ * @param  contents
+ * @return Vec
+ */
+pub fn parse_builtin_modules(contents: &str) -> Vec<Module> {
+	contents
+		.lines()
+		.filter_map(|line| {
+			let name = Path::new(line.trim())
+				.file_name()?
+				.to_str()?
+				.strip_suffix(".ko")?;
+			Some(Module {
+				name: blacklist::normalize_name(name),
+				builtin: true,
+				..Module::default()
+			})
+		})
+		.collect()
+}
+
+/**
+ * Get the approximate load time of the module from its sysfs directory.
+ *
+ * @param  module_name
+ * @return Option
+ */
+pub fn module_load_time(module_name: &str) -> Option<SystemTime> {
+	let path = Path::new(SYS_MODULE_DIR).join(module_name);
+	if util::remote().is_some() {
+		return util::exec_cmd("stat", &["-c", "%Y", &path.to_string_lossy()])
+			.ok()?
+			.trim()
+			.parse()
+			.ok()
+			.map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+	}
+	let metadata = fs::metadata(path).ok()?;
+	metadata.created().or_else(|_| metadata.modified()).ok()
+}
+
+/**
+ * Parse the names of the available modules from the contents of 'modules.dep'.
+ *
+ * Compressed module files (e.g. '.ko.zst') are supported.
+ *
+ * @param  contents
+ * @return BTreeSet
+ */
+pub fn parse_modules_dep(contents: &str) -> BTreeSet<String> {
+	contents
+		.lines()
+		.filter_map(|line| module_name_from_path(line.split(':').next()?))
+		.collect()
+}
+
+/**
+ * Get the normalized module name from the path of a module file.
+ *
+ * @param  path
+ * @return Option
+ */
+fn module_name_from_path(path: &str) -> Option<String> {
+	let file_name = Path::new(path.trim()).file_name()?.to_str()?;
+	let name = &file_name[..file_name.find(".ko")?];
+	Some(blacklist::normalize_name(name))
+}
+
+/**
+ * Parse the dependencies of the given module from the contents of 'modules.dep'.
+ *
+ * Dependencies are returned in the order of loading them (reverse of the listed order).
+ *
+ * @param  contents
+ * @param  module_name
+ * @return Vec
+ */
+pub fn parse_load_order(contents: &str, module_name: &str) -> Vec<String> {
+	let module_name = blacklist::normalize_name(module_name);
+	contents
+		.lines()
+		.find_map(|line| {
+			let (path, depends) = line.split_once(':')?;
+			(module_name_from_path(path)? == module_name).then(|| {
+				depends.split_whitespace().rev().map(String::from).collect()
+			})
+		})
+		.unwrap_or_default()
+}
+
+/**
+ * Parse the filesystem type of the root mount point from '/proc/mounts'.
+ *
+ * The last mount on '/' is used since it hides the earlier ones.
+ *
+ * @param  contents
+ * @return String (Option)
+ */
+pub fn parse_root_fs_type(contents: &str) -> Option<String> {
+	contents
+		.lines()
+		.rev()
+		.filter_map(|line| {
+			let mut fields = line.split_whitespace().skip(1);
+			(fields.next()? == "/").then_some(fields.next()?)
+		})
+		.find(|fs_type| *fs_type != "rootfs")
+		.map(String::from)
+}
+
+/**
+ * Read the modules that are available for the running kernel.
+ *
+ * @return BTreeSet
+ */
+fn read_available_modules() -> BTreeSet<String> {
+	info::kernel_release()
+		.and_then(|release| {
+			util::read_file(
+				Path::new(LIB_MODULES_DIR).join(release).join("modules.dep"),
+			)
+			.ok()
+		})
+		.map(|contents| parse_modules_dep(&contents))
+		.unwrap_or_default()
+}
+
+/**
+ * Read the built-in modules of the running kernel.
+ *
+ * @return Vec
+ */
+fn read_builtin_modules() -> Vec<Module> {
+	info::kernel_release()
+		.and_then(|release| {
+			util::read_file(
+				Path::new(LIB_MODULES_DIR)
+					.join(release)
+					.join("modules.builtin"),
+			)
+			.ok()
+		})
+		.map(|contents| parse_builtin_modules(&contents))
+		.unwrap_or_default()
+}
+
+/**
+ * Parse the device paths and modaliases from the output of 'grep -H'.
+ *
+ * @param  output
+ * @return Vec
+ */
+pub fn parse_modaliases(output: &str) -> Vec<(PathBuf, String)> {
+	output
+		.lines()
+		.filter_map(|line| {
+			let (device, modalias) = line.split_once("/modalias:")?;
+			Some((PathBuf::from(device), modalias.trim().to_string()))
+		})
+		.collect()
+}
+
+/**
+ * Read the modaliases of the devices that are present on the system.
+ *
+ * Modaliases are read with a single command on the remote hosts.
+ *
+ * @return Vec
+ */
+pub fn read_modaliases() -> Vec<(PathBuf, String)> {
+	if util::remote().is_some() {
+		return util::exec_cmd(
+			"sh",
+			&[
+				"-c",
+				&format!("grep -H . {SYS_BUS_DIR}/*/devices/*/modalias"),
+			],
+		)
+		.map(|output| parse_modaliases(&output))
+		.unwrap_or_default();
+	}
+	let mut modaliases = Vec::new();
+	for bus in util::read_dir(SYS_BUS_DIR).unwrap_or_default() {
+		for device in util::read_dir(bus.join("devices")).unwrap_or_default() {
+			if let Ok(modalias) = util::read_file(device.join("modalias")) {
+				modaliases.push((device, modalias.trim().to_string()));
 			}
-			let module_size =
-				ByteSize::b(columns[1].to_string().parse().unwrap_or(0)).to_string();
-			module_list.push(vec![module_name, module_size, used_modules]);
-		}
-		/* Reverse the kernel modules if the argument is provided. */
-		if self.args.reverse {
-			module_list.reverse();
 		}
-		self.default_list.clone_from(&module_list);
-		self.list = module_list;
-		self.scroll_list(ScrollDirection::Top);
+	}
+	modaliases
+}
+
+/**
+ * Get the devices whose modaliases match the alias patterns of a module.
+ *
+ * @param  aliases
+ * @param  modaliases
+ * @return Vec
+ */
+pub fn matching_devices<'a>(
+	aliases: &[String],
+	modaliases: &'a [(PathBuf, String)],
+) -> Vec<&'a Path> {
+	modaliases
+		.iter()
+		.filter(|(_, modalias)| {
+			aliases
+				.iter()
+				.any(|alias| util::glob_match(alias, modalias))
+		})
+		.map(|(device, _)| device.as_path())
+		.collect()
+}
+
+/**
+ * Get the line of the module information that shows the matched devices.
+ *
+ * @param  module_info
+ * @param  modaliases
+ * @return String
+ */
+pub fn hardware_status(
+	module_info: Option<&str>,
+	modaliases: &[(PathBuf, String)],
+) -> String {
+	let aliases = module_info.map(parse_aliases).unwrap_or_default();
+	if aliases.is_empty() {
+		return String::from("unknown (no aliases)");
+	}
+	match matching_devices(&aliases, modaliases).as_slice() {
+		[] => String::from("no"),
+		devices => format!(
+			"yes ({})",
+			devices
+				.iter()
+				.filter_map(|v| v.file_name())
+				.map(|v| v.to_string_lossy())
+				.collect::<Vec<_>>()
+				.join(", ")
+		),
+	}
+}
+
+/**
+ * Check if the pseudo-filesystems that the modules are read from are mounted.
+ *
+ * They can be missing in the containers and chroots, which would otherwise
+ * result in an empty module list.
+ *
+ * @param  exists
+ * @return Result
+ */
+pub fn check_filesystems<F: Fn(&str) -> bool>(exists: F) -> Result<(), String> {
+	let missing = REQUIRED_FILESYSTEMS
+		.iter()
+		.filter(|(path, ..)| !exists(path))
+		.map(|(path, name, cmd)| {
+			format!("{path} is not found, {name} is not mounted (try '{cmd}')")
+		})
+		.collect::<Vec<String>>();
+	if missing.is_empty() {
 		Ok(())
+	} else {
+		Err(missing.join("; "))
+	}
+}
+
+/**
+ * Read the loaded kernel modules using the given listing properties.
+ *
+ * @param  ListArgs
+ * @return Result
+ */
+pub fn read_modules(args: &ListArgs) -> Result<Vec<Module>, Box<dyn Error>> {
+	/* Fall back to 'lsmod' if the modules cannot be read from procfs. */
+	let mut modules = match util::read_file(PROC_MODULES) {
+		Ok(contents) => parse_proc_modules(&contents),
+		/* Read the states from sysfs since 'lsmod' does not show them. */
+		Err(e) => parse_lsmod(
+			&util::exec_cmd("lsmod", &[])
+				.map_err(|_| format!("{PROC_MODULES}: {e}"))?,
+		)
+		.into_iter()
+		.map(|module| Module {
+			initstate: util::read_file(
+				Path::new(SYS_MODULE_DIR)
+					.join(&module.name)
+					.join("initstate"),
+			)
+			.ok()
+			.map(|v| v.trim().to_string()),
+			..module
+		})
+		.collect(),
+	};
+	/* Append the built-in modules that are not listed as loaded. */
+	if args.builtin && args.filter == ListFilter::All {
+		let builtin_modules = read_builtin_modules()
+			.into_iter()
+			.filter(|builtin| !modules.iter().any(|v| v.name == builtin.name))
+			.collect::<Vec<Module>>();
+		modules.extend(builtin_modules);
+	}
+	Ok(source::apply_list_args(modules, args))
+}
+
+/**
+ * Read the module parameters and their values from the given directory.
+ *
+ * Values of the parameters that cannot be read are returned as None.
+ *
+ * @param  dir
+ * @return Vec
+ */
+pub fn read_parameters(dir: &Path) -> Vec<(String, Option<String>)> {
+	let mut parameters = util::read_dir(dir)
+		.map(|paths| {
+			paths
+				.into_iter()
+				.filter_map(|path| {
+					Some((
+						path.file_name()?.to_string_lossy().to_string(),
+						util::read_file(&path)
+							.ok()
+							.map(|v| v.trim_end().to_string()),
+					))
+				})
+				.collect::<Vec<(String, Option<String>)>>()
+		})
+		.unwrap_or_default();
+	parameters.sort();
+	parameters
+}
+
+/**
+ * Read the names of the modules that are holding a module from sysfs.
+ *
+ * The holders directory contains a symbolic link for each module that
+ * uses the module, which is more accurate than the dependents in procfs.
+ *
+ * @param  dir
+ * @return Vec (Option)
+ */
+pub fn read_holders(dir: &Path) -> Option<Vec<String>> {
+	let mut holders = util::read_dir(dir)
+		.ok()?
+		.into_iter()
+		.filter_map(|path| Some(path.file_name()?.to_string_lossy().to_string()))
+		.collect::<Vec<String>>();
+	holders.sort();
+	Some(holders)
+}
+
+/**
+ * Get the arguments for loading a module again with its current parameters.
+ *
+ * Parameters without a value that can be passed back to the module
+ * (e.g. unset strings) are omitted.
+ *
+ * @param  parameters
+ * @return Vec
+ */
+pub fn reload_parameters(parameters: &[(String, Option<String>)]) -> Vec<String> {
+	parameters
+		.iter()
+		.filter_map(|(name, value)| match value.as_deref()? {
+			"" | "(null)" => None,
+			v if v.contains(char::is_whitespace) => None,
+			v => Some(format!("{name}={v}")),
+		})
+		.collect()
+}
+
+/**
+ * Get the permission bits of the given file.
+ *
+ * @param  path
+ * @return mode (Option)
+ */
+pub fn file_mode(path: &Path) -> Option<u32> {
+	if util::remote().is_some() {
+		return util::exec_cmd("stat", &["-c", "%a", &path.to_string_lossy()])
+			.ok()
+			.and_then(|mode| u32::from_str_radix(mode.trim(), 8).ok());
+	}
+	fs::metadata(path)
+		.ok()
+		.map(|v| v.permissions().mode() & 0o7777)
+}
+
+/**
+ * Check if the given file is writable according to its permissions.
+ *
+ * @param  path
+ * @return bool
+ */
+pub fn is_writable(path: &Path) -> bool {
+	file_mode(path).is_some_and(|mode| mode & 0o222 != 0)
+}
+
+/* Type, description and file mode of a module parameter */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParameterInfo {
+	pub param_type: Option<String>,
+	pub description: Option<String>,
+	pub mode: Option<u32>,
+}
+
+impl ParameterInfo {
+	/**
+	 * Get the permissions of the parameter file (e.g. "rw" or "r").
+	 *
+	 * @return permissions (Option)
+	 */
+	pub fn permissions(&self) -> Option<&'static str> {
+		self.mode
+			.map(|mode| match (mode & 0o444 != 0, mode & 0o222 != 0) {
+				(true, true) => "rw",
+				(true, false) => "r",
+				(false, true) => "w",
+				(false, false) => "-",
+			})
 	}
 
 	/**
-	 * Get the current command using current module name.
+	 * Get the annotation that follows the parameter name (e.g. " (int, rw)").
 	 *
-	 * @return Command
+	 * @return String
 	 */
-	pub fn get_current_command(&self) -> Command {
-		self.command.get(&self.current_name)
+	pub fn annotation(&self) -> String {
+		let fields = [self.param_type.as_deref(), self.permissions()]
+			.into_iter()
+			.flatten()
+			.collect::<Vec<&str>>();
+		if fields.is_empty() {
+			String::new()
+		} else {
+			format!(" ({})", fields.join(", "))
+		}
+	}
+}
+
+/**
+ * Check if the given text is a type of the module parameters (e.g. "uint").
+ *
+ * @param  text
+ * @return bool
+ */
+fn is_parameter_type(text: &str) -> bool {
+	let text = text.strip_prefix("array of ").unwrap_or(text);
+	!text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/**
+ * Parse the types and descriptions of the parameters from the module information.
+ *
+ * Types are read from the 'parmtype' fields or from the end of the 'parm'
+ * fields where 'modinfo' appends them (e.g. "debug:Debug level (int)").
+ * Parameters without a description or a type are also included.
+ *
+ * @param  module_info
+ * @return BTreeMap
+ */
+pub fn parse_parameter_info(module_info: &str) -> BTreeMap<String, ParameterInfo> {
+	let mut parameters = BTreeMap::<String, ParameterInfo>::new();
+	for field in info_field(module_info, "parm") {
+		let (name, description) = field.split_once(':').unwrap_or((field, ""));
+		let entry = parameters.entry(name.trim().to_string()).or_default();
+		let mut description = description.trim();
+		if let Some((rest, param_type)) = description
+			.strip_suffix(')')
+			.and_then(|v| v.rsplit_once('('))
+			.filter(|(_, v)| is_parameter_type(v))
+		{
+			entry.param_type = Some(param_type.to_string());
+			description = rest.trim_end();
+		}
+		entry.description = Some(description.to_string()).filter(|v| !v.is_empty());
+	}
+	for field in info_field(module_info, "parmtype") {
+		if let Some((name, param_type)) = field
+			.split_once(':')
+			.map(|(name, v)| (name.trim(), v.trim()))
+			.filter(|(_, v)| !v.is_empty())
+		{
+			parameters.entry(name.to_string()).or_default().param_type =
+				Some(param_type.to_string());
+		}
+	}
+	parameters
+}
+
+/**
+ * Write the value of a module parameter and read it back for confirming.
+ *
+ * @param  path
+ * @param  value
+ * @return Result
+ */
+pub fn write_parameter(path: &Path, value: &str) -> Result<Option<String>, String> {
+	if util::remote().is_some() {
+		return Err(String::from("not supported on the remote host"));
+	}
+	fs::write(path, value).map_err(|e| match e.kind() {
+		ErrorKind::PermissionDenied => {
+			String::from("permission denied (root privileges are required)")
+		}
+		_ => e.to_string(),
+	})?;
+	Ok(fs::read_to_string(path)
+		.ok()
+		.map(|v| v.trim_end().to_string()))
+}
+
+/**
+ * Get the index of the entry to select in the given direction.
+ *
+ * Selection wraps around at the first and the last entries.
+ *
+ * @param  index
+ * @param  count
+ * @param  direction
+ * @return usize
+ */
+fn select_entry(index: usize, count: usize, direction: ScrollDirection) -> usize {
+	let last = count.saturating_sub(1);
+	match direction {
+		ScrollDirection::Up if index == 0 => last,
+		ScrollDirection::Up => index - 1,
+		ScrollDirection::Down if index >= last => 0,
+		ScrollDirection::Down => index + 1,
+		ScrollDirection::Top => 0,
+		ScrollDirection::Bottom => last,
+		_ => index,
+	}
+}
+
+/**
+ * Get the signature status of a module from its information and taint flags.
+ *
+ * @param  module_info
+ * @param  taint
+ * @return String
+ */
+pub fn signature_status(module_info: Option<&str>, taint: Option<&str>) -> String {
+	let signer = module_info.and_then(|info| {
+		info.lines()
+			.find_map(|line| line.strip_prefix("signer:"))
+			.map(str::trim)
+			.filter(|v| !v.is_empty())
+	});
+	let taint = taint.unwrap_or_default();
+	let status = match signer {
+		Some(signer) => format!("Signed by: {signer}"),
+		None if taint.contains('E') => String::from("Unsigned (would taint)"),
+		None => String::from("unknown"),
+	};
+	if taint.contains('O') {
+		format!("{status}, out-of-tree")
+	} else {
+		status
+	}
+}
+
+/**
+ * Get the license, author and description lines from the module information.
+ *
+ * Multiple authors are joined and the missing fields are omitted.
+ *
+ * @param  module_info
+ * @return String
+ */
+pub fn module_summary(module_info: Option<&str>) -> String {
+	let mut summary = String::new();
+	for field in ["license", "author", "description"] {
+		let values = info_field(module_info.unwrap_or_default(), field);
+		if !values.is_empty() {
+			summary +=
+				&format!("{:<16}{}\n", format!("{field}:"), values.join(", "));
+		}
+	}
+	summary
+}
+
+/**
+ * Get the hardware aliases (e.g. PCI and USB IDs) from the 'alias' fields of
+ * the module information.
+ *
+ * @param  module_info
+ * @return Vec
+ */
+pub fn parse_aliases(module_info: &str) -> Vec<String> {
+	module_info
+		.lines()
+		.filter_map(|line| line.strip_prefix("alias:"))
+		.map(str::trim)
+		.filter(|v| !v.is_empty())
+		.map(String::from)
+		.collect()
+}
+
+/**
+ * Get the path of the module file from the 'filename' field of its information.
+ *
+ * This is the same value as the output of 'modinfo -n'.
+ * Modules that are compiled into the kernel do not have a file.
+ *
+ * @param  module_info
+ * @return String
+ */
+pub fn module_path(module_info: Option<&str>) -> String {
+	match module_info.map(|info| {
+		info.lines()
+			.find_map(|line| line.strip_prefix("filename:"))
+			.map(str::trim)
+	}) {
+		Some(Some(path)) if !path.is_empty() && path != "(builtin)" => {
+			path.to_string()
+		}
+		Some(_) => String::from("(built-in)"),
+		None => String::from("unknown"),
+	}
+}
+
+/**
+ * Get whether the module is shipped with the kernel from its information.
+ *
+ * The 'intree' field (i.e. 'modinfo -F intree') is 'Y' for the in-tree
+ * modules and the 'O' taint flag is set for the loaded out-of-tree ones.
+ * Built-in modules and the modules without the field are unknown.
+ *
+ * @param  module_info
+ * @param  taint
+ * @return str
+ */
+pub fn tree_status(module_info: Option<&str>, taint: Option<&str>) -> &'static str {
+	if taint.is_some_and(|v| v.contains('O')) {
+		return "out-of-tree";
+	}
+	match module_info.and_then(|info| info_field(info, "intree").first().copied()) {
+		Some("Y") => "in-tree",
+		Some(_) => "out-of-tree",
+		None => "unknown",
+	}
+}
+
+/**
+ * Get the values of the given field from the module information.
+ *
+ * @param  module_info
+ * @param  field
+ * @return Vec
+ */
+pub fn info_field<'a>(module_info: &'a str, field: &str) -> Vec<&'a str> {
+	module_info
+		.lines()
+		.filter_map(|line| line.strip_prefix(field)?.strip_prefix(':'))
+		.map(str::trim)
+		.filter(|v| !v.is_empty())
+		.collect()
+}
+
+/**
+ * Create a Markdown report of a module for the issue trackers.
+ *
+ * Parameters are read from the 'parm' fields of the module information
+ * if the current values of them are not given (i.e. module is not loaded).
+ *
+ * @param  module_name
+ * @param  module_info
+ * @param  parameters
+ * @param  taint
+ * @return String
+ */
+pub fn format_report(
+	module_name: &str,
+	module_info: &str,
+	parameters: &[(String, Option<String>)],
+	taint: Option<&str>,
+) -> String {
+	let field = |name: &str| match info_field(module_info, name).join(", ") {
+		v if v.is_empty() => String::from("-"),
+		v => v,
+	};
+	let mut report = format!(
+		"### {module_name}
+
+"
+	);
+	for (name, value) in [
+		("Version", field("version")),
+		("Path", module_path(Some(module_info))),
+		("License", field("license")),
+		("Dependencies", field("depends")),
+		("Signature", signature_status(Some(module_info), taint)),
+	] {
+		let _ = writeln!(report, "- **{name}:** `{value}`");
+	}
+	let descriptions = info_field(module_info, "parm");
+	if !parameters.is_empty() || !descriptions.is_empty() {
+		report.push_str(
+			"
+#### Parameters
+
+",
+		);
+	}
+	if parameters.is_empty() {
+		for parameter in descriptions {
+			let (name, description) =
+				parameter.split_once(':').unwrap_or((parameter, ""));
+			let _ = writeln!(report, "- `{name}`: {}", description.trim());
+		}
+	}
+	for (name, value) in parameters {
+		let _ =
+			writeln!(report, "- `{name}`: `{}`", value.as_deref().unwrap_or("?"));
+	}
+	report
+}
+
+/**
+ * Create a snapshot of all the loaded modules that are read from the source.
+ *
+ * @param  source
+ * @param  args
+ * @return Result
+ */
+fn capture_snapshot(
+	source: &dyn ModuleSource,
+	args: ListArgs,
+) -> Result<Snapshot, Box<dyn Error>> {
+	Ok(Snapshot::capture(
+		source,
+		&source.list_modules(&ListArgs {
+			filter: ListFilter::All,
+			builtin: false,
+			..args
+		})?,
+	))
+}
+
+/**
+ * Read the Markdown report of the given module through the given source.
+ *
+ * Current values of the parameters are included if the module is loaded.
+ *
+ * @param  source
+ * @param  module_name
+ * @return report (Option)
+ */
+pub fn read_report(source: &dyn ModuleSource, module_name: &str) -> Option<String> {
+	let module_info = source
+		.module_info(module_name)
+		.ok()
+		.filter(|v| !v.trim().is_empty())?;
+	let dir = Path::new(SYS_MODULE_DIR).join(module_name);
+	let taint = util::read_file(dir.join("taint")).ok();
+	Some(format_report(
+		module_name,
+		&module_info,
+		&source.parameters(module_name),
+		taint.as_deref(),
+	))
+}
+
+/* Searchable fields of a module that are read from its information */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchEntry {
+	pub description: String,
+	pub aliases: Vec<String>,
+}
+
+impl SearchEntry {
+	/**
+	 * Create a search entry from the module information.
+	 *
+	 * @param  module_info
+	 * @return SearchEntry
+	 */
+	pub fn parse(module_info: &str) -> Self {
+		Self {
+			description: info_field(module_info, "description").join(" "),
+			aliases: parse_aliases(module_info),
+		}
+	}
+
+	/**
+	 * Get the name of the first field that matches the given function.
+	 *
+	 * @param  matches
+	 * @return Option
+	 */
+	pub fn matched_field(
+		&self,
+		matches: impl Fn(&str) -> bool,
+	) -> Option<&'static str> {
+		if matches(&self.description) {
+			Some("description")
+		} else if self.aliases.iter().any(|alias| matches(alias)) {
+			Some("alias")
+		} else {
+			None
+		}
+	}
+}
+
+/* Modules that are loaded and unloaded since the previous refresh */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuleChanges {
+	pub added: BTreeSet<String>,
+	pub removed: BTreeSet<String>,
+	pub time: Instant,
+}
+
+impl ModuleChanges {
+	/**
+	 * Get the summary of the changes, e.g. "+1 loaded, -2 unloaded: a, b".
+	 *
+	 * Names of the unloaded modules are shown since they are not listed anymore.
+	 *
+	 * @return String
+	 */
+	pub fn summary(&self) -> String {
+		let mut summary = Vec::new();
+		if !self.added.is_empty() {
+			summary.push(format!("+{} loaded", self.added.len()));
+		}
+		if !self.removed.is_empty() {
+			let mut names = self
+				.removed
+				.iter()
+				.take(MAX_REMOVED_NAMES)
+				.map(String::as_str)
+				.collect::<Vec<&str>>()
+				.join(", ");
+			if self.removed.len() > MAX_REMOVED_NAMES {
+				names += ", ...";
+			}
+			summary.push(format!("-{} unloaded: {names}", self.removed.len()));
+		}
+		summary.join(", ")
+	}
+}
+
+/* Loadable kernel modules */
+pub struct KernelModules<'a> {
+	pub modules: Vec<Module>,
+	pub marked: BTreeSet<String>,
+	pub bookmarks: BTreeSet<String>,
+	batch: Vec<String>,
+	pub blacklist: BTreeMap<String, Vec<BlacklistEntry>>,
+	pub boot_modules: BTreeMap<String, Vec<PathBuf>>,
+	pub default_list: Vec<Vec<String>>,
+	pub list: Vec<Vec<String>>,
+	pub current_name: String,
+	pub current_info: StyledText<'a>,
+	pub command: ModuleCommand,
+	pub command_params: Vec<String>,
+	pub cmd_args: CommandArgs,
+	pub index: usize,
+	pub info_scroll_offset: usize,
+	pub dependents: Vec<String>,
+	pub dependent_index: usize,
+	pub parameters: Vec<(String, Option<String>)>,
+	pub parameter_info: BTreeMap<String, ParameterInfo>,
+	pub parameter_index: usize,
+	pub parameters_module: String,
+	pub style: Style,
+	pub args: ListArgs,
+	pub refreshing: bool,
+	pub search_index: BTreeMap<String, SearchEntry>,
+	pub indexing: Option<(usize, usize)>,
+	index_cancel: Arc<AtomicBool>,
+	pub loaded_count: usize,
+	pub loaded_size: u64,
+	pub used_count: usize,
+	pub blacklisted_count: usize,
+	pub last_action: Option<(ModuleCommand, String)>,
+	pub status: Option<Result<String, String>>,
+	pub running: Option<(ModuleCommand, String, Instant)>,
+	pub groups: Vec<String>,
+	pub collapsed: BTreeSet<String>,
+	pub changes: Option<ModuleChanges>,
+	listed: Option<(ListArgs, BTreeSet<String>)>,
+	pending_selection: Option<String>,
+	retry_privilege: Option<PrivilegeCmd>,
+	undo_pending: bool,
+	unload_order: Vec<String>,
+	load_prerequisites: Vec<String>,
+	confirm_time: Instant,
+	info_cache: LruCache<Rc<str>>,
+	available: OnceLock<BTreeSet<String>>,
+	modaliases: Option<Vec<(PathBuf, String)>>,
+	source: DataSource,
+}
+
+impl KernelModules<'_> {
+	/**
+	 * Create a new kernel modules instance.
+	 *
+	 * @param  ListArgs
+	 * @param  CommandArgs
+	 * @param  Style
+	 * @return KernelModules
+	 */
+	pub fn new(args: ListArgs, cmd_args: CommandArgs, style: Style) -> Self {
+		Self::with_source(args, cmd_args, style, DataSource::default())
+	}
+
+	/**
+	 * Create a new kernel modules instance that reads from the given source.
+	 *
+	 * @param  ListArgs
+	 * @param  CommandArgs
+	 * @param  Style
+	 * @param  DataSource
+	 * @return KernelModules
+	 */
+	pub fn with_source(
+		args: ListArgs,
+		cmd_args: CommandArgs,
+		style: Style,
+		source: DataSource,
+	) -> Self {
+		let mut kernel_modules = Self {
+			modules: Vec::new(),
+			marked: BTreeSet::new(),
+			bookmarks: BTreeSet::new(),
+			batch: Vec::new(),
+			blacklist: BTreeMap::new(),
+			boot_modules: BTreeMap::new(),
+			default_list: Vec::new(),
+			list: Vec::new(),
+			current_name: String::new(),
+			current_info: StyledText::default(),
+			command: ModuleCommand::None,
+			command_params: Vec::new(),
+			cmd_args,
+			index: 0,
+			info_scroll_offset: 0,
+			dependents: Vec::new(),
+			dependent_index: 0,
+			parameters: Vec::new(),
+			parameter_info: BTreeMap::new(),
+			parameter_index: 0,
+			parameters_module: String::new(),
+			args,
+			style,
+			refreshing: false,
+			search_index: BTreeMap::new(),
+			indexing: None,
+			index_cancel: Arc::default(),
+			loaded_count: 0,
+			loaded_size: 0,
+			used_count: 0,
+			blacklisted_count: 0,
+			last_action: None,
+			status: None,
+			running: None,
+			groups: Vec::new(),
+			collapsed: BTreeSet::new(),
+			changes: None,
+			listed: None,
+			pending_selection: None,
+			retry_privilege: None,
+			undo_pending: false,
+			unload_order: Vec::new(),
+			load_prerequisites: Vec::new(),
+			confirm_time: Instant::now(),
+			info_cache: LruCache::new(INFO_CACHE_SIZE),
+			available: OnceLock::new(),
+			modaliases: None,
+			source,
+		};
+		if let Err(e) = kernel_modules.refresh() {
+			eprintln!("{e}");
+		}
+		kernel_modules
+	}
+
+	/* Parse kernel modules from '/proc/modules'. */
+	pub fn refresh(&mut self) -> Result<(), Box<dyn Error>> {
+		self.modules = self.source.list_modules(&self.args)?;
+		self.filter_bookmarks();
+		self.refresh_blacklist();
+		self.update_totals();
+		self.default_list = self
+			.modules
+			.iter()
+			.map(|module| module.row(self.args.size_format))
+			.collect();
+		self.list.clone_from(&self.default_list);
+		self.scroll_list(ScrollDirection::Top);
+		Ok(())
+	}
+
+	/* Count the loaded, in-use and blacklisted modules regardless of the list filter. */
+	fn update_totals(&mut self) {
+		let loaded_modules = (self.args.filter != ListFilter::All
+			|| self.args.size_filter.is_some()
+			|| self.args.hide_used
+			|| self.args.bookmarked)
+			.then(|| {
+				self.source
+					.list_modules(&ListArgs {
+						filter: ListFilter::All,
+						size_filter: None,
+						hide_used: false,
+						bookmarked: false,
+						..self.args
+					})
+					.ok()
+			})
+			.flatten();
+		let modules = loaded_modules
+			.as_deref()
+			.unwrap_or(&self.modules)
+			.iter()
+			.filter(|v| !v.builtin);
+		self.loaded_count = modules.clone().count();
+		self.loaded_size = modules.clone().map(|v| v.size).sum();
+		self.used_count = modules.clone().filter(|v| v.refcount > 0).count();
+		self.blacklisted_count =
+			modules.filter(|v| self.is_blacklisted(&v.name)).count();
+	}
+
+	/* Read the blacklisted modules and the modules that are loaded at boot. */
+	fn refresh_blacklist(&mut self) {
+		/* Configuration files of the system are not read in demo mode. */
+		if self.cmd_args.demo {
+			return;
+		}
+		let blacklist = blacklist::read_blacklist(MODPROBE_DIRS);
+		let boot_modules =
+			boot::read_boot_modules(MODULES_LOAD_DIRS, &[ETC_MODULES]);
+		/* Module information contains the blacklist and boot entries. */
+		if blacklist != self.blacklist || boot_modules != self.boot_modules {
+			self.info_cache.clear();
+			self.blacklist = blacklist;
+			self.boot_modules = boot_modules;
+		}
+	}
+
+	/**
+	 * Check if the given loaded module is out-of-tree from its taint flags.
+	 *
+	 * @param  module_name
+	 * @return bool
+	 */
+	pub fn is_out_of_tree(&self, module_name: &str) -> bool {
+		self.modules
+			.iter()
+			.find(|module| module.name == module_name)
+			.and_then(|module| module.taint.as_deref())
+			.is_some_and(|taint| taint.contains('O'))
+	}
+
+	/**
+	 * Get the files that load the given module at boot.
+	 *
+	 * @param  module_name
+	 * @return Vec
+	 */
+	pub fn boot_files(&self, module_name: &str) -> &[PathBuf] {
+		self.boot_modules
+			.get(&blacklist::normalize_name(module_name))
+			.map(Vec::as_slice)
+			.unwrap_or_default()
+	}
+
+	/**
+	 * Check if the given module is loaded at boot from the file that is managed by kmon.
+	 *
+	 * @param  module_name
+	 * @return bool
+	 */
+	pub fn is_boot_managed(&self, module_name: &str) -> bool {
+		self.boot_files(module_name)
+			.contains(&self.cmd_args.boot.path)
+	}
+
+	/**
+	 * Check if the given module is blacklisted.
+	 *
+	 * @param  module_name
+	 * @return bool
+	 */
+	pub fn is_blacklisted(&self, module_name: &str) -> bool {
+		self.blacklist
+			.contains_key(&blacklist::normalize_name(module_name))
+	}
+
+	/**
+	 * Get the blacklist files that the given module will be removed from.
+	 *
+	 * Only the file that is managed by kmon is used unless it is configured
+	 * to remove the module from all the files.
+	 *
+	 * @param  module_name
+	 * @return Vec
+	 */
+	pub fn get_unblacklist_files(&self, module_name: &str) -> Vec<String> {
+		let mut files = self
+			.blacklist
+			.get(&blacklist::normalize_name(module_name))
+			.map(|entries| {
+				entries
+					.iter()
+					.filter(|entry| {
+						self.cmd_args.blacklist.remove_all
+							|| entry.path == self.cmd_args.blacklist.path
+					})
+					.map(|entry| entry.path.to_string_lossy().to_string())
+					.collect::<Vec<String>>()
+			})
+			.unwrap_or_default();
+		files.dedup();
+		files
+	}
+
+	/**
+	 * Check if the given module is built into the kernel.
+	 *
+	 * @param  module_name
+	 * @return bool
+	 */
+	pub fn is_builtin(&self, module_name: &str) -> bool {
+		self.args.builtin
+			&& self
+				.modules
+				.iter()
+				.any(|module| module.builtin && module.name == module_name)
+	}
+
+	/**
+	 * Check if the given module is in use by checking its reference count.
+	 *
+	 * @param  module_name
+	 * @return bool
+	 */
+	pub fn is_in_use(&self, module_name: &str) -> bool {
+		self.modules
+			.iter()
+			.any(|module| module.refcount > 0 && module.name == module_name)
+	}
+
+	/**
+	 * Get the error message for a module that cannot be unloaded because it
+	 * is in use, naming the modules or the number of references holding it.
+	 *
+	 * @param  module_name
+	 * @return String
+	 */
+	pub fn busy_message(&self, module_name: &str) -> String {
+		let refcount = self
+			.modules
+			.iter()
+			.find(|module| module.name == module_name)
+			.map(|module| module.refcount)
+			.unwrap_or_default();
+		match self.get_dependent_modules(module_name) {
+			Some(dependents) if !dependents.is_empty() => format!(
+				"{module_name} is in use by {}, unload them first",
+				dependents.join(", ")
+			),
+			_ if refcount > 0 => format!(
+				"{module_name} is held by {refcount} process(es) or device(s), \
+				stop them first"
+			),
+			_ => format!("{module_name} is in use, try again later"),
+		}
+	}
+
+	/**
+	 * Check if the given module is live (i.e. not initializing or being removed).
+	 *
+	 * @param  module_name
+	 * @return bool
+	 */
+	pub fn is_live(&self, module_name: &str) -> bool {
+		self.modules
+			.iter()
+			.find(|module| module.name == module_name)
+			.is_none_or(Module::is_live)
+	}
+
+	/**
+	 * Toggle the visibility of the modules that are in use.
+	 *
+	 * Selection is kept if the selected module is still listed.
+	 */
+	pub fn toggle_used(&mut self) -> Result<(), Box<dyn Error>> {
+		self.args.hide_used = !self.args.hide_used;
+		self.refresh_selected()
+	}
+
+	/* Keep only the bookmarked modules if the bookmarks are shown. */
+	fn filter_bookmarks(&mut self) {
+		if self.args.bookmarked {
+			self.modules
+				.retain(|module| self.bookmarks.contains(&module.name));
+		}
+	}
+
+	/**
+	 * Check if the given module is bookmarked.
+	 *
+	 * @param  module_name
+	 * @return bool
+	 */
+	pub fn is_bookmarked(&self, module_name: &str) -> bool {
+		self.bookmarks.contains(module_name)
+	}
+
+	/**
+	 * Add or remove the bookmark of the selected module.
+	 *
+	 * @return status (Option)
+	 */
+	pub fn toggle_bookmark(&mut self) -> Option<String> {
+		let module_name = self
+			.list
+			.get(self.index)?
+			.first()?
+			.split_whitespace()
+			.next()?
+			.to_string();
+		let status = if self.bookmarks.remove(&module_name) {
+			format!("removed the bookmark of {module_name}")
+		} else {
+			self.bookmarks.insert(module_name.to_string());
+			format!("bookmarked {module_name}")
+		};
+		/* Remove the module from the list of bookmarks if it is shown. */
+		if self.args.bookmarked {
+			let _ = self.refresh_selected();
+		}
+		Some(status)
+	}
+
+	/**
+	 * Toggle the list that only shows the bookmarked modules.
+	 *
+	 * Selection is kept if the selected module is still listed.
+	 */
+	pub fn toggle_bookmarks(&mut self) -> Result<(), Box<dyn Error>> {
+		self.args.bookmarked = !self.args.bookmarked;
+		self.refresh_selected()
+	}
+
+	/**
+	 * Toggle the visibility of the built-in modules.
+	 *
+	 * Selection is kept if the selected module is still listed.
+	 */
+	pub fn toggle_builtin(&mut self) -> Result<(), Box<dyn Error>> {
+		self.args.builtin = !self.args.builtin;
+		self.refresh_selected()
+	}
+
+	/* Toggle the display format of the module sizes. */
+	pub fn toggle_size_format(&mut self) {
+		self.args.size_format = self.args.size_format.toggle();
+		let modules = std::mem::take(&mut self.modules);
+		self.set_modules(&modules);
+	}
+
+	/**
+	 * Read the kernel modules in the background and send them as an event.
+	 *
+	 * @param tx
+	 */
+	pub fn refresh_async(&mut self, tx: &Sender<Event<Key>>) {
+		self.refreshing = true;
+		self.info_cache.clear();
+		self.modaliases = None;
+		let tx = tx.clone();
+		let args = self.args;
+		let source = self.source.clone();
+		thread::spawn(move || {
+			tx.send(Event::Modules(
+				source.list_modules(&args).map_err(|e| e.to_string()),
+			))
+			.unwrap_or_default();
+		});
+	}
+
+	/**
+	 * Read the searchable fields of the modules that are not indexed yet in
+	 * the background and send them as events in batches.
+	 *
+	 * Index is kept between the refreshes, so 'modinfo' is executed once for
+	 * each module instead of every search. Modules are shared between a
+	 * limited number of threads for running 'modinfo' concurrently.
+	 *
+	 * @param tx
+	 */
+	pub fn index_async(&mut self, tx: &Sender<Event<Key>>) {
+		let names = self
+			.modules
+			.iter()
+			.filter(|module| !self.search_index.contains_key(&module.name))
+			.map(|module| module.name.to_string())
+			.collect::<Vec<String>>();
+		if self.indexing.is_some() || names.is_empty() {
+			return;
+		}
+		self.indexing = Some((0, names.len()));
+		self.index_cancel = Arc::default();
+		let thread_count = INDEX_THREADS.min(names.len());
+		let names = Arc::new(Mutex::new(names.into_iter()));
+		for _ in 0..thread_count {
+			let tx = tx.clone();
+			let source = self.source.clone();
+			let names = Arc::clone(&names);
+			let cancel = Arc::clone(&self.index_cancel);
+			thread::spawn(move || {
+				let mut entries = Vec::new();
+				loop {
+					let name = match names.lock() {
+						Ok(mut names) if !cancel.load(AtomicOrdering::Relaxed) => {
+							names.next()
+						}
+						_ => None,
+					};
+					match name {
+						Some(name) => {
+							let entry = source
+								.module_info(&name)
+								.map(|info| SearchEntry::parse(&info))
+								.unwrap_or_default();
+							entries.push((name, entry));
+							if entries.len() < INDEX_BATCH {
+								continue;
+							}
+						}
+						None if entries.is_empty() => break,
+						None => {}
+					}
+					if tx
+						.send(Event::SearchIndex(mem::take(&mut entries)))
+						.is_err()
+					{
+						break;
+					}
+				}
+			});
+		}
+	}
+
+	/**
+	 * Add the given entries to the search index and update the progress.
+	 *
+	 * @param entries
+	 */
+	pub fn set_search_index(&mut self, entries: Vec<(String, SearchEntry)>) {
+		if let Some((indexed, total)) = self.indexing {
+			let indexed = indexed + entries.len();
+			self.indexing = (indexed < total).then_some((indexed, total));
+		}
+		self.search_index.extend(entries);
+	}
+
+	/* Stop indexing the remaining modules in the background. */
+	pub fn cancel_index(&mut self) {
+		self.index_cancel.store(true, AtomicOrdering::Relaxed);
+		self.indexing = None;
+	}
+
+	/**
+	 * Update the module list with the given modules.
+	 *
+	 * Selection and scroll position are kept if the selected module still exists.
+	 *
+	 * @param modules
+	 */
+	pub fn set_modules(&mut self, modules: &[Module]) {
+		self.refreshing = false;
+		self.update_changes(modules);
+		self.modules = modules.to_vec();
+		self.filter_bookmarks();
+		self.refresh_blacklist();
+		self.update_totals();
+		self.default_list = modules
+			.iter()
+			.map(|module| module.row(self.args.size_format))
+			.collect();
+		self.list.clone_from(&self.default_list);
+		match self.list.iter().position(|module| {
+			module[0].split_whitespace().next() == Some(self.current_name.as_str())
+		}) {
+			Some(index) => self.index = index,
+			/* Keep the shown message (e.g. command results) on the block. */
+			None if self.current_name.starts_with('!') && !self.list.is_empty() => {
+				self.index = self.index.min(self.list.len() - 1)
+			}
+			None => self.scroll_list(ScrollDirection::Top),
+		}
+	}
+
+	/**
+	 * Compare the modules with the previous ones that are listed with the
+	 * same properties and save the loaded and unloaded modules.
+	 *
+	 * Previous changes are kept until they expire if nothing is changed.
+	 *
+	 * @param modules
+	 */
+	fn update_changes(&mut self, modules: &[Module]) {
+		let names = modules
+			.iter()
+			.map(|module| module.name.to_string())
+			.collect::<BTreeSet<String>>();
+		if let Some((args, listed)) = &self.listed {
+			if *args == self.args {
+				let added =
+					names.difference(listed).cloned().collect::<BTreeSet<_>>();
+				let removed =
+					listed.difference(&names).cloned().collect::<BTreeSet<_>>();
+				if !added.is_empty() || !removed.is_empty() {
+					self.changes = Some(ModuleChanges {
+						added,
+						removed,
+						time: Instant::now(),
+					});
+				}
+			}
+		}
+		self.listed = Some((self.args, names));
+	}
+
+	/**
+	 * Get the changes of the modules until the highlight duration is passed.
+	 *
+	 * @return ModuleChanges (Option)
+	 */
+	pub fn recent_changes(&self) -> Option<&ModuleChanges> {
+		self.changes
+			.as_ref()
+			.filter(|changes| changes.time.elapsed() < CHANGE_DURATION)
+	}
+
+	/**
+	 * Set the prefixes of the module groups.
+	 *
+	 * @param GroupsConfig
+	 */
+	pub fn set_groups(&mut self, config: &GroupsConfig) {
+		self.groups.clone_from(&config.prefixes);
+		self.collapsed = if config.collapsed {
+			self.groups.iter().cloned().collect()
+		} else {
+			BTreeSet::new()
+		};
+	}
+
+	/**
+	 * Get the prefix of the group that the given module belongs to.
+	 *
+	 * @param  name
+	 * @return prefix (Option)
+	 */
+	pub fn group_of(&self, name: &str) -> Option<&str> {
+		self.groups
+			.iter()
+			.map(String::as_str)
+			.find(|prefix| name.starts_with(prefix))
+	}
+
+	/**
+	 * Get the prefix of the group if the given name is a group header.
+	 *
+	 * @param  name
+	 * @return prefix (Option)
+	 */
+	pub fn group_header<'b>(&self, name: &'b str) -> Option<&'b str> {
+		name.strip_suffix('*')
+			.filter(|prefix| self.groups.iter().any(|v| v == prefix))
+	}
+
+	/**
+	 * Replace the rows of the modules in the collapsed groups with header rows.
+	 *
+	 * Header row of a group is placed at the position of its first module
+	 * and shows the number of the modules with their total size.
+	 *
+	 * @param  rows
+	 * @return rows
+	 */
+	pub fn group_rows(&self, rows: Vec<Vec<String>>) -> Vec<Vec<String>> {
+		if self.collapsed.is_empty() {
+			return rows;
+		}
+		let mut grouped = Vec::new();
+		let mut headers = BTreeMap::<&str, (usize, usize, u64)>::new();
+		for row in rows {
+			let name = row[0].split_whitespace().next().unwrap_or_default();
+			match self
+				.group_of(name)
+				.filter(|prefix| self.collapsed.contains(*prefix))
+			{
+				Some(prefix) => {
+					let size = self
+						.modules
+						.iter()
+						.find(|v| v.name == name)
+						.map_or(0, |v| v.size);
+					let header = headers.entry(prefix).or_insert_with(|| {
+						grouped.push(Vec::new());
+						(grouped.len() - 1, 0, 0)
+					});
+					header.1 += 1;
+					header.2 += size;
+				}
+				None => grouped.push(row),
+			}
+		}
+		for (prefix, (index, count, size)) in headers {
+			grouped[index] = vec![
+				format!(" {prefix}* ({count})"),
+				self.args.size_format.format(size),
+				String::new(),
+			];
+		}
+		grouped
+	}
+
+	/**
+	 * Get the group prefix of the selected row, which is either a group header
+	 * or a module in an expanded group.
+	 *
+	 * @return prefix (Option)
+	 */
+	fn selected_group(&self) -> Option<String> {
+		let name = self.list.get(self.index)?[0].split_whitespace().next()?;
+		self.group_header(name)
+			.or_else(|| self.group_of(name))
+			.map(String::from)
+	}
+
+	/**
+	 * Expand the selected group or collapse the group of the selected module.
+	 *
+	 * @return toggled
+	 */
+	pub fn toggle_group(&mut self) -> bool {
+		let Some(prefix) = self.selected_group() else {
+			return false;
+		};
+		if !self.collapsed.remove(&prefix) {
+			self.collapsed.insert(prefix.to_string());
+		}
+		self.pending_selection = Some(prefix);
+		true
+	}
+
+	/**
+	 * Collapse all the groups or expand them if any of them is collapsed.
+	 *
+	 * @return toggled
+	 */
+	pub fn toggle_groups(&mut self) -> bool {
+		if self.groups.is_empty() {
+			return false;
+		}
+		self.pending_selection = self.selected_group().or_else(|| {
+			self.list
+				.get(self.index)
+				.and_then(|v| v[0].split_whitespace().next())
+				.map(String::from)
+		});
+		self.collapsed = if self.collapsed.is_empty() {
+			self.groups.iter().cloned().collect()
+		} else {
+			BTreeSet::new()
+		};
+		true
+	}
+
+	/**
+	 * Select the module or group that was selected before regrouping the list.
+	 *
+	 * Groups are selected by their header or their first module.
+	 */
+	pub fn select_pending(&mut self) {
+		let Some(target) = self.pending_selection.take() else {
+			return;
+		};
+		let is_group = self.groups.contains(&target);
+		if let Some(index) = self.list.iter().position(|row| {
+			let name = row[0].split_whitespace().next().unwrap_or_default();
+			if is_group {
+				name.starts_with(&target)
+			} else {
+				name == target
+			}
+		}) {
+			self.select_module(index);
+		}
+	}
+
+	/**
+	 * Show the modules of the group on the information block.
+	 *
+	 * @param prefix
+	 */
+	fn show_group(&mut self, prefix: &str) {
+		let modules = self
+			.modules
+			.iter()
+			.filter(|v| self.group_of(&v.name) == Some(prefix))
+			.map(|v| (v.name.to_string(), self.args.size_format.format(v.size)))
+			.collect::<Vec<(String, String)>>();
+		self.current_info.set(
+			Text::from(
+				modules
+					.iter()
+					.map(|(name, size)| {
+						Line::from(vec![
+							Span::styled("-", self.style.colored),
+							Span::styled(
+								format!(" {name:<24}{size}"),
+								self.style.default,
+							),
+						])
+					})
+					.collect::<Vec<Line>>(),
+			),
+			modules
+				.iter()
+				.map(|(name, size)| format!("{name:<24}{size}"))
+				.collect::<Vec<String>>()
+				.join("\n"),
+		);
+		self.current_name = format!("!Group: {prefix}*");
+	}
+
+	/**
+	 * Switch to the next filter of the module list.
+	 *
+	 * Selection is kept if the selected module passes the new filter.
+	 */
+	pub fn next_filter(&mut self) -> Result<(), Box<dyn Error>> {
+		self.args.filter = self.args.filter.next().unwrap_or(ListFilter::All);
+		self.refresh_selected()
+	}
+
+	/**
+	 * Set the size filter of the module list from the given expression.
+	 *
+	 * An empty expression clears the filter.
+	 *
+	 * @param  expr
+	 */
+	pub fn set_size_filter(&mut self, expr: &str) -> Result<(), Box<dyn Error>> {
+		self.args.size_filter = if expr.trim().is_empty() {
+			None
+		} else {
+			Some(SizeFilter::try_from(expr)?)
+		};
+		self.refresh_selected()
+	}
+
+	/**
+	 * Switch to the next sort type of the module list.
+	 *
+	 * Selection is kept if the selected module is still listed.
+	 */
+	pub fn next_sort(&mut self) -> Result<(), Box<dyn Error>> {
+		self.args.sort = self.args.sort.next();
+		self.refresh_selected()
+	}
+
+	/**
+	 * Toggle the ascending/descending order of the module list.
+	 *
+	 * Current list is reversed without reading the modules again since
+	 * the reversal is the last step after filtering and sorting them.
+	 * Selected module is kept.
+	 */
+	pub fn toggle_sort_direction(&mut self) {
+		self.args.reverse = !self.args.reverse;
+		let mut modules = std::mem::take(&mut self.modules);
+		modules.reverse();
+		self.set_modules(&modules);
+	}
+
+	/**
+	 * Refresh the module list and select the previously selected module.
+	 *
+	 * @return Result
+	 */
+	fn refresh_selected(&mut self) -> Result<(), Box<dyn Error>> {
+		let current_name = self.current_name.to_string();
+		self.refresh()?;
+		if let Some(index) = self.list.iter().position(|module| {
+			module[0].split_whitespace().next() == Some(current_name.as_str())
+		}) {
+			self.select_module(index);
+		}
+		Ok(())
+	}
+
+	/**
+	 * Create a snapshot of all the loaded modules regardless of the filter.
+	 *
+	 * @return Result
+	 */
+	pub fn snapshot(&self) -> Result<Snapshot, Box<dyn Error>> {
+		capture_snapshot(&*self.source, self.args)
+	}
+
+	/**
+	 * Save a snapshot of all the loaded modules in the background and send
+	 * the saved path as an event.
+	 *
+	 * Versions of the modules might be read with 'modinfo', so the snapshot
+	 * is not created on the UI thread.
+	 *
+	 * @param tx
+	 */
+	pub fn save_snapshot_async(&self, tx: &Sender<Event<Key>>) {
+		let tx = tx.clone();
+		let args = self.args;
+		let source = self.source.clone();
+		thread::spawn(move || {
+			tx.send(Event::Snapshot(
+				capture_snapshot(&*source, args)
+					.map_err(|e| e.to_string())
+					.and_then(|snapshot| snapshot.save(None)),
+			))
+			.unwrap_or_default();
+		});
+	}
+
+	/**
+	 * Get the information of the selected module as markdown for the bug reports.
+	 *
+	 * The report is the same as the one printed by the 'report' subcommand.
+	 *
+	 * @return report (Option)
+	 */
+	pub fn module_report(&self) -> Option<String> {
+		if self.current_name.starts_with('!') {
+			return None;
+		}
+		let row = self.list.get(self.index)?;
+		read_report(&*self.source, row[0].split_whitespace().next()?)
+	}
+
+	/**
+	 * Get the runtime parameters of the given module.
+	 *
+	 * @param  module_name
+	 * @return Vec
+	 */
+	pub fn get_parameters(
+		&self,
+		module_name: &str,
+	) -> Vec<(String, Option<String>)> {
+		self.source.parameters(module_name)
+	}
+
+	/**
+	 * Get the types, descriptions and permissions of the current parameters.
+	 *
+	 * @param  module_name
+	 * @return BTreeMap
+	 */
+	pub fn get_parameter_info(
+		&self,
+		module_name: &str,
+	) -> BTreeMap<String, ParameterInfo> {
+		let mut parameter_info = self
+			.source
+			.module_info(module_name)
+			.map(|v| parse_parameter_info(&v))
+			.unwrap_or_default();
+		let dir = Path::new(SYS_MODULE_DIR)
+			.join(module_name)
+			.join("parameters");
+		for (name, _) in &self.parameters {
+			parameter_info.entry(name.to_string()).or_default().mode =
+				file_mode(&dir.join(name));
+		}
+		parameter_info
+	}
+
+	/**
+	 * Get the current command using current module name.
+	 *
+	 * @return Command
+	 */
+	pub fn get_current_command(&self) -> Command {
+		/* Use the privilege tool that is selected for retrying the command. */
+		let retry_args;
+		let args = match self.retry_privilege {
+			Some(privilege) => {
+				retry_args = CommandArgs {
+					privilege,
+					..self.cmd_args.clone()
+				};
+				&retry_args
+			}
+			None => &self.cmd_args,
+		};
+		self.command.get_with_prerequisites(
+			&self.current_name,
+			&self.command_params,
+			&self.load_prerequisites,
+			args,
+		)
+	}
+
+	/**
+	 * Get the shell command that would perform the current action.
+	 *
+	 * The pending command is preferred over the given one, which is applied
+	 * to the selected module or the marked modules (one command per line).
+	 *
+	 * @param  module_command
+	 * @return String (Option)
+	 */
+	pub fn shell_command(&self, module_command: ModuleCommand) -> Option<String> {
+		if !self.command.is_none() {
+			if self.batch.is_empty() {
+				return Some(self.get_current_command().cmd);
+			}
+			return Some(self.batch_commands(self.command, &self.batch).join("\n"));
+		}
+		if module_command.is_none() || module_command == ModuleCommand::Load {
+			return None;
+		}
+		if !self.marked.is_empty() {
+			let names = self.marked.iter().cloned().collect::<Vec<String>>();
+			return Some(self.batch_commands(module_command, &names).join("\n"));
+		}
+		if self.current_name.starts_with('!') {
+			return None;
+		}
+		let params = match module_command {
+			ModuleCommand::Reload => {
+				reload_parameters(&self.get_parameters(&self.current_name))
+			}
+			ModuleCommand::Unblacklist => {
+				self.get_unblacklist_files(&self.current_name)
+			}
+			_ => Vec::new(),
+		};
+		Some(
+			module_command
+				.get_with_params(&self.current_name, &params, &self.cmd_args)
+				.cmd,
+		)
+	}
+
+	/**
+	 * Get the commands for executing the given command on each module.
+	 *
+	 * @param  module_command
+	 * @param  names
+	 * @return Vec
+	 */
+	fn batch_commands(
+		&self,
+		module_command: ModuleCommand,
+		names: &[String],
+	) -> Vec<String> {
+		names
+			.iter()
+			.map(|name| {
+				module_command
+					.get_with_params(name, &[], &self.cmd_args)
+					.cmd
+			})
+			.collect()
+	}
+
+	/* Toggle the selection of the current module for the batch commands. */
+	pub fn toggle_mark(&mut self) {
+		if self.current_name.starts_with('!')
+			|| !self.command.is_none()
+			|| self.running.is_some()
+			|| self.is_builtin(&self.current_name)
+		{
+			return;
+		}
+		if !self.marked.remove(&self.current_name) {
+			self.marked.insert(self.current_name.to_string());
+		}
+	}
+
+	/**
+	 * Set the command for the given modules and show confirmation message.
+	 *
+	 * @param module_command
+	 * @param names
+	 */
+	fn set_batch_command(
+		&mut self,
+		module_command: ModuleCommand,
+		names: Vec<String>,
+	) {
+		self.batch = names;
+		self.unload_order.clear();
+		self.command = module_command;
+		self.command_params = Vec::new();
+		self.confirm_time = Instant::now();
+		self.current_name = format!("{} modules", self.batch.len());
+		let commands = self.batch_commands(module_command, &self.batch);
+		let mut spans = vec![
+			Line::from(Span::styled(
+				self.cmd_args
+					.confirm
+					.prompt(i18n::text("prompt.execute_batch"), false),
+				self.style.colored,
+			)),
+			Line::default(),
+		];
+		spans.append(&mut self.protected_warning());
+		for cmd in &commands {
+			spans.append(
+				&mut Text::styled(cmd.to_string(), self.style.default).lines,
+			);
+		}
+		self.current_info
+			.set(Text::from(spans), commands.join("\n"));
+		self.info_scroll_offset = 0;
+	}
+
+	/**
+	 * Set the load command for the modules that are listed in the given file.
+	 *
+	 * @param path
+	 */
+	pub fn set_load_file_command(&mut self, path: &str) {
+		if self.cmd_args.read_only {
+			return;
+		}
+		match cmd::read_module_list(Path::new(path)) {
+			Ok(names) if !names.is_empty() => {
+				self.set_batch_command(ModuleCommand::Load, names)
+			}
+			result => {
+				let e = result
+					.err()
+					.unwrap_or_else(|| format!("no modules are listed in {path}"));
+				self.current_info.set(
+					Text::from(vec![
+						Line::from(Span::styled(
+							"Failed to load the modules from file:",
+							self.style.colored,
+						)),
+						Line::default(),
+						Line::from(Span::styled(e.to_string(), self.style.default)),
+					]),
+					format!("Load Error\n{e}"),
+				);
+				self.current_name =
+					format!("!Error{}", self.style.unicode.get(Symbol::NoEntry));
+				self.command = ModuleCommand::None;
+			}
+		}
+	}
+
+	/**
+	 * Execute the batch command for each module and show the results.
+	 *
+	 * Remaining modules are skipped after a failure if 'stop_on_error' is set.
+	 *
+	 * @return command_executed
+	 */
+	fn execute_batch(&mut self) -> bool {
+		let batch = std::mem::take(&mut self.batch);
+		let result = ExecutionResult::execute(
+			self.command,
+			&self.current_name,
+			"",
+			&batch,
+			&self.cmd_args,
+		);
+		self.show_execution(result)
+	}
+
+	/**
+	 * Show the results of the executed batch command.
+	 *
+	 * @param  results
+	 * @param  hooks
+	 * @return command_executed
+	 */
+	fn finish_batch(
+		&mut self,
+		results: Vec<BatchResult>,
+		hooks: Result<(), String>,
+	) -> bool {
+		let command_executed =
+			results.iter().any(|v| v.status == cmd::BatchStatus::Done);
+		let hook_error = hooks.err();
+		let mut raw_text = results
+			.iter()
+			.map(|v| v.describe(self.cmd_args.dry_run))
+			.collect::<Vec<String>>();
+		if results.len() > 1 {
+			raw_text.push(String::new());
+			raw_text.push(BatchResult::summary(&results));
+		}
+		let spans = raw_text
+			.iter()
+			.map(|line| {
+				Line::from(Span::styled(line.to_string(), self.style.default))
+			})
+			.collect::<Vec<Line>>();
+		self.current_info
+			.set(Text::from(spans), raw_text.join("\n"));
+		self.current_name = format!(
+			"!{}{}",
+			if self.cmd_args.dry_run {
+				"Dry run"
+			} else {
+				"Batch results"
+			},
+			self.style.unicode.get(Symbol::FuelPump)
+		);
+		self.info_scroll_offset = 0;
+		self.marked.clear();
+		self.command = ModuleCommand::None;
+		/* Batch commands cannot be undone. */
+		self.last_action = None;
+		let summary = BatchResult::summary(&results);
+		self.status = Some(match hook_error {
+			Some(e) => Err(format!("{summary}, {e}")),
+			None if results
+				.iter()
+				.any(|v| matches!(v.status, cmd::BatchStatus::Failed(_))) =>
+			{
+				Err(summary)
+			}
+			None => Ok(summary),
+		});
+		command_executed
+	}
+
+	/**
+	 * Set the current module command and show confirmation message.
+	 *
+	 * @param module_command
+	 * @param command_name
+	 */
+	pub fn set_current_command(
+		&mut self,
+		module_command: ModuleCommand,
+		command_name: String,
+	) {
+		if (self.cmd_args.read_only && !module_command.is_none())
+			|| self.running.is_some()
+		{
+			return;
+		}
+		self.undo_pending = false;
+		self.retry_privilege = None;
+		/* Built-in modules cannot be unloaded or blacklisted. */
+		if self.marked.is_empty()
+			&& matches!(
+				module_command,
+				ModuleCommand::Unload
+					| ModuleCommand::ForceUnload
+					| ModuleCommand::Reload
+					| ModuleCommand::Blacklist
+					| ModuleCommand::AddBoot
+			) && self.is_builtin(&self.current_name)
+		{
+			self.show_builtin_error(&self.current_name.to_string());
+			return;
+		}
+		/* Apply the command to all the selected modules. */
+		if !self.marked.is_empty()
+			&& matches!(
+				module_command,
+				ModuleCommand::Unload
+					| ModuleCommand::ForceUnload
+					| ModuleCommand::Reload
+					| ModuleCommand::Blacklist
+			) {
+			self.set_batch_command(
+				module_command,
+				self.marked.iter().cloned().collect(),
+			);
+			return;
+		}
+		/* Parse the module parameters for the load command. */
+		let (command_name, params) = if module_command == ModuleCommand::Load {
+			match ModuleCommand::parse_load_input(&command_name) {
+				Ok(v) => v,
+				Err(e) => {
+					self.current_info.set(
+						Text::from(vec![
+							Line::from(Span::styled(
+								"Failed to parse the load command:",
+								self.style.colored,
+							)),
+							Line::from(Span::styled(
+								format!("'{command_name}'"),
+								self.style.default,
+							)),
+							Line::default(),
+							Line::from(Span::styled(
+								e.to_string(),
+								self.style.default,
+							)),
+						]),
+						format!("Parse Error\n'{command_name}'\n{e}"),
+					);
+					self.current_name =
+						format!("!Error{}", self.style.unicode.get(Symbol::NoEntry));
+					self.command = ModuleCommand::None;
+					return;
+				}
+			}
+		} else if module_command == ModuleCommand::Load
+			&& self.is_builtin(&command_name)
+		{
+			self.show_builtin_error(&command_name);
+			return;
+		} else if module_command == ModuleCommand::Reload {
+			/* Load the module again with its current parameters. */
+			let params = reload_parameters(&self.get_parameters(&self.current_name));
+			(command_name, params)
+		} else if module_command == ModuleCommand::Unblacklist {
+			/* Use the blacklist files as parameters of the command. */
+			let files = self.get_unblacklist_files(&self.current_name);
+			if files.is_empty() && !self.current_name.starts_with('!') {
+				self.show_unblacklist_error();
+				return;
+			}
+			(command_name, files)
+		} else if module_command == ModuleCommand::RemoveBoot
+			&& !self.current_name.starts_with('!')
+			&& !self.is_boot_managed(&self.current_name)
+		{
+			self.show_boot_error();
+			return;
+		} else {
+			(command_name, Vec::new())
+		};
+		if !command_name.contains(' ') && !self.current_name.starts_with('!') {
+			if !command_name.is_empty() {
+				self.current_name = command_name;
+			}
+			self.command = module_command;
+			self.command_params = params;
+			self.confirm_time = Instant::now();
+			/* Insert the dependencies of the module file in insmod mode. */
+			let load_order = if module_command == ModuleCommand::Load {
+				self.get_load_order(&self.current_name)
+			} else {
+				Vec::new()
+			};
+			self.load_prerequisites = if self.cmd_args.loader == Loader::Insmod
+				&& ModuleCommand::is_module_filename(&self.current_name)
+			{
+				load_order
+					.iter()
+					.filter(|(_, _, loaded)| !loaded)
+					.map(|(_, path, _)| path.to_string_lossy().to_string())
+					.collect()
+			} else {
+				Vec::new()
+			};
+			/* Offer unloading the dependent modules as well. */
+			let dependents = self
+				.get_dependent_modules(&self.current_name)
+				.unwrap_or_default();
+			self.unload_order = if module_command == ModuleCommand::Unload
+				&& !dependents.is_empty()
+			{
+				self.loaded_dependency_graph()
+					.unload_order(&self.current_name)
+			} else {
+				Vec::new()
+			};
+			self.current_info.set(
+				Text::from({
+					let mut spans = vec![
+						Line::from(Span::styled(
+							self.cmd_args.confirm.prompt(
+								i18n::text("prompt.execute"),
+								!self.unload_order.is_empty(),
+							),
+							self.style.colored,
+						)),
+						Line::from(Span::styled(
+							self.get_current_command().cmd,
+							self.style.default,
+						)),
+						Line::default(),
+					];
+					spans.append(&mut self.protected_warning());
+					/* Warn about the modules that are using the module. */
+					if module_command == ModuleCommand::ForceUnload {
+						spans.push(Line::from(Span::styled(
+							match self.get_dependent_modules(&self.current_name) {
+								Some(dependents) if !dependents.is_empty() => {
+									format!(
+										"Warning: module is in use by {}",
+										dependents.join(", ")
+									)
+								}
+								_ => String::from("Warning: module might be in use"),
+							},
+							self.style.default,
+						)));
+						spans.push(Line::from(Span::styled(
+							"Forcing the removal can crash the system!",
+							self.style.default,
+						)));
+						spans.push(Line::default());
+					}
+					if !load_order.is_empty() {
+						spans.push(Line::from(Span::styled(
+							"Dependencies are loaded in the following order:",
+							self.style.default,
+						)));
+						spans.push(Line::from(Span::styled(
+							load_order
+								.iter()
+								.map(|(name, _, loaded)| {
+									if *loaded {
+										format!("{name} (loaded)")
+									} else {
+										name.to_string()
+									}
+								})
+								.collect::<Vec<String>>()
+								.join(", "),
+							self.style.default,
+						)));
+						spans.push(Line::default());
+					}
+					if !self.unload_order.is_empty() {
+						spans.push(Line::from(Span::styled(
+							format!(
+								"Warning: module is in use by {}",
+								dependents.join(", ")
+							),
+							self.style.default,
+						)));
+						spans.push(Line::from(Span::styled(
+							"Press 'a' for unloading the dependent modules in order:",
+							self.style.default,
+						)));
+						spans.push(Line::from(Span::styled(
+							self.unload_order.join(", "),
+							self.style.default,
+						)));
+						spans.push(Line::default());
+					}
+					/* List the blacklist entries that are not removed. */
+					if module_command == ModuleCommand::Unblacklist {
+						let entries = self
+							.blacklist
+							.get(&blacklist::normalize_name(&self.current_name))
+							.into_iter()
+							.flatten()
+							.filter(|entry| {
+								!self.command_params.contains(
+									&entry.path.to_string_lossy().to_string(),
+								)
+							})
+							.map(BlacklistEntry::to_string)
+							.collect::<Vec<String>>();
+						if !entries.is_empty() {
+							spans.push(Line::from(Span::styled(
+								format!(
+									"Module is also blacklisted in {}",
+									entries.join(", ")
+								),
+								self.style.default,
+							)));
+							spans.push(Line::default());
+						}
+					}
+					spans.append(
+						&mut Text::styled(
+							self.get_current_command().desc,
+							self.style.colored,
+						)
+						.lines,
+					);
+					spans
+				}),
+				self.get_current_command().cmd,
+			);
+			self.info_scroll_offset = 0;
+		}
+	}
+
+	/**
+	 * Show the reason why the command cannot be executed for a built-in module.
+	 *
+	 * @param module_name
+	 */
+	fn show_builtin_error(&mut self, module_name: &str) {
+		self.current_info.set(
+			Text::from(vec![
+				Line::from(Span::styled(
+					"Module is built into the kernel:",
+					self.style.colored,
+				)),
+				Line::from(Span::styled(
+					format!("'{module_name}'"),
+					self.style.default,
+				)),
+				Line::default(),
+				Line::from(Span::styled(BUILTIN_ERROR, self.style.default)),
+			]),
+			format!("Built-in Module\n'{module_name}'\n{BUILTIN_ERROR}"),
+		);
+		self.current_name =
+			format!("!Error{}", self.style.unicode.get(Symbol::NoEntry));
+		self.command = ModuleCommand::None;
+		self.info_scroll_offset = 0;
+	}
+
+	/**
+	 * Set the command that reverts the last executed command.
+	 *
+	 * The last command cannot be undone if its inverse is not known
+	 * or the module is not in the expected state anymore.
+	 *
+	 * @return Result
+	 */
+	pub fn set_undo_command(&mut self) -> Result<(), String> {
+		let (command, module_name) = self
+			.last_action
+			.clone()
+			.ok_or_else(|| String::from("nothing to undo"))?;
+		let inverse = command
+			.inverse()
+			.ok_or_else(|| format!("'{}' cannot be undone", command.name()))?;
+		let module = self
+			.source
+			.list_modules(&ListArgs {
+				filter: ListFilter::All,
+				builtin: false,
+				..self.args
+			})
+			.map_err(|e| e.to_string())?
+			.into_iter()
+			.find(|module| module.name == module_name);
+		self.refresh_blacklist();
+		match (inverse, module) {
+			(ModuleCommand::Unload, None) => {
+				return Err(format!("cannot undo: {module_name} is not loaded"));
+			}
+			(ModuleCommand::Unload, Some(module)) if module.refcount > 0 => {
+				return Err(format!("cannot undo: {module_name} is in use"));
+			}
+			(ModuleCommand::Load, Some(_)) => {
+				return Err(format!("cannot undo: {module_name} is already loaded"));
+			}
+			(ModuleCommand::Unblacklist, _)
+				if self.get_unblacklist_files(&module_name).is_empty() =>
+			{
+				return Err(format!(
+					"cannot undo: {module_name} is not blacklisted"
+				));
+			}
+			(ModuleCommand::Blacklist, _) if self.is_blacklisted(&module_name) => {
+				return Err(format!(
+					"cannot undo: {module_name} is already blacklisted"
+				));
+			}
+			(ModuleCommand::RemoveBoot, _)
+				if !self.is_boot_managed(&module_name) =>
+			{
+				return Err(format!(
+					"cannot undo: {module_name} is not loaded at boot"
+				));
+			}
+			_ => {}
+		}
+		self.current_name.clone_from(&module_name);
+		self.set_current_command(inverse, module_name);
+		self.undo_pending = self.command == inverse;
+		Ok(())
+	}
+
+	/* Show the reason why the current module cannot be removed from the boot modules. */
+	fn show_boot_error(&mut self) {
+		let files = self
+			.boot_files(&self.current_name)
+			.iter()
+			.map(|v| v.display().to_string())
+			.collect::<Vec<String>>();
+		let message = format!(
+			"Module is not loaded at boot from {}:",
+			self.cmd_args.boot.path.display()
+		);
+		let mut lines = vec![format!("'{}'", self.current_name)];
+		if !files.is_empty() {
+			lines.push(String::new());
+			lines.push(String::from("Module is loaded at boot from:"));
+			lines.extend(files);
+		}
+		let mut spans = vec![Line::from(Span::styled(
+			message.to_string(),
+			self.style.colored,
+		))];
+		for line in &lines {
+			spans.push(Line::from(Span::styled(
+				line.to_string(),
+				self.style.default,
+			)));
+		}
+		self.current_info.set(
+			Text::from(spans),
+			format!("Boot Error\n{message}\n{}", lines.join("\n")),
+		);
+		self.current_name =
+			format!("!Error{}", self.style.unicode.get(Symbol::NoEntry));
+		self.command = ModuleCommand::None;
+		self.info_scroll_offset = 0;
+	}
+
+	/* Show the reason why the current module cannot be unblacklisted. */
+	fn show_unblacklist_error(&mut self) {
+		let entries = self
+			.blacklist
+			.get(&blacklist::normalize_name(&self.current_name))
+			.map(|entries| {
+				entries
+					.iter()
+					.map(BlacklistEntry::to_string)
+					.collect::<Vec<String>>()
+			})
+			.unwrap_or_default();
+		let mut lines = vec![format!("'{}'", self.current_name), String::new()];
+		let message = if entries.is_empty() {
+			lines.pop();
+			String::from("Module is not blacklisted:")
+		} else {
+			lines.append(&mut entries.to_vec());
+			lines.push(String::new());
+			lines.push(String::from(
+				"Set 'remove_all' in the [blacklist] section of the \
+				configuration file for removing it from these files.",
+			));
+			format!(
+				"Module is not blacklisted in {}:",
+				self.cmd_args.blacklist.path.display()
+			)
+		};
+		let mut spans = vec![Line::from(Span::styled(
+			message.to_string(),
+			self.style.colored,
+		))];
+		for line in &lines {
+			spans.push(Line::from(Span::styled(
+				line.to_string(),
+				self.style.default,
+			)));
+		}
+		self.current_info.set(
+			Text::from(spans),
+			format!("Unblacklist Error\n{message}\n{}", lines.join("\n")),
+		);
+		self.current_name =
+			format!("!Error{}", self.style.unicode.get(Symbol::NoEntry));
+		self.command = ModuleCommand::None;
+		self.info_scroll_offset = 0;
+	}
+
+	/**
+	 * Get the modules that depend on the given module.
+	 *
+	 * Holders of the module are read from sysfs if they are available and
+	 * the dependents in the module list (from procfs) are used otherwise.
+	 *
+	 * @param  module_name
+	 * @return Option
+	 */
+	pub fn get_dependent_modules(&self, module_name: &str) -> Option<Vec<String>> {
+		let module = self.default_list.iter().find(|module| {
+			module[0].split_whitespace().next() == Some(module_name)
+		})?;
+		if let Some(holders) = self.source.holders(module_name) {
+			return Some(holders);
+		}
+		module[2].split(' ').nth(1).map(|dependents| {
+			dependents
+				.split(',')
+				.filter(|v| !v.is_empty() && *v != "-")
+				.map(String::from)
+				.collect()
+		})
+	}
+
+	/**
+	 * Get the dependency graph of the loaded modules.
+	 *
+	 * Dependencies of the given module are resolved from its information as well.
+	 *
+	 * @param  module_name
+	 * @return DependencyGraph
+	 */
+	pub fn get_dependency_graph(&self, module_name: &str) -> DependencyGraph {
+		let mut graph = self.loaded_dependency_graph();
+		let mut modules = vec![module_name.to_string()];
+		let mut visited = Vec::new();
+		while let Some(module) = modules.pop() {
+			if visited.contains(&module) {
+				continue;
+			}
+			if let Ok(depends) = self.source.module_field(&module, "depends") {
+				for dependency in depends.split(',').filter(|v| !v.is_empty()) {
+					graph.add_dependency(&module, dependency);
+				}
+			}
+			modules.extend(graph.get(&module, Relation::Depends));
+			visited.push(module);
+		}
+		graph
+	}
+
+	/**
+	 * Get the dependencies that need to be loaded before the given module.
+	 *
+	 * Dependencies are read from 'modules.dep' of the running kernel.
+	 *
+	 * @param  module_name
+	 * @return Vec (name, path, loaded)
+	 */
+	pub fn get_load_order(&self, module_name: &str) -> Vec<(String, PathBuf, bool)> {
+		let Some(dir) =
+			info::kernel_release().map(|v| Path::new(LIB_MODULES_DIR).join(v))
+		else {
+			return Vec::new();
+		};
+		let module_name = module_name_from_path(module_name)
+			.unwrap_or_else(|| blacklist::normalize_name(module_name));
+		util::read_file(dir.join("modules.dep"))
+			.map(|contents| {
+				parse_load_order(&contents, &module_name)
+					.iter()
+					.filter_map(|path| {
+						let name = module_name_from_path(path)?;
+						let loaded =
+							util::path_exists(Path::new(SYS_MODULE_DIR).join(&name));
+						Some((name, dir.join(path), loaded))
+					})
+					.collect()
+			})
+			.unwrap_or_default()
+	}
+
+	/**
+	 * Get the dependency graph of the loaded modules using their dependents.
+	 *
+	 * @return DependencyGraph
+	 */
+	fn loaded_dependency_graph(&self) -> DependencyGraph {
+		DependencyGraph::new(
+			&self
+				.default_list
+				.iter()
+				.filter_map(|module| module[0].split_whitespace().next())
+				.map(|name| {
+					(
+						name.to_string(),
+						self.get_dependent_modules(name).unwrap_or_default(),
+					)
+				})
+				.collect::<Vec<(String, Vec<String>)>>(),
+		)
+	}
+
+	/**
+	 * Get the protected module that is unloaded by the current command.
+	 *
+	 * Unloading a protected module requires typing its name for confirming.
+	 *
+	 * @param  recursive
+	 * @return String (Option)
+	 */
+	pub fn protected_module(&self, recursive: bool) -> Option<String> {
+		if !matches!(
+			self.command,
+			ModuleCommand::Unload
+				| ModuleCommand::ForceUnload
+				| ModuleCommand::Reload
+		) {
+			return None;
+		}
+		let root_fs = util::read_file(PROC_MOUNTS)
+			.ok()
+			.and_then(|v| parse_root_fs_type(&v));
+		let names = if recursive {
+			&self.unload_order
+		} else if !self.batch.is_empty() {
+			&self.batch
+		} else {
+			std::slice::from_ref(&self.current_name)
+		};
+		names
+			.iter()
+			.find(|name| {
+				self.cmd_args.confirm.is_protected(name, root_fs.as_deref())
+			})
+			.cloned()
+	}
+
+	/**
+	 * Get the warning lines about unloading a protected module.
+	 *
+	 * @return Vec
+	 */
+	fn protected_warning(&self) -> Vec<Line<'static>> {
+		match self.protected_module(false) {
+			Some(name) => vec![
+				Line::from(Span::styled(
+					format!("Warning: '{name}' is a protected module"),
+					self.style.default,
+				)),
+				Line::from(Span::styled(
+					"Unloading it can hang the system, type its name for confirming.",
+					self.style.default,
+				)),
+				Line::default(),
+			],
+			None => Vec::new(),
+		}
+	}
+
+	/**
+	 * Check if the current command can unload the dependent modules as well.
+	 *
+	 * @return bool
+	 */
+	pub fn has_recursive_unload(&self) -> bool {
+		self.command == ModuleCommand::Unload && !self.unload_order.is_empty()
+	}
+
+	/**
+	 * Unload the current module after unloading its dependent modules.
+	 *
+	 * @return command_executed
+	 */
+	pub fn execute_recursive_unload(&mut self) -> bool {
+		if !self.has_recursive_unload() {
+			return false;
+		}
+		self.batch = std::mem::take(&mut self.unload_order);
+		self.execute_batch()
+	}
+
+	/**
+	 * Execute the current module command.
+	 *
+	 * @return command_executed
+	 */
+	pub fn execute_command(&mut self) -> bool {
+		self.unload_order.clear();
+		if !self.batch.is_empty() && !self.command.is_none() {
+			return self.execute_batch();
+		}
+		if self.command.is_none() {
+			return false;
+		}
+		let result = ExecutionResult::execute(
+			self.command,
+			&self.current_name,
+			&self.get_current_command().cmd,
+			&[],
+			&self.cmd_args,
+		);
+		self.show_execution(result)
+	}
+
+	/**
+	 * Execute the current module command in the background.
+	 *
+	 * Result is sent as an event for showing it with 'finish_execution'.
+	 *
+	 * @param  tx
+	 * @return started
+	 */
+	pub fn execute_command_async(&mut self, tx: &Sender<Event<Key>>) -> bool {
+		if self.command.is_none() || self.running.is_some() {
+			return false;
+		}
+		self.unload_order.clear();
+		let batch = std::mem::take(&mut self.batch);
+		let cmd = self.get_current_command().cmd;
+		let command = self.command;
+		let module_name = self.current_name.to_string();
+		let cmd_args = self.cmd_args.clone();
+		self.running =
+			Some((self.command, self.current_name.to_string(), Instant::now()));
+		let tx = tx.clone();
+		/* Hooks are run on the same thread for showing the spinner until they finish. */
+		thread::spawn(move || {
+			tx.send(Event::Execution(ExecutionResult::execute(
+				command,
+				&module_name,
+				&cmd,
+				&batch,
+				&cmd_args,
+			)))
+			.unwrap_or_default();
+		});
+		true
+	}
+
+	/**
+	 * Unload the current module with its dependent modules in the background.
+	 *
+	 * @param  tx
+	 * @return started
+	 */
+	pub fn execute_recursive_unload_async(
+		&mut self,
+		tx: &Sender<Event<Key>>,
+	) -> bool {
+		if !self.has_recursive_unload() {
+			return false;
+		}
+		self.batch = std::mem::take(&mut self.unload_order);
+		self.execute_command_async(tx)
+	}
+
+	/**
+	 * Show the result of the command that is executed in the background.
+	 *
+	 * @param  result
+	 * @return command_executed
+	 */
+	pub fn finish_execution(&mut self, result: ExecutionResult) -> bool {
+		let Some((command, name, _)) = self.running.take() else {
+			return false;
+		};
+		self.command = command;
+		self.current_name = name;
+		self.show_execution(result)
+	}
+
+	/**
+	 * Show the result of the executed module command or batch.
+	 *
+	 * @param  result
+	 * @return command_executed
+	 */
+	fn show_execution(&mut self, result: ExecutionResult) -> bool {
+		match result {
+			ExecutionResult::Single(result, hooks) => {
+				self.finish_command(result, hooks)
+			}
+			ExecutionResult::Batch(results, hooks) => {
+				self.finish_batch(results, hooks)
+			}
+		}
 	}
 
 	/**
-	 * Set the current module command and show confirmation message.
+	 * Show the result of the executed module command.
 	 *
-	 * @param module_command
-	 * @param command_name
+	 * @param  result
+	 * @param  hooks
+	 * @return command_executed
 	 */
-	pub fn set_current_command(
+	fn finish_command(
 		&mut self,
-		module_command: ModuleCommand,
-		command_name: String,
-	) {
-		if !command_name.contains(' ') && !self.current_name.starts_with('!') {
-			if !command_name.is_empty() {
-				self.current_name = command_name;
+		result: Result<CmdOutput, CmdError>,
+		hooks: Result<(), String>,
+	) -> bool {
+		let mut command_executed = false;
+		let result = result.and_then(CmdOutput::check);
+		/* Offer retrying the command with root privileges if it is not permitted. */
+		let retry = match &result {
+			Err(e)
+				if self.retry_privilege.is_none()
+					&& self.cmd_args.privilege == PrivilegeCmd::None
+					&& !self.cmd_args.dry_run
+					&& self.command.is_permission_error(e) =>
+			{
+				PrivilegeCmd::escalation()
 			}
-			self.command = module_command;
-			self.current_info.set(
-				Text::from({
-					let mut spans = vec![
+			_ => None,
+		};
+		match result.err() {
+			/* Show the command that would be executed. */
+			None if self.cmd_args.dry_run => {
+				command_executed = true;
+				let cmd = self.get_current_command().cmd;
+				self.current_info.set(
+					Text::from(vec![
 						Line::from(Span::styled(
-							"Execute the following command? [y/N]:",
+							"Dry run, the following command is not executed:",
 							self.style.colored,
 						)),
 						Line::from(Span::styled(
-							self.get_current_command().cmd,
+							cmd.to_string(),
 							self.style.default,
 						)),
-						Line::default(),
-					];
-					spans.append(
-						&mut Text::styled(
-							self.get_current_command().desc,
-							self.style.colored,
+					]),
+					format!("Dry Run\n{cmd}"),
+				);
+				self.current_name =
+					format!("!Dry run{}", self.style.unicode.get(Symbol::FuelPump));
+				self.info_scroll_offset = 0;
+			}
+			None => {
+				command_executed = true;
+				if self.undo_pending {
+					self.status = self.last_action.take().map(|(command, name)| {
+						Ok(format!("undone: {} {name}", command.name()))
+					});
+				} else {
+					self.status =
+						Some(Ok(self.command.result_message(&self.current_name)));
+					self.last_action =
+						Some((self.command, self.current_name.to_string()));
+				}
+				/* Report the hook failures along with the result. */
+				if let Err(e) = hooks {
+					self.status = Some(Err(match self.status.take() {
+						Some(status) => {
+							format!("{}, {e}", status.unwrap_or_else(|v| v))
+						}
+						None => e,
+					}));
+				}
+				/* Select the loaded module or keep the selection after refreshing. */
+				if self.command == ModuleCommand::Load {
+					self.current_name = if self.cmd_args.select_after_load {
+						let file_name = Path::new(&self.current_name)
+							.file_name()
+							.and_then(|v| v.to_str())
+							.unwrap_or_default();
+						blacklist::normalize_name(
+							file_name.split('.').next().unwrap_or_default(),
 						)
-						.lines,
-					);
-					spans
-				}),
-				self.get_current_command().cmd,
-			);
-			self.info_scroll_offset = 0;
-		}
-	}
-
-	/**
-	 * Execute the current module command.
-	 *
-	 * @return command_executed
-	 */
-	pub fn execute_command(&mut self) -> bool {
-		let mut command_executed = false;
-		if !self.command.is_none() {
-			match util::exec_cmd("sh", &["-c", &self.get_current_command().cmd]) {
-				Ok(_) => command_executed = true,
-				Err(e) => {
-					self.current_info.set(
-						Text::from({
-							let mut spans = vec![
-								Line::from(Span::styled(
-									"Failed to execute command:",
-									self.style.colored,
-								)),
-								Line::from(Span::styled(
-									format!("'{}'", self.get_current_command().cmd),
-									self.style.default,
-								)),
-								Line::default(),
-							];
-							spans.append(
-								&mut Text::styled(e.to_string(), self.style.default)
-									.lines,
-							);
-							spans
-						}),
-						format!(
-							"Execution Error\n'{}'\n{}",
-							self.get_current_command().cmd,
-							e
-						),
-					);
-					self.current_name =
-						format!("!Error{}", self.style.unicode.get(Symbol::NoEntry));
+					} else {
+						self.list
+							.get(self.index)
+							.and_then(|v| v[0].split_whitespace().next())
+							.map(String::from)
+							.unwrap_or_default()
+					};
 				}
 			}
-			self.command = ModuleCommand::None;
+			/* Name the modules that are holding the module if it is busy. */
+			Some(error) => {
+				let message = match &error {
+					CmdError::Status {
+						code: Some(code),
+						stderr,
+					} => format!("{stderr}\n(exit code: {code})"),
+					error => error.to_string(),
+				};
+				self.status = Some(Err(if self.command.is_busy_error(&error) {
+					self.busy_message(&self.current_name)
+				} else {
+					message
+						.lines()
+						.find(|v| !v.trim().is_empty())
+						.unwrap_or_default()
+						.to_string()
+				}));
+				self.current_info.set(
+					Text::from({
+						let mut spans = vec![
+							Line::from(Span::styled(
+								i18n::text("info.execution_failed"),
+								self.style.colored,
+							)),
+							Line::from(Span::styled(
+								format!("'{}'", self.get_current_command().cmd),
+								self.style.default,
+							)),
+							Line::default(),
+						];
+						spans.append(
+							&mut Text::styled(
+								message.to_string(),
+								self.style.default,
+							)
+							.lines,
+						);
+						/* Explain the reason of the failure if it is known. */
+						if let Some(hint) = self.command.get_error_hint(&error) {
+							spans.push(Line::default());
+							spans.push(Line::from(Span::styled(
+								hint,
+								self.style.colored,
+							)));
+						}
+						if let Some(tool) = retry.and_then(PrivilegeCmd::executable)
+						{
+							spans.push(Line::default());
+							spans.push(Line::from(Span::styled(
+								ConfirmConfig {
+									prompt: None,
+									..self.cmd_args.confirm.clone()
+								}
+								.prompt(&format!("Retry with {tool}?"), false),
+								self.style.colored,
+							)));
+						}
+						spans
+					}),
+					format!(
+						"Execution Error\n'{}'\n{}",
+						self.get_current_command().cmd,
+						message
+					),
+				);
+				/* Keep the command for retrying it after the confirmation. */
+				if retry.is_some() {
+					self.retry_privilege = retry;
+					self.confirm_time = Instant::now();
+					return command_executed;
+				}
+				self.current_name =
+					format!("!Error{}", self.style.unicode.get(Symbol::NoEntry));
+			}
 		}
+		self.command = ModuleCommand::None;
+		self.retry_privilege = None;
+		self.load_prerequisites.clear();
+		self.undo_pending = false;
 		command_executed
 	}
 
@@ -248,8 +3523,13 @@ impl KernelModules<'_> {
 	 * @return cancelled
 	 */
 	pub fn cancel_execution(&mut self) -> bool {
-		if !self.command.is_none() {
+		if !self.command.is_none() && self.running.is_none() {
 			self.command = ModuleCommand::None;
+			self.retry_privilege = None;
+			self.batch.clear();
+			self.unload_order.clear();
+			self.load_prerequisites.clear();
+			self.undo_pending = false;
 			if self.index != 0 {
 				self.index -= 1;
 				self.scroll_list(ScrollDirection::Down);
@@ -263,6 +3543,31 @@ impl KernelModules<'_> {
 		}
 	}
 
+	/**
+	 * Get the remaining time for answering the confirmation if the timeout is set.
+	 *
+	 * @return Duration (Option)
+	 */
+	pub fn confirmation_remaining(&self) -> Option<Duration> {
+		if self.command.is_none() || self.running.is_some() {
+			return None;
+		}
+		self.cmd_args
+			.confirm
+			.timeout()
+			.map(|timeout| timeout.saturating_sub(self.confirm_time.elapsed()))
+	}
+
+	/**
+	 * Cancel the execution of current command if the confirmation is timed out.
+	 *
+	 * @return bool
+	 */
+	pub fn cancel_expired_execution(&mut self) -> bool {
+		self.confirmation_remaining().is_some_and(|v| v.is_zero())
+			&& self.cancel_execution()
+	}
+
 	/**
 	 * Scroll to the position of used module at given index.
 	 *
@@ -298,6 +3603,274 @@ impl KernelModules<'_> {
 		}
 	}
 
+	/**
+	 * Select the module at the given index of the list.
+	 *
+	 * @param index
+	 */
+	pub fn select_module(&mut self, index: usize) {
+		if index < self.list.len() {
+			self.info_scroll_offset = 0;
+			self.index = index;
+			self.update_current_module();
+		}
+	}
+
+	/**
+	 * Check if the dependent modules are shown on the information block.
+	 *
+	 * @return bool
+	 */
+	pub fn is_showing_dependents(&self) -> bool {
+		!self.dependents.is_empty()
+			&& self.current_name.starts_with("!Dependent modules")
+	}
+
+	/**
+	 * Render the dependent modules with the selected one highlighted.
+	 */
+	pub fn render_dependents(&mut self) {
+		let mut dependents_text = Vec::new();
+		for (i, module) in self.dependents.iter().enumerate() {
+			dependents_text.push(Line::from(vec![
+				Span::styled("-", self.style.colored),
+				Span::styled(
+					format!(" {module}"),
+					if i == self.dependent_index {
+						self.style.selected
+					} else {
+						self.style.default
+					},
+				),
+			]));
+		}
+		self.current_info.set(
+			Text::from(dependents_text),
+			self.dependents
+				.iter()
+				.map(|v| format!("- {v}"))
+				.collect::<Vec<String>>()
+				.join("\n"),
+		);
+	}
+
+	/**
+	 * Get the modules that are available for the running kernel.
+	 *
+	 * Available modules are read once on the first call and cached.
+	 *
+	 * @return BTreeSet
+	 */
+	fn available(&self) -> &BTreeSet<String> {
+		self.available.get_or_init(read_available_modules)
+	}
+
+	/**
+	 * Get the count of the modules that are available for the running kernel.
+	 *
+	 * @return Option
+	 */
+	pub fn available_count(&self) -> Option<usize> {
+		Some(self.available().len()).filter(|v| *v > 0)
+	}
+
+	/**
+	 * Get the available modules that are not loaded and start with the given prefix.
+	 *
+	 * Available modules are read once and cached for the later completions.
+	 *
+	 * @param  prefix
+	 * @return Vec
+	 */
+	pub fn get_completions(&mut self, prefix: &str) -> Vec<String> {
+		let prefix = blacklist::normalize_name(prefix);
+		self.available()
+			.range(prefix.to_string()..)
+			.take_while(|name| name.starts_with(&prefix))
+			.filter(|name| {
+				!self
+					.modules
+					.iter()
+					.any(|module| !module.builtin && &&module.name == name)
+			})
+			.cloned()
+			.collect()
+	}
+
+	/**
+	 * Check if the module parameters are shown on the information block.
+	 *
+	 * @return bool
+	 */
+	pub fn is_showing_parameters(&self) -> bool {
+		!self.parameters.is_empty() && self.current_name.starts_with("!Parameters")
+	}
+
+	/**
+	 * Get the line of the selected entry on the information block.
+	 *
+	 * @return Option
+	 */
+	pub fn selected_info_line(&self) -> Option<usize> {
+		if self.is_showing_dependents() {
+			Some(self.dependent_index)
+		} else if self.is_showing_parameters() {
+			Some(self.parameter_index)
+		} else {
+			None
+		}
+	}
+
+	/**
+	 * Render the module parameters with the selected one highlighted.
+	 */
+	pub fn render_parameters(&mut self) {
+		let mut parameters_text = Vec::new();
+		let mut parameters_text_raw = Vec::new();
+		for (i, (name, value)) in self.parameters.iter().enumerate() {
+			let value = value.as_deref().unwrap_or("<unreadable>");
+			let info = self.parameter_info.get(name);
+			let name = format!(
+				"{name}{}",
+				info.map(ParameterInfo::annotation).unwrap_or_default()
+			);
+			let description = info
+				.and_then(|v| v.description.as_deref())
+				.map(|v| format!(" - {v}"))
+				.unwrap_or_default();
+			parameters_text.push(Line::from(vec![
+				Span::styled(format!("{name}: "), self.style.colored),
+				Span::styled(
+					value.to_string(),
+					if i == self.parameter_index {
+						self.style.selected
+					} else {
+						self.style.default
+					},
+				),
+				Span::styled(description.to_string(), self.style.default),
+			]));
+			parameters_text_raw.push(format!("{name}: {value}{description}"));
+		}
+		if parameters_text.is_empty() {
+			parameters_text.push(Line::from(Span::styled(
+				"no parameters available",
+				self.style.default,
+			)));
+			parameters_text_raw.push(String::from("no parameters available"));
+		}
+		self.current_info
+			.set(Text::from(parameters_text), parameters_text_raw.join("\n"));
+	}
+
+	/**
+	 * Get the path of the selected module parameter.
+	 *
+	 * @return Option
+	 */
+	fn selected_parameter_path(&self) -> Option<(String, PathBuf)> {
+		self.parameters.get(self.parameter_index).map(|(name, _)| {
+			(
+				name.to_string(),
+				Path::new(SYS_MODULE_DIR)
+					.join(&self.parameters_module)
+					.join("parameters")
+					.join(name),
+			)
+		})
+	}
+
+	/**
+	 * Get the value of the selected module parameter for editing it.
+	 *
+	 * @return Result
+	 */
+	pub fn get_editable_parameter(&self) -> Result<String, String> {
+		let (name, path) = self
+			.selected_parameter_path()
+			.ok_or_else(|| String::from("no parameter is selected"))?;
+		/* Parameters of the demo mode are not read from sysfs. */
+		if !self.cmd_args.demo && !is_writable(&path) {
+			return Err(format!("{name} is read-only"));
+		}
+		Ok(self
+			.parameters
+			.get(self.parameter_index)
+			.and_then(|(_, value)| value.clone())
+			.unwrap_or_default())
+	}
+
+	/**
+	 * Write the value of the selected module parameter and re-read it.
+	 *
+	 * @param  value
+	 * @return Result
+	 */
+	pub fn set_parameter(&mut self, value: &str) -> Result<String, String> {
+		let (name, path) = self
+			.selected_parameter_path()
+			.ok_or_else(|| String::from("no parameter is selected"))?;
+		if self.cmd_args.read_only {
+			return Err(String::from(i18n::text("status.read_only")));
+		} else if self.cmd_args.dry_run {
+			return Ok(format!("{value} would be written to {}", path.display()));
+		}
+		/* Parameters of the real kernel are not changed in the demo mode. */
+		let value = if self.cmd_args.demo {
+			Some(value.trim_end().to_string())
+		} else {
+			write_parameter(&path, value)
+				.map_err(|e| format!("failed to set {name}: {e}"))?
+		};
+		if let Some(parameter) = self.parameters.get_mut(self.parameter_index) {
+			parameter.1.clone_from(&value);
+		}
+		self.render_parameters();
+		Ok(format!(
+			"{name} = {}",
+			value.as_deref().unwrap_or("<unreadable>")
+		))
+	}
+
+	/**
+	 * Select the highlighted dependent module in the module list.
+	 *
+	 * @return Result
+	 */
+	pub fn select_dependent(&mut self) -> Result<(), String> {
+		let Some(module_name) = self.dependents.get(self.dependent_index) else {
+			return Ok(());
+		};
+		match self
+			.list
+			.iter()
+			.position(|module| module[0] == format!(" {module_name}"))
+		{
+			Some(index) => {
+				self.select_module(index);
+				Ok(())
+			}
+			None => Err(format!("{module_name} is not in the module list")),
+		}
+	}
+
+	/**
+	 * Show the unfiltered module list and select the module with given name.
+	 *
+	 * The first module is selected if the module is not listed.
+	 *
+	 * @param module_name
+	 */
+	pub fn select_unfiltered(&mut self, module_name: &str) {
+		self.list.clone_from(&self.default_list);
+		match self.list.iter().position(|module| {
+			module[0].split_whitespace().next() == Some(module_name)
+		}) {
+			Some(index) => self.select_module(index),
+			None => self.scroll_list(ScrollDirection::Top),
+		}
+	}
+
 	/**
 	 * Scroll module list up/down and select module.
 	 *
@@ -316,30 +3889,199 @@ impl KernelModules<'_> {
 				ScrollDirection::Bottom => self.index = self.list.len() - 1,
 				_ => {}
 			}
-			/* Set current module name. */
-			self.current_name = self.list[self.index][0]
-				.split_whitespace()
-				.next()
-				.unwrap_or("?")
-				.trim()
-				.to_string();
-			/* Execute 'modinfo' and add style to its output. */
-			self.current_info.stylize_data(
-				Box::leak(
-					util::exec_cmd("modinfo", &[&self.current_name])
-						.unwrap_or_else(|_| {
-							String::from("module information not available")
-						})
-						.replace("signature: ", "signature: \n")
-						.into_boxed_str(),
+			self.update_current_module();
+		}
+	}
+
+	/**
+	 * Select the first module whose name starts with the given prefix.
+	 *
+	 * @param  prefix
+	 * @return bool
+	 */
+	pub fn select_prefix(&mut self, prefix: &str) -> bool {
+		let prefix = prefix.to_lowercase();
+		match self.list.iter().position(|module| {
+			module[0].trim_start().to_lowercase().starts_with(&prefix)
+		}) {
+			Some(index) => {
+				self.select_module(index);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/**
+	 * Scroll module list up/down by the given number of rows.
+	 *
+	 * Wraps around at the first/last module like scroll_list.
+	 *
+	 * @param direction
+	 * @param amount
+	 */
+	pub fn scroll_list_by(&mut self, direction: ScrollDirection, amount: usize) {
+		match direction {
+			ScrollDirection::Up if self.index > 0 => {
+				self.select_module(self.index.saturating_sub(amount))
+			}
+			ScrollDirection::Down if self.index + 1 < self.list.len() => {
+				self.select_module((self.index + amount).min(self.list.len() - 1))
+			}
+			_ => self.scroll_list(direction),
+		}
+	}
+
+	/**
+	 * Update the name and information of the selected module.
+	 */
+	fn update_current_module(&mut self) {
+		/* Set current module name. */
+		self.current_name = self.list[self.index][0]
+			.split_whitespace()
+			.next()
+			.unwrap_or("?")
+			.trim()
+			.to_string();
+		/* Show the modules of the group if a group header is selected. */
+		if let Some(prefix) = self.group_header(&self.current_name).map(String::from)
+		{
+			self.show_group(&prefix);
+			self.command = ModuleCommand::None;
+			return;
+		}
+		let current_name = self.current_name.to_string();
+		/* Show where the module is blacklisted. */
+		let blacklist_info = self
+			.blacklist
+			.get(&blacklist::normalize_name(&current_name))
+			.map(|entries| {
+				format!(
+					"{:<16}{}\n",
+					"blacklisted:",
+					entries
+						.iter()
+						.map(BlacklistEntry::to_string)
+						.collect::<Vec<String>>()
+						.join(", ")
+				)
+			})
+			.unwrap_or_default();
+		/* Show the files that load the module at boot. */
+		let boot_info = format!(
+			"{:<16}{}\n",
+			"boot:",
+			match self.boot_files(&current_name) {
+				[] => String::from("no"),
+				files => format!(
+					"yes (from {})",
+					files
+						.iter()
+						.map(|v| v.display().to_string())
+						.collect::<Vec<String>>()
+						.join(", ")
 				),
-				":",
-				self.style.clone(),
-			);
-			/* Clear the current command. */
-			if !self.command.is_none() {
-				self.command = ModuleCommand::None;
 			}
+		);
+		/* Show the modules that are holding the module. */
+		let holders_info = self
+			.source
+			.holders(&current_name)
+			.map(|holders| {
+				format!(
+					"{:<16}{}\n",
+					"holders:",
+					if holders.is_empty() {
+						String::from("-")
+					} else {
+						holders.join(", ")
+					}
+				)
+			})
+			.unwrap_or_default();
+		/* Present devices are read once for matching the module aliases. */
+		if self.modaliases.is_none() {
+			self.modaliases = Some(if self.cmd_args.demo {
+				Vec::new()
+			} else {
+				read_modaliases()
+			});
+		}
+		/* Execute 'modinfo' (if not cached) and add style to its output. */
+		let module_info = self.info_cache.get_or_insert_with(&current_name, || {
+			let module_info = self.source.module_info(&current_name);
+			let taint = util::read_file(
+				Path::new(SYS_MODULE_DIR).join(&current_name).join("taint"),
+			)
+			.ok();
+			Rc::from(
+				format!(
+					"{}{}{}{}{:<16}{}\n{:<16}{}\n{:<16}{}\n{:<16}{}\n{}",
+					blacklist_info,
+					boot_info,
+					holders_info,
+					module_summary(module_info.as_deref().ok()),
+					"sig_status:",
+					signature_status(module_info.as_deref().ok(), taint.as_deref()),
+					"path:",
+					module_path(module_info.as_deref().ok()),
+					"tree:",
+					tree_status(module_info.as_deref().ok(), taint.as_deref()),
+					"hardware:",
+					hardware_status(
+						module_info.as_deref().ok(),
+						self.modaliases.as_deref().unwrap_or_default()
+					),
+					module_info.unwrap_or_else(|e| {
+						if e.is_not_found() {
+							e.to_string()
+						} else {
+							String::from("module information not available")
+						}
+					})
+				)
+				.replace("signature: ", "signature: \n"),
+			)
+		});
+		self.current_info
+			.stylize_data(&module_info, ":", self.style.clone());
+		/* Load time is not cached since it is shown as a relative duration. */
+		let load_time = module_load_time(&current_name)
+			.and_then(|time| time.elapsed().ok())
+			.map(|duration| format!("{} ago", util::format_duration(duration)))
+			.unwrap_or_else(|| String::from("unknown"));
+		self.current_info.styled_text.lines.insert(
+			0,
+			Line::from(vec![
+				Span::styled("loaded:", self.style.colored),
+				Span::styled(format!("{:<9}{load_time}", ""), self.style.default),
+			]),
+		);
+		/* Dependent modules are listed on the dependents view. */
+		let used_by = match self
+			.get_dependent_modules(&current_name)
+			.unwrap_or_default()
+			.len()
+		{
+			0 => String::from("none"),
+			1 => String::from("1 module"),
+			count => format!("{count} modules"),
+		};
+		self.current_info.styled_text.lines.insert(
+			1,
+			Line::from(vec![
+				Span::styled("used by:", self.style.colored),
+				Span::styled(format!("{:<8}{used_by}", ""), self.style.default),
+			]),
+		);
+		self.current_info.raw_text = format!(
+			"{:<16}{load_time}\n{:<16}{used_by}\n{}",
+			"loaded:", "used by:", self.current_info.raw_text
+		);
+		/* Clear the current command. */
+		if !self.command.is_none() {
+			self.command = ModuleCommand::None;
+			self.retry_privilege = None;
 		}
 	}
 
@@ -375,18 +4117,26 @@ impl KernelModules<'_> {
 		direction: ScrollDirection,
 		smooth_scroll: bool,
 	) {
+		/* Move the selection if the dependent modules or parameters are shown. */
+		if self.is_showing_dependents() {
+			self.dependent_index =
+				select_entry(self.dependent_index, self.dependents.len(), direction);
+			self.render_dependents();
+			return;
+		} else if self.is_showing_parameters() {
+			self.parameter_index =
+				select_entry(self.parameter_index, self.parameters.len(), direction);
+			self.render_parameters();
+			return;
+		}
 		let scroll_amount = if smooth_scroll { 1 } else { 2 };
 		match direction {
-			ScrollDirection::Up => {
-				if self.info_scroll_offset > scroll_amount - 1 {
-					self.info_scroll_offset -= scroll_amount;
-				}
+			ScrollDirection::Up if self.info_scroll_offset > scroll_amount - 1 => {
+				self.info_scroll_offset -= scroll_amount;
 			}
-			ScrollDirection::Down => {
-				if self.current_info.lines() > 0 {
-					self.info_scroll_offset += scroll_amount;
-					self.info_scroll_offset %= self.current_info.lines() * 2;
-				}
+			ScrollDirection::Down if self.current_info.lines() > 0 => {
+				self.info_scroll_offset = (self.info_scroll_offset + scroll_amount)
+					.min(self.current_info.lines().saturating_sub(1));
 			}
 			_ => {}
 		}
@@ -396,16 +4146,57 @@ impl KernelModules<'_> {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::kernel::log::LogSource;
+	use crate::kernel::source::{MockSource, ModuleSource};
+	use std::path::PathBuf;
+	use std::sync::atomic::AtomicUsize;
+	/* Module source that counts the reads of the module information */
+	#[derive(Debug, Default)]
+	struct CountingSource {
+		source: MockSource,
+		info_reads: Arc<AtomicUsize>,
+	}
+	impl ModuleSource for CountingSource {
+		fn list_modules(
+			&self,
+			args: &ListArgs,
+		) -> Result<Vec<Module>, Box<dyn Error>> {
+			self.source.list_modules(args)
+		}
+		fn module_info(&self, module_name: &str) -> Result<String, CmdError> {
+			self.info_reads.fetch_add(1, AtomicOrdering::SeqCst);
+			self.source.module_info(module_name)
+		}
+		fn holders(&self, module_name: &str) -> Option<Vec<String>> {
+			self.source.holders(module_name)
+		}
+		fn parameters(&self, module_name: &str) -> Vec<(String, Option<String>)> {
+			self.source.parameters(module_name)
+		}
+		fn kernel_log(&self, log_source: LogSource, human: bool) -> String {
+			self.source.kernel_log(log_source, human)
+		}
+		fn kernel_info(&self) -> Vec<Vec<String>> {
+			self.source.kernel_info()
+		}
+		fn kernel_release(&self) -> Option<String> {
+			self.source.kernel_release()
+		}
+	}
 	#[test]
 	fn test_kernel_modules() {
-		let args = ArgMatches::default();
-		let mut list_args = ListArgs::new(&args);
+		let config = Config::default();
+		let mut list_args = ListArgs::new(&config);
 		list_args.sort = SortType::Size;
 		list_args.reverse = true;
-		let mut kernel_modules = KernelModules::new(list_args, Style::new(&args));
+		let mut kernel_modules = KernelModules::new(
+			list_args,
+			CommandArgs::new(&config),
+			Style::new(&config),
+		);
 		for sort_type in SortType::iter().rev().chain(SortType::iter()) {
 			kernel_modules.args.sort = *sort_type;
-			kernel_modules.refresh();
+			let _ = kernel_modules.refresh();
 		}
 		for direction in ScrollDirection::iter().rev().chain(ScrollDirection::iter())
 		{
@@ -427,13 +4218,1008 @@ mod tests {
 		assert!(!kernel_modules.execute_command());
 		kernel_modules.set_current_command(ModuleCommand::Load, String::new());
 		kernel_modules.scroll_list(ScrollDirection::Top);
+		kernel_modules.toggle_mark();
+		assert_eq!(1, kernel_modules.marked.len());
+		kernel_modules.toggle_mark();
+		assert!(kernel_modules.marked.is_empty());
+		kernel_modules.marked =
+			BTreeSet::from([String::from("~a"), String::from("~b")]);
+		let commands = ["~a", "~b"]
+			.map(|v| {
+				ModuleCommand::Unload
+					.get_with_params(v, &[], &kernel_modules.cmd_args)
+					.cmd
+			})
+			.join("\n");
+		assert_eq!(
+			Some(&commands),
+			kernel_modules.shell_command(ModuleCommand::Unload).as_ref()
+		);
+		assert_eq!(None, kernel_modules.shell_command(ModuleCommand::Load));
+		kernel_modules.set_current_command(ModuleCommand::Unload, String::new());
+		assert_eq!("2 modules", kernel_modules.current_name);
+		assert_eq!(
+			Some(commands),
+			kernel_modules.shell_command(ModuleCommand::None)
+		);
+		assert!(!kernel_modules.execute_command());
+		assert_eq!(4, kernel_modules.current_info.lines());
+		assert!(kernel_modules.current_info.raw_text.contains("~b: failed"));
+		assert!(kernel_modules
+			.current_info
+			.raw_text
+			.ends_with("2 failed, 0 skipped"));
+		assert!(kernel_modules.marked.is_empty());
+		kernel_modules.cmd_args.dry_run = true;
+		kernel_modules.scroll_list(ScrollDirection::Top);
+		kernel_modules.set_current_command(ModuleCommand::Load, String::from("~"));
+		assert_eq!(
+			Some(kernel_modules.get_current_command().cmd),
+			kernel_modules.shell_command(ModuleCommand::Unload)
+		);
+		assert!(kernel_modules.execute_command());
+		assert_eq!("!Dry run", kernel_modules.current_name);
+		assert_eq!(None, kernel_modules.shell_command(ModuleCommand::Unload));
+		assert!(kernel_modules.current_info.raw_text.ends_with(
+			&ModuleCommand::Load
+				.get_with_params("~", &[], &kernel_modules.cmd_args)
+				.cmd
+		));
+		kernel_modules.marked = BTreeSet::from([String::from("~a")]);
+		kernel_modules.set_current_command(ModuleCommand::Unload, String::new());
+		assert!(kernel_modules.execute_command());
+		assert!(kernel_modules.current_info.raw_text.starts_with("~a: "));
+		assert!(kernel_modules.current_info.raw_text.contains("modprobe -r"));
+		let (tx, rx) = std::sync::mpsc::channel();
+		kernel_modules.scroll_list(ScrollDirection::Top);
+		kernel_modules.set_current_command(ModuleCommand::Load, String::from("~"));
+		assert!(kernel_modules.execute_command_async(&tx));
+		assert!(kernel_modules.running.is_some());
+		assert!(!kernel_modules.execute_command_async(&tx));
+		assert!(!kernel_modules.cancel_execution());
+		if let Ok(Event::Execution(result)) = rx.recv() {
+			assert!(kernel_modules.finish_execution(result));
+		}
+		assert!(kernel_modules.running.is_none());
+		assert!(kernel_modules.command.is_none());
+		assert_eq!("!Dry run", kernel_modules.current_name);
+		kernel_modules.cmd_args.dry_run = false;
+		kernel_modules.cmd_args.read_only = true;
+		kernel_modules.scroll_list(ScrollDirection::Top);
+		kernel_modules.set_current_command(ModuleCommand::Unload, String::new());
+		assert!(kernel_modules.command.is_none());
+		kernel_modules.cmd_args.read_only = false;
+		let path = std::env::temp_dir().join("kmon-test-load-file.txt");
+		fs::write(&path, "kmon-test-a\n# comment\nkmon-test-b\n").unwrap();
+		kernel_modules.set_load_file_command(&path.to_string_lossy());
+		assert_eq!("2 modules", kernel_modules.current_name);
+		assert_eq!(ModuleCommand::Load, kernel_modules.command);
+		assert_eq!(None, kernel_modules.confirmation_remaining());
+		kernel_modules.cmd_args.confirm.timeout = Some(10);
+		assert!(kernel_modules
+			.confirmation_remaining()
+			.is_some_and(|v| v.as_secs() < 10));
+		assert!(!kernel_modules.cancel_expired_execution());
+		kernel_modules.confirm_time -= Duration::from_secs(10);
+		assert!(kernel_modules.cancel_expired_execution());
+		kernel_modules.cmd_args.confirm.timeout = None;
+		kernel_modules.set_load_file_command(&path.to_string_lossy());
+		assert!(kernel_modules.cancel_execution());
+		fs::remove_file(&path).unwrap();
+		kernel_modules.set_load_file_command(&path.to_string_lossy());
+		assert!(kernel_modules.current_name.starts_with("!Error"));
+		assert!(kernel_modules.command.is_none());
+		assert_eq!(
+			BTreeSet::from([
+				String::from("e1000e"),
+				String::from("snd_hda_codec"),
+				String::from("snd_hda_intel"),
+			]),
+			parse_modules_dep(
+				"kernel/drivers/net/e1000e.ko: kernel/lib/crc32.ko\n\
+				kernel/sound/pci/hda/snd-hda-intel.ko.zst:\n\
+				kernel/sound/pci/hda/snd-hda-codec.ko.xz: kernel/sound/snd.ko\n\
+				invalid\n"
+			)
+		);
+		let modules_dep = "kernel/sound/pci/hda/snd-hda-intel.ko.zst: \
+			kernel/sound/pci/hda/snd-hda-codec.ko kernel/sound/snd.ko\n\
+			kernel/sound/snd.ko:\n";
+		assert_eq!(
+			vec![
+				"kernel/sound/snd.ko",
+				"kernel/sound/pci/hda/snd-hda-codec.ko"
+			],
+			parse_load_order(modules_dep, "snd-hda-intel")
+		);
+		assert!(parse_load_order(modules_dep, "snd").is_empty());
+		assert!(parse_load_order(modules_dep, "e1000e").is_empty());
+		assert!(kernel_modules.get_load_order("~").is_empty());
+		let modules = kernel_modules.modules.clone();
+		kernel_modules.available = OnceLock::from(parse_modules_dep(
+			"a/snd-a.ko:\na/snd-b.ko:\na/snc.ko:\na/~loaded.ko:\n",
+		));
+		assert_eq!(Some(4), kernel_modules.available_count());
+		kernel_modules.set_modules(&modules);
+		kernel_modules.set_modules(&[Module {
+			name: String::from("~loaded"),
+			..Module::default()
+		}]);
+		let changes = kernel_modules.recent_changes().cloned().unwrap();
+		assert_eq!(BTreeSet::from([String::from("~loaded")]), changes.added);
+		assert_eq!(modules.len(), changes.removed.len());
+		assert_eq!(
+			"+1 loaded, -4 unloaded: a, b, c, ...",
+			ModuleChanges {
+				added: BTreeSet::from([String::from("x")]),
+				removed: ["a", "b", "c", "d"]
+					.into_iter()
+					.map(String::from)
+					.collect(),
+				time: Instant::now(),
+			}
+			.summary()
+		);
+		assert_eq!(
+			vec!["snd_a", "snd_b"],
+			kernel_modules.get_completions("snd-")
+		);
+		assert!(kernel_modules.get_completions("~").is_empty());
+		assert_eq!(3, kernel_modules.get_completions("").len());
+		kernel_modules.set_modules(&modules);
+		assert_eq!(
+			Some(&BTreeSet::from([String::from("~loaded")])),
+			kernel_modules.recent_changes().map(|v| &v.removed)
+		);
+		kernel_modules.changes.as_mut().unwrap().time -= CHANGE_DURATION;
+		assert!(kernel_modules.recent_changes().is_none());
+		let builtin_modules = parse_builtin_modules(
+			"kernel/fs/ext4/ext4.ko\nkernel/drivers/char/hw_random/rng-core.ko\n\ninvalid\n",
+		);
+		assert_eq!(
+			vec!["ext4", "rng_core"],
+			builtin_modules
+				.iter()
+				.map(|v| v.name.as_str())
+				.collect::<Vec<&str>>()
+		);
+		assert_eq!(
+			vec![" ext4", "-", "built-in"],
+			builtin_modules[0].row(SizeFormat::Human)
+		);
+		let modules = kernel_modules.modules.clone();
+		kernel_modules.set_modules(&[builtin_modules, modules.clone()].concat());
+		assert!(!kernel_modules.is_builtin("ext4"));
+		kernel_modules.args.builtin = true;
+		assert!(kernel_modules.is_builtin("ext4"));
+		kernel_modules.scroll_list(ScrollDirection::Top);
+		assert_eq!("ext4", kernel_modules.current_name);
+		kernel_modules.toggle_mark();
+		assert!(kernel_modules.marked.is_empty());
+		kernel_modules.set_current_command(ModuleCommand::Unload, String::new());
+		assert!(kernel_modules.command.is_none());
+		assert!(kernel_modules
+			.current_info
+			.raw_text
+			.ends_with(BUILTIN_ERROR));
+		kernel_modules
+			.set_current_command(ModuleCommand::Load, String::from("ext4"));
+		assert!(kernel_modules.command.is_none());
+		kernel_modules.args.builtin = false;
+		kernel_modules.set_modules(&[
+			Module {
+				name: String::from("~u"),
+				refcount: 1,
+				..Module::default()
+			},
+			Module {
+				name: String::from("~v"),
+				..Module::default()
+			},
+		]);
+		assert!(kernel_modules.is_in_use("~u"));
+		assert!(!kernel_modules.is_in_use("~v"));
+		assert_eq!(
+			"~u is held by 1 process(es) or device(s), stop them first",
+			kernel_modules.busy_message("~u")
+		);
+		assert_eq!(
+			"~v is in use, try again later",
+			kernel_modules.busy_message("~v")
+		);
+		assert!(kernel_modules.toggle_used().is_ok());
+		assert!(kernel_modules.args.hide_used);
+		assert!(kernel_modules.modules.iter().all(|v| v.refcount == 0));
+		assert!(kernel_modules.toggle_used().is_ok());
+		assert!(!kernel_modules.args.hide_used);
+		kernel_modules.set_modules(&modules);
+		kernel_modules.scroll_list(ScrollDirection::Top);
+		let module_name = kernel_modules.current_name.to_string();
+		assert_eq!(
+			Some(format!("bookmarked {module_name}")),
+			kernel_modules.toggle_bookmark()
+		);
+		assert!(kernel_modules.is_bookmarked(&module_name));
+		assert!(kernel_modules.toggle_bookmarks().is_ok());
+		assert_eq!(1, kernel_modules.list.len());
+		assert_eq!(
+			Some(format!("removed the bookmark of {module_name}")),
+			kernel_modules.toggle_bookmark()
+		);
+		assert!(kernel_modules.list.is_empty());
+		assert_eq!(None, kernel_modules.toggle_bookmark());
+		assert!(kernel_modules.toggle_bookmarks().is_ok());
+		assert!(!kernel_modules.list.is_empty());
+		kernel_modules.last_action = None;
+		assert_eq!(
+			Err(String::from("nothing to undo")),
+			kernel_modules.set_undo_command()
+		);
+		for (command, error) in [
+			(ModuleCommand::Reload, "'reload' cannot be undone"),
+			(ModuleCommand::Load, "cannot undo: ~ is not loaded"),
+			(
+				ModuleCommand::Blacklist,
+				"cannot undo: ~ is not blacklisted",
+			),
+		] {
+			kernel_modules.last_action = Some((command, String::from("~")));
+			assert_eq!(Err(String::from(error)), kernel_modules.set_undo_command());
+		}
+		kernel_modules.last_action =
+			Some((ModuleCommand::Unload, String::from("~")));
+		assert!(kernel_modules.set_undo_command().is_ok());
+		assert_eq!(ModuleCommand::Load, kernel_modules.command);
+		assert_eq!("~", kernel_modules.current_name);
+		assert!(!kernel_modules.execute_command());
+		assert!(kernel_modules.status.take().unwrap().is_err());
+		assert!(kernel_modules.last_action.is_some());
+		kernel_modules.scroll_list(ScrollDirection::Top);
+		assert_eq!(None, kernel_modules.get_dependent_modules("~"));
+		let module_name = kernel_modules.current_name.clone();
+		kernel_modules.current_name = String::from("!Dependent modules of ~");
+		kernel_modules.dependents = vec![String::from("~"), module_name.clone()];
+		assert!(kernel_modules.is_showing_dependents());
+		kernel_modules.scroll_mod_info(ScrollDirection::Up, false);
+		assert_eq!(1, kernel_modules.dependent_index);
+		assert!(kernel_modules.current_info.raw_text.ends_with(&module_name));
+		kernel_modules.scroll_mod_info(ScrollDirection::Down, false);
+		assert_eq!(0, kernel_modules.dependent_index);
+		assert_eq!(
+			Err(String::from("~ is not in the module list")),
+			kernel_modules.select_dependent()
+		);
+		kernel_modules.scroll_mod_info(ScrollDirection::Bottom, false);
+		assert_eq!(Ok(()), kernel_modules.select_dependent());
+		assert_eq!(module_name, kernel_modules.current_name);
+		assert!(!kernel_modules.is_showing_dependents());
+		kernel_modules.dependents.clear();
+		kernel_modules
+			.current_info
+			.set(Text::default(), String::from("a\nb\nc"));
+		for _ in 0..3 {
+			kernel_modules.scroll_mod_info(ScrollDirection::Down, false);
+		}
+		assert_eq!(2, kernel_modules.info_scroll_offset);
+		let default_list = kernel_modules.default_list.clone();
+		for (name, dependents) in [("~a", vec![String::from("~b")]), ("~b", vec![])]
+		{
+			kernel_modules.default_list.push(
+				Module {
+					name: String::from(name),
+					refcount: dependents.len() as u64,
+					dependents,
+					..Module::default()
+				}
+				.row(kernel_modules.args.size_format),
+			);
+		}
+		kernel_modules.current_name = String::from("~b");
+		kernel_modules.set_current_command(ModuleCommand::Unload, String::new());
+		assert!(!kernel_modules.has_recursive_unload());
+		kernel_modules.cancel_execution();
+		kernel_modules.current_name = String::from("~a");
+		kernel_modules.set_current_command(ModuleCommand::Unload, String::new());
+		assert!(kernel_modules.has_recursive_unload());
+		kernel_modules.cmd_args.dry_run = true;
+		kernel_modules.execute_recursive_unload();
+		kernel_modules.cmd_args.dry_run = false;
+		let raw_text = kernel_modules.current_info.raw_text.to_string();
+		assert!(raw_text.find("~b").unwrap() < raw_text.find("~a").unwrap());
+		assert!(!kernel_modules.has_recursive_unload());
+		assert!(!kernel_modules.execute_recursive_unload());
+		kernel_modules.default_list = default_list;
+		kernel_modules.status = None;
+		assert!(!kernel_modules.is_blacklisted("~"));
+		assert!(kernel_modules.get_unblacklist_files("~").is_empty());
+		kernel_modules.blacklist.insert(
+			String::from("a_b"),
+			vec![
+				BlacklistEntry {
+					path: PathBuf::from("/tmp/a.conf"),
+					line: 1,
+				},
+				BlacklistEntry {
+					path: kernel_modules.cmd_args.blacklist.path.to_path_buf(),
+					line: 3,
+				},
+			],
+		);
+		assert_eq!(
+			vec![String::from(blacklist::BLACKLIST_FILE)],
+			kernel_modules.get_unblacklist_files("a-b")
+		);
+		kernel_modules.cmd_args.blacklist.remove_all = true;
+		assert_eq!(2, kernel_modules.get_unblacklist_files("a_b").len());
+		let last_name = kernel_modules.list[kernel_modules.list.len() - 1][0]
+			.split_whitespace()
+			.next()
+			.unwrap()
+			.to_uppercase();
+		assert!(kernel_modules.select_prefix(&last_name));
+		assert!(kernel_modules
+			.current_name
+			.to_uppercase()
+			.starts_with(&last_name));
+		assert!(!kernel_modules.select_prefix("~"));
+		kernel_modules.scroll_list(ScrollDirection::Top);
+		kernel_modules.scroll_list_by(ScrollDirection::Down, usize::MAX);
+		assert_eq!(kernel_modules.list.len() - 1, kernel_modules.index);
+		kernel_modules.scroll_list_by(ScrollDirection::Down, 5);
+		assert_eq!(0, kernel_modules.index);
+		kernel_modules.scroll_list_by(ScrollDirection::Up, 5);
+		assert_eq!(kernel_modules.list.len() - 1, kernel_modules.index);
+		kernel_modules.scroll_list(ScrollDirection::Top);
+		kernel_modules
+			.set_current_command(ModuleCommand::Unblacklist, String::new());
+		assert!(kernel_modules.current_name.starts_with("!Error"));
+		assert!(kernel_modules.command.is_none());
+		for _ in 0..3 {
+			kernel_modules.next_filter().unwrap();
+			assert!(kernel_modules.default_list.iter().all(|module| {
+				match kernel_modules.args.filter {
+					ListFilter::All => true,
+					ListFilter::InUse => !module[2].starts_with("0 "),
+					ListFilter::Unused => module[2].starts_with("0 "),
+				}
+			}));
+		}
+		assert_eq!(ListFilter::All, kernel_modules.args.filter);
+		assert_eq!(
+			Ok(SizeFilter {
+				bytes: 1 << 20,
+				below: false
+			}),
+			SizeFilter::try_from(">1M")
+		);
+		assert_eq!(
+			Ok(SizeFilter {
+				bytes: 1536,
+				below: true
+			}),
+			SizeFilter::try_from("< 1.5KiB")
+		);
+		assert_eq!(Ok(100), SizeFilter::try_from(">=100").map(|v| v.bytes));
+		assert!(SizeFilter::try_from(">1X").is_err());
+		assert!(SizeFilter::try_from("<=1K").is_err());
+		assert_eq!(">1M", SizeFilter::try_from(">1024K").unwrap().to_string());
+		assert_eq!("<1536", SizeFilter::try_from("<1536").unwrap().to_string());
+		assert!(SizeFilter::try_from(">1K").unwrap().matches(&Module {
+			size: 1024,
+			..Module::default()
+		}));
+		let module_count = kernel_modules.default_list.len();
+		let totals = (
+			kernel_modules.loaded_count,
+			kernel_modules.loaded_size,
+			kernel_modules.used_count,
+			kernel_modules.blacklisted_count,
+		);
+		kernel_modules.set_size_filter(">1G").unwrap();
+		assert!(kernel_modules.default_list.is_empty());
+		assert_ne!(0, kernel_modules.loaded_count);
+		assert_eq!(
+			totals,
+			(
+				kernel_modules.loaded_count,
+				kernel_modules.loaded_size,
+				kernel_modules.used_count,
+				kernel_modules.blacklisted_count,
+			)
+		);
+		assert!(kernel_modules.set_size_filter(">1X").is_err());
+		kernel_modules.set_size_filter("").unwrap();
+		assert_eq!(None, kernel_modules.args.size_filter);
+		assert_eq!(module_count, kernel_modules.default_list.len());
+		kernel_modules.args.sort = SortType::None;
+		kernel_modules.args.reverse = false;
+		kernel_modules.scroll_list(ScrollDirection::Bottom);
+		let current_name = kernel_modules.current_name.to_string();
+		kernel_modules.next_sort().unwrap();
+		assert_eq!(SortType::Name, kernel_modules.args.sort);
+		assert_eq!(current_name, kernel_modules.current_name);
+		let names = kernel_modules
+			.modules
+			.iter()
+			.map(|module| module.name.to_string())
+			.collect::<Vec<String>>();
+		kernel_modules.toggle_sort_direction();
+		assert_eq!(
+			names.into_iter().rev().collect::<Vec<String>>(),
+			kernel_modules
+				.modules
+				.iter()
+				.map(|module| module.name.to_string())
+				.collect::<Vec<String>>()
+		);
+		assert!(SortType::Name.is_descending(kernel_modules.args.reverse));
+		assert!(SortType::Size.is_descending(false));
+		assert_eq!(
+			Some(String::from("name desc")),
+			kernel_modules
+				.args
+				.sort
+				.description(kernel_modules.args.reverse)
+		);
+		assert_eq!(current_name, kernel_modules.current_name);
+		assert_eq!(
+			Some(String::from("size desc")),
+			SortType::Size.description(false)
+		);
+		assert_eq!(None, SortType::Size.next().next().description(false));
+		let (tx, rx) = std::sync::mpsc::channel();
+		kernel_modules.scroll_list(ScrollDirection::Bottom);
+		let index = kernel_modules.index;
+		kernel_modules.refresh_async(&tx);
+		assert!(kernel_modules.refreshing);
+		if let Ok(Event::Modules(Ok(modules))) = rx.recv() {
+			kernel_modules.set_modules(&modules);
+		}
+		assert!(!kernel_modules.refreshing);
+		assert_eq!(index, kernel_modules.index);
+		kernel_modules.index_async(&tx);
+		assert_eq!(
+			Some((0, kernel_modules.modules.len())),
+			kernel_modules.indexing
+		);
+		while kernel_modules.indexing.is_some() {
+			if let Ok(Event::SearchIndex(entries)) = rx.recv() {
+				assert!(entries.len() <= INDEX_BATCH);
+				kernel_modules.set_search_index(entries);
+			}
+		}
+		assert_eq!(
+			kernel_modules.modules.len(),
+			kernel_modules.search_index.len()
+		);
+		kernel_modules.index_async(&tx);
+		assert_eq!(None, kernel_modules.indexing);
+		kernel_modules.search_index.clear();
+		kernel_modules.index_async(&tx);
+		kernel_modules.cancel_index();
+		assert_eq!(None, kernel_modules.indexing);
+		kernel_modules.set_search_index(Vec::new());
+		assert_eq!(None, kernel_modules.indexing);
+		let current_name = kernel_modules.current_name.to_string();
+		kernel_modules.list.truncate(1);
+		kernel_modules.select_unfiltered(&current_name);
+		assert_eq!(index, kernel_modules.index);
+		assert_eq!(kernel_modules.default_list.len(), kernel_modules.list.len());
+		let info_reads = Arc::new(AtomicUsize::new(0));
+		kernel_modules.source = DataSource::new(CountingSource {
+			info_reads: Arc::clone(&info_reads),
+			..CountingSource::default()
+		});
+		kernel_modules.info_cache.clear();
+		kernel_modules.select_module(0);
+		kernel_modules.select_module(0);
+		assert_eq!(1, kernel_modules.info_cache.len());
+		assert_eq!(1, info_reads.load(AtomicOrdering::SeqCst));
+		kernel_modules.select_module(1);
+		kernel_modules.select_module(0);
+		assert_eq!(2, info_reads.load(AtomicOrdering::SeqCst));
+		kernel_modules.info_cache.clear();
+		kernel_modules.select_module(0);
+		assert_eq!(3, info_reads.load(AtomicOrdering::SeqCst));
+		kernel_modules.source = DataSource::default();
+		assert!(!kernel_modules.toggle_groups());
+		kernel_modules.set_groups(&GroupsConfig {
+			prefixes: vec![String::from("~")],
+			collapsed: true,
+		});
+		let rows = ["~a", "x", "~b"]
+			.iter()
+			.map(|name| vec![format!(" {name}"), String::new(), String::new()])
+			.collect::<Vec<Vec<String>>>();
+		let grouped = kernel_modules.group_rows(rows.clone());
+		assert_eq!(2, grouped.len());
+		assert!(grouped[0][0].starts_with(" ~* (2)"));
+		assert_eq!(Some("~"), kernel_modules.group_header("~*"));
+		assert_eq!(None, kernel_modules.group_header("x*"));
+		kernel_modules.list = grouped;
+		kernel_modules.select_module(0);
+		assert_eq!("!Group: ~*", kernel_modules.current_name);
+		assert!(kernel_modules.toggle_group());
+		kernel_modules.list = kernel_modules.group_rows(rows.clone());
+		assert_eq!(3, kernel_modules.list.len());
+		kernel_modules.select_module(1);
+		kernel_modules.select_pending();
+		assert_eq!(0, kernel_modules.index);
+		kernel_modules.select_module(1);
+		assert!(!kernel_modules.toggle_group());
+		assert!(kernel_modules.toggle_groups());
+		kernel_modules.list = kernel_modules.group_rows(rows);
+		kernel_modules.select_pending();
+		assert_eq!(1, kernel_modules.index);
+		assert_eq!(2, kernel_modules.list.len());
+		kernel_modules.set_groups(&GroupsConfig::default());
+		let dir = std::env::temp_dir().join("kmon-test-parameters");
+		fs::create_dir_all(dir.join("nested")).unwrap();
+		fs::write(dir.join("enable"), "Y\n").unwrap();
+		fs::write(dir.join("debug"), "0\n").unwrap();
+		assert_eq!(
+			vec![
+				(String::from("debug"), Some(String::from("0"))),
+				(String::from("enable"), Some(String::from("Y"))),
+				(String::from("nested"), None),
+			],
+			read_parameters(&dir)
+		);
+		assert!(is_writable(&dir.join("debug")));
+		assert_eq!(
+			Ok(Some(String::from("1"))),
+			write_parameter(&dir.join("debug"), "1\n")
+		);
+		fs::set_permissions(dir.join("enable"), fs::Permissions::from_mode(0o444))
+			.unwrap();
+		assert!(!is_writable(&dir.join("enable")));
+		assert!(!is_writable(&dir.join("~")));
+		assert_eq!(Some(0o444), file_mode(&dir.join("enable")));
+		fs::create_dir_all(dir.join("holders")).unwrap();
+		for holder in ["nf_nat", "nf_conntrack_netlink"] {
+			fs::write(dir.join("holders").join(holder), "").unwrap();
+		}
+		assert_eq!(
+			Some(vec![
+				String::from("nf_conntrack_netlink"),
+				String::from("nf_nat")
+			]),
+			read_holders(&dir.join("holders"))
+		);
+		assert_eq!(None, read_holders(&dir.join("~")));
+		assert_eq!(None, file_mode(&dir.join("~")));
+		assert!(write_parameter(&dir.join("nested"), "1").is_err());
+		fs::remove_dir_all(dir).unwrap();
+		assert!(kernel_modules.get_parameters("~").is_empty());
+		let current_name = kernel_modules.current_name.to_string();
+		kernel_modules.current_name = String::from("!Parameters of ~");
+		kernel_modules.parameters_module = String::from("~");
+		kernel_modules.parameters = vec![
+			(String::from("debug"), Some(String::from("0"))),
+			(String::from("enable"), None),
+		];
+		assert!(kernel_modules.is_showing_parameters());
+		kernel_modules.scroll_mod_info(ScrollDirection::Down, false);
+		assert_eq!(Some(1), kernel_modules.selected_info_line());
+		assert_eq!(
+			"debug: 0\nenable: <unreadable>",
+			kernel_modules.current_info.raw_text
+		);
+		kernel_modules.parameter_info = BTreeMap::from([
+			(
+				String::from("debug"),
+				ParameterInfo {
+					param_type: Some(String::from("int")),
+					description: Some(String::from("enable debug output")),
+					mode: Some(0o644),
+				},
+			),
+			(
+				String::from("enable"),
+				ParameterInfo {
+					mode: Some(0o200),
+					..ParameterInfo::default()
+				},
+			),
+		]);
+		kernel_modules.render_parameters();
+		assert_eq!(
+			"debug (int, rw): 0 - enable debug output\nenable (w): <unreadable>",
+			kernel_modules.current_info.raw_text
+		);
+		assert_eq!(
+			Err(String::from("enable is read-only")),
+			kernel_modules.get_editable_parameter()
+		);
+		assert!(kernel_modules.set_parameter("1").is_err());
+		kernel_modules.cmd_args.dry_run = true;
+		assert_eq!(
+			Ok(String::from(
+				"1 would be written to /sys/module/~/parameters/enable"
+			)),
+			kernel_modules.set_parameter("1")
+		);
+		kernel_modules.cmd_args.dry_run = false;
+		kernel_modules.parameters.clear();
+		assert_eq!(None, kernel_modules.selected_info_line());
+		kernel_modules.current_name = current_name;
+		assert_eq!(2, select_entry(0, 3, ScrollDirection::Up));
+		assert_eq!(0, select_entry(2, 3, ScrollDirection::Down));
+		assert_eq!(2, select_entry(0, 3, ScrollDirection::Bottom));
+		let snapshot = kernel_modules.snapshot().unwrap();
+		assert_eq!(kernel_modules.modules.len(), snapshot.modules.len());
+		let (tx, rx) = std::sync::mpsc::channel();
+		kernel_modules.save_snapshot_async(&tx);
+		if let Ok(Event::Snapshot(result)) = rx.recv() {
+			let path = result.unwrap();
+			assert_eq!(
+				Ok(snapshot),
+				Snapshot::parse(&fs::read_to_string(&path).unwrap())
+			);
+			fs::remove_file(path).unwrap();
+		} else {
+			panic!("snapshot is not saved");
+		}
+		assert_eq!(
+			"Signed by: Build time autogenerated kernel key",
+			signature_status(
+				Some("name: ext4\nsigner: Build time autogenerated kernel key\n"),
+				Some("\n")
+			)
+		);
+		assert_eq!(
+			"Unsigned (would taint), out-of-tree",
+			signature_status(Some("name: nvidia\n"), Some("POE\n"))
+		);
+		assert_eq!("unknown", signature_status(None, None));
+		assert_eq!(
+			"in-tree",
+			tree_status(Some("name: ext4\nintree: Y\n"), None)
+		);
+		assert_eq!(
+			"out-of-tree",
+			tree_status(Some("name: zfs\nintree: N\n"), None)
+		);
+		assert_eq!(
+			"out-of-tree",
+			tree_status(Some("name: nvidia\n"), Some("(POE)"))
+		);
+		assert_eq!("unknown", tree_status(Some("filename: (builtin)\n"), None));
+		assert_eq!("unknown", tree_status(None, None));
+		kernel_modules.modules.push(Module {
+			name: String::from("oot_test"),
+			taint: Some(String::from("(OE)")),
+			..Module::default()
+		});
+		assert!(kernel_modules.is_out_of_tree("oot_test"));
+		assert!(!kernel_modules.is_out_of_tree("nonexistent"));
+		kernel_modules.modules.pop();
+		assert_eq!(
+			"/lib/modules/6.9.0/kernel/drivers/net/e1000e.ko",
+			module_path(Some(
+				"filename:       /lib/modules/6.9.0/kernel/drivers/net/e1000e.ko\n\
+				version:        3.2.6-k\n"
+			))
+		);
+		assert_eq!(
+			vec!["pci:v00008086d000010D3sv*sd*bc*sc*i*", "usb:v0BDAp8153d*"],
+			parse_aliases(
+				"filename:       e1000e.ko\n\
+				alias:          pci:v00008086d000010D3sv*sd*bc*sc*i*\n\
+				alias:          usb:v0BDAp8153d*\n\
+				aliases:        x\n"
+			)
+		);
+		assert!(parse_aliases("name: ext4\n").is_empty());
+		let modaliases = parse_modaliases(
+			"/sys/bus/pci/devices/0000:00:1f.6/modalias:pci:v00008086d000010D3sv00sd00bc02sc00i00\n\
+			/sys/bus/usb/devices/1-2:1.0/modalias:usb:v046Dp0825d0010\n\
+			invalid\n",
+		);
+		assert_eq!(2, modaliases.len());
+		assert_eq!(
+			PathBuf::from("/sys/bus/pci/devices/0000:00:1f.6"),
+			modaliases[0].0
+		);
+		let module_info = "alias: pci:v00008086d000010D3sv*sd*bc*sc*i*\n";
+		assert_eq!(
+			"yes (0000:00:1f.6)",
+			hardware_status(Some(module_info), &modaliases)
+		);
+		assert_eq!(
+			"no",
+			hardware_status(Some("alias: usb:v0BDA*\n"), &modaliases)
+		);
+		assert_eq!(
+			"unknown (no aliases)",
+			hardware_status(Some("name: ext4\n"), &modaliases)
+		);
+		assert!(matching_devices(&[String::from("*")], &[]).is_empty());
+		assert_eq!(
+			vec!["debug=-1", "enable=Y,N"],
+			reload_parameters(&[
+				(String::from("debug"), Some(String::from("-1"))),
+				(String::from("enable"), Some(String::from("Y,N"))),
+				(String::from("model"), Some(String::from("(null)"))),
+				(String::from("name"), Some(String::from("a b"))),
+				(String::from("secret"), None),
+			])
+		);
+		let entry = SearchEntry::parse(
+			"description:    Intel(R) PRO/1000 Network Driver\n\
+			alias:          pci:v00008086d000010D3sv*sd*bc*sc*i*\n",
+		);
+		assert_eq!("Intel(R) PRO/1000 Network Driver", entry.description);
+		assert_eq!(
+			Some("description"),
+			entry.matched_field(|v| v.contains("Network"))
+		);
+		assert_eq!(
+			Some("alias"),
+			entry.matched_field(|v| v.starts_with("pci:"))
+		);
+		assert_eq!(None, entry.matched_field(|v| v.contains("usb")));
+		let module_info = "filename:       /lib/modules/6.9.0/e1000e.ko\n\
+			version:        3.2.6-k\n\
+			license:        GPL v2\n\
+			depends:        \n\
+			parm:           debug:Debug level (0=none,...,16=all) (int)\n";
+		assert_eq!(
+			"### e1000e\n\n\
+			- **Version:** `3.2.6-k`\n\
+			- **Path:** `/lib/modules/6.9.0/e1000e.ko`\n\
+			- **License:** `GPL v2`\n\
+			- **Dependencies:** `-`\n\
+			- **Signature:** `unknown`\n\n\
+			#### Parameters\n\n\
+			- `debug`: Debug level (0=none,...,16=all) (int)\n",
+			format_report("e1000e", module_info, &[], None)
+		);
+		assert!(format_report(
+			"e1000e",
+			module_info,
+			&[(String::from("debug"), Some(String::from("-1")))],
+			None
+		)
+		.ends_with("\n- `debug`: `-1`\n"));
+		assert_eq!(
+			BTreeMap::from([(
+				String::from("debug"),
+				ParameterInfo {
+					param_type: Some(String::from("int")),
+					description: Some(String::from(
+						"Debug level (0=none,...,16=all)"
+					)),
+					mode: None,
+				}
+			)]),
+			parse_parameter_info(module_info)
+		);
+		let parameter_info = parse_parameter_info(
+			"parm:           quiet\n\
+			parm:           mode:Mode (0=off)\n\
+			parmtype:       mode:uint\n\
+			parmtype:       ids:array of charp\n",
+		);
+		assert_eq!(
+			vec!["ids", "mode", "quiet"],
+			parameter_info.keys().collect::<Vec<&String>>()
+		);
+		assert_eq!(" (array of charp)", parameter_info["ids"].annotation());
+		assert_eq!(
+			(Some("uint"), Some("Mode (0=off)")),
+			(
+				parameter_info["mode"].param_type.as_deref(),
+				parameter_info["mode"].description.as_deref()
+			)
+		);
+		assert_eq!(ParameterInfo::default(), parameter_info["quiet"]);
+		assert_eq!(Ok(()), check_filesystems(|_| true));
+		assert_eq!(
+			Err(String::from(
+				"/sys/module is not found, sysfs is not mounted \
+				(try 'mount -t sysfs sysfs /sys')"
+			)),
+			check_filesystems(|v| v != SYS_MODULE_DIR)
+		);
+		assert_eq!(
+			2,
+			check_filesystems(|_| false)
+				.unwrap_err()
+				.split("; ")
+				.count()
+		);
+		assert_eq!("", parameter_info["quiet"].annotation());
+		assert_eq!("(built-in)", module_path(Some("filename: (builtin)\n")));
+		assert_eq!("(built-in)", module_path(Some("name: ext4\n")));
+		assert_eq!("unknown", module_path(None));
+		assert_eq!(
+			"license:        GPL v2\n\
+			author:         Intel Corporation, <linux.nics@intel.com>\n\
+			description:    Intel(R) PRO/1000 Network Driver\n",
+			module_summary(Some(
+				"filename:       /lib/modules/6.9.0/kernel/drivers/net/e1000e.ko\n\
+				license:        GPL v2\n\
+				description:    Intel(R) PRO/1000 Network Driver\n\
+				author:         Intel Corporation, <linux.nics@intel.com>\n\
+				srcversion:     1B3A2F1A4A5B6C7D8E9F0A1\n"
+			))
+		);
+		assert_eq!(
+			"license:        NVIDIA\n",
+			module_summary(Some("license:        NVIDIA\nauthor:\nversion: 1.0\n"))
+		);
+		assert_eq!(
+			"author:         A, B\n",
+			module_summary(Some("author: A\nauthor: B\n"))
+		);
+		assert_eq!("", module_summary(None));
+		assert_eq!(None, module_load_time("~"));
+		kernel_modules.scroll_list(ScrollDirection::Top);
+		assert!(kernel_modules.current_info.raw_text.starts_with("loaded:"));
+		assert!(kernel_modules
+			.current_info
+			.raw_text
+			.lines()
+			.nth(1)
+			.is_some_and(|v| v.starts_with("used by:")));
+		let current_name = kernel_modules.current_name.to_string();
+		kernel_modules.current_name = String::from("!Parameters");
+		assert_eq!(None, kernel_modules.module_report());
+		kernel_modules.current_name = current_name;
+		assert_eq!(
+			kernel_modules.current_info.raw_text.lines().count(),
+			kernel_modules.current_info.lines()
+		);
+		let modules = parse_proc_modules(
+			"snd_hda_intel 57344 3 - Live 0xffffffffc0a4c000\n\
+			snd 98304 2 snd_hda_intel,snd_pcm, Live 0xffffffffc0a0e000\n\
+			nvidia 56717312 1 - Live 0x0000000000000000 (POE)\n\
+			invalid\n",
+		);
+		assert_eq!(
+			vec!["snd_hda_intel", "snd", "nvidia"],
+			modules
+				.iter()
+				.map(|v| v.name.as_str())
+				.collect::<Vec<&str>>()
+		);
+		assert_eq!(
+			(
+				98304,
+				2,
+				vec![String::from("snd_hda_intel"), String::from("snd_pcm")]
+			),
+			(
+				modules[1].size,
+				modules[1].refcount,
+				modules[1].dependents.clone()
+			)
+		);
+		assert!(modules[0].dependents.is_empty());
+		assert_eq!(Some(String::from("(POE)")), modules[2].taint);
+		assert!(modules.iter().all(Module::is_live));
+		assert_eq!(Some(0xffffffffc0a4c000), modules[0].offset);
+		assert_eq!(Some(0), modules[2].offset);
+		let module = Module::parse("snd 98304 - - Live (OE)").unwrap();
+		assert_eq!((0, None), (module.refcount, module.offset));
+		assert_eq!(Some(String::from("(OE)")), module.taint);
+		let dependents = (0..1000).map(|v| format!("m{v}")).collect::<Vec<_>>();
+		let module =
+			Module::parse(&format!("snd 98304 1000 {}, Live", dependents.join(",")))
+				.unwrap();
+		assert_eq!(dependents, module.dependents);
+		assert_eq!(None, module.offset);
+		assert_eq!(None, Module::parse("snd large 0 - Live"));
+		assert_eq!(None, Module::parse("snd 98304 0"));
+		let module = Module::parse("snd 98304 0 - Unloading 0x0").unwrap();
+		assert_eq!("going", module.initstate());
+		assert!(!module.is_live());
+		assert_eq!(" snd (going)", module.row(SizeFormat::default())[0]);
+		assert!(Module::default().is_live());
+		assert!(parse_proc_modules("").is_empty());
+		let lsmod_modules = parse_lsmod(
+			"Module                  Size  Used by\n\
+			snd_hda_intel          57344  3\n\
+			snd                    98304  2 snd_hda_intel,snd_pcm\n\
+			nvidia               56717312  1\n",
+		);
+		assert_eq!(
+			modules
+				.iter()
+				.map(|v| (v.name.as_str(), v.size, v.refcount, v.dependents.clone()))
+				.collect::<Vec<_>>(),
+			lsmod_modules
+				.iter()
+				.map(|v| (v.name.as_str(), v.size, v.refcount, v.dependents.clone()))
+				.collect::<Vec<_>>()
+		);
+		let mut modules = ["a 10 2 - Live", "b 30 1 - Live", "c 20 2 x,y, Live"]
+			.iter()
+			.filter_map(|v| Module::parse(v))
+			.collect::<Vec<Module>>();
+		for (sort_type, names) in [
+			(SortType::Size, ["b", "c", "a"]),
+			(SortType::Refcount, ["a", "c", "b"]),
+			(SortType::Dependent, ["c", "a", "b"]),
+			(SortType::Name, ["a", "b", "c"]),
+		] {
+			modules.sort_by(|a, b| sort_type.compare(a, b));
+			assert_eq!(
+				names.to_vec(),
+				modules
+					.iter()
+					.map(|v| v.name.as_str())
+					.collect::<Vec<&str>>()
+			);
+		}
+		kernel_modules.toggle_size_format();
+		assert_eq!(SizeFormat::Raw, kernel_modules.args.size_format);
+		assert_eq!(
+			kernel_modules.modules[0].size.to_string(),
+			kernel_modules.default_list[0][1]
+		);
 		for command in [
 			ModuleCommand::Unload,
+			ModuleCommand::ForceUnload,
 			ModuleCommand::Blacklist,
 			ModuleCommand::None,
 		] {
 			kernel_modules.set_current_command(command, String::new());
 			assert_eq!(!command.is_none(), kernel_modules.cancel_execution());
 		}
+		let mut kernel_modules = KernelModules::with_source(
+			ListArgs::new(&config),
+			CommandArgs::new(&config),
+			Style::new(&config),
+			DataSource::new(MockSource {
+				modules: parse_proc_modules("loop 3000 0 - Live 0x0\n"),
+				module_info: BTreeMap::from([(
+					String::from("loop"),
+					String::from("description: Loopback device support\n"),
+				)]),
+				holders: BTreeMap::from([(
+					String::from("loop"),
+					vec![String::from("squashfs")],
+				)]),
+				..MockSource::default()
+			}),
+		);
+		assert_eq!(" loop", kernel_modules.default_list[0][0]);
+		assert_eq!("loop", kernel_modules.current_name);
+		assert!(kernel_modules
+			.current_info
+			.raw_text
+			.contains("Loopback device support"));
+		assert!(kernel_modules
+			.current_info
+			.raw_text
+			.contains("holders:        squashfs\n"));
+		assert_eq!(
+			Some(vec![String::from("squashfs")]),
+			kernel_modules.get_dependent_modules("loop")
+		);
+		assert_eq!(None, kernel_modules.get_dependent_modules("ext4"));
+		let report = kernel_modules.module_report().unwrap();
+		assert!(report.starts_with("### loop\n\n- **Version:** `-`\n"));
+		assert_eq!(read_report(&*kernel_modules.source, "loop"), Some(report));
+		kernel_modules.cmd_args.confirm.protected = vec![String::from("loop")];
+		kernel_modules.set_current_command(ModuleCommand::Unload, String::new());
+		assert_eq!(
+			Some(String::from("loop")),
+			kernel_modules.protected_module(false)
+		);
+		assert_eq!(3, kernel_modules.protected_warning().len());
+		assert!(kernel_modules.cancel_execution());
+		kernel_modules.set_current_command(ModuleCommand::Blacklist, String::new());
+		assert_eq!(None, kernel_modules.protected_module(false));
+		assert_eq!(
+			Some(String::from("ext4")),
+			parse_root_fs_type(
+				"rootfs / rootfs rw 0 0\n/dev/sda1 / ext4 rw 0 0\n\
+				proc /proc proc rw 0 0\n"
+			)
+		);
+		assert_eq!(None, parse_root_fs_type("proc /proc proc rw 0 0\n"));
 	}
 }
@@ -1,29 +1,356 @@
 use crate::app::ScrollDirection;
+use crate::config::Config;
+use crate::event::Event;
+use crate::kernel::source::DataSource;
 use crate::util;
-use std::fmt::Write as _;
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::{Display, Formatter, Write as _};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Read};
+use std::ops::Range;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use termion::event::Key;
+
+/* Device of the kernel log buffer */
+const KMSG_DEVICE: &str = "/dev/kmsg";
+
+/* Duration of highlighting the lines that are new since the last refresh */
+const NEW_LINES_DURATION: Duration = Duration::from_secs(3);
+
+/* Uptime of the system in procfs */
+const PROC_UPTIME: &str = "/proc/uptime";
+
+/* Sources of the kernel activities */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogSource {
+	#[default]
+	Dmesg,
+	Kmsg,
+	Journal,
+}
+
+impl TryFrom<&str> for LogSource {
+	type Error = ();
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		match s {
+			"dmesg" => Ok(Self::Dmesg),
+			"kmsg" => Ok(Self::Kmsg),
+			"journal" => Ok(Self::Journal),
+			_ => Err(()),
+		}
+	}
+}
+
+impl LogSource {
+	/**
+	 * Get the executable that is used for reading the logs.
+	 *
+	 * @return executable (Option)
+	 */
+	pub fn executable(self) -> Option<&'static str> {
+		match self {
+			Self::Dmesg => Some("dmesg"),
+			Self::Kmsg => None,
+			Self::Journal => Some("journalctl"),
+		}
+	}
+}
+
+/* Formats of the timestamps of the kernel activities */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogTime {
+	Raw,
+	Iso,
+	Relative,
+}
+
+impl TryFrom<&str> for LogTime {
+	type Error = ();
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		match s {
+			"raw" => Ok(Self::Raw),
+			"iso" => Ok(Self::Iso),
+			"relative" => Ok(Self::Relative),
+			_ => Err(()),
+		}
+	}
+}
+
+/**
+ * Get the boot time of the system using '/proc/uptime'.
+ *
+ * @param  now
+ * @return SystemTime (Option)
+ */
+fn boot_time(now: SystemTime) -> Option<SystemTime> {
+	let uptime = util::read_file(PROC_UPTIME).ok()?;
+	let uptime = uptime.split_whitespace().next()?.parse::<f64>().ok()?;
+	now.checked_sub(Duration::try_from_secs_f64(uptime).ok()?)
+}
+
+/**
+ * Reformat the leading timestamp (seconds since boot) of the given log line.
+ *
+ * Lines without a timestamp are returned unchanged.
+ *
+ * @param  line
+ * @param  time_format
+ * @param  boot_time
+ * @param  now
+ * @return String
+ */
+pub fn format_timestamp(
+	line: &str,
+	time_format: LogTime,
+	boot_time: SystemTime,
+	now: SystemTime,
+) -> String {
+	let (_, message) = LogLevel::parse(line);
+	let prefix = &line[..line.len() - message.len()];
+	let Some((time, rest)) = message
+		.strip_prefix('[')
+		.and_then(|v| v.split_once(']'))
+		.and_then(|(timestamp, rest)| {
+			let seconds = timestamp.trim().parse::<f64>().ok()?;
+			Some((boot_time + Duration::try_from_secs_f64(seconds).ok()?, rest))
+		})
+	else {
+		return line.to_string();
+	};
+	let timestamp = match time_format {
+		LogTime::Raw => return line.to_string(),
+		LogTime::Iso => util::format_utc_time(
+			time.duration_since(UNIX_EPOCH)
+				.map(|v| v.as_secs())
+				.unwrap_or_default(),
+		),
+		LogTime::Relative => format!(
+			"{} ago",
+			util::format_duration(now.duration_since(time).unwrap_or_default())
+		),
+	};
+	format!("{prefix}[{timestamp}]{rest}")
+}
+
+/**
+ * Format a log entry with its raw priority and timestamp in microseconds.
+ *
+ * @param  priority
+ * @param  timestamp
+ * @param  message
+ * @return String
+ */
+fn format_entry(priority: u32, timestamp: u64, message: &str) -> String {
+	format!(
+		"<{priority}>[{:>5}.{:06}] {message}",
+		timestamp / 1_000_000,
+		timestamp % 1_000_000
+	)
+}
+
+/**
+ * Parse a record of '/dev/kmsg' (e.g. "6,339,5140900,-;message").
+ *
+ * Records of the other facilities than kernel are skipped.
+ *
+ * @param  record
+ * @return entry (Option)
+ */
+pub fn parse_kmsg_record(record: &str) -> Option<String> {
+	let (prefix, message) = record.split_once(';')?;
+	let mut fields = prefix.split(',');
+	let priority = fields.next()?.parse::<u32>().ok()?;
+	let timestamp = fields.nth(1)?.parse::<u64>().ok()?;
+	(priority >> 3 == 0).then(|| {
+		format_entry(
+			priority,
+			timestamp,
+			message.lines().next().unwrap_or_default(),
+		)
+	})
+}
+
+/**
+ * Parse an entry of the JSON output of 'journalctl'.
+ *
+ * @param  line
+ * @return entry (Option)
+ */
+pub fn parse_journal_entry(line: &str) -> Option<String> {
+	let entry = serde_json::from_str::<Value>(line).ok()?;
+	let field = |name: &str| entry.get(name).and_then(Value::as_str);
+	Some(format_entry(
+		field("PRIORITY")?.parse().ok()?,
+		field("_SOURCE_MONOTONIC_TIMESTAMP")
+			.or_else(|| field("__MONOTONIC_TIMESTAMP"))
+			.and_then(|v| v.parse().ok())
+			.unwrap_or_default(),
+		field("MESSAGE")?,
+	))
+}
+
+/* Log levels (priorities) of the kernel messages */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Sequence)]
+pub enum LogLevel {
+	Emerg,
+	Alert,
+	Crit,
+	Err,
+	Warn,
+	Notice,
+	Info,
+	#[default]
+	Debug,
+}
+
+impl LogLevel {
+	/**
+	 * Get the log level from its numeric priority.
+	 *
+	 * @param  priority
+	 * @return LogLevel (Option)
+	 */
+	fn from_priority(priority: u8) -> Option<Self> {
+		enum_iterator::all::<Self>().nth(usize::from(priority))
+	}
+
+	/**
+	 * Parse the level of the given log line and strip its prefix.
+	 *
+	 * Supports the decoded prefix of 'dmesg --decode' (e.g. "kern  :info  : ")
+	 * and the raw priority prefix (e.g. "<6>") in which the facility is
+	 * also encoded.
+	 *
+	 * @param  line
+	 * @return (LogLevel (Option), message)
+	 */
+	pub fn parse(line: &str) -> (Option<Self>, &str) {
+		if let Some((priority, message)) =
+			line.strip_prefix('<').and_then(|v| v.split_once('>'))
+		{
+			if let Ok(priority) = priority.parse::<u32>() {
+				return (Self::from_priority((priority & 7) as u8), message);
+			}
+		}
+		let mut fields = line.splitn(3, ':');
+		if let (Some(facility), Some(level), Some(message)) =
+			(fields.next(), fields.next(), fields.next())
+		{
+			let facility = facility.trim_end();
+			if facility.is_empty()
+				|| !facility.chars().all(|c| c.is_ascii_lowercase())
+			{
+				return (None, line);
+			}
+			if let Some(level) = enum_iterator::all::<Self>()
+				.find(|v| v.to_string() == level.trim_end())
+			{
+				return (Some(level), message.strip_prefix(' ').unwrap_or(message));
+			}
+		}
+		(None, line)
+	}
+}
+
+impl Display for LogLevel {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Emerg => "emerg",
+				Self::Alert => "alert",
+				Self::Crit => "crit",
+				Self::Err => "err",
+				Self::Warn => "warn",
+				Self::Notice => "notice",
+				Self::Info => "info",
+				Self::Debug => "debug",
+			}
+		)
+	}
+}
 
 /* Kernel activity logs */
 #[derive(Clone, Debug, Default)]
 pub struct KernelLogs {
 	pub output: String,
+	pub level: LogLevel,
+	pub query: String,
+	pub case_sensitive: bool,
 	pub selected_output: String,
+	pub selected_levels: Vec<Option<LogLevel>>,
+	pub selected_new: Range<usize>,
+	new_lines: Option<(usize, Instant)>,
 	last_line: String,
 	crop_offset: usize,
 	pub index: usize,
+	pub follow: bool,
+	pub reverse: bool,
+	source: LogSource,
+	max_lines: Option<usize>,
+	time_format: Option<LogTime>,
+	disabled: bool,
+	data_source: DataSource,
 }
 
 impl KernelLogs {
 	/**
-	 * Update the output variable value if 'dmesg' logs changed.
+	 * Create a new kernel logs instance from the given configuration.
+	 *
+	 * @param  config
+	 * @return KernelLogs
+	 */
+	pub fn new(config: &Config) -> Self {
+		Self {
+			source: config.kernel_log,
+			max_lines: config.log_lines,
+			time_format: config.log_time,
+			disabled: config.no_log,
+			..Self::default()
+		}
+	}
+
+	/**
+	 * Create a new kernel logs instance that reads from the given source.
+	 *
+	 * @param  config
+	 * @param  source
+	 * @return KernelLogs
+	 */
+	pub fn with_source(config: &Config, source: DataSource) -> Self {
+		Self {
+			data_source: source,
+			..Self::new(config)
+		}
+	}
+
+	/**
+	 * Update the output variable value if the logs changed.
+	 *
+	 * Only the most recent lines are kept if the line limit is set.
+	 * Logs are not read if the kernel activities are disabled.
 	 *
 	 * @return logs_updated
 	 */
 	pub fn update(&mut self) -> bool {
-		self.output = util::exec_cmd(
-			"dmesg",
-			&["--kernel", "--human", "--ctime", "--color=never"],
-		)
-		.unwrap_or_else(|_| String::from("failed to retrieve dmesg output"));
+		if self.disabled {
+			return false;
+		}
+		self.output = self
+			.data_source
+			.kernel_log(self.source, self.time_format.is_none());
+		if let Some(max_lines) = self.max_lines {
+			self.output = Self::limit_lines(&self.output, max_lines);
+		}
 		let logs_updated =
 			self.output.lines().next_back().unwrap_or_default() != self.last_line;
 		self.last_line = self
@@ -32,68 +359,493 @@ impl KernelLogs {
 			.next_back()
 			.unwrap_or_default()
 			.to_string();
+		/* Timestamps are reformatted after comparing the last lines. */
+		let now = SystemTime::now();
+		if let (Some(time_format), Some(boot_time)) = (
+			self.time_format.filter(|v| *v != LogTime::Raw),
+			boot_time(now),
+		) {
+			self.output = self.output.lines().fold(String::new(), |mut s, line| {
+				let _ = writeln!(
+					s,
+					"{}",
+					format_timestamp(line, time_format, boot_time, now)
+				);
+				s
+			});
+		}
 		logs_updated
 	}
 
-	/* Refresh the kernel logs. */
-	pub fn refresh(&mut self) {
+	/**
+	 * Read the kernel logs using 'dmesg'.
+	 *
+	 * Timestamps are printed in seconds since boot unless the human-readable
+	 * format is requested.
+	 *
+	 * @param  human
+	 * @return output
+	 */
+	pub fn read_dmesg(human: bool) -> String {
+		util::exec_cmd(
+			"dmesg",
+			if human {
+				&[
+					"--kernel",
+					"--human",
+					"--ctime",
+					"--decode",
+					"--color=never",
+				]
+			} else {
+				&["--kernel", "--decode", "--color=never"]
+			},
+		)
+		.unwrap_or_else(|e| {
+			if e.is_not_found() {
+				e.to_string()
+			} else {
+				String::from("failed to retrieve dmesg output")
+			}
+		})
+	}
+
+	/**
+	 * Read the kernel logs from '/dev/kmsg'.
+	 *
+	 * Each read returns a single record until the end of the buffer.
+	 *
+	 * @return output
+	 */
+	pub fn read_kmsg() -> String {
+		let mut file = match OpenOptions::new()
+			.read(true)
+			.custom_flags(libc::O_NONBLOCK)
+			.open(KMSG_DEVICE)
+		{
+			Ok(v) => v,
+			Err(e) => return format!("failed to read {KMSG_DEVICE}: {e}"),
+		};
+		let mut output = String::new();
+		let mut buffer = vec![0; 8192];
+		loop {
+			match file.read(&mut buffer) {
+				Ok(0) => break,
+				Ok(len) => {
+					if let Some(entry) =
+						parse_kmsg_record(&String::from_utf8_lossy(&buffer[..len]))
+					{
+						let _ = writeln!(output, "{entry}");
+					}
+				}
+				/* Records that are overwritten while reading. */
+				Err(e) if e.kind() == ErrorKind::BrokenPipe => continue,
+				Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+				Err(e) => return format!("failed to read {KMSG_DEVICE}: {e}"),
+			}
+		}
+		output
+	}
+
+	/**
+	 * Read the kernel logs of the current boot using 'journalctl'.
+	 *
+	 * @return output
+	 */
+	pub fn read_journal() -> String {
+		match util::exec_cmd(
+			"journalctl",
+			&["--dmesg", "--boot", "--no-pager", "--output=json"],
+		) {
+			Ok(output) => output.lines().filter_map(parse_journal_entry).fold(
+				String::new(),
+				|mut s, entry| {
+					let _ = writeln!(s, "{entry}");
+					s
+				},
+			),
+			Err(e) if e.is_not_found() => e.to_string(),
+			Err(_) => String::from("failed to retrieve journalctl output"),
+		}
+	}
+
+	/**
+	 * Keep the given number of lines from the end of the output.
+	 *
+	 * @param  output
+	 * @param  max_lines
+	 * @return String
+	 */
+	fn limit_lines(output: &str, max_lines: usize) -> String {
+		let lines = output.lines().collect::<Vec<&str>>();
+		lines[lines.len().saturating_sub(max_lines)..].iter().fold(
+			String::new(),
+			|mut s, line| {
+				let _ = writeln!(s, "{line}");
+				s
+			},
+		)
+	}
+
+	/**
+	 * Set the output and scroll to the newest line in follow mode.
+	 *
+	 * @param output
+	 */
+	pub fn set_output(&mut self, output: String) {
+		/* Count the lines after the last line of the previous output. */
+		let new_lines = self.output.lines().next_back().and_then(|last_line| {
+			let lines = output.lines().collect::<Vec<&str>>();
+			lines
+				.iter()
+				.rposition(|v| *v == last_line)
+				.map(|i| lines.len() - i - 1)
+		});
+		self.output = output;
+		match new_lines {
+			Some(0) => {}
+			Some(count) => {
+				self.new_lines = Some((count, Instant::now()));
+				/* Keep the scrolled lines in place unless the newest line is visible. */
+				if !self.follow && self.index != 0 {
+					self.index += self.new_line_count();
+				}
+			}
+			/* Previous lines are not found if the ring buffer is cleared. */
+			None => self.new_lines = None,
+		}
+		if self.follow {
+			self.index = 0;
+		}
+	}
+
+	/**
+	 * Check if the new lines since the last refresh are still highlighted.
+	 *
+	 * @return bool
+	 */
+	pub fn is_highlighting(&self) -> bool {
+		self.new_lines
+			.is_some_and(|(_, time)| time.elapsed() < NEW_LINES_DURATION)
+	}
+
+	/**
+	 * Get the number of visible lines that are new since the last refresh.
+	 *
+	 * New lines are counted until the highlight duration is passed.
+	 *
+	 * @return usize
+	 */
+	pub fn new_line_count(&self) -> usize {
+		match self.new_lines {
+			Some((count, time)) if time.elapsed() < NEW_LINES_DURATION => {
+				let line_count = self.output.lines().count();
+				self.output
+					.lines()
+					.skip(line_count.saturating_sub(count))
+					.map(LogLevel::parse)
+					.filter(|(level, _)| self.is_visible(*level))
+					.count()
+			}
+			_ => 0,
+		}
+	}
+
+	/* Toggle the follow mode that keeps the newest line visible. */
+	pub fn toggle_follow(&mut self) {
+		self.follow = !self.follow;
+		if self.follow {
+			self.index = 0;
+		}
+	}
+
+	/* Toggle showing the newest lines first (at the top of the block). */
+	pub fn toggle_reverse(&mut self) {
+		self.reverse = !self.reverse;
+	}
+
+	/**
+	 * Refresh the kernel logs in the background.
+	 *
+	 * @param tx
+	 */
+	pub fn refresh(&mut self, tx: &Sender<Event<Key>>) {
+		if self.disabled {
+			return;
+		}
 		self.last_line = String::new();
 		self.index = 0;
 		self.crop_offset = 0;
-		self.update();
+		let tx = tx.clone();
+		let mut kernel_logs = self.clone();
+		thread::spawn(move || {
+			kernel_logs.update();
+			tx.send(Event::Kernel(kernel_logs.output))
+				.unwrap_or_default();
+		});
+	}
+
+	/**
+	 * Get the log lines and their levels that are visible with the minimum log level.
+	 *
+	 * Prefixes of the log levels are stripped and the lines without
+	 * a known level are always shown.
+	 *
+	 * @return Iterator
+	 */
+	fn entries(&self) -> impl Iterator<Item = (Option<LogLevel>, &str)> {
+		self.output
+			.lines()
+			.map(LogLevel::parse)
+			.filter(|(level, _)| self.is_visible(*level))
+	}
+
+	/**
+	 * Check if the lines with the given level are visible with the minimum log level.
+	 *
+	 * @param  level
+	 * @return bool
+	 */
+	fn is_visible(&self, level: Option<LogLevel>) -> bool {
+		level.is_none_or(|v| v <= self.level)
+	}
+
+	/**
+	 * Get the log lines that are visible with the minimum log level.
+	 *
+	 * @return Iterator
+	 */
+	fn lines(&self) -> impl Iterator<Item = &str> {
+		self.entries().map(|(_, line)| line)
+	}
+
+	/**
+	 * Check if the given line matches the search query.
+	 *
+	 * @param  line
+	 * @return bool
+	 */
+	pub fn is_match(&self, line: &str) -> bool {
+		if self.query.is_empty() {
+			false
+		} else if self.case_sensitive {
+			line.contains(&self.query)
+		} else {
+			line.to_lowercase().contains(&self.query.to_lowercase())
+		}
+	}
+
+	/**
+	 * Get the number of the visible lines that match the search query.
+	 *
+	 * @return usize
+	 */
+	pub fn match_count(&self) -> usize {
+		self.lines().filter(|line| self.is_match(line)).count()
+	}
+
+	/**
+	 * Set the search query and scroll to the most recent match.
+	 *
+	 * @param query
+	 * @param case_sensitive
+	 */
+	pub fn set_query(&mut self, query: &str, case_sensitive: bool) {
+		self.query = query.to_string();
+		self.case_sensitive = case_sensitive;
+		self.index = 0;
+		if !self.lines().last().is_some_and(|line| self.is_match(line)) {
+			self.find_match(false);
+		}
+	}
+
+	/**
+	 * Scroll to the next/previous line that matches the search query.
+	 *
+	 * The search starts from the newest visible line of the block and wraps
+	 * around. Directions follow the order of the lines on the block.
+	 *
+	 * @param forward
+	 */
+	pub fn find_match(&mut self, forward: bool) {
+		let forward = forward != self.reverse;
+		let matches = self
+			.lines()
+			.enumerate()
+			.filter(|(_, line)| self.is_match(line))
+			.map(|(i, _)| i)
+			.collect::<Vec<usize>>();
+		let line_count = self.lines().count();
+		let current = line_count.saturating_sub(self.index + 1);
+		let position = if forward {
+			matches
+				.iter()
+				.find(|i| **i > current)
+				.or_else(|| matches.first())
+		} else {
+			matches
+				.iter()
+				.rev()
+				.find(|i| **i < current)
+				.or_else(|| matches.last())
+		};
+		if let Some(position) = position {
+			self.index = line_count - position - 1;
+			self.follow = false;
+		}
+	}
+
+	/**
+	 * Save the visible log lines to the given file.
+	 *
+	 * A timestamped file is created in the current directory
+	 * if the path is not specified.
+	 *
+	 * @param  path
+	 * @return Result
+	 */
+	pub fn save(&self, path: Option<&Path>) -> Result<PathBuf, String> {
+		let path = match path {
+			Some(v) => v.to_path_buf(),
+			None => PathBuf::from(format!(
+				"kmon-dmesg-{}.log",
+				SystemTime::now()
+					.duration_since(UNIX_EPOCH)
+					.map(|v| v.as_secs())
+					.unwrap_or_default()
+			)),
+		};
+		let contents = self.lines().fold(String::new(), |mut s, line| {
+			let _ = writeln!(s, "{line}");
+			s
+		});
+		fs::write(&path, contents).map_err(|e| {
+			format!("failed to save the kernel logs ({}): {e}", path.display())
+		})?;
+		Ok(path)
+	}
+
+	/* Cycle the minimum log level of the visible lines. */
+	pub fn next_level(&mut self) {
+		self.level = self.level.previous().unwrap_or(LogLevel::Debug);
+		self.index = 0;
 	}
 
 	/**
 	 * Select a part of the output depending on the area properties.
 	 *
+	 * Levels of the selected lines are also updated for styling them.
+	 * Visible lines are reversed after filtering them if the newest
+	 * lines are shown first.
+	 *
 	 * @param  area_height
 	 * @param  area_sub
 	 * @return selected_output
 	 */
 	pub fn select(&mut self, area_height: u16, area_sub: u16) -> &str {
-		self.selected_output = self
-			.output
-			.lines()
-			.map(|line| match line.char_indices().nth(self.crop_offset) {
-				Some((pos, _)) => &line[pos..],
-				None => "",
-			})
-			.skip(
-				area_height
-					.checked_sub(area_sub)
-					.and_then(|height| {
-						(self.output.lines().count() - self.index)
-							.checked_sub(height as usize)
-					})
-					.unwrap_or(0),
-			)
-			.fold(String::new(), |mut s, i| {
-				let _ = writeln!(s, "{i}");
+		let entries = if self.reverse {
+			self.selected_new = 0..self.new_line_count().saturating_sub(self.index);
+			let mut entries =
+				self.entries().collect::<Vec<(Option<LogLevel>, &str)>>();
+			entries.reverse();
+			entries
+				.into_iter()
+				.skip(self.index)
+				.take(area_height.saturating_sub(area_sub).into())
+				.map(|(level, line)| (level, self.crop_line(line)))
+				.collect::<Vec<(Option<LogLevel>, &str)>>()
+		} else {
+			let line_count = self.lines().count();
+			let skipped_lines = area_height
+				.checked_sub(area_sub)
+				.and_then(|height| {
+					line_count
+						.saturating_sub(self.index)
+						.checked_sub(height as usize)
+				})
+				.unwrap_or(0);
+			self.selected_new = (line_count - self.new_line_count())
+				.saturating_sub(skipped_lines)..usize::MAX;
+			self.entries()
+				.map(|(level, line)| (level, self.crop_line(line)))
+				.skip(skipped_lines)
+				.collect::<Vec<(Option<LogLevel>, &str)>>()
+		};
+		let selected_levels = entries.iter().map(|(level, _)| *level).collect();
+		let selected_output =
+			entries.iter().fold(String::new(), |mut s, (_, line)| {
+				let _ = writeln!(s, "{line}");
 				s
 			});
+		self.selected_levels = selected_levels;
+		self.selected_output = selected_output;
 		&self.selected_output
 	}
 
+	/**
+	 * Get the part of the line that is visible with the horizontal scroll.
+	 *
+	 * @param  line
+	 * @return str
+	 */
+	fn crop_line<'a>(&self, line: &'a str) -> &'a str {
+		match line.char_indices().nth(self.crop_offset) {
+			Some((pos, _)) => &line[pos..],
+			None => "",
+		}
+	}
+
+	/**
+	 * Get the line number of the newest visible line and the total line count.
+	 *
+	 * Lines are counted from the newest one if they are shown first.
+	 *
+	 * @return (line, line_count)
+	 */
+	pub fn scroll_position(&self) -> (usize, usize) {
+		let line_count = self.lines().count();
+		if self.reverse {
+			((self.index + 1).min(line_count), line_count)
+		} else {
+			(line_count.saturating_sub(self.index), line_count)
+		}
+	}
+
 	/**
 	 * Scroll the kernel logs up/down.
 	 *
+	 * Scrolling up disables the follow mode.
+	 *
 	 * @param direction
 	 * @param smooth_scroll
 	 */
 	pub fn scroll(&mut self, direction: ScrollDirection, smooth_scroll: bool) {
-		let scroll_amount = if smooth_scroll { 1 } else { 3 };
+		self.scroll_by(direction, if smooth_scroll { 1 } else { 3 });
+	}
+
+	/**
+	 * Scroll the kernel logs up/down by the given number of lines.
+	 *
+	 * Vertical directions are swapped if the newest lines are shown first.
+	 *
+	 * @param direction
+	 * @param scroll_amount
+	 */
+	pub fn scroll_by(&mut self, direction: ScrollDirection, scroll_amount: usize) {
+		let direction = match direction {
+			ScrollDirection::Up if self.reverse => ScrollDirection::Down,
+			ScrollDirection::Down if self.reverse => ScrollDirection::Up,
+			v => v,
+		};
 		match direction {
-			ScrollDirection::Up => {
-				if self.index + scroll_amount <= self.output.lines().count() {
-					self.index += scroll_amount;
-				}
+			ScrollDirection::Up
+				if self.index + scroll_amount <= self.lines().count() =>
+			{
+				self.index += scroll_amount;
+				self.follow = false;
 			}
 			ScrollDirection::Down => {
-				if self.index > scroll_amount - 1 {
-					self.index -= scroll_amount;
-				} else {
-					self.index = 0;
-				}
+				self.index = self.index.saturating_sub(scroll_amount);
 			}
 			ScrollDirection::Left => {
 				self.crop_offset = self.crop_offset.saturating_sub(10)
@@ -119,5 +871,184 @@ mod tests {
 		assert!(kernel_logs.update());
 		assert_ne!(0, kernel_logs.output.lines().count());
 		assert_ne!(0, kernel_logs.select(10, 2).len());
+		assert_eq!(
+			(
+				Some(LogLevel::Info),
+				"[Mon Jan  1 00:00:00 2024] usb 1-1: new device"
+			),
+			LogLevel::parse(
+				"kern  :info  : [Mon Jan  1 00:00:00 2024] usb 1-1: new device"
+			)
+		);
+		assert_eq!(
+			(Some(LogLevel::Err), "failed"),
+			LogLevel::parse("<3>failed")
+		);
+		assert_eq!((Some(LogLevel::Warn), "x"), LogLevel::parse("<12>x"));
+		assert_eq!(
+			(None, "usb 1-1: new device"),
+			LogLevel::parse("usb 1-1: new device")
+		);
+		kernel_logs = KernelLogs {
+			output: String::from("<3>error\n<7>debug\ncontinued\n"),
+			..Default::default()
+		};
+		assert_eq!(3, kernel_logs.lines().count());
+		assert_eq!(
+			"<7>debug\ncontinued\n",
+			KernelLogs::limit_lines(&kernel_logs.output, 2)
+		);
+		let mut new_logs = kernel_logs.clone();
+		assert_eq!(0, new_logs.new_line_count());
+		new_logs.index = 1;
+		new_logs.set_output(String::from(
+			"<3>error\n<7>debug\ncontinued\n<3>failed\n<7>debug\n",
+		));
+		assert_eq!(2, new_logs.new_line_count());
+		assert_eq!(3, new_logs.index);
+		new_logs.select(10, 2);
+		assert_eq!(3..usize::MAX, new_logs.selected_new);
+		new_logs.index = 0;
+		new_logs.toggle_reverse();
+		assert_eq!("debug\nfailed\ncontinued\n", new_logs.select(5, 2));
+		assert_eq!(0..2, new_logs.selected_new);
+		assert_eq!((1, 5), new_logs.scroll_position());
+		new_logs.scroll_by(ScrollDirection::Down, 1);
+		assert_eq!(1, new_logs.index);
+		assert_eq!("failed\ncontinued\ndebug\n", new_logs.select(5, 2));
+		assert_eq!(0..1, new_logs.selected_new);
+		new_logs.scroll_by(ScrollDirection::Up, 1);
+		assert_eq!(0, new_logs.index);
+		new_logs.toggle_reverse();
+		new_logs.level = LogLevel::Err;
+		assert_eq!(1, new_logs.new_line_count());
+		new_logs.level = LogLevel::default();
+		new_logs.follow = true;
+		new_logs.set_output(String::from("<6>cleared\n"));
+		assert_eq!((0, 0), (new_logs.new_line_count(), new_logs.index));
+		assert_eq!(
+			Some(String::from("<6>[    5.140900] usb 1-1: new device")),
+			parse_kmsg_record(
+				"6,339,5140900,-;usb 1-1: new device\n SUBSYSTEM=usb\n"
+			)
+		);
+		assert_eq!(
+			None,
+			parse_kmsg_record("30,340,5140901,-;systemd[1]: started")
+		);
+		assert_eq!(None, parse_kmsg_record("invalid"));
+		assert_eq!(
+			Some(String::from("<3>[   12.000001] failed")),
+			parse_journal_entry(
+				r#"{"PRIORITY":"3","_SOURCE_MONOTONIC_TIMESTAMP":"12000001","MESSAGE":"failed"}"#
+			)
+		);
+		assert_eq!(None, parse_journal_entry(r#"{"MESSAGE":[1,2]}"#));
+		let now = UNIX_EPOCH + Duration::from_secs(951827445);
+		let boot = now - Duration::from_secs(3600);
+		assert_eq!(
+			"kern  :info  : [2000-02-29T11:30:50Z] usb 1-1: new device",
+			format_timestamp(
+				"kern  :info  : [    5.140900] usb 1-1: new device",
+				LogTime::Iso,
+				boot,
+				now
+			)
+		);
+		assert_eq!(
+			"<3>[~59m ago] failed",
+			format_timestamp(
+				"<3>[   60.000000] failed",
+				LogTime::Relative,
+				boot,
+				now
+			)
+		);
+		for line in ["<3>[   60.000000] failed", "<6>no timestamp", "[x] y", ""] {
+			assert_eq!(line, format_timestamp(line, LogTime::Raw, boot, now));
+			if !line.contains("60") {
+				assert_eq!(line, format_timestamp(line, LogTime::Iso, boot, now));
+			}
+		}
+		assert_eq!(Ok(LogTime::Relative), LogTime::try_from("relative"));
+		assert!(LogTime::try_from("utc").is_err());
+		assert_eq!(
+			(Some(LogLevel::Warn), "[    0.000000] x"),
+			LogLevel::parse(&format_entry(4, 0, "x"))
+		);
+		let mut kmsg_logs = KernelLogs::new(&Config {
+			kernel_log: LogSource::Kmsg,
+			log_time: Some(LogTime::Raw),
+			..Config::default()
+		});
+		assert_eq!(LogSource::Kmsg, kmsg_logs.source);
+		kmsg_logs.update();
+		assert!(
+			kmsg_logs
+				.output
+				.starts_with(&format!("failed to read {KMSG_DEVICE}"))
+				|| (!kmsg_logs.output.is_empty()
+					&& kmsg_logs
+						.output
+						.lines()
+						.all(|line| LogLevel::parse(line).0.is_some()))
+		);
+		assert!(!KernelLogs::new(&Config {
+			no_log: true,
+			..Config::default()
+		})
+		.update());
+		assert_eq!("error\ndebug\ncontinued\n", kernel_logs.select(10, 2));
+		assert_eq!(
+			vec![Some(LogLevel::Err), Some(LogLevel::Debug), None],
+			kernel_logs.selected_levels
+		);
+		kernel_logs.next_level();
+		assert_eq!(LogLevel::Info, kernel_logs.level);
+		assert_eq!(
+			vec!["error", "continued"],
+			kernel_logs.lines().collect::<Vec<&str>>()
+		);
+		for _ in 0..7 {
+			kernel_logs.next_level();
+		}
+		assert_eq!(LogLevel::Debug, kernel_logs.level);
+		kernel_logs.output = String::from("usb 1\nerror\nUSB 2\nother\n");
+		kernel_logs.set_query("usb", false);
+		assert_eq!(2, kernel_logs.match_count());
+		assert_eq!(1, kernel_logs.index);
+		kernel_logs.find_match(false);
+		assert_eq!(3, kernel_logs.index);
+		kernel_logs.find_match(false);
+		assert_eq!(1, kernel_logs.index);
+		kernel_logs.find_match(true);
+		assert_eq!(3, kernel_logs.index);
+		assert_eq!((1, 4), kernel_logs.scroll_position());
+		kernel_logs.toggle_follow();
+		assert!(kernel_logs.follow);
+		assert_eq!(0, kernel_logs.index);
+		kernel_logs.scroll(ScrollDirection::Up, true);
+		assert!(!kernel_logs.follow);
+		kernel_logs.scroll_by(ScrollDirection::Down, 10);
+		assert_eq!(0, kernel_logs.index);
+		kernel_logs.scroll_by(ScrollDirection::Up, 10);
+		assert_eq!(0, kernel_logs.index);
+		kernel_logs.follow = true;
+		kernel_logs.set_output(String::from("usb 1\nerror\nUSB 2\nother\n"));
+		assert_eq!((4, 4), kernel_logs.scroll_position());
+		kernel_logs.find_match(true);
+		assert!(!kernel_logs.follow);
+		kernel_logs.set_query("usb", true);
+		assert_eq!(1, kernel_logs.match_count());
+		let path = std::env::temp_dir().join("kmon-test-dmesg.log");
+		assert_eq!(Ok(path.clone()), kernel_logs.save(Some(&path)));
+		assert_eq!(
+			"usb 1\nerror\nUSB 2\nother\n",
+			fs::read_to_string(&path).unwrap()
+		);
+		fs::remove_file(path).unwrap();
+		assert!(kernel_logs
+			.save(Some(Path::new("/nonexistent/kmon.log")))
+			.is_err());
 	}
 }
@@ -1,12 +1,26 @@
+pub mod blacklist;
+pub mod boot;
+#[cfg(any(target_os = "freebsd", test))]
+pub mod bsd;
+pub mod cache;
 pub mod cmd;
+pub mod demo;
+pub mod deps;
 pub mod info;
 pub mod lkm;
 pub mod log;
+pub mod snapshot;
+pub mod source;
+use crate::config::{Config, RefreshTarget};
+use crate::event::Event;
 use crate::style::Style;
-use clap::ArgMatches;
+use cmd::CommandArgs;
 use info::KernelInfo;
 use lkm::{KernelModules, ListArgs};
 use log::KernelLogs;
+use source::DataSource;
+use std::sync::mpsc::Sender;
+use termion::event::Key;
 
 /* Kernel struct for logs, information and modules */
 pub struct Kernel {
@@ -19,21 +33,68 @@ impl Kernel {
 	/**
 	 * Create a new kernel instance.
 	 *
-	 * @param  ArgMatches
+	 * @param  Config
 	 * @return Kernel
 	 */
-	pub fn new(args: &ArgMatches) -> Self {
+	pub fn new(config: &Config) -> Self {
+		Self::with_source(config, DataSource::default())
+	}
+
+	/**
+	 * Create a new kernel instance that reads from the given source.
+	 *
+	 * @param  Config
+	 * @param  DataSource
+	 * @return Kernel
+	 */
+	pub fn with_source(config: &Config, source: DataSource) -> Self {
+		let mut modules = KernelModules::with_source(
+			ListArgs {
+				builtin: config.builtin,
+				..ListArgs::new(config)
+			},
+			CommandArgs::new(config),
+			Style::new(config),
+			source.clone(),
+		);
+		modules.set_groups(&config.groups);
 		Self {
-			logs: KernelLogs::default(),
-			info: KernelInfo::default(),
-			modules: KernelModules::new(ListArgs::new(args), Style::new(args)),
+			logs: KernelLogs::with_source(config, source.clone()),
+			info: KernelInfo::with_source(source),
+			modules,
 		}
 	}
 
-	/* Refresh kernel logs, modules and information. */
-	pub fn refresh(&mut self) {
-		self.logs.refresh();
+	/**
+	 * Refresh kernel logs, modules and information.
+	 *
+	 * Logs and modules are read in the background and sent as events.
+	 *
+	 * @param tx
+	 */
+	pub fn refresh(&mut self, tx: &Sender<Event<Key>>) {
+		self.logs.refresh(tx);
 		self.info.refresh();
-		let _ = self.modules.refresh();
+		self.modules.refresh_async(tx);
+	}
+
+	/**
+	 * Refresh the given data after executing a module command.
+	 *
+	 * @param targets
+	 * @param tx
+	 */
+	pub fn refresh_after_op(
+		&mut self,
+		targets: &[RefreshTarget],
+		tx: &Sender<Event<Key>>,
+	) {
+		for target in targets {
+			match target {
+				RefreshTarget::Modules => self.modules.refresh_async(tx),
+				RefreshTarget::Logs => self.logs.refresh(tx),
+				RefreshTarget::Info => self.info.refresh(),
+			}
+		}
 	}
 }
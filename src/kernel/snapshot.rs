@@ -0,0 +1,221 @@
+use crate::kernel::lkm::Module;
+use crate::kernel::source::ModuleSource;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/* Header line of the snapshot files */
+const SNAPSHOT_HEADER: &str = "# kmon module snapshot";
+
+/* Placeholder of the modules without a version */
+const NO_VERSION: &str = "-";
+
+/* Differences between two snapshots */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+	pub added: Vec<(String, String)>,
+	pub removed: Vec<(String, String)>,
+	pub changed: Vec<(String, String, String)>,
+}
+
+impl SnapshotDiff {
+	/**
+	 * Check if there are no differences.
+	 *
+	 * @return bool
+	 */
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+	}
+}
+
+/* Loaded kernel modules and their versions */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Snapshot {
+	pub modules: BTreeMap<String, String>,
+}
+
+impl Snapshot {
+	/**
+	 * Create a snapshot of the given modules using their versions from the source.
+	 *
+	 * @param  source
+	 * @param  modules
+	 * @return Snapshot
+	 */
+	pub fn capture(source: &dyn ModuleSource, modules: &[Module]) -> Self {
+		Self {
+			modules: modules
+				.iter()
+				.map(|module| {
+					let version = source
+						.module_field(&module.name, "version")
+						.ok()
+						.map(|v| v.trim().to_string())
+						.filter(|v| !v.is_empty())
+						.unwrap_or_else(|| String::from(NO_VERSION));
+					(module.name.to_string(), version)
+				})
+				.collect(),
+		}
+	}
+
+	/**
+	 * Parse the snapshot from the contents of a snapshot file.
+	 *
+	 * Empty lines and the lines that start with '#' are ignored.
+	 *
+	 * @param  contents
+	 * @return Result
+	 */
+	pub fn parse(contents: &str) -> Result<Self, String> {
+		let mut modules = BTreeMap::new();
+		for (i, line) in contents.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			match line.split_whitespace().collect::<Vec<&str>>()[..] {
+				[name, version] => {
+					modules.insert(name.to_string(), version.to_string());
+				}
+				_ => {
+					return Err(format!("invalid snapshot entry at line {}", i + 1))
+				}
+			}
+		}
+		Ok(Self { modules })
+	}
+
+	/**
+	 * Read the snapshot from the given file.
+	 *
+	 * @param  path
+	 * @return Result
+	 */
+	pub fn load(path: &Path) -> Result<Self, String> {
+		fs::read_to_string(path)
+			.map_err(|e| e.to_string())
+			.and_then(|contents| Self::parse(&contents))
+			.map_err(|e| {
+				format!("failed to read the snapshot ({}): {e}", path.display())
+			})
+	}
+
+	/**
+	 * Compare the snapshot with a newer one.
+	 *
+	 * @param  new
+	 * @return SnapshotDiff
+	 */
+	pub fn diff(&self, new: &Self) -> SnapshotDiff {
+		let mut diff = SnapshotDiff::default();
+		for (name, version) in &self.modules {
+			match new.modules.get(name) {
+				Some(new_version) if new_version != version => diff.changed.push((
+					name.to_string(),
+					version.to_string(),
+					new_version.to_string(),
+				)),
+				Some(_) => {}
+				None => diff.removed.push((name.to_string(), version.to_string())),
+			}
+		}
+		for (name, version) in &new.modules {
+			if !self.modules.contains_key(name) {
+				diff.added.push((name.to_string(), version.to_string()));
+			}
+		}
+		diff
+	}
+
+	/**
+	 * Write the snapshot to the given file or 'kmon-snapshot-<timestamp>.txt'
+	 * if the path is not specified.
+	 *
+	 * @param  path
+	 * @return Result
+	 */
+	pub fn save(&self, path: Option<&Path>) -> Result<PathBuf, String> {
+		let path = match path {
+			Some(v) => v.to_path_buf(),
+			None => PathBuf::from(format!(
+				"kmon-snapshot-{}.txt",
+				SystemTime::now()
+					.duration_since(UNIX_EPOCH)
+					.map(|v| v.as_secs())
+					.unwrap_or_default()
+			)),
+		};
+		fs::write(&path, self.to_string()).map_err(|e| {
+			format!("failed to save the snapshot ({}): {e}", path.display())
+		})?;
+		Ok(path)
+	}
+}
+
+/* Implementation of Display for writing the snapshot files */
+impl Display for Snapshot {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "{SNAPSHOT_HEADER}")?;
+		for (name, version) in &self.modules {
+			writeln!(f, "{name} {version}")?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::kernel::source::MockSource;
+	#[test]
+	fn test_snapshot() {
+		let snapshot = Snapshot {
+			modules: BTreeMap::from([
+				(String::from("snd"), String::from(NO_VERSION)),
+				(String::from("e1000e"), String::from("3.2.6-k")),
+			]),
+		};
+		assert_eq!(
+			"# kmon module snapshot\ne1000e 3.2.6-k\nsnd -\n",
+			snapshot.to_string()
+		);
+		assert_eq!(Ok(snapshot.clone()), Snapshot::parse(&snapshot.to_string()));
+		assert!(Snapshot::parse("\n# comment\nsnd 1.0\n").is_ok());
+		assert!(Snapshot::parse("snd\n").is_err());
+		let path = std::env::temp_dir().join("kmon-test-snapshot.txt");
+		assert_eq!(Ok(path.to_path_buf()), snapshot.save(Some(&path)));
+		assert_eq!(Ok(snapshot.clone()), Snapshot::load(&path));
+		fs::remove_file(&path).unwrap();
+		assert!(Snapshot::load(&path).is_err());
+		let new = Snapshot::parse("nouveau 1.0\ne1000e 3.3.0-k\n").unwrap();
+		assert_eq!(
+			SnapshotDiff {
+				added: vec![(String::from("nouveau"), String::from("1.0"))],
+				removed: vec![(String::from("snd"), String::from(NO_VERSION))],
+				changed: vec![(
+					String::from("e1000e"),
+					String::from("3.2.6-k"),
+					String::from("3.3.0-k")
+				)],
+			},
+			snapshot.diff(&new)
+		);
+		assert!(new.diff(&new).is_empty());
+		assert_eq!(
+			Some(&String::from(NO_VERSION)),
+			Snapshot::capture(
+				&MockSource::default(),
+				&[Module {
+					name: String::from("~"),
+					..Module::default()
+				}]
+			)
+			.modules
+			.get("~")
+		);
+	}
+}
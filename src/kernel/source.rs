@@ -0,0 +1,333 @@
+use crate::kernel::info::{self, KernelInfo};
+use crate::kernel::lkm::{self, ListArgs, Module};
+use crate::kernel::log::{KernelLogs, LogSource};
+use crate::util::{self, CmdError};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Debug;
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+
+/* Source of the kernel modules, their information and the kernel logs */
+pub trait ModuleSource: Debug + Send + Sync {
+	/**
+	 * Read the loaded kernel modules using the given listing properties.
+	 *
+	 * @param  args
+	 * @return Result
+	 */
+	fn list_modules(&self, args: &ListArgs) -> Result<Vec<Module>, Box<dyn Error>>;
+
+	/**
+	 * Read the information of a module (the output of 'modinfo').
+	 *
+	 * @param  module_name
+	 * @return Result
+	 */
+	fn module_info(&self, module_name: &str) -> Result<String, CmdError>;
+
+	/**
+	 * Read a field of the module information (the output of 'modinfo -F').
+	 *
+	 * Values of the fields that are given multiple times are separated
+	 * with newlines.
+	 *
+	 * @param  module_name
+	 * @param  field
+	 * @return Result
+	 */
+	fn module_field(
+		&self,
+		module_name: &str,
+		field: &str,
+	) -> Result<String, CmdError> {
+		self.module_info(module_name)
+			.map(|info| lkm::info_field(&info, field).join("\n"))
+	}
+
+	/**
+	 * Read the modules that are holding a reference to the given module.
+	 *
+	 * None is returned if the holders are not available from the source.
+	 *
+	 * @param  module_name
+	 * @return Vec (Option)
+	 */
+	fn holders(&self, module_name: &str) -> Option<Vec<String>>;
+
+	/**
+	 * Read the runtime parameters of the given module and their values.
+	 *
+	 * @param  module_name
+	 * @return Vec
+	 */
+	fn parameters(&self, module_name: &str) -> Vec<(String, Option<String>)>;
+
+	/**
+	 * Read the kernel logs from the given log source.
+	 *
+	 * @param  log_source
+	 * @param  human
+	 * @return String
+	 */
+	fn kernel_log(&self, log_source: LogSource, human: bool) -> String;
+
+	/**
+	 * Read the kernel information (the title and the value of each entry).
+	 *
+	 * @return Vec
+	 */
+	fn kernel_info(&self) -> Vec<Vec<String>>;
+
+	/**
+	 * Read the release of the running kernel.
+	 *
+	 * @return release (Option)
+	 */
+	fn kernel_release(&self) -> Option<String>;
+}
+
+/**
+ * Filter and sort the given modules using the listing properties.
+ *
+ * @param  modules
+ * @param  args
+ * @return Vec
+ */
+pub fn apply_list_args(modules: Vec<Module>, args: &ListArgs) -> Vec<Module> {
+	let mut modules = modules
+		.into_iter()
+		.filter(|module| args.filter.matches(module))
+		.filter(|module| args.size_filter.is_none_or(|v| v.matches(module)))
+		.filter(|module| !(args.hide_used && module.refcount > 0))
+		.collect::<Vec<Module>>();
+	modules.sort_by(|a, b| args.sort.compare(a, b));
+	if args.reverse {
+		modules.reverse();
+	}
+	modules
+}
+
+/* Kernel modules and logs of the running Linux system (or the remote host) */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LocalLinuxSource;
+
+impl ModuleSource for LocalLinuxSource {
+	fn list_modules(&self, args: &ListArgs) -> Result<Vec<Module>, Box<dyn Error>> {
+		lkm::read_modules(args)
+	}
+
+	fn module_info(&self, module_name: &str) -> Result<String, CmdError> {
+		util::exec_cmd("modinfo", &[module_name])
+	}
+
+	fn module_field(
+		&self,
+		module_name: &str,
+		field: &str,
+	) -> Result<String, CmdError> {
+		/* Version of the loaded module is read from sysfs without 'modinfo'. */
+		if field == "version" {
+			if let Some(version) = util::read_file(
+				Path::new(lkm::SYS_MODULE_DIR)
+					.join(module_name)
+					.join("version"),
+			)
+			.ok()
+			.filter(|v| !v.trim().is_empty())
+			{
+				return Ok(version.trim().to_string());
+			}
+		}
+		/* Replacing the invalid characters would change the field silently. */
+		util::exec_cmd_utf8("modinfo", &["-F", field, module_name])
+	}
+
+	fn holders(&self, module_name: &str) -> Option<Vec<String>> {
+		/* Reading a directory for each module is too slow over SSH. */
+		if util::remote().is_some() {
+			return None;
+		}
+		lkm::read_holders(
+			&Path::new(lkm::SYS_MODULE_DIR)
+				.join(module_name)
+				.join("holders"),
+		)
+	}
+
+	fn parameters(&self, module_name: &str) -> Vec<(String, Option<String>)> {
+		lkm::read_parameters(
+			&Path::new(lkm::SYS_MODULE_DIR)
+				.join(module_name)
+				.join("parameters"),
+		)
+	}
+
+	fn kernel_log(&self, log_source: LogSource, human: bool) -> String {
+		match log_source {
+			LogSource::Dmesg => KernelLogs::read_dmesg(human),
+			LogSource::Kmsg => KernelLogs::read_kmsg(),
+			LogSource::Journal => KernelLogs::read_journal(),
+		}
+	}
+
+	fn kernel_info(&self) -> Vec<Vec<String>> {
+		KernelInfo::uname_infos()
+	}
+
+	fn kernel_release(&self) -> Option<String> {
+		info::kernel_release()
+	}
+}
+
+/* Fixed kernel modules and logs for the deterministic tests */
+#[derive(Clone, Debug, Default)]
+pub struct MockSource {
+	pub modules: Vec<Module>,
+	pub module_info: BTreeMap<String, String>,
+	pub kernel_log: String,
+	pub holders: BTreeMap<String, Vec<String>>,
+	pub parameters: BTreeMap<String, Vec<(String, Option<String>)>>,
+	pub kernel_info: Vec<Vec<String>>,
+	pub kernel_release: Option<String>,
+}
+
+impl ModuleSource for MockSource {
+	fn list_modules(&self, args: &ListArgs) -> Result<Vec<Module>, Box<dyn Error>> {
+		Ok(apply_list_args(self.modules.clone(), args))
+	}
+
+	fn module_info(&self, module_name: &str) -> Result<String, CmdError> {
+		self.module_info
+			.get(module_name)
+			.cloned()
+			.ok_or_else(|| CmdError::Status {
+				code: Some(1),
+				stderr: format!("modinfo: ERROR: Module {module_name} not found."),
+			})
+	}
+
+	fn holders(&self, module_name: &str) -> Option<Vec<String>> {
+		self.holders.get(module_name).cloned()
+	}
+
+	fn parameters(&self, module_name: &str) -> Vec<(String, Option<String>)> {
+		self.parameters
+			.get(module_name)
+			.cloned()
+			.unwrap_or_default()
+	}
+
+	fn kernel_log(&self, _: LogSource, _: bool) -> String {
+		self.kernel_log.to_string()
+	}
+
+	fn kernel_info(&self) -> Vec<Vec<String>> {
+		self.kernel_info.clone()
+	}
+
+	fn kernel_release(&self) -> Option<String> {
+		self.kernel_release.clone()
+	}
+}
+
+/* Shared data source that is chosen at startup */
+#[derive(Clone, Debug)]
+pub struct DataSource(Arc<dyn ModuleSource>);
+
+impl DataSource {
+	/**
+	 * Create a new data source.
+	 *
+	 * @param  source
+	 * @return DataSource
+	 */
+	pub fn new(source: impl ModuleSource + 'static) -> Self {
+		Self(Arc::new(source))
+	}
+}
+
+impl Default for DataSource {
+	fn default() -> Self {
+		Self::new(LocalLinuxSource)
+	}
+}
+
+impl Deref for DataSource {
+	type Target = dyn ModuleSource;
+	fn deref(&self) -> &Self::Target {
+		self.0.as_ref()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::Config;
+	use crate::kernel::lkm::{ListFilter, SortType};
+	#[test]
+	fn test_source() {
+		let source = DataSource::new(MockSource {
+			modules: lkm::parse_proc_modules(
+				"ext4 1000 1 - Live 0x0\nloop 3000 0 - Live 0x0\n",
+			),
+			module_info: BTreeMap::from([(
+				String::from("loop"),
+				String::from("license: GPL\n"),
+			)]),
+			kernel_log: String::from("test"),
+			holders: BTreeMap::from([(String::from("ext4"), Vec::new())]),
+			parameters: BTreeMap::from([(
+				String::from("loop"),
+				vec![(String::from("max_loop"), Some(String::from("8")))],
+			)]),
+			kernel_info: vec![vec![
+				String::from("Kernel Release"),
+				String::from("test"),
+			]],
+			kernel_release: Some(String::from("6.9.7")),
+		});
+		let mut args = ListArgs::new(&Config::default());
+		args.sort = SortType::Size;
+		assert_eq!(
+			vec!["loop", "ext4"],
+			source
+				.list_modules(&args)
+				.unwrap()
+				.iter()
+				.map(|module| module.name.as_str())
+				.collect::<Vec<&str>>()
+		);
+		args.filter = ListFilter::Unused;
+		assert_eq!(1, source.list_modules(&args).unwrap().len());
+		assert_eq!(
+			Ok(String::from("license: GPL\n")),
+			source.module_info("loop")
+		);
+		assert!(source.module_info("ext4").is_err());
+		assert_eq!(
+			Ok(String::from("GPL")),
+			source.module_field("loop", "license")
+		);
+		assert_eq!(Ok(String::new()), source.module_field("loop", "depends"));
+		assert!(source.module_field("ext4", "license").is_err());
+		assert_eq!(
+			vec![(String::from("max_loop"), Some(String::from("8")))],
+			source.parameters("loop")
+		);
+		assert!(source.parameters("ext4").is_empty());
+		assert_eq!(Some(Vec::new()), source.holders("ext4"));
+		assert_eq!(None, source.holders("loop"));
+		assert_eq!("test", source.kernel_log(LogSource::Dmesg, false));
+		let mut kernel_info = KernelInfo::with_source(source.clone());
+		assert_eq!("6.9.7", kernel_info.release);
+		kernel_info.next();
+		assert_eq!("test", kernel_info.current_info[1]);
+		let kernel_info =
+			KernelInfo::with_source(DataSource::new(MockSource::default()));
+		assert_eq!(vec!["Kernel Release", "unknown"], kernel_info.current_info);
+		assert_eq!("unknown", kernel_info.release);
+		assert!(format!("{:?}", DataSource::default()).contains("LocalLinuxSource"));
+	}
+}
@@ -0,0 +1,843 @@
+use crate::app::Block;
+use crate::i18n;
+use enum_iterator::{all, Sequence};
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use termion::event::Key;
+
+/* Key that always quits regardless of the key bindings */
+pub const EMERGENCY_QUIT_KEY: Key = Key::Ctrl('c');
+
+/* Actions that can be triggered with key bindings */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Sequence)]
+pub enum Action {
+	Help,
+	PreviousBlock,
+	NextBlock,
+	SwitchFocus,
+	ScrollUp,
+	ScrollDown,
+	SmoothScrollUp,
+	SmoothScrollDown,
+	ScrollLogUp,
+	ScrollLogDown,
+	ScrollLogLeft,
+	ScrollLogRight,
+	ScrollInfoUp,
+	ScrollInfoDown,
+	ScrollTop,
+	ScrollBottom,
+	ExpandBlock,
+	ShrinkBlock,
+	BlockPosition,
+	Clear,
+	Dependent,
+	NextInfo,
+	Search,
+	NextMatch,
+	PreviousMatch,
+	Filter,
+	SizeFilter,
+	Builtin,
+	HideUsed,
+	Bookmark,
+	Bookmarks,
+	Group,
+	Groups,
+	SizeColumn,
+	UsedByColumn,
+	Sort,
+	SortDirection,
+	SizeFormat,
+	LogLevel,
+	SaveLogs,
+	Follow,
+	ReverseLogs,
+	Snapshot,
+	Load,
+	LoadFile,
+	Mark,
+	Unload,
+	ForceUnload,
+	Blacklist,
+	Unblacklist,
+	AddBoot,
+	RemoveBoot,
+	Reload,
+	Depmod,
+	Undo,
+	Options,
+	Execute,
+	ExecuteRecursive,
+	Cancel,
+	Copy,
+	CopyInfo,
+	CopyCommand,
+	OpenDocs,
+	Paste,
+	Refresh,
+	Pause,
+	Palette,
+	Quit,
+}
+
+impl Action {
+	/**
+	 * Get the name of the action that is used in the configuration file.
+	 *
+	 * @return str
+	 */
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Help => "help",
+			Self::PreviousBlock => "previous_block",
+			Self::NextBlock => "next_block",
+			Self::SwitchFocus => "switch_focus",
+			Self::ScrollUp => "scroll_up",
+			Self::ScrollDown => "scroll_down",
+			Self::SmoothScrollUp => "smooth_scroll_up",
+			Self::SmoothScrollDown => "smooth_scroll_down",
+			Self::ScrollLogUp => "scroll_log_up",
+			Self::ScrollLogDown => "scroll_log_down",
+			Self::ScrollLogLeft => "scroll_log_left",
+			Self::ScrollLogRight => "scroll_log_right",
+			Self::ScrollInfoUp => "scroll_info_up",
+			Self::ScrollInfoDown => "scroll_info_down",
+			Self::ScrollTop => "scroll_top",
+			Self::ScrollBottom => "scroll_bottom",
+			Self::ExpandBlock => "expand_block",
+			Self::ShrinkBlock => "shrink_block",
+			Self::BlockPosition => "block_position",
+			Self::Clear => "clear",
+			Self::Dependent => "dependent",
+			Self::NextInfo => "next_info",
+			Self::Search => "search",
+			Self::NextMatch => "next_match",
+			Self::PreviousMatch => "previous_match",
+			Self::Filter => "filter",
+			Self::SizeFilter => "size_filter",
+			Self::Builtin => "builtin",
+			Self::HideUsed => "hide_used",
+			Self::Bookmark => "bookmark",
+			Self::Bookmarks => "bookmarks",
+			Self::Group => "group",
+			Self::Groups => "groups",
+			Self::SizeColumn => "size_column",
+			Self::UsedByColumn => "used_by_column",
+			Self::Sort => "sort",
+			Self::SortDirection => "sort_direction",
+			Self::SizeFormat => "size_format",
+			Self::LogLevel => "log_level",
+			Self::SaveLogs => "save_logs",
+			Self::Follow => "follow",
+			Self::ReverseLogs => "reverse_logs",
+			Self::Snapshot => "snapshot",
+			Self::Load => "load",
+			Self::LoadFile => "load_file",
+			Self::Mark => "mark",
+			Self::Unload => "unload",
+			Self::ForceUnload => "force_unload",
+			Self::Blacklist => "blacklist",
+			Self::Unblacklist => "unblacklist",
+			Self::AddBoot => "add_boot",
+			Self::RemoveBoot => "remove_boot",
+			Self::Reload => "reload",
+			Self::Depmod => "depmod",
+			Self::Undo => "undo",
+			Self::Options => "options",
+			Self::Execute => "execute",
+			Self::ExecuteRecursive => "execute_recursive",
+			Self::Cancel => "cancel",
+			Self::Copy => "copy",
+			Self::CopyInfo => "copy_info",
+			Self::CopyCommand => "copy_command",
+			Self::OpenDocs => "open_docs",
+			Self::Paste => "paste",
+			Self::Refresh => "refresh",
+			Self::Pause => "pause",
+			Self::Palette => "palette",
+			Self::Quit => "quit",
+		}
+	}
+
+	/**
+	 * Get the description of the action for the help message.
+	 *
+	 * @return str
+	 */
+	pub fn description(self) -> &'static str {
+		i18n::text(&format!("action.{}", self.name()))
+	}
+
+	/**
+	 * Check if the action modifies the kernel modules or the ring buffer.
+	 *
+	 * @return bool
+	 */
+	pub fn is_mutating(self) -> bool {
+		matches!(
+			self,
+			Self::Clear
+				| Self::Load | Self::LoadFile
+				| Self::Unload
+				| Self::ForceUnload
+				| Self::Blacklist
+				| Self::Unblacklist
+				| Self::AddBoot
+				| Self::RemoveBoot
+				| Self::Reload
+				| Self::Depmod
+				| Self::Undo
+		)
+	}
+
+	/**
+	 * Check if the action is used on the kernel activities.
+	 *
+	 * @return bool
+	 */
+	pub fn is_log_action(self) -> bool {
+		matches!(
+			self,
+			Self::ScrollLogUp
+				| Self::ScrollLogDown
+				| Self::ScrollLogLeft
+				| Self::ScrollLogRight
+				| Self::LogLevel
+				| Self::SaveLogs
+				| Self::Follow
+				| Self::ReverseLogs
+		)
+	}
+
+	/**
+	 * Get the default keys of the action.
+	 *
+	 * @return keys
+	 */
+	fn default_keys(self) -> &'static [&'static str] {
+		match self {
+			Self::Help => &["?", "f1"],
+			Self::PreviousBlock => &["left", "h", "H"],
+			Self::NextBlock => &["right", "l", "L"],
+			Self::SwitchFocus => &[],
+			Self::ScrollUp => &["up", "k", "K"],
+			Self::ScrollDown => &["down", "j", "J"],
+			Self::SmoothScrollUp => &["alt-k", "alt-K"],
+			Self::SmoothScrollDown => &["alt-j", "alt-J"],
+			Self::ScrollLogUp => &["pgup"],
+			Self::ScrollLogDown => &["pgdown"],
+			Self::ScrollLogLeft => &["alt-h", "alt-H"],
+			Self::ScrollLogRight => &["alt-l", "alt-L"],
+			Self::ScrollInfoUp => &["<", "alt-space"],
+			Self::ScrollInfoDown => &[">"],
+			Self::ScrollTop => &["ctrl-t", "home"],
+			Self::ScrollBottom => &["ctrl-b", "end"],
+			Self::ExpandBlock => &["alt-e"],
+			Self::ShrinkBlock => &["alt-s"],
+			Self::BlockPosition => &["ctrl-x"],
+			Self::Clear => &["ctrl-l", "ctrl-u", "alt-c", "alt-C"],
+			Self::Dependent => &["d", "alt-d"],
+			Self::NextInfo => &["\\", "tab", "backtab"],
+			Self::Search => &["/", "s", "S", "enter"],
+			Self::NextMatch | Self::PreviousMatch => &[],
+			Self::Filter => &["f", "F"],
+			Self::SizeFilter => &["alt-f", "alt-F"],
+			Self::Builtin => &["ctrl-k"],
+			Self::HideUsed => &["ctrl-n"],
+			Self::Bookmark => &["*"],
+			Self::Bookmarks => &["ctrl-w"],
+			Self::Group => &["g"],
+			Self::Groups => &["G"],
+			Self::SizeColumn => &["alt-2"],
+			Self::UsedByColumn => &["alt-3"],
+			Self::Sort => &["t"],
+			Self::SortDirection => &["T"],
+			Self::SizeFormat => &["z", "Z"],
+			Self::LogLevel => &["p", "P"],
+			Self::SaveLogs => &["ctrl-s"],
+			Self::Follow => &["ctrl-f"],
+			Self::ReverseLogs => &["alt-t", "alt-T"],
+			Self::Snapshot => &["ctrl-e"],
+			Self::Load => &["+", "i", "I", "insert"],
+			Self::LoadFile => &["ctrl-o"],
+			Self::Mark => &["space"],
+			Self::Unload => &["-", "u", "backspace", "ctrl-h"],
+			Self::ForceUnload => &["U"],
+			Self::Blacklist => &["x", "X", "b", "B", "delete"],
+			Self::Unblacklist => &["alt-x", "alt-b"],
+			Self::AddBoot => &["ctrl-a"],
+			Self::RemoveBoot => &["alt-a"],
+			Self::Reload => &["ctrl-r", "ctrl-R", "alt-r", "alt-R"],
+			Self::Depmod => &["D"],
+			Self::Undo => &["ctrl-z"],
+			Self::Options => &["m", "o"],
+			Self::Execute => &["y", "Y"],
+			Self::ExecuteRecursive => &["a", "A"],
+			Self::Cancel => &["n", "N"],
+			Self::Copy => &["c", "C"],
+			Self::CopyInfo => &["ctrl-y"],
+			Self::CopyCommand => &["alt-y", "alt-Y"],
+			Self::OpenDocs => &["alt-o", "alt-O"],
+			Self::Paste => &["v", "ctrl-v", "ctrl-V"],
+			Self::Refresh => &["r", "R", "f5"],
+			Self::Pause => &["ctrl-p"],
+			Self::Palette => &[":"],
+			Self::Quit => &["q", "Q", "ctrl-c", "ctrl-d", "esc"],
+		}
+	}
+
+	/**
+	 * Get the default keys of the action that are only used in the given block.
+	 *
+	 * These keys take precedence over the global ones while the block is selected.
+	 *
+	 * @param  block
+	 * @return keys
+	 */
+	fn default_block_keys(self, block: Block) -> &'static [&'static str] {
+		match (block, self) {
+			(Block::ModuleTable | Block::Activities, Self::SwitchFocus) => &["tab"],
+			(Block::ModuleTable | Block::Activities, Self::NextMatch) => &["n"],
+			(Block::ModuleTable | Block::Activities, Self::PreviousMatch) => &["N"],
+			(Block::Activities, Self::Follow) => &["f", "F"],
+			(Block::Activities, Self::ReverseLogs) => &["t"],
+			_ => &[],
+		}
+	}
+}
+
+/* Implementation of Display for using Action members as string */
+impl Display for Action {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.name())
+	}
+}
+
+/* One or more keys given in the configuration file */
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum KeyList {
+	Single(String),
+	Multiple(Vec<String>),
+}
+
+impl KeyList {
+	/**
+	 * Get the keys as a slice.
+	 *
+	 * @return keys
+	 */
+	fn keys(&self) -> &[String] {
+		match self {
+			Self::Single(key) => std::slice::from_ref(key),
+			Self::Multiple(keys) => keys,
+		}
+	}
+}
+
+/* Keys of an action or the key bindings of a block given in the configuration file */
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum KeyEntry {
+	Keys(KeyList),
+	Block(BTreeMap<String, KeyList>),
+}
+
+/* Actions with their bound keys */
+type ActionKeys = Vec<(Action, Vec<Key>)>;
+
+/* Key bindings of the actions */
+#[derive(Clone, Debug)]
+pub struct KeyBindings {
+	actions: ActionKeys,
+	keys: HashMap<Key, Action>,
+	block_actions: Vec<(Block, ActionKeys)>,
+	block_keys: HashMap<(Block, Key), Action>,
+}
+
+/* Default initialization values for KeyBindings */
+impl Default for KeyBindings {
+	fn default() -> Self {
+		Self::new(&BTreeMap::new(), &BTreeMap::new())
+			.expect("invalid default key bindings")
+	}
+}
+
+/* Implementation of Deserialize for parsing the key bindings from config */
+impl<'de> Deserialize<'de> for KeyBindings {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let mut overrides = BTreeMap::new();
+		let mut block_overrides = BTreeMap::new();
+		for (name, entry) in BTreeMap::<String, KeyEntry>::deserialize(deserializer)?
+		{
+			match entry {
+				KeyEntry::Keys(keys) => {
+					overrides.insert(name, keys);
+				}
+				KeyEntry::Block(keys) => {
+					block_overrides.insert(name, keys);
+				}
+			}
+		}
+		Self::new(&overrides, &block_overrides).map_err(de::Error::custom)
+	}
+}
+
+/* Implementation of Serialize for writing the key bindings as config */
+impl Serialize for KeyBindings {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let key_list = |keys: &[Key]| {
+			KeyList::Multiple(keys.iter().map(|key| format_key(*key)).collect())
+		};
+		let mut entries = self
+			.actions
+			.iter()
+			.map(|(action, keys)| {
+				(action.name().to_string(), KeyEntry::Keys(key_list(keys)))
+			})
+			.collect::<BTreeMap<String, KeyEntry>>();
+		/* Only the actions that have keys in a block are written. */
+		for (block, actions) in &self.block_actions {
+			let keys = actions
+				.iter()
+				.filter(|(_, keys)| !keys.is_empty())
+				.map(|(action, keys)| (action.name().to_string(), key_list(keys)))
+				.collect::<BTreeMap<String, KeyList>>();
+			if !keys.is_empty() {
+				entries.insert(block.name().to_string(), KeyEntry::Block(keys));
+			}
+		}
+		entries.serialize(serializer)
+	}
+}
+
+impl KeyBindings {
+	/**
+	 * Create key bindings by overriding the default keys of actions.
+	 *
+	 * Block overrides are given with the block names (e.g. 'activities')
+	 * and replace the default keys of the actions in that block.
+	 *
+	 * @param  overrides
+	 * @param  block_overrides
+	 * @return Result
+	 */
+	fn new(
+		overrides: &BTreeMap<String, KeyList>,
+		block_overrides: &BTreeMap<String, BTreeMap<String, KeyList>>,
+	) -> Result<Self, String> {
+		if let Some(name) = block_overrides
+			.keys()
+			.find(|name| !all::<Block>().any(|block| block.name() == name.as_str()))
+		{
+			return Err(format!("unknown block: '{name}'"));
+		}
+		let (actions, keys) = Self::bind(None, overrides)?;
+		let mut block_actions = Vec::new();
+		let mut block_keys = HashMap::new();
+		for block in all::<Block>() {
+			let (actions, keys) = Self::bind(
+				Some(block),
+				block_overrides
+					.get(block.name())
+					.unwrap_or(&BTreeMap::new()),
+			)?;
+			block_keys.extend(keys.into_iter().map(|(key, v)| ((block, key), v)));
+			block_actions.push((block, actions));
+		}
+		Ok(Self {
+			actions,
+			keys,
+			block_actions,
+			block_keys,
+		})
+	}
+
+	/**
+	 * Bind the keys of actions globally or in the given block.
+	 *
+	 * The emergency quit key is always bound to the quit action and
+	 * cannot be used for the other actions.
+	 *
+	 * @param  block
+	 * @param  overrides
+	 * @return Result
+	 */
+	fn bind(
+		block: Option<Block>,
+		overrides: &BTreeMap<String, KeyList>,
+	) -> Result<(ActionKeys, HashMap<Key, Action>), String> {
+		let mut actions = all::<Action>()
+			.map(|action| {
+				(
+					action,
+					match block {
+						Some(block) => action.default_block_keys(block),
+						None => action.default_keys(),
+					}
+					.iter()
+					.filter_map(|key| parse_key(key))
+					.collect::<Vec<Key>>(),
+				)
+			})
+			.collect::<Vec<(Action, Vec<Key>)>>();
+		for (name, keys) in overrides {
+			let (_, action_keys) = actions
+				.iter_mut()
+				.find(|(action, _)| action.name() == name)
+				.ok_or_else(|| format!("unknown action: '{name}'"))?;
+			*action_keys = keys
+				.keys()
+				.iter()
+				.map(|key| {
+					parse_key(key)
+						.ok_or_else(|| format!("invalid key for '{name}': '{key}'"))
+				})
+				.collect::<Result<Vec<Key>, String>>()?;
+		}
+		for (action, action_keys) in actions.iter_mut() {
+			if *action == Action::Quit {
+				if block.is_none() && !action_keys.contains(&EMERGENCY_QUIT_KEY) {
+					action_keys.push(EMERGENCY_QUIT_KEY);
+				}
+			} else if action_keys.contains(&EMERGENCY_QUIT_KEY) {
+				return Err(format!(
+						"'{}' is reserved for quitting and cannot be bound to '{action}'{}",
+						format_key(EMERGENCY_QUIT_KEY),
+						block
+							.map(|v| format!(" in '{}'", v.name()))
+						.unwrap_or_default(),
+				));
+			}
+		}
+		let mut keys = HashMap::new();
+		for (action, action_keys) in &actions {
+			for key in action_keys {
+				match keys.insert(*key, *action) {
+					Some(other) if other != *action => {
+						return Err(format!(
+							"conflicting key bindings{}: '{}' is bound to both '{}' and '{}'",
+							block
+								.map(|v| format!(" in '{}'", v.name()))
+								.unwrap_or_default(),
+							format_key(*key),
+							other,
+							action
+						))
+					}
+					_ => {}
+				}
+			}
+		}
+		Ok((actions, keys))
+	}
+
+	/**
+	 * Get the action that is bound to the given key.
+	 *
+	 * @param  key
+	 * @return Action
+	 */
+	pub fn get(&self, key: Key) -> Option<Action> {
+		self.keys.get(&key).copied()
+	}
+
+	/**
+	 * Get the action that is bound to the given key while the block is selected.
+	 *
+	 * Keys of the block take precedence over the global key bindings.
+	 *
+	 * @param  block
+	 * @param  key
+	 * @return Action
+	 */
+	pub fn get_in(&self, block: Block, key: Key) -> Option<Action> {
+		self.block_keys
+			.get(&(block, key))
+			.copied()
+			.or_else(|| self.get(key))
+	}
+
+	/**
+	 * Get the keys of the given action.
+	 *
+	 * @param  action
+	 * @return keys
+	 */
+	pub fn keys(&self, action: Action) -> &[Key] {
+		self.actions
+			.iter()
+			.find(|(v, _)| *v == action)
+			.map(|(_, keys)| keys.as_slice())
+			.unwrap_or_default()
+	}
+
+	/**
+	 * Get all the actions with their keys for the command palette.
+	 *
+	 * @return Vec
+	 */
+	pub fn entries(&self) -> Vec<(Action, String)> {
+		self.actions
+			.iter()
+			.map(|(action, keys)| (*action, format_keys(keys)))
+			.collect()
+	}
+
+	/**
+	 * Get the key bindings as (keys, description) pairs for the help message.
+	 *
+	 * @return Vec
+	 */
+	pub fn help(&self) -> Vec<(String, &'static str)> {
+		let mut help = self
+			.actions
+			.iter()
+			.filter(|(_, keys)| !keys.is_empty())
+			.map(|(action, keys)| (format_keys(keys), action.description()))
+			.collect::<Vec<(String, &'static str)>>();
+		/* Append the keys that are only used in a block with its name. */
+		for (block, actions) in &self.block_actions {
+			help.extend(actions.iter().filter(|(_, keys)| !keys.is_empty()).map(
+				|(action, keys)| {
+					(
+						format!("{} [{}]", format_keys(keys), block.name()),
+						action.description(),
+					)
+				},
+			));
+		}
+		help
+	}
+}
+
+/**
+ * Parse a key from the given string (e.g. "q", "ctrl-c", "alt-k", "f5").
+ *
+ * @param  key
+ * @return Key
+ */
+pub fn parse_key(key: &str) -> Option<Key> {
+	let named_key = |key: &str| -> Option<Key> {
+		match key {
+			"enter" => Some(Key::Char('\n')),
+			"tab" => Some(Key::Char('\t')),
+			"space" => Some(Key::Char(' ')),
+			"backtab" => Some(Key::BackTab),
+			"backspace" => Some(Key::Backspace),
+			"left" => Some(Key::Left),
+			"right" => Some(Key::Right),
+			"up" => Some(Key::Up),
+			"down" => Some(Key::Down),
+			"home" => Some(Key::Home),
+			"end" => Some(Key::End),
+			"pgup" => Some(Key::PageUp),
+			"pgdown" => Some(Key::PageDown),
+			"delete" => Some(Key::Delete),
+			"insert" => Some(Key::Insert),
+			"esc" => Some(Key::Esc),
+			_ => None,
+		}
+	};
+	let single_char = |key: &str| -> Option<char> {
+		let mut chars = key.chars();
+		match (chars.next(), chars.next()) {
+			(Some(c), None) => Some(c),
+			_ => named_key(key).and_then(|v| match v {
+				Key::Char(c) => Some(c),
+				_ => None,
+			}),
+		}
+	};
+	if let Some(c) = key.strip_prefix("ctrl-").and_then(single_char) {
+		Some(Key::Ctrl(c))
+	} else if let Some(c) = key.strip_prefix("alt-").and_then(single_char) {
+		Some(Key::Alt(c))
+	} else if let Some(n) = key
+		.strip_prefix('f')
+		.and_then(|v| v.parse::<u8>().ok())
+		.filter(|n| (1..=12).contains(n))
+	{
+		Some(Key::F(n))
+	} else if let Some(v) = named_key(key) {
+		Some(v)
+	} else {
+		single_char(key).map(Key::Char)
+	}
+}
+
+/**
+ * Format the given keys as a comma separated list.
+ *
+ * @param  keys
+ * @return String
+ */
+fn format_keys(keys: &[Key]) -> String {
+	keys.iter()
+		.map(|key| format_key(*key))
+		.collect::<Vec<String>>()
+		.join(", ")
+}
+
+/**
+ * Format the given key as a string that can be parsed back.
+ *
+ * @param  key
+ * @return String
+ */
+pub fn format_key(key: Key) -> String {
+	let format_char = |c: char| -> String {
+		match c {
+			'\n' => String::from("enter"),
+			'\t' => String::from("tab"),
+			' ' => String::from("space"),
+			c => c.to_string(),
+		}
+	};
+	match key {
+		Key::Char(c) => format_char(c),
+		Key::Ctrl(c) => format!("ctrl-{}", format_char(c)),
+		Key::Alt(c) => format!("alt-{}", format_char(c)),
+		Key::F(n) => format!("f{n}"),
+		Key::BackTab => String::from("backtab"),
+		Key::Backspace => String::from("backspace"),
+		Key::Left => String::from("left"),
+		Key::Right => String::from("right"),
+		Key::Up => String::from("up"),
+		Key::Down => String::from("down"),
+		Key::Home => String::from("home"),
+		Key::End => String::from("end"),
+		Key::PageUp => String::from("pgup"),
+		Key::PageDown => String::from("pgdown"),
+		Key::Delete => String::from("delete"),
+		Key::Insert => String::from("insert"),
+		Key::Esc => String::from("esc"),
+		_ => String::from("?"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	#[test]
+	fn test_key_bindings() {
+		let key_bindings = KeyBindings::default();
+		for action in all::<Action>() {
+			assert_eq!(action.default_keys().len(), key_bindings.keys(action).len());
+			for key in key_bindings.keys(action) {
+				assert_eq!(Some(action), key_bindings.get(*key));
+				assert_eq!(Some(*key), parse_key(&format_key(*key)));
+			}
+		}
+		assert_eq!(Some(Action::Quit), key_bindings.get(Key::Ctrl('c')));
+		assert_eq!(None, key_bindings.get(Key::Char('~')));
+		assert_eq!(
+			Some(Action::Follow),
+			key_bindings.get_in(Block::Activities, Key::Char('f'))
+		);
+		assert_eq!(
+			Some(Action::Filter),
+			key_bindings.get_in(Block::ModuleTable, Key::Char('f'))
+		);
+		assert_eq!(Some(Action::Cancel), key_bindings.get(Key::Char('n')));
+		assert_eq!(
+			Some(Action::PreviousMatch),
+			key_bindings.get_in(Block::ModuleTable, Key::Char('N'))
+		);
+		assert_eq!(
+			Some(Action::SwitchFocus),
+			key_bindings.get_in(Block::Activities, Key::Char('\t'))
+		);
+		assert_eq!(
+			Some(Action::NextInfo),
+			key_bindings.get_in(Block::ModuleInfo, Key::Char('\t'))
+		);
+		assert!(key_bindings.help().contains(&(
+			String::from("f, F [activities]"),
+			"follow the new kernel activities"
+		)));
+		let mut block_overrides = BTreeMap::new();
+		block_overrides.insert(
+			String::from("activities"),
+			BTreeMap::from([(
+				String::from("follow"),
+				KeyList::Single(String::from("w")),
+			)]),
+		);
+		let key_bindings =
+			KeyBindings::new(&BTreeMap::new(), &block_overrides).unwrap();
+		assert_eq!(
+			Some(Action::Follow),
+			key_bindings.get_in(Block::Activities, Key::Char('w'))
+		);
+		assert_eq!(
+			Some(Action::Filter),
+			key_bindings.get_in(Block::Activities, Key::Char('f'))
+		);
+		block_overrides.insert(
+			String::from("activities"),
+			BTreeMap::from([(
+				String::from("follow"),
+				KeyList::Single(String::from("n")),
+			)]),
+		);
+		assert!(KeyBindings::new(&BTreeMap::new(), &block_overrides)
+			.unwrap_err()
+			.contains("in 'activities'"));
+		block_overrides.insert(String::from("popup"), BTreeMap::new());
+		assert!(KeyBindings::new(&BTreeMap::new(), &block_overrides)
+			.unwrap_err()
+			.contains("unknown block"));
+		let mut overrides = BTreeMap::new();
+		overrides.insert(
+			String::from("quit"),
+			KeyList::Multiple(vec![String::from("ctrl-q"), String::from("f10")]),
+		);
+		overrides.insert(String::from("help"), KeyList::Single(String::from("w")));
+		let key_bindings = KeyBindings::new(&overrides, &BTreeMap::new()).unwrap();
+		assert_eq!(Some(Action::Quit), key_bindings.get(Key::F(10)));
+		assert_eq!(None, key_bindings.get(Key::Char('q')));
+		assert_eq!(None, key_bindings.get(Key::Ctrl('d')));
+		assert_eq!(Some(Action::Quit), key_bindings.get(EMERGENCY_QUIT_KEY));
+		assert_eq!(Some(Action::Help), key_bindings.get(Key::Char('w')));
+		assert!(key_bindings
+			.help()
+			.contains(&(String::from("ctrl-q, f10, ctrl-c"), "quit")));
+		overrides.insert(
+			String::from("help"),
+			KeyList::Single(String::from("ctrl-c")),
+		);
+		assert!(KeyBindings::new(&overrides, &BTreeMap::new())
+			.unwrap_err()
+			.contains("reserved for quitting"));
+		block_overrides.clear();
+		block_overrides.insert(
+			String::from("module-info"),
+			BTreeMap::from([(
+				String::from("copy"),
+				KeyList::Single(String::from("ctrl-c")),
+			)]),
+		);
+		assert!(KeyBindings::new(&BTreeMap::new(), &block_overrides)
+			.unwrap_err()
+			.contains("in 'module-info'"));
+		overrides.insert(String::from("help"), KeyList::Single(String::from("u")));
+		assert!(KeyBindings::new(&overrides, &BTreeMap::new())
+			.unwrap_err()
+			.contains("conflicting"));
+		overrides.insert(String::from("help"), KeyList::Single(String::from("xyz")));
+		assert!(KeyBindings::new(&overrides, &BTreeMap::new()).is_err());
+		overrides.clear();
+		overrides.insert(String::from("fly"), KeyList::Single(String::from("f")));
+		assert!(KeyBindings::new(&overrides, &BTreeMap::new()).is_err());
+	}
+}
@@ -0,0 +1,300 @@
+use crate::app::{App, BlockSize, Orientation};
+use crate::config::Config;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+/* Height of the search area, borders and header around the module list rows */
+const LIST_CHROME_HEIGHT: u16 = 6;
+
+/* Height of the user input and kernel information row */
+const INPUT_HEIGHT: u16 = 3;
+
+/* Properties of the block layout that can be changed at runtime */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LayoutArgs {
+	pub block_size: BlockSize,
+	pub orientation: Orientation,
+	pub max_list_height: Option<u16>,
+	pub no_log: bool,
+}
+
+impl LayoutArgs {
+	/**
+	 * Create a new layout arguments instance.
+	 *
+	 * @param  Config
+	 * @return LayoutArgs
+	 */
+	pub fn new(config: &Config) -> Self {
+		Self {
+			block_size: BlockSize::from(&config.layout),
+			orientation: config.layout.orientation,
+			max_list_height: config.layout.max_list_height,
+			no_log: config.no_log,
+		}
+	}
+}
+
+/* Areas of the terminal blocks */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockLayout {
+	pub header: Rect,
+	pub body: Rect,
+	pub status_bar: Rect,
+	pub compact: bool,
+	pub user_input: Rect,
+	pub kernel_info: Rect,
+	pub blocks: Vec<Option<Rect>>,
+}
+
+impl BlockLayout {
+	/**
+	 * Get the areas that are drawn in the body of the terminal.
+	 *
+	 * @return Vec
+	 */
+	pub fn body_areas(&self) -> Vec<Rect> {
+		if self.compact {
+			return vec![self.body];
+		}
+		[self.user_input, self.kernel_info]
+			.into_iter()
+			.chain(self.blocks.iter().flatten().copied())
+			.collect()
+	}
+}
+
+/**
+ * Get the constraints for splitting the area between the module list
+ * (along with the search area) and the block that follows it.
+ *
+ * If a maximum height is set for the module list and the second block
+ * is stacked below it, the remaining rows are given to that block.
+ *
+ * @param  area
+ * @param  percentage
+ * @param  stacked
+ * @param  max_list_height
+ * @return constraints
+ */
+pub fn list_constraints(
+	area: Rect,
+	percentage: u16,
+	stacked: bool,
+	max_list_height: Option<u16>,
+) -> [Constraint; 2] {
+	match max_list_height {
+		Some(rows) if stacked => {
+			let height =
+				(u32::from(area.height) * u32::from(percentage) / 100) as u16;
+			[
+				Constraint::Length(
+					height.min(rows.saturating_add(LIST_CHROME_HEIGHT)),
+				),
+				Constraint::Min(0),
+			]
+		}
+		_ => [
+			Constraint::Percentage(percentage),
+			Constraint::Percentage(100 - percentage),
+		],
+	}
+}
+
+/**
+ * Compute the areas of the blocks for the given terminal area.
+ *
+ * Blocks that change their positions are returned in the drawing order.
+ * The area of the module list is skipped (None) while the module
+ * information takes the whole width and the kernel activities are
+ * left out if they are hidden.
+ *
+ * @param  area
+ * @param  args
+ * @return BlockLayout
+ */
+pub fn compute_layout(area: Rect, args: &LayoutArgs) -> BlockLayout {
+	let status_chunks = Layout::default()
+		.direction(Direction::Vertical)
+		.constraints([
+			Constraint::Length(1),
+			Constraint::Min(0),
+			Constraint::Length(1),
+		])
+		.split(area);
+	let mut layout = BlockLayout {
+		header: status_chunks[0],
+		body: status_chunks[1],
+		status_bar: status_chunks[2],
+		compact: App::is_compact(area),
+		..BlockLayout::default()
+	};
+	/* Show only the selected block on the small terminals. */
+	if layout.compact {
+		return layout;
+	}
+	/* Give the space of the hidden kernel activities to the other blocks. */
+	let chunks = Layout::default()
+		.direction(Direction::Vertical)
+		.constraints(if args.no_log {
+			[Constraint::Percentage(100), Constraint::Length(0)]
+		} else {
+			list_constraints(
+				layout.body,
+				100 - args.block_size.activities,
+				args.orientation == Orientation::Horizontal,
+				args.max_list_height,
+			)
+		})
+		.split(layout.body);
+	let list_chunks = Layout::default()
+		.direction(args.orientation.direction())
+		.constraints(list_constraints(
+			chunks[0],
+			100 - args.block_size.info,
+			args.orientation == Orientation::Vertical,
+			args.max_list_height,
+		))
+		.split(chunks[0]);
+	let input_chunks = Layout::default()
+		.direction(Direction::Vertical)
+		.constraints([
+			Constraint::Length(INPUT_HEIGHT),
+			Constraint::Percentage(100),
+		])
+		.split(list_chunks[0]);
+	let info_chunks = Layout::default()
+		.direction(Direction::Horizontal)
+		.constraints([
+			Constraint::Percentage(args.block_size.input),
+			Constraint::Percentage(100 - args.block_size.input),
+		])
+		.split(input_chunks[0]);
+	layout.user_input = info_chunks[0];
+	layout.kernel_info = info_chunks[1];
+	layout.blocks = vec![
+		Some(input_chunks[1]).filter(|_| args.block_size.info != 100),
+		Some(list_chunks[1]),
+	];
+	if !args.no_log {
+		layout.blocks.push(Some(chunks[1]));
+	}
+	layout
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	/**
+	 * Check if the body areas of the layout tile its body without overlapping.
+	 *
+	 * @param layout
+	 */
+	fn assert_tiled(layout: &BlockLayout) {
+		let areas = layout.body_areas();
+		for (i, a) in areas.iter().enumerate() {
+			assert!(layout.body.union(*a) == layout.body, "{a:?} is outside");
+			for b in areas.iter().skip(i + 1) {
+				assert!(!a.intersects(*b), "{a:?} overlaps {b:?}");
+			}
+		}
+		assert_eq!(
+			u32::from(layout.body.width) * u32::from(layout.body.height),
+			areas
+				.iter()
+				.map(|v| u32::from(v.width) * u32::from(v.height))
+				.sum::<u32>()
+		);
+	}
+	#[test]
+	fn test_layout() {
+		let args = LayoutArgs::new(&Config::default());
+		assert_eq!(BlockSize::default(), args.block_size);
+		for (width, height) in [(200, 60), (60, 40), (60, 120), (100, 16)] {
+			let area = Rect::new(0, 0, width, height);
+			for args in [
+				args,
+				LayoutArgs {
+					no_log: true,
+					..args
+				},
+				LayoutArgs {
+					orientation: Orientation::Vertical,
+					..args
+				},
+				LayoutArgs {
+					block_size: BlockSize {
+						input: 30,
+						info: 75,
+						activities: 50,
+					},
+					..args
+				},
+				LayoutArgs {
+					max_list_height: Some(10),
+					..args
+				},
+			] {
+				let layout = compute_layout(area, &args);
+				assert!(!layout.compact);
+				assert_eq!(Rect::new(0, 0, width, 1), layout.header);
+				assert_eq!(Rect::new(0, height - 1, width, 1), layout.status_bar);
+				assert_eq!(Rect::new(0, 1, width, height - 2), layout.body);
+				assert_eq!(if args.no_log { 2 } else { 3 }, layout.blocks.len());
+				assert_eq!(INPUT_HEIGHT, layout.user_input.height);
+				assert_tiled(&layout);
+			}
+		}
+		let layout = compute_layout(Rect::new(0, 0, 200, 60), &args);
+		let blocks = layout.blocks.iter().flatten().collect::<Vec<&Rect>>();
+		assert_eq!(72, layout.user_input.width);
+		assert_eq!(blocks[0].width + blocks[1].width, 200);
+		assert_eq!(14, blocks[2].height);
+		let layout = compute_layout(
+			Rect::new(0, 0, 200, 60),
+			&LayoutArgs {
+				no_log: true,
+				..args
+			},
+		);
+		assert_eq!(58, layout.blocks[1].unwrap().height);
+		let layout = compute_layout(
+			Rect::new(0, 0, 200, 60),
+			&LayoutArgs {
+				block_size: BlockSize {
+					info: 100,
+					..BlockSize::default()
+				},
+				..args
+			},
+		);
+		assert_eq!(None, layout.blocks[0]);
+		assert_eq!(200, layout.blocks[1].unwrap().width);
+		assert_tiled(&layout);
+		for (width, height) in [(59, 40), (100, 15)] {
+			let layout = compute_layout(Rect::new(0, 0, width, height), &args);
+			assert!(layout.compact);
+			assert!(layout.blocks.is_empty());
+			assert_eq!(
+				vec![Rect::new(0, 1, width, height - 2)],
+				layout.body_areas()
+			);
+		}
+		let area = Rect::new(0, 0, 80, 60);
+		assert_eq!(
+			[Constraint::Percentage(75), Constraint::Percentage(25)],
+			list_constraints(area, 75, true, None)
+		);
+		assert_eq!(
+			[Constraint::Length(26), Constraint::Min(0)],
+			list_constraints(area, 75, true, Some(20))
+		);
+		assert_eq!(
+			[Constraint::Length(24), Constraint::Min(0)],
+			list_constraints(Rect::new(0, 0, 80, 32), 75, true, Some(20))
+		);
+		assert_eq!(
+			[Constraint::Percentage(60), Constraint::Percentage(40)],
+			list_constraints(area, 60, false, Some(20))
+		);
+	}
+}
@@ -1,24 +1,42 @@
 #![allow(clippy::tabs_in_doc_comments)]
 
 pub mod app;
+pub mod clipboard;
+pub mod config;
+pub mod date;
+pub mod docs;
 pub mod event;
+pub mod i18n;
 pub mod kernel;
+pub mod keys;
+pub mod layout;
+pub mod modules;
 pub mod widgets;
 #[macro_use]
 pub mod util;
 pub mod args;
+pub mod output;
+pub mod state;
 pub mod style;
 
-use crate::app::{App, Block, InputMode, ScrollDirection};
+use crate::app::{App, Block, Column, InputMode, ScrollDirection, SearchScope};
+use crate::config::{Config, ConfirmDefault, EnterAction};
+use crate::docs::{DOCS_DIR, DOCS_URL};
 use crate::kernel::cmd::ModuleCommand;
+use crate::kernel::lkm;
 use crate::kernel::Kernel;
+use crate::keys::{Action, EMERGENCY_QUIT_KEY};
+use crate::state::State;
 use enum_iterator::Sequence;
 use event::{Event, Events};
 use ratatui::backend::Backend;
-use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::layout::Rect;
 use ratatui::Terminal;
 use std::error::Error;
-use termion::event::Key;
+use std::mem;
+use std::path::Path;
+use std::time::Instant;
+use termion::event::{Key, MouseButton, MouseEvent};
 use unicode_width::UnicodeWidthStr;
 
 /**
@@ -27,122 +45,316 @@ use unicode_width::UnicodeWidthStr;
  * @param  Terminal
  * @param  Kernel
  * @param  Events
+ * @param  Config
  * @return Result
  */
 pub fn start_tui<B>(
 	mut terminal: Terminal<B>,
 	mut kernel: Kernel,
 	events: &Events,
+	config: &Config,
 ) -> Result<(), Box<dyn Error>>
 where
 	B: Backend,
 {
 	/* Configure the application. */
-	let mut app = App::new(Block::ModuleTable, kernel.modules.style.clone());
+	let mut app = App::new(Block::ModuleTable, kernel.modules.style.clone(), config);
+	if let Some(block) = config.block.filter(|v| !app.is_hidden(*v)) {
+		app.selected_block = block;
+	}
+	/* Warn once if the modules cannot be modified without privileges. */
+	let cmd_args = &kernel.modules.cmd_args;
+	if !cmd_args.read_only && !cmd_args.demo && cmd_args.privilege.is_unprivileged()
+	{
+		app.banner = Some(i18n::text("status.unprivileged").to_string());
+	}
+	/* Explain the empty module list if procfs or sysfs is not mounted. */
+	if !cmd_args.demo && !cfg!(target_os = "freebsd") {
+		if let Err(e) = lkm::check_filesystems(|v| util::path_exists(v)) {
+			app.banner = Some(e);
+		}
+	}
+	if app.search_scope == SearchScope::All {
+		kernel.modules.index_async(&events.tx);
+	}
+	/* Frames are only drawn after the events that can change them. */
+	let mut redraw = true;
+	let mut animating = false;
+	let mut area = Rect::default();
 	/* Draw terminal and render the widgets. */
 	loop {
-		terminal.draw(|frame| {
-			let chunks = Layout::default()
-				.direction(Direction::Vertical)
-				.constraints(
-					[
-						Constraint::Percentage(100 - app.block_size.activities),
-						Constraint::Percentage(app.block_size.activities),
-					]
-					.as_ref(),
-				)
-				.split(frame.size());
-			{
-				let chunks = Layout::default()
-					.direction(Direction::Horizontal)
-					.constraints(
-						[
-							Constraint::Percentage(100 - app.block_size.info),
-							Constraint::Percentage(app.block_size.info),
-						]
-						.as_ref(),
-					)
-					.split(chunks[0]);
-				{
-					let chunks = Layout::default()
-						.direction(Direction::Vertical)
-						.constraints(
-							[Constraint::Length(3), Constraint::Percentage(100)]
-								.as_ref(),
-						)
-						.split(chunks[0]);
-					{
-						let chunks = Layout::default()
-							.direction(Direction::Horizontal)
-							.constraints(
-								[
-									Constraint::Percentage(app.block_size.input),
-									Constraint::Percentage(
-										100 - app.block_size.input,
-									),
-								]
-								.as_ref(),
-							)
-							.split(chunks[0]);
-						app.draw_user_input(frame, chunks[0], &events.tx);
+		if redraw {
+			area = terminal
+				.draw(|frame| {
+					app.block_areas.clear();
+					/* Show a message instead of the blocks if they do not fit. */
+					if App::is_too_small(frame.size()) {
+						app.draw_size_warning(frame, frame.size());
+						return;
+					}
+					let layout =
+						layout::compute_layout(frame.size(), &app.layout_args());
+					if layout.compact {
+						app.draw_compact_layout(
+							frame,
+							layout.body,
+							&mut kernel,
+							&events.tx,
+						);
+					} else {
+						app.block_areas.push((Block::UserInput, layout.user_input));
+						app.draw_user_input(frame, layout.user_input, &events.tx);
 						app.draw_kernel_info(
 							frame,
-							chunks[1],
+							layout.kernel_info,
 							&kernel.info.current_info,
 						);
+						/* Draw the blocks in their positions or skip the hidden ones. */
+						for area in &layout.blocks {
+							match area {
+								Some(area) => {
+									app.draw_dynamic_block(frame, *area, &mut kernel)
+								}
+								None => app.block_index += 1,
+							}
+						}
 					}
-					if app.block_size.info != 100 {
-						app.draw_dynamic_block(frame, chunks[1], &mut kernel);
-					} else {
-						app.block_index += 1;
+					app.draw_header(
+						frame,
+						layout.header,
+						&kernel.info.release,
+						&kernel.modules,
+					);
+					app.draw_banner(frame, layout.header);
+					app.draw_status_bar(frame, layout.status_bar, &kernel.modules);
+					if app.show_help {
+						app.draw_help_popup(frame, frame.size());
 					}
-				}
-				app.draw_dynamic_block(frame, chunks[1], &mut kernel);
-			}
-			app.draw_dynamic_block(frame, chunks[1], &mut kernel);
-			if !app.input_mode.is_none() {
-				frame.set_cursor(1 + app.input_query.width() as u16, 1);
-			}
-		})?;
+					app.draw_palette(frame, frame.size());
+					if !app.completions.is_empty()
+						&& app.input_mode == InputMode::Load
+					{
+						app.draw_completions(frame);
+					}
+					app.draw_notifications(frame, frame.size());
+					if app.quit_pending {
+						app.draw_quit_dialog(frame, frame.size());
+					}
+					if !app.input_mode.is_none() {
+						frame.set_cursor(1 + app.input_query.width() as u16, 2);
+					}
+				})?
+				.area;
+		}
+		redraw = true;
 		/* Handle terminal events. */
 		match events.rx.recv()? {
 			/* Key input events. */
+			/* Quit with the emergency quit key in every mode. */
+			Event::Input(input) if input == EMERGENCY_QUIT_KEY => break,
+			/* Quit after the confirmation or dismiss the quit dialog. */
+			Event::Input(input) if app.quit_pending => {
+				app.quit_pending = false;
+				if app.key_bindings.get(input) == Some(Action::Execute) {
+					break;
+				}
+			}
+			/* Filter and run the actions on the command palette. */
+			Event::Input(input) if app.palette.is_some() => {
+				if let Some(action) = app.handle_palette_key(input) {
+					app.pending_action = Some(action);
+					events.tx.send(Event::Input(Key::Null)).unwrap();
+				}
+			}
 			Event::Input(input) => {
+				app.banner = None;
 				let mut hide_options = true;
 				if app.input_mode.is_none() {
+					/* Status messages are cleared on the next action. */
+					app.clear_status();
+					/* Select the module that starts with the typed prefix. */
+					if kernel.modules.command.is_none()
+						&& !app.show_help && !app.show_options
+					{
+						if let Some(prefix) = app.type_ahead(input).map(String::from)
+						{
+							if prefix.is_empty()
+								|| kernel.modules.select_prefix(&prefix)
+							{
+								app.set_status(i18n::format("status.find", &prefix));
+							} else {
+								app.set_status(i18n::format(
+									"status.find_no_match",
+									&prefix,
+								));
+							}
+							continue;
+						}
+					}
+					/* Select the default choice of the confirmation with enter. */
+					let action = if let Some(action) = app.pending_action.take() {
+						/* Run the action that is selected on the command palette. */
+						Some(action)
+					} else if input == Key::Char('\n')
+						&& !kernel.modules.command.is_none()
+					{
+						match kernel.modules.cmd_args.confirm.default {
+							ConfirmDefault::Yes => Some(Action::Execute),
+							ConfirmDefault::No => Some(Action::Cancel),
+						}
+					} else if app.show_help
+						|| app.show_options
+						|| !kernel.modules.command.is_none()
+					{
+						app.key_bindings.get(input)
+					} else {
+						/* Keys of the selected block take precedence. */
+						app.key_bindings.get_in(app.selected_block, input)
+					};
+					/* Run the configured action of enter on the selected module. */
+					let action = match action {
+						Some(Action::Search)
+							if input == Key::Char('\n')
+								&& app.selected_block == Block::ModuleTable
+								&& !app.show_help && !app.show_options =>
+						{
+							match config.enter_action {
+								EnterAction::Info => {
+									app.selected_block = Block::ModuleInfo;
+									None
+								}
+								EnterAction::Search => Some(Action::Search),
+								EnterAction::Reload => Some(Action::Reload),
+								EnterAction::Copy => Some(Action::Copy),
+							}
+						}
+						action => action,
+					};
+					/* Ignore the actions of the kernel activities if they are hidden. */
+					let action =
+						action.filter(|v| !(app.no_log && v.is_log_action()));
 					/* Default input mode. */
-					match input {
+					match action {
+						/* Close the help popup. */
+						Some(Action::Help | Action::Quit) if app.show_help => {
+							app.show_help = false;
+						}
+						/* Scroll the help popup up. */
+						Some(
+							action @ (Action::ScrollUp
+							| Action::SmoothScrollUp
+							| Action::ScrollLogUp),
+						) if app.show_help => {
+							app.scroll_help(
+								ScrollDirection::Up,
+								action == Action::ScrollLogUp,
+							);
+						}
+						/* Scroll the help popup down. */
+						Some(
+							action @ (Action::ScrollDown
+							| Action::SmoothScrollDown
+							| Action::ScrollLogDown),
+						) if app.show_help => {
+							app.scroll_help(
+								ScrollDirection::Down,
+								action == Action::ScrollLogDown,
+							);
+						}
+						/* Scroll to the top/bottom of the help popup. */
+						Some(
+							action @ (Action::ScrollTop | Action::ScrollBottom),
+						) if app.show_help => {
+							app.scroll_help(
+								if action == Action::ScrollTop {
+									ScrollDirection::Top
+								} else {
+									ScrollDirection::Bottom
+								},
+								false,
+							);
+						}
+						/* Ignore the other keys while the help popup is shown. */
+						_ if app.show_help => {}
+						/* Select the highlighted option. */
+						_ if app.show_options && input == Key::Char('\n') => {
+							if let Ok(command) = ModuleCommand::try_from(
+								app.options
+									.selected()
+									.map(|(v, _)| v.to_string())
+									.unwrap_or_default(),
+							) {
+								if command == ModuleCommand::Load {
+									app.selected_block = Block::UserInput;
+									app.input_mode = InputMode::Load;
+									app.input_query = String::new();
+								} else {
+									kernel
+										.modules
+										.set_current_command(command, String::new());
+								}
+							} else {
+								match app.options.selected().map(|(v, _)| v.as_ref())
+								{
+									Some("dependent") => {
+										app.show_dependent_modules(
+											&mut kernel.modules,
+										);
+									}
+									Some("copy") => app.set_clipboard_contents(
+										&kernel.modules.current_name,
+									),
+									_ => {}
+								}
+							}
+						}
 						/* Quit. */
-						Key::Char('q')
-						| Key::Char('Q')
-						| Key::Ctrl('c')
-						| Key::Ctrl('d')
-						| Key::Esc => {
+						Some(Action::Quit) => {
 							if app.show_options {
 								app.show_options = false;
+							} else if app.confirm_quit {
+								app.quit_pending = true;
 							} else {
 								break;
 							}
 						}
+						/* Re-read the values of the module parameters. */
+						Some(Action::Refresh)
+							if app.selected_block == Block::ModuleInfo
+								&& kernel
+									.modules
+									.current_name
+									.starts_with("!Parameters") =>
+						{
+							app.show_module_parameters(&mut kernel.modules);
+						}
 						/* Refresh. */
-						Key::Char('r') | Key::Char('R') | Key::F(5) => {
+						Some(Action::Refresh) => {
 							app.refresh();
-							kernel.refresh();
+							app.refresh_pending = app.paused;
+							kernel.refresh(&events.tx);
+						}
+						/* Pause/resume the auto-refresh. */
+						Some(Action::Pause) => {
+							app.paused = !app.paused;
 						}
 						/* Show help message. */
-						Key::Char('?') | Key::F(1) => {
-							app.show_help_message(&mut kernel.modules);
+						Some(Action::Help) => {
+							app.show_help_message();
+						}
+						/* Show the command palette. */
+						Some(Action::Palette) => {
+							app.show_palette();
 						}
-						Key::Char('m') | Key::Char('o') => {
+						/* Show the options menu. */
+						Some(Action::Options) => {
 							app.show_options = true;
 							hide_options = false;
 						}
 						/* Scroll the selected block up. */
-						Key::Up
-						| Key::Char('k')
-						| Key::Char('K')
-						| Key::Alt('k')
-						| Key::Alt('K') => {
+						Some(
+							action @ (Action::ScrollUp | Action::SmoothScrollUp),
+						) => {
 							if app.show_options {
 								app.options.previous();
 								continue;
@@ -150,29 +362,27 @@ where
 								app.options.state.select(Some(0));
 							}
 							match app.selected_block {
-								Block::ModuleTable => {
-									kernel.modules.scroll_list(ScrollDirection::Up)
-								}
+								Block::ModuleTable => kernel.modules.scroll_list_by(
+									ScrollDirection::Up,
+									app.scroll_step(action),
+								),
 								Block::ModuleInfo => kernel.modules.scroll_mod_info(
 									ScrollDirection::Up,
-									input == Key::Alt('k') || input == Key::Alt('K'),
+									action == Action::SmoothScrollUp,
 								),
 								Block::Activities => {
 									kernel.logs.scroll(
 										ScrollDirection::Up,
-										input == Key::Alt('k')
-											|| input == Key::Alt('K'),
+										action == Action::SmoothScrollUp,
 									);
 								}
 								_ => {}
 							}
 						}
 						/* Scroll the selected block down. */
-						Key::Down
-						| Key::Char('j')
-						| Key::Char('J')
-						| Key::Alt('j')
-						| Key::Alt('J') => {
+						Some(
+							action @ (Action::ScrollDown | Action::SmoothScrollDown),
+						) => {
 							if app.show_options {
 								app.options.next();
 								continue;
@@ -180,40 +390,46 @@ where
 								app.options.state.select(Some(0));
 							}
 							match app.selected_block {
-								Block::ModuleTable => {
-									kernel.modules.scroll_list(ScrollDirection::Down)
-								}
+								Block::ModuleTable => kernel.modules.scroll_list_by(
+									ScrollDirection::Down,
+									app.scroll_step(action),
+								),
 								Block::ModuleInfo => kernel.modules.scroll_mod_info(
 									ScrollDirection::Down,
-									input == Key::Alt('j') || input == Key::Alt('J'),
+									action == Action::SmoothScrollDown,
 								),
 								Block::Activities => {
 									kernel.logs.scroll(
 										ScrollDirection::Down,
-										input == Key::Alt('j')
-											|| input == Key::Alt('J'),
+										action == Action::SmoothScrollDown,
 									);
 								}
 								_ => {}
 							}
 						}
+						/* Select the previous terminal block. */
+						Some(Action::PreviousBlock) => {
+							app.select_next_block(false);
+						}
 						/* Select the next terminal block. */
-						Key::Left | Key::Char('h') | Key::Char('H') => {
-							app.selected_block = match app.selected_block.previous()
-							{
-								Some(v) => v,
-								None => Block::last().unwrap(),
-							}
+						Some(Action::NextBlock) => {
+							app.select_next_block(true);
 						}
-						/* Select the previous terminal block. */
-						Key::Right | Key::Char('l') | Key::Char('L') => {
-							app.selected_block = match app.selected_block.next() {
-								Some(v) => v,
-								None => Block::first().unwrap(),
-							}
+						/* Switch the focus between the module list and the kernel activities. */
+						Some(Action::SwitchFocus) => {
+							let block = if app.no_log {
+								Block::ModuleInfo
+							} else {
+								Block::Activities
+							};
+							app.selected_block = if app.selected_block == block {
+								Block::ModuleTable
+							} else {
+								block
+							};
 						}
 						/* Expand the selected block. */
-						Key::Alt('e') => {
+						Some(Action::ExpandBlock) => {
 							let block_size = app.block_size();
 							if *block_size < 95 {
 								*block_size += 5;
@@ -222,132 +438,357 @@ where
 							}
 						}
 						/* Shrink the selected block. */
-						Key::Alt('s') => {
+						Some(Action::ShrinkBlock) => {
 							let block_size = app.block_size();
-							*block_size =
-								(*block_size).checked_sub(5).unwrap_or_default()
+							*block_size = (*block_size).saturating_sub(5)
 						}
 						/* Change the block position. */
-						Key::Ctrl('x') => {
-							if app.block_index == 2 {
+						Some(Action::BlockPosition) => {
+							if app.block_index >= app.block_count() - 1 {
 								app.block_index = 0;
 							} else {
 								app.block_index += 1;
 							}
 						}
 						/* Scroll to the top of the module list. */
-						Key::Ctrl('t') | Key::Home => {
+						Some(Action::ScrollTop) => {
 							app.options.state.select(Some(0));
 							app.selected_block = Block::ModuleTable;
 							kernel.modules.scroll_list(ScrollDirection::Top)
 						}
 						/* Scroll to the bottom of the module list. */
-						Key::Ctrl('b') | Key::End => {
+						Some(Action::ScrollBottom) => {
 							app.options.state.select(Some(0));
 							app.selected_block = Block::ModuleTable;
 							kernel.modules.scroll_list(ScrollDirection::Bottom)
 						}
 						/* Scroll kernel activities up. */
-						Key::PageUp => {
+						Some(Action::ScrollLogUp) => {
 							app.selected_block = Block::Activities;
-							kernel.logs.scroll(ScrollDirection::Up, false);
+							match app.scroll.page {
+								Some(page) => {
+									kernel.logs.scroll_by(ScrollDirection::Up, page)
+								}
+								None => {
+									kernel.logs.scroll(ScrollDirection::Up, false)
+								}
+							}
 						}
 						/* Scroll kernel activities down. */
-						Key::PageDown => {
+						Some(Action::ScrollLogDown) => {
 							app.selected_block = Block::Activities;
-							kernel.logs.scroll(ScrollDirection::Down, false);
+							match app.scroll.page {
+								Some(page) => kernel
+									.logs
+									.scroll_by(ScrollDirection::Down, page),
+								None => {
+									kernel.logs.scroll(ScrollDirection::Down, false)
+								}
+							}
 						}
 						/* Scroll kernel activities left. */
-						Key::Alt('h') | Key::Alt('H') => {
+						Some(Action::ScrollLogLeft) => {
 							app.selected_block = Block::Activities;
 							kernel.logs.scroll(ScrollDirection::Left, false);
 						}
 						/* Scroll kernel activities right. */
-						Key::Alt('l') | Key::Alt('L') => {
+						Some(Action::ScrollLogRight) => {
 							app.selected_block = Block::Activities;
 							kernel.logs.scroll(ScrollDirection::Right, false);
 						}
 						/* Scroll module information up. */
-						Key::Char('<') | Key::Alt(' ') => {
+						Some(Action::ScrollInfoUp) => {
 							app.selected_block = Block::ModuleInfo;
 							kernel
 								.modules
 								.scroll_mod_info(ScrollDirection::Up, false)
 						}
 						/* Scroll module information down. */
-						Key::Char('>') | Key::Char(' ') => {
+						Some(Action::ScrollInfoDown) => {
 							app.selected_block = Block::ModuleInfo;
 							kernel
 								.modules
 								.scroll_mod_info(ScrollDirection::Down, false)
 						}
+						/* Switch between the module information, dependency tree and parameters. */
+						Some(Action::NextInfo)
+							if app.selected_block == Block::ModuleInfo =>
+						{
+							let current_name = &kernel.modules.current_name;
+							if current_name.starts_with("!Dependency tree") {
+								app.show_module_parameters(&mut kernel.modules);
+							} else if current_name.starts_with("!Parameters") {
+								app.show_module_aliases(&mut kernel.modules);
+							} else if current_name.starts_with("!Aliases") {
+								kernel.modules.select_module(kernel.modules.index);
+							} else {
+								app.show_dependency_tree(&mut kernel.modules);
+							}
+						}
 						/* Show the next kernel information. */
-						Key::Char('\\') | Key::Char('\t') | Key::BackTab => {
+						Some(Action::NextInfo) => {
 							kernel.info.next();
 						}
 						/* Display the dependent modules. */
-						Key::Char('d') | Key::Alt('d') => {
+						Some(Action::Dependent) => {
 							app.show_dependent_modules(&mut kernel.modules);
 						}
+						/* Switch to the next filter of the module list. */
+						Some(Action::Filter) => {
+							let _ = kernel.modules.next_filter();
+						}
+						/* Edit the size filter and keep the search query. */
+						Some(Action::SizeFilter) => {
+							app.selected_block = Block::UserInput;
+							app.input_mode = InputMode::SizeFilter;
+							app.saved_query = Some(mem::replace(
+								&mut app.input_query,
+								kernel
+									.modules
+									.args
+									.size_filter
+									.map(|v| v.to_string())
+									.unwrap_or_default(),
+							));
+						}
+						/* Toggle the visibility of the built-in modules. */
+						Some(Action::Builtin) => {
+							let _ = kernel.modules.toggle_builtin();
+						}
+						/* Toggle the visibility of the modules that are in use. */
+						Some(Action::HideUsed) => {
+							let _ = kernel.modules.toggle_used();
+						}
+						/* Add/remove the bookmark of the selected module. */
+						Some(Action::Bookmark) => {
+							if let Some(result) = kernel.modules.toggle_bookmark() {
+								app.set_status(result);
+							}
+						}
+						/* Toggle the list of the bookmarked modules. */
+						Some(Action::Bookmarks) => {
+							let _ = kernel.modules.toggle_bookmarks();
+						}
+						/* Expand/collapse the group of the selected module. */
+						Some(Action::Group) if !kernel.modules.toggle_group() => {
+							app.set_status(String::from(i18n::text(
+								"status.not_in_group",
+							)));
+						}
+						/* Expand/collapse all the module groups. */
+						Some(Action::Groups) if !kernel.modules.toggle_groups() => {
+							app.set_status(String::from(
+								"no module groups are configured",
+							));
+						}
+						/* Show/hide the columns of the module table. */
+						Some(Action::SizeColumn) => {
+							let visible = app.toggle_column(Column::Size);
+							app.set_status(format!(
+								"size column {}",
+								if visible { "shown" } else { "hidden" }
+							));
+						}
+						Some(Action::UsedByColumn) => {
+							let visible = app.toggle_column(Column::UsedBy);
+							app.set_status(format!(
+								"used by column {}",
+								if visible { "shown" } else { "hidden" }
+							));
+						}
+						/* Switch to the next sort type of the module list. */
+						Some(Action::Sort) => {
+							let _ = kernel.modules.next_sort();
+						}
+						/* Toggle the sort direction of the module list. */
+						Some(Action::SortDirection) => {
+							kernel.modules.toggle_sort_direction();
+						}
+						/* Toggle the display format of the module sizes. */
+						Some(Action::SizeFormat) => {
+							kernel.modules.toggle_size_format();
+						}
+						/* Cycle the minimum level of the kernel logs. */
+						Some(Action::LogLevel) => {
+							kernel.logs.next_level();
+						}
+						/* Toggle following the new kernel activities. */
+						Some(Action::Follow) => {
+							kernel.logs.toggle_follow();
+						}
+						/* Toggle showing the newest kernel activities first. */
+						Some(Action::ReverseLogs) => {
+							kernel.logs.toggle_reverse();
+						}
+						/* Save the kernel activities to a file. */
+						Some(Action::SaveLogs) => {
+							let result =
+								kernel.logs.save(config.log_file.as_deref());
+							app.show_saved_logs(&mut kernel.modules, result);
+						}
+						/* Disable the module commands in read-only mode. */
+						Some(action) if app.read_only && action.is_mutating() => {
+							app.show_read_only_status();
+						}
+						/* Revert the last executed module command. */
+						Some(Action::Undo) => {
+							if let Err(e) = kernel.modules.set_undo_command() {
+								app.show_result(Err(e));
+							}
+						}
+						/* Save a snapshot of the loaded modules to a file. */
+						Some(Action::Snapshot) => {
+							kernel.modules.save_snapshot_async(&events.tx);
+						}
 						/* Clear the kernel ring buffer. */
-						Key::Ctrl('l')
-						| Key::Ctrl('u')
-						| Key::Alt('c')
-						| Key::Alt('C') => {
+						Some(Action::Clear) => {
 							kernel.modules.set_current_command(
 								ModuleCommand::Clear,
 								String::new(),
 							);
 						}
+						/* Update the module dependencies. */
+						Some(Action::Depmod) => {
+							kernel.modules.set_current_command(
+								ModuleCommand::Depmod,
+								String::new(),
+							);
+						}
+						/* Select the module for the batch commands. */
+						Some(Action::Mark) => {
+							kernel.modules.toggle_mark();
+						}
 						/* Unload kernel module. */
-						Key::Char('u')
-						| Key::Char('U')
-						| Key::Char('-')
-						| Key::Backspace
-						| Key::Ctrl('h') => {
+						Some(Action::Unload) => {
 							kernel.modules.set_current_command(
 								ModuleCommand::Unload,
 								String::new(),
 							);
 						}
+						/* Force unload kernel module. */
+						Some(Action::ForceUnload) => {
+							kernel.modules.set_current_command(
+								ModuleCommand::ForceUnload,
+								String::new(),
+							);
+						}
 						/* Blacklist kernel module. */
-						Key::Char('x')
-						| Key::Char('X')
-						| Key::Char('b')
-						| Key::Char('B')
-						| Key::Delete => {
+						Some(Action::Blacklist) => {
 							kernel.modules.set_current_command(
 								ModuleCommand::Blacklist,
 								String::new(),
 							);
 						}
+						/* Remove kernel module from blacklist. */
+						Some(Action::Unblacklist) => {
+							kernel.modules.set_current_command(
+								ModuleCommand::Unblacklist,
+								String::new(),
+							);
+						}
+						/* Add kernel module to the boot modules. */
+						Some(Action::AddBoot) => {
+							kernel.modules.set_current_command(
+								ModuleCommand::AddBoot,
+								String::new(),
+							);
+						}
+						/* Remove kernel module from the boot modules. */
+						Some(Action::RemoveBoot) => {
+							kernel.modules.set_current_command(
+								ModuleCommand::RemoveBoot,
+								String::new(),
+							);
+						}
 						/* Reload kernel module. */
-						Key::Ctrl('r')
-						| Key::Ctrl('R')
-						| Key::Alt('r')
-						| Key::Alt('R') => {
+						Some(Action::Reload) => {
 							kernel.modules.set_current_command(
 								ModuleCommand::Reload,
 								String::new(),
 							);
+							/* Reload the module without confirmation if it is allowed. */
+							if config.confirm.skip_reload
+								&& kernel.modules.marked.is_empty()
+								&& kernel.modules.command == ModuleCommand::Reload
+								&& kernel.modules.protected_module(false).is_none()
+							{
+								kernel.modules.execute_command_async(&events.tx);
+							}
 						}
-						/* Execute the current command. */
-						Key::Char('y') | Key::Char('Y') => {
-							if kernel.modules.execute_command() {
-								events
-									.tx
-									.send(Event::Input(Key::Char('r')))
-									.unwrap();
+						/* Ask for the name of the protected module before unloading it. */
+						Some(
+							action @ (Action::Execute | Action::ExecuteRecursive),
+						) if (action == Action::Execute
+							&& !kernel.modules.command.is_none()
+							|| kernel.modules.has_recursive_unload())
+							&& kernel
+								.modules
+								.protected_module(action == Action::ExecuteRecursive)
+								.is_some() =>
+						{
+							app.confirm_action = Some(action);
+							if let Some(name) = kernel
+								.modules
+								.protected_module(action == Action::ExecuteRecursive)
+							{
+								app.set_status(format!(
+									"type '{name}' for confirming"
+								));
 							}
+							app.selected_block = Block::UserInput;
+							app.input_mode = InputMode::Confirm;
+							app.saved_query = Some(mem::take(&mut app.input_query));
+						}
+						/* Execute the current command. */
+						Some(Action::Execute)
+							if !kernel.modules.command.is_none() =>
+						{
+							kernel.modules.execute_command_async(&events.tx);
+						}
+						/* Unload the current module with its dependent modules. */
+						Some(Action::ExecuteRecursive)
+							if kernel.modules.has_recursive_unload() =>
+						{
+							kernel
+								.modules
+								.execute_recursive_unload_async(&events.tx);
 						}
 						/* Cancel the execution of current command. */
-						Key::Char('n') | Key::Char('N') => {
-							if kernel.modules.cancel_execution() {
-								app.selected_block = Block::ModuleTable;
-							}
+						Some(Action::Cancel)
+							if kernel.modules.cancel_execution() =>
+						{
+							app.selected_block = Block::ModuleTable;
+						}
+						/* Jump to the next/previous match of the log search. */
+						Some(
+							action @ (Action::NextMatch | Action::PreviousMatch),
+						) if app.selected_block == Block::Activities
+							&& !kernel.logs.query.is_empty() =>
+						{
+							kernel.logs.find_match(action == Action::NextMatch);
+						}
+						/* Jump to the next/previous module that matches the search query. */
+						Some(
+							action @ (Action::NextMatch | Action::PreviousMatch),
+						) if app.selected_block == Block::ModuleTable
+							&& !app.input_query.is_empty()
+							&& !kernel.modules.list.is_empty() =>
+						{
+							kernel.modules.scroll_list(
+								if action == Action::PreviousMatch {
+									ScrollDirection::Up
+								} else {
+									ScrollDirection::Down
+								},
+							);
+							app.set_status(format!(
+								"match {}/{}",
+								kernel.modules.index + 1,
+								kernel.modules.list.len()
+							));
 						}
 						/* Copy the data in selected block to clipboard. */
-						Key::Char('c') | Key::Char('C') => {
+						Some(Action::Copy) => {
 							app.set_clipboard_contents(match app.selected_block {
 								Block::ModuleTable => &kernel.modules.current_name,
 								Block::ModuleInfo => {
@@ -359,113 +800,222 @@ where
 								_ => "",
 							});
 						}
-						/* Paste the clipboard contents and switch to search mode. */
-						Key::Char('v') | Key::Ctrl('V') | Key::Ctrl('v') => {
-							let clipboard_contents = app.get_clipboard_contents();
-							app.input_query += &clipboard_contents;
-							events.tx.send(Event::Input(Key::Char('\n'))).unwrap();
-							kernel.modules.index = 0;
+						/* Copy the information of the selected module as markdown. */
+						Some(Action::CopyInfo) => {
+							match kernel.modules.module_report() {
+								Some(report) => app.set_clipboard_contents(&report),
+								None => app.set_status(String::from(
+									"module information is not shown",
+								)),
+							}
 						}
-						/* User input mode. */
-						Key::Char('\n')
-						| Key::Char('s')
-						| Key::Char('S')
-						| Key::Char('i')
-						| Key::Char('I')
-						| Key::Char('+')
-						| Key::Char('/')
-						| Key::Insert => {
-							if input == Key::Char('\n') && app.show_options {
-								if let Ok(command) = ModuleCommand::try_from(
+						/* Copy the shell command of the pending or highlighted action. */
+						Some(Action::CopyCommand) => {
+							let command = if app.show_options {
+								ModuleCommand::try_from(
 									app.options
 										.selected()
 										.map(|(v, _)| v.to_string())
 										.unwrap_or_default(),
-								) {
-									if command == ModuleCommand::Load {
-										events
-											.tx
-											.send(Event::Input(Key::Char('+')))
-											.unwrap();
-									} else {
-										kernel.modules.set_current_command(
-											command,
-											String::new(),
-										);
-									}
-								} else {
-									match app
-										.options
-										.selected()
-										.map(|(v, _)| v.as_ref())
-									{
-										Some("dependent") => {
-											app.show_dependent_modules(
-												&mut kernel.modules,
-											);
-										}
-										Some("copy") => app.set_clipboard_contents(
-											&kernel.modules.current_name,
-										),
-										_ => {}
-									}
-								}
+								)
+								.unwrap_or(ModuleCommand::None)
 							} else {
-								app.selected_block = Block::UserInput;
-								app.input_mode = match input {
-									Key::Char('+')
-									| Key::Char('i')
-									| Key::Char('I')
-									| Key::Insert => InputMode::Load,
-									_ => InputMode::Search,
-								};
-								if input != Key::Char('\n') {
-									app.input_query = String::new();
+								ModuleCommand::Unload
+							};
+							match kernel.modules.shell_command(command) {
+								Some(cmd) => app.set_clipboard_contents(&cmd),
+								None => app.set_status(String::from(i18n::text(
+									"status.no_command",
+								))),
+							}
+						}
+						/* Open the documentation of the selected module. */
+						Some(Action::OpenDocs)
+							if !kernel.modules.current_name.starts_with('!') =>
+						{
+							let module_name =
+								kernel.modules.current_name.to_string();
+							let target = docs::find_local_docs(
+								Path::new(DOCS_DIR),
+								&module_name,
+							)
+							.map(|v| v.display().to_string())
+							.unwrap_or_else(|| {
+								docs::docs_url(
+									config.docs_url.as_deref().unwrap_or(DOCS_URL),
+									&module_name,
+								)
+							});
+							/* Show the target for copying it if it cannot be opened. */
+							app.set_status(match docs::open(&target) {
+								Ok(()) => format!("opening {target}"),
+								Err(_) => format!("documentation: {target}"),
+							});
+						}
+						/* Paste the clipboard contents and switch to search mode. */
+						Some(Action::Paste) => {
+							let clipboard_contents = app.get_clipboard_contents();
+							app.input_query += &clipboard_contents;
+							app.selected_block = Block::UserInput;
+							app.input_mode = InputMode::Search;
+							kernel.modules.index = 0;
+						}
+						/* Edit the selected module parameter. */
+						Some(Action::Search)
+							if app.selected_block == Block::ModuleInfo
+								&& input == Key::Char('\n')
+								&& kernel.modules.is_showing_parameters() =>
+						{
+							if app.read_only {
+								app.show_read_only_status();
+							} else {
+								match kernel.modules.get_editable_parameter() {
+									Ok(value) => {
+										app.selected_block = Block::UserInput;
+										app.input_mode = InputMode::Parameter;
+										app.input_query = value;
+									}
+									Err(e) => app.show_result(Err(e)),
 								}
 							}
 						}
+						/* Select the highlighted dependent module. */
+						Some(Action::Search)
+							if app.selected_block == Block::ModuleInfo
+								&& input == Key::Char('\n')
+								&& kernel.modules.is_showing_dependents() =>
+						{
+							match kernel.modules.select_dependent() {
+								Ok(()) => app.selected_block = Block::ModuleTable,
+								Err(e) => app.show_result(Err(e)),
+							}
+						}
+						/* Search in the kernel activities. */
+						Some(Action::Search)
+							if app.selected_block == Block::Activities =>
+						{
+							app.selected_block = Block::UserInput;
+							app.input_mode = InputMode::LogSearch;
+							app.input_query.clone_from(&kernel.logs.query);
+						}
+						/* User input mode. */
+						Some(
+							action @ (Action::Search
+							| Action::Load
+							| Action::LoadFile),
+						) => {
+							app.selected_block = Block::UserInput;
+							app.input_mode = match action {
+								Action::Load => InputMode::Load,
+								Action::LoadFile => InputMode::LoadFile,
+								_ => InputMode::Search,
+							};
+							if input != Key::Char('\n') {
+								app.input_query = String::new();
+							}
+						}
 						/* Other character input. */
-						Key::Char(v) => {
-							/* Check if input is a number except zero. */
-							let index = v.to_digit(10).unwrap_or(0);
-							/* Show the used module info at given index. */
-							if index != 0 && !kernel.modules.list.is_empty() {
-								app.selected_block = Block::ModuleTable;
-								kernel.modules.show_used_module(index as usize - 1);
+						None => {
+							if let Key::Char(v) = input {
+								/* Check if input is a number except zero. */
+								let index = v.to_digit(10).unwrap_or(0);
+								/* Show the used module info at given index. */
+								if index != 0 && !kernel.modules.list.is_empty() {
+									app.selected_block = Block::ModuleTable;
+									kernel
+										.modules
+										.show_used_module(index as usize - 1);
+								}
 							}
 						}
 						_ => {}
 					}
+					/* Switch to search mode if the input block is selected. */
+					if app.selected_block == Block::UserInput
+						&& app.input_mode.is_none()
+					{
+						app.input_mode = InputMode::Search;
+					}
 				} else {
+					/* Hide the completions on the keys other than tab. */
+					if input != Key::Char('\t') {
+						app.completions.clear();
+					}
 					/* User input mode. */
 					match input {
-						/* Quit with ctrl-d. */
-						Key::Ctrl('d') => {
-							break;
+						/* Quit with ctrl-d unless it is removed from the quit keys. */
+						Key::Ctrl('d')
+							if app
+								.key_bindings
+								.keys(Action::Quit)
+								.contains(&Key::Ctrl('d')) =>
+						{
+							if app.confirm_quit {
+								app.quit_pending = true;
+							} else {
+								break;
+							}
+						}
+						/* Recall the previous/next query of the search history. */
+						Key::Up | Key::Down
+							if app
+								.input_history()
+								.is_some_and(|v| !v.is_empty()) =>
+						{
+							let recalled = app.recall_query(input == Key::Up);
+							if recalled && app.input_mode == InputMode::Search {
+								kernel.modules.index = 0;
+							}
 						}
 						/* Switch to the previous input mode. */
-						Key::Up => {
-							app.input_mode = match app.input_mode.previous() {
-								Some(v) => v,
-								None => InputMode::last().unwrap(),
-							};
-							if app.input_mode.is_none() {
-								app.input_mode = InputMode::last().unwrap();
+						Key::Up | Key::AltUp if app.input_mode.is_switchable() => {
+							loop {
+								app.input_mode = match app.input_mode.previous() {
+									Some(v) => v,
+									None => InputMode::last().unwrap(),
+								};
+								if app.input_mode.is_switchable() {
+									break;
+								}
 							}
 							app.input_query = String::new();
 						}
 						/* Switch to the next input mode. */
-						Key::Down => {
-							app.input_mode = match app.input_mode.next() {
-								Some(v) => v,
-								None => InputMode::first()
-									.and_then(|v| v.next())
-									.unwrap(),
-							};
+						Key::Down | Key::AltDown
+							if app.input_mode.is_switchable() =>
+						{
+							loop {
+								app.input_mode = match app.input_mode.next() {
+									Some(v) => v,
+									None => InputMode::first().unwrap(),
+								};
+								if app.input_mode.is_switchable() {
+									break;
+								}
+							}
 							app.input_query = String::new();
 						}
+						/* Switch to the next mode of the module search. */
+						Key::Ctrl('r') => {
+							app.search_mode =
+								app.search_mode.next().unwrap_or_default();
+							kernel.modules.index = 0;
+						}
+						/* Toggle searching the descriptions and aliases. */
+						Key::Alt('d') if app.input_mode == InputMode::Search => {
+							app.search_scope =
+								app.search_scope.next().unwrap_or_default();
+							if app.search_scope == SearchScope::All {
+								kernel.modules.index_async(&events.tx);
+							}
+							kernel.modules.index = 0;
+						}
+						/* Toggle the case-sensitive log search. */
+						Key::Alt('c') if app.input_mode == InputMode::LogSearch => {
+							app.case_sensitive = !app.case_sensitive;
+						}
 						/* Copy input query to the clipboard. */
-						Key::Ctrl('c') => {
+						Key::Alt('w') => {
 							let query = app.input_query.clone();
 							app.set_clipboard_contents(&query);
 						}
@@ -474,6 +1024,10 @@ where
 							let clipboard_contents = app.get_clipboard_contents();
 							app.input_query += &clipboard_contents;
 						}
+						/* Complete the module name while loading a module. */
+						Key::Char('\t') if app.input_mode == InputMode::Load => {
+							app.complete_module_name(&mut kernel.modules);
+						}
 						/* Exit user input mode. */
 						Key::Char('\n')
 						| Key::Char('\t')
@@ -481,6 +1035,11 @@ where
 						| Key::F(1)
 						| Key::Right
 						| Key::Left => {
+							/* Remember the search query. */
+							let query = app.input_query.clone();
+							if let Some(history) = app.input_history() {
+								history.push(&query);
+							}
 							/* Select the next eligible block for action. */
 							app.selected_block = match input {
 								Key::Left => match app.selected_block.previous() {
@@ -488,15 +1047,17 @@ where
 									None => Block::last().unwrap(),
 								},
 								Key::Char('\n') => match app.input_mode {
-									InputMode::Load
+									InputMode::Load | InputMode::LoadFile
 										if !app.input_query.is_empty() =>
 									{
 										Block::ModuleInfo
 									}
+									InputMode::LogSearch => Block::Activities,
+									InputMode::Parameter => Block::ModuleInfo,
 									_ => Block::ModuleTable,
 								},
 								Key::Char('?') | Key::F(1) => {
-									app.show_help_message(&mut kernel.modules);
+									app.show_help_message();
 									app.input_mode = InputMode::None;
 									Block::ModuleTable
 								}
@@ -508,6 +1069,15 @@ where
 							{
 								kernel.modules.scroll_list(ScrollDirection::Top);
 							/* Load kernel module. */
+							} else if matches!(
+								app.input_mode,
+								InputMode::Load | InputMode::LoadFile
+							) && !app.input_query.is_empty()
+								&& app.read_only
+							{
+								app.show_read_only_status();
+								app.selected_block = Block::ModuleTable;
+								app.input_query = String::new();
 							} else if app.input_mode == InputMode::Load
 								&& !app.input_query.is_empty()
 							{
@@ -516,6 +1086,70 @@ where
 									app.input_query,
 								);
 								app.input_query = String::new();
+							/* Load the kernel modules listed in the file. */
+							} else if app.input_mode == InputMode::LoadFile
+								&& !app.input_query.is_empty()
+							{
+								kernel
+									.modules
+									.set_load_file_command(&app.input_query);
+								app.input_query = String::new();
+							/* Write the value of the module parameter. */
+							} else if app.input_mode == InputMode::Parameter {
+								if input == Key::Char('\n') {
+									let result = kernel
+										.modules
+										.set_parameter(&app.input_query);
+									app.show_result(result);
+								}
+								app.selected_block = Block::ModuleInfo;
+								app.input_query = String::new();
+							/* Apply the size filter and restore the search query. */
+							} else if app.input_mode == InputMode::SizeFilter {
+								if input == Key::Char('\n') {
+									if let Err(e) = kernel
+										.modules
+										.set_size_filter(&app.input_query)
+									{
+										app.show_result(Err(e.to_string()));
+									}
+								}
+								app.input_query =
+									app.saved_query.take().unwrap_or_default();
+							/* Execute the command if the protected module name is typed. */
+							} else if app.input_mode == InputMode::Confirm {
+								let action = app.confirm_action.take();
+								let recursive =
+									action == Some(Action::ExecuteRecursive);
+								if input == Key::Char('\n')
+									&& kernel
+										.modules
+										.protected_module(recursive)
+										.as_deref() == Some(app.input_query.trim())
+								{
+									if recursive {
+										kernel
+											.modules
+											.execute_recursive_unload_async(
+												&events.tx,
+											);
+									} else {
+										kernel
+											.modules
+											.execute_command_async(&events.tx);
+									}
+								} else if kernel.modules.cancel_execution()
+									&& !app.input_query.is_empty()
+								{
+									app.set_status(String::from(
+										"command is cancelled (module name does not match)",
+									));
+								}
+								app.input_query =
+									app.saved_query.take().unwrap_or_default();
+							/* Keep the query of the log search. */
+							} else if app.input_mode == InputMode::LogSearch {
+								app.input_query = String::new();
 							}
 							/* Set the input mode flag. */
 							app.input_mode = InputMode::None;
@@ -523,17 +1157,58 @@ where
 						/* Append character to input query. */
 						Key::Char(c) => {
 							app.input_query.push(c);
-							kernel.modules.index = 0;
+							if !matches!(
+								app.input_mode,
+								InputMode::Parameter
+									| InputMode::SizeFilter | InputMode::Confirm
+							) {
+								kernel.modules.index = 0;
+							}
 						}
 						/* Delete the last character from input query. */
 						Key::Backspace | Key::Ctrl('h') => {
 							app.input_query.pop();
-							kernel.modules.index = 0;
+							if !matches!(
+								app.input_mode,
+								InputMode::Parameter
+									| InputMode::SizeFilter | InputMode::Confirm
+							) {
+								kernel.modules.index = 0;
+							}
 						}
 						/* Clear the input query. */
 						Key::Delete | Key::Ctrl('l') => {
 							app.input_query = String::new();
-							kernel.modules.index = 0;
+							if !matches!(
+								app.input_mode,
+								InputMode::Parameter
+									| InputMode::SizeFilter | InputMode::Confirm
+							) {
+								kernel.modules.index = 0;
+							}
+						}
+						/* Clear the search query and keep the selected module. */
+						Key::Esc if app.input_mode == InputMode::Search => {
+							app.search_history.reset();
+							app.input_query = String::new();
+							app.input_mode = InputMode::None;
+							app.selected_block = Block::ModuleTable;
+							let current_name =
+								kernel.modules.current_name.to_string();
+							kernel.modules.select_unfiltered(&current_name);
+						}
+						/* Cancel editing the size filter. */
+						Key::Esc if app.input_mode == InputMode::SizeFilter => {
+							app.input_query =
+								app.saved_query.take().unwrap_or_default();
+							app.input_mode = InputMode::None;
+							app.selected_block = Block::ModuleTable;
+						}
+						/* Cancel editing the module parameter. */
+						Key::Esc if app.input_mode == InputMode::Parameter => {
+							app.input_query = String::new();
+							app.input_mode = InputMode::None;
+							app.selected_block = Block::ModuleInfo;
 						}
 						/* Clear the input query and exit user input mode. */
 						Key::Esc => {
@@ -542,34 +1217,153 @@ where
 						}
 						_ => {}
 					}
+					/* Apply the query to the kernel activities while searching them. */
+					if app.input_mode == InputMode::LogSearch {
+						kernel.logs.set_query(&app.input_query, app.case_sensitive);
+					}
 				}
 				if hide_options {
 					app.show_options = false;
 				}
 			}
-			/* Kernel events. */
-			Event::Kernel(logs) => {
-				kernel.logs.output = logs;
+			/* Mouse events. */
+			Event::Mouse(MouseEvent::Press(button, x, y))
+				if app.input_mode.is_none() =>
+			{
+				/* Terminal coordinates of the mouse events start from 1. */
+				let block_area =
+					app.block_at(x.saturating_sub(1), y.saturating_sub(1));
+				let direction = match button {
+					MouseButton::WheelUp => ScrollDirection::Up,
+					MouseButton::WheelDown => ScrollDirection::Down,
+					MouseButton::Left if app.show_help => continue,
+					MouseButton::Left => {
+						app.show_options = false;
+						if let Some((block, area)) = block_area {
+							app.selected_block = block;
+							if block == Block::ModuleTable {
+								if let Some(index) = App::module_at(
+									area,
+									y.saturating_sub(1),
+									&kernel.modules,
+								) {
+									kernel.modules.select_module(index);
+								}
+							}
+						}
+						continue;
+					}
+					_ => continue,
+				};
+				if app.show_help {
+					app.scroll_help(direction, false);
+					continue;
+				}
+				if app.show_options {
+					if direction == ScrollDirection::Up {
+						app.options.previous();
+					} else {
+						app.options.next();
+					}
+					continue;
+				}
+				if let Some((block, _)) = block_area {
+					app.selected_block = block;
+					match block {
+						Block::ModuleTable => kernel.modules.scroll_list(direction),
+						Block::ModuleInfo => {
+							kernel.modules.scroll_mod_info(direction, false)
+						}
+						Block::Activities => kernel.logs.scroll(direction, false),
+						Block::UserInput => {}
+					}
+				}
+			}
+			/* Dismiss the expired notifications and confirmations. */
+			Event::Tick => {
+				app.on_tick();
+				if kernel.modules.cancel_expired_execution() {
+					app.set_status(String::from(i18n::text("status.cancelled")));
+				}
+				/* Draw once more after the animations are finished for clearing them. */
+				let was_animating = animating;
+				animating = app.is_animating(&kernel);
+				redraw = animating || was_animating || terminal.size()? != area;
+			}
+			/* Kernel events that are ignored while paused unless refreshed manually. */
+			Event::Kernel(logs) if !app.paused || app.refresh_pending => {
+				app.refresh_pending = false;
+				app.last_refresh = Instant::now();
+				kernel.logs.set_output(logs);
+			}
+			/* Modules that are loaded or unloaded by other processes. */
+			Event::ModulesChanged if !kernel.modules.refreshing && !app.paused => {
+				kernel.modules.refresh_async(&events.tx);
+			}
+			/* Kernel modules that are read in the background. */
+			Event::Modules(modules) => match modules {
+				Ok(modules) => {
+					app.last_refresh = Instant::now();
+					kernel.modules.set_modules(&modules);
+					/* Index the new modules for searching all fields. */
+					if app.search_scope == SearchScope::All {
+						kernel.modules.index_async(&events.tx);
+					}
+				}
+				Err(_) => kernel.modules.refreshing = false,
+			},
+			/* Searchable fields of the modules that are read in the background. */
+			Event::SearchIndex(entries) => kernel.modules.set_search_index(entries),
+			/* Snapshot that is saved in the background. */
+			Event::Snapshot(result) => app.show_result(
+				result.map(|path| format!("snapshot saved to {}", path.display())),
+			),
+			/* Result of the module command that is executed in the background. */
+			Event::Execution(result) => {
+				let command_executed = kernel.modules.finish_execution(result);
+				if let Some(result) = kernel.modules.status.take() {
+					app.show_result(result);
+				}
+				if command_executed {
+					app.refresh();
+					kernel.refresh_after_op(&config.refresh_after_op, &events.tx);
+				}
 			}
 			_ => {}
 		}
 	}
+	/* Stop the background indexing before quitting. */
+	kernel.modules.cancel_index();
+	/* Remember the view state for the next session. */
+	if !cfg!(test) && !config.demo {
+		if let Some(path) = State::path() {
+			let mut state = State::capture(&kernel.modules, config.theme.clone());
+			state.block =
+				Some(app.selected_block).filter(|v| *v != Block::UserInput);
+			if config.search.save_history {
+				state.search_history = app.search_history.entries().to_vec();
+				state.log_search_history = app.log_search_history.entries().to_vec();
+			}
+			state.save(&path)?;
+		}
+	}
 	Ok(())
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use clap::ArgMatches;
 	use ratatui::backend::TestBackend;
+	use std::sync::atomic::{AtomicBool, Ordering};
 	use std::sync::mpsc::Sender;
+	use std::sync::Arc;
 	use std::thread;
 	use std::time::Duration;
 	#[test]
 	fn test_tui() -> Result<(), Box<dyn Error>> {
-		let args = ArgMatches::default();
-		let kernel = Kernel::new(&args);
-		let events = Events::new(100, &kernel.logs);
+		let config = Config::default();
+		let kernel = Kernel::new(&config);
+		let events = Events::new(100, None, &kernel.logs);
 		let tx = events.tx.clone();
 		thread::spawn(move || {
 			/* Test the general keys. */
@@ -589,6 +1383,9 @@ mod tests {
 				Key::Char('u'),
 				Key::Ctrl('r'),
 				Key::Char('y'),
+				Key::Ctrl('p'),
+				Key::Char('r'),
+				Key::Ctrl('p'),
 				Key::PageUp,
 				Key::PageDown,
 				Key::Alt('l'),
@@ -626,7 +1423,7 @@ mod tests {
 				Key::Delete,
 				Key::Char('~'),
 				Key::Backspace,
-				Key::Ctrl('c'),
+				Key::Alt('w'),
 				Key::Ctrl('v'),
 				Key::Char('a'),
 				Key::Char('\n'),
@@ -643,7 +1440,43 @@ mod tests {
 			/* Exit. */
 			send_key(&tx, Key::Esc)
 		});
-		start_tui(Terminal::new(TestBackend::new(20, 10))?, kernel, &events)
+		start_tui(
+			Terminal::new(TestBackend::new(20, 10))?,
+			kernel,
+			&events,
+			&config,
+		)
+	}
+	#[test]
+	fn test_emergency_quit() -> Result<(), Box<dyn Error>> {
+		/* Search and load modes. */
+		for mode_key in [Key::Char('/'), Key::Char('+')] {
+			let config = Config::default();
+			let kernel = Kernel::new(&config);
+			let events = Events::new(100, None, &kernel.logs);
+			let tx = events.tx.clone();
+			let fallback = Arc::new(AtomicBool::new(false));
+			let fallback_sent = fallback.clone();
+			thread::spawn(move || {
+				for key in [mode_key, Key::Char('a'), EMERGENCY_QUIT_KEY] {
+					send_key(&tx, key);
+				}
+				/* Leave the input mode and quit if ctrl-c is ignored. */
+				thread::sleep(Duration::from_secs(5));
+				fallback_sent.store(true, Ordering::SeqCst);
+				for key in [Key::Esc, Key::Esc, Key::Char('q')] {
+					let _ = tx.send(Event::Input(key));
+				}
+			});
+			start_tui(
+				Terminal::new(TestBackend::new(20, 10))?,
+				kernel,
+				&events,
+				&config,
+			)?;
+			assert!(!fallback.load(Ordering::SeqCst));
+		}
+		Ok(())
 	}
 	/**
 	 * Try to send a key event until Sender succeeds.
@@ -1,11 +1,24 @@
 use kmon::args;
+use kmon::config::Config;
 use kmon::event::Events;
+use kmon::i18n::{self, Lang};
+#[cfg(target_os = "freebsd")]
+use kmon::kernel::bsd::BsdSource;
+use kmon::kernel::cmd::CommandArgs;
+use kmon::kernel::demo;
+use kmon::kernel::lkm::{self, ListArgs, PROC_MODULES};
+use kmon::kernel::source::DataSource;
 use kmon::kernel::Kernel;
+use kmon::output::{self, OutputFormat, EXIT_FAILURE, EXIT_SUCCESS, EXIT_USAGE};
+use kmon::state::State;
+use kmon::style::Theme;
 use kmon::util;
 use ratatui::backend::TermionBackend;
 use ratatui::Terminal;
 use std::error::Error;
-use std::io::stdout;
+use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
+use std::process;
 use termion::input::MouseTerminal;
 use termion::raw::IntoRawMode;
 use termion::screen::IntoAlternateScreen;
@@ -17,20 +30,183 @@ use termion::screen::IntoAlternateScreen;
  */
 fn main() -> Result<(), Box<dyn Error>> {
 	let args = args::get_args().get_matches();
-	let kernel = Kernel::new(&args);
+	if args.get_flag("version") {
+		print!("{}", output::version_info(args.get_flag("verbose")));
+		process::exit(EXIT_SUCCESS);
+	}
+	/* Session state is only restored for the terminal user interface. */
+	let interactive = !args.get_flag("demo")
+		&& args.get_one::<String>("output").is_none()
+		&& args.get_one::<PathBuf>("load-file").is_none()
+		&& args.get_one::<PathBuf>("snapshot").is_none()
+		&& !matches!(
+			args.subcommand_name(),
+			Some(
+				"completions"
+					| "mangen" | "diff"
+					| "report" | "info"
+					| "graph" | "config"
+			)
+		);
+	let state = State::path()
+		.filter(|_| interactive)
+		.map(|path| State::load(&path))
+		.unwrap_or_default();
+	let mut config = Config::new(&args, &state).unwrap_or_else(|e| {
+		eprintln!("{e}");
+		process::exit(EXIT_USAGE);
+	});
+	if let Some(path) = &config.command_log.path {
+		util::set_command_log(path, config.command_log.verbose).unwrap_or_else(
+			|e| {
+				eprintln!("{e}");
+				process::exit(EXIT_FAILURE);
+			},
+		);
+	}
+	i18n::set_lang(
+		config
+			.lang
+			.as_deref()
+			.and_then(|v| Lang::try_from(v).ok())
+			.unwrap_or_else(Lang::from_env),
+	);
+	if let Some(target) = &config.remote {
+		util::set_remote(target);
+	}
+	if let Some(("completions", matches)) = args.subcommand() {
+		if let Some(shell) = matches.get_one::<String>("shell") {
+			process::exit(output::print_completions(shell));
+		}
+	}
+	if let Some(("config", matches)) = args.subcommand() {
+		process::exit(output::print_config(&if matches.get_flag("defaults") {
+			Config::default()
+		} else {
+			config
+		}));
+	}
+	if let Some(("mangen", _)) = args.subcommand() {
+		process::exit(output::print_manpage());
+	}
+	if let Some(("diff", matches)) = args.subcommand() {
+		if let (Some(old), Some(new)) = (
+			matches.get_one::<PathBuf>("old"),
+			matches.get_one::<PathBuf>("new"),
+		) {
+			process::exit(output::print_snapshot_diff(old, new));
+		}
+	}
+	#[cfg(target_os = "freebsd")]
+	let source = if config.demo {
+		DataSource::new(demo::source())
+	} else {
+		DataSource::new(BsdSource)
+	};
+	#[cfg(not(target_os = "freebsd"))]
+	let source = if config.demo {
+		DataSource::new(demo::source())
+	} else {
+		DataSource::default()
+	};
+	/* Modules cannot be read without procfs and sysfs (e.g. in a chroot). */
+	if !interactive && !config.demo && !cfg!(target_os = "freebsd") {
+		if let Err(e) = lkm::check_filesystems(|v| util::path_exists(v)) {
+			eprintln!("error: {e}");
+			process::exit(EXIT_FAILURE);
+		}
+	}
+	if let Some(("report", matches)) = args.subcommand() {
+		if let Some(module_name) = matches.get_one::<String>("module") {
+			process::exit(output::print_module_report(&*source, module_name));
+		}
+	}
+	if let Some(("info", matches)) = args.subcommand() {
+		let names = matches
+			.get_many::<String>("module")
+			.into_iter()
+			.flatten()
+			.cloned()
+			.collect::<Vec<String>>();
+		process::exit(output::print_module_info(&*source, &names));
+	}
+	if let Some(("graph", matches)) = args.subcommand() {
+		process::exit(output::print_dependency_graph(
+			&*source,
+			&ListArgs::new(&config),
+			matches.get_one::<String>("root").map(String::as_str),
+		));
+	}
+	if let Some(format) = args
+		.get_one::<String>("output")
+		.and_then(|v| OutputFormat::try_from(v.as_str()).ok())
+	{
+		process::exit(output::print_modules(
+			&*source,
+			format,
+			&ListArgs::new(&config),
+		));
+	}
+	if let Some(cmd) = config
+		.privilege_cmd
+		.filter(|v| !v.is_available())
+		.and_then(|v| v.executable())
+	{
+		eprintln!("warning: '{cmd}' is not found in PATH");
+	}
+	/* Features that depend on the missing tools show their own errors. */
+	let executables = match config.remote {
+		Some(_) => vec!["ssh"],
+		None if cfg!(target_os = "freebsd") => vec!["kldstat"],
+		None => ["modinfo"]
+			.into_iter()
+			.chain(config.kernel_log.executable())
+			.collect::<Vec<&str>>(),
+	};
+	for cmd in util::missing_executables(&executables) {
+		eprintln!("warning: '{cmd}' is not found in PATH");
+	}
+	if let Some(path) = args.get_one::<PathBuf>("load-file") {
+		process::exit(output::load_modules(path, &CommandArgs::new(&config)));
+	}
+	if let Some(path) = args.get_one::<PathBuf>("snapshot") {
+		process::exit(output::save_snapshot(
+			&*source,
+			path,
+			&ListArgs::new(&config),
+		));
+	}
+	/* Use the light theme on the terminals with a light background. */
+	if config.detects_background() {
+		if let Some(background) = util::query_background_color() {
+			config.detected_theme =
+				Some(Theme::for_background(background).to_string());
+		}
+	}
+	let mut kernel = Kernel::with_source(&config, source);
+	kernel.modules.bookmarks.clone_from(&state.bookmarks);
+	if let Some(module_name) = &state.module {
+		kernel.modules.select_unfiltered(module_name);
+	}
 	let events = Events::new(
-		args.get_one::<String>("rate")
-			.unwrap()
-			.parse::<u64>()
-			.unwrap_or(250),
+		config.tickrate,
+		config.refresh_interval().filter(|_| !config.no_log),
 		&kernel.logs,
 	);
+	/* Fall back to the tick interval if the modules cannot be watched. */
+	if config.watch && !config.demo {
+		events.watch(Path::new(PROC_MODULES), &kernel.logs);
+	}
 	if !cfg!(test) {
 		util::setup_panic_hook()?;
 		let stdout = stdout().into_raw_mode()?.into_alternate_screen()?;
-		let stdout = MouseTerminal::from(stdout);
+		let stdout: Box<dyn Write> = if config.mouse {
+			Box::new(MouseTerminal::from(stdout))
+		} else {
+			Box::new(stdout)
+		};
 		let backend = TermionBackend::new(stdout);
-		kmon::start_tui(Terminal::new(backend)?, kernel, &events)
+		kmon::start_tui(Terminal::new(backend)?, kernel, &events, &config)
 	} else {
 		Ok(())
 	}
@@ -0,0 +1,171 @@
+use crate::config::Config;
+use crate::kernel::lkm::{self, ListArgs};
+use crate::util;
+use std::error::Error;
+
+/* Types of the module list that are exposed to the library users */
+pub use crate::kernel::lkm::{ListFilter, Module, SortType};
+
+/**
+ * Read the loaded kernel modules with the default listing properties.
+ *
+ * Modules are read from '/proc/modules' ('lsmod' is used if it cannot be
+ * read) and sorted by their size.
+ *
+ * @return Result
+ */
+pub fn list() -> Result<Vec<Module>, Box<dyn Error>> {
+	list_with(ListOptions::default())
+}
+
+/**
+ * Read the loaded kernel modules with the given listing properties.
+ *
+ * @param  options
+ * @return Result
+ */
+pub fn list_with(options: ListOptions) -> Result<Vec<Module>, Box<dyn Error>> {
+	let mut args = ListArgs::new(&Config::default());
+	args.sort = options.sort;
+	args.reverse = options.reverse;
+	args.filter = options.filter;
+	args.builtin = options.builtin;
+	lkm::read_modules(&args)
+}
+
+/**
+ * Parse the kernel modules from the contents of '/proc/modules'.
+ *
+ * Lines that cannot be parsed are skipped.
+ *
+ * @param  contents
+ * @return Vec
+ */
+pub fn parse(contents: &str) -> Vec<Module> {
+	lkm::parse_proc_modules(contents)
+}
+
+/**
+ * Read the information of a module with 'modinfo'.
+ *
+ * @param  module_name
+ * @return Result
+ */
+pub fn info(module_name: &str) -> Result<ModuleInfo, Box<dyn Error>> {
+	Ok(ModuleInfo::parse(&util::exec_cmd(
+		"modinfo",
+		&[module_name],
+	)?))
+}
+
+/* Listing properties of the kernel modules */
+#[derive(Clone, Copy, Debug)]
+pub struct ListOptions {
+	pub sort: SortType,
+	pub reverse: bool,
+	pub filter: ListFilter,
+	pub builtin: bool,
+}
+
+impl Default for ListOptions {
+	fn default() -> Self {
+		let args = ListArgs::new(&Config::default());
+		Self {
+			sort: args.sort,
+			reverse: args.reverse,
+			filter: args.filter,
+			builtin: args.builtin,
+		}
+	}
+}
+
+/* Fields of the module information that is printed by 'modinfo' */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModuleInfo {
+	pub path: Option<String>,
+	pub license: Option<String>,
+	pub authors: Vec<String>,
+	pub description: Option<String>,
+	pub version: Option<String>,
+	pub depends: Vec<String>,
+	pub aliases: Vec<String>,
+	pub parameters: Vec<(String, String)>,
+}
+
+impl ModuleInfo {
+	/**
+	 * Parse the module information from the output of 'modinfo'.
+	 *
+	 * @param  module_info
+	 * @return ModuleInfo
+	 */
+	pub fn parse(module_info: &str) -> Self {
+		let field = |name| {
+			lkm::info_field(module_info, name)
+				.first()
+				.map(|v| v.to_string())
+		};
+		Self {
+			path: field("filename").filter(|v| v != "(builtin)"),
+			license: field("license"),
+			authors: lkm::info_field(module_info, "author")
+				.into_iter()
+				.map(String::from)
+				.collect(),
+			description: Some(lkm::info_field(module_info, "description").join(" "))
+				.filter(|v| !v.is_empty()),
+			version: field("version"),
+			depends: lkm::info_field(module_info, "depends")
+				.into_iter()
+				.flat_map(|v| v.split(','))
+				.map(str::trim)
+				.filter(|v| !v.is_empty())
+				.map(String::from)
+				.collect(),
+			aliases: lkm::parse_aliases(module_info),
+			parameters: lkm::info_field(module_info, "parm")
+				.into_iter()
+				.map(|v| {
+					let (name, description) = v.split_once(':').unwrap_or((v, ""));
+					(name.trim().to_string(), description.trim().to_string())
+				})
+				.collect(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	#[test]
+	fn test_modules() {
+		let modules = parse("ext4 1000 2 jbd2,mbcache, Live 0xffff0000\ninvalid\n");
+		assert_eq!(1, modules.len());
+		assert_eq!(vec!["jbd2", "mbcache"], modules[0].dependents);
+		assert!(!ListOptions::default().reverse);
+		let module_info = ModuleInfo::parse(
+			"filename:       /lib/modules/ext4.ko\n\
+			author:         Remy Card\n\
+			author:         Theodore Ts'o\n\
+			description:    Fourth Extended Filesystem\n\
+			license:        GPL\n\
+			alias:          fs-ext4\n\
+			depends:        mbcache,jbd2\n\
+			parm:           debug:Enable debugging (int)\n",
+		);
+		assert_eq!(Some("/lib/modules/ext4.ko"), module_info.path.as_deref());
+		assert_eq!(2, module_info.authors.len());
+		assert_eq!(Some("GPL"), module_info.license.as_deref());
+		assert_eq!(None, module_info.version);
+		assert_eq!(vec!["mbcache", "jbd2"], module_info.depends);
+		assert_eq!(vec!["fs-ext4"], module_info.aliases);
+		assert_eq!(
+			vec![(
+				String::from("debug"),
+				String::from("Enable debugging (int)")
+			)],
+			module_info.parameters
+		);
+		assert_eq!(None, ModuleInfo::parse("filename: (builtin)\n").path);
+	}
+}
@@ -0,0 +1,637 @@
+use crate::args;
+use crate::config::Config;
+use crate::kernel::cmd::{
+	self, BatchResult, BatchStatus, CommandArgs, ModuleCommand,
+};
+use crate::kernel::deps::{DependencyGraph, Relation};
+use crate::kernel::lkm::{self, ListArgs, Module, SizeFormat};
+use crate::kernel::snapshot::{Snapshot, SnapshotDiff};
+use crate::kernel::source::ModuleSource;
+use clap_complete::Shell;
+use clap_mangen::Man;
+use serde_json::json;
+use std::io::{self, stdout, BufRead};
+use std::path::Path;
+use termion::color;
+
+/* Exit code of the successful non-interactive operations */
+pub const EXIT_SUCCESS: i32 = 0;
+
+/* Exit code of the failed non-interactive operations */
+pub const EXIT_FAILURE: i32 = 1;
+
+/* Exit code of the invalid arguments or configuration */
+pub const EXIT_USAGE: i32 = 2;
+
+/* Header row of the CSV output */
+const CSV_HEADER: &[&str] = &["name", "size", "refcount", "dependents"];
+
+/* Supported formats of the non-interactive output */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+	Json,
+	Csv,
+}
+
+impl TryFrom<&str> for OutputFormat {
+	type Error = ();
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		match s {
+			"json" => Ok(Self::Json),
+			"csv" => Ok(Self::Csv),
+			_ => Err(()),
+		}
+	}
+}
+
+impl OutputFormat {
+	/**
+	 * Format the given kernel modules.
+	 *
+	 * @param  modules
+	 * @param  size_format
+	 * @return String
+	 */
+	pub fn format_modules(
+		self,
+		modules: &[Module],
+		size_format: SizeFormat,
+	) -> String {
+		match self {
+			Self::Json => serde_json::to_string_pretty(modules).unwrap_or_default(),
+			Self::Csv => {
+				let mut rows = vec![CSV_HEADER.join(",")];
+				for module in modules {
+					rows.push(
+						[
+							module.name.to_string(),
+							size_format.format(module.size),
+							module.refcount.to_string(),
+							module.dependents.join(","),
+						]
+						.iter()
+						.map(|field| Self::escape_csv_field(field))
+						.collect::<Vec<String>>()
+						.join(","),
+					);
+				}
+				rows.join("\n")
+			}
+		}
+	}
+
+	/**
+	 * Format the given error message.
+	 *
+	 * @param  message
+	 * @return String
+	 */
+	pub fn format_error(self, message: &str) -> String {
+		match self {
+			Self::Json => json!({ "error": message }).to_string(),
+			Self::Csv => message.to_string(),
+		}
+	}
+
+	/**
+	 * Quote a CSV field if it contains special characters (RFC 4180).
+	 *
+	 * @param  field
+	 * @return String
+	 */
+	fn escape_csv_field(field: &str) -> String {
+		if field.contains([',', '"', '\n', '\r']) {
+			format!("\"{}\"", field.replace('"', "\"\""))
+		} else {
+			field.to_string()
+		}
+	}
+}
+
+/**
+ * Print the kernel module list to stdout in the given format.
+ *
+ * @param  source
+ * @param  format
+ * @param  args
+ * @return exit code
+ */
+pub fn print_modules(
+	source: &dyn ModuleSource,
+	format: OutputFormat,
+	args: &ListArgs,
+) -> i32 {
+	match source.list_modules(args) {
+		Ok(modules) => {
+			println!("{}", format.format_modules(&modules, args.size_format));
+			EXIT_SUCCESS
+		}
+		Err(e) => {
+			eprintln!("{}", format.format_error(&e.to_string()));
+			EXIT_FAILURE
+		}
+	}
+}
+
+/**
+ * Save a snapshot of the loaded kernel modules to the given file.
+ *
+ * @param  source
+ * @param  path
+ * @param  args
+ * @return exit code
+ */
+pub fn save_snapshot(
+	source: &dyn ModuleSource,
+	path: &Path,
+	args: &ListArgs,
+) -> i32 {
+	match source
+		.list_modules(args)
+		.map_err(|e| e.to_string())
+		.and_then(|modules| Snapshot::capture(source, &modules).save(Some(path)))
+	{
+		Ok(path) => {
+			println!("Snapshot saved to {}", path.display());
+			EXIT_SUCCESS
+		}
+		Err(e) => {
+			eprintln!("{e}");
+			EXIT_FAILURE
+		}
+	}
+}
+
+/**
+ * Load the modules that are listed in the given file and print the results.
+ *
+ * @param  path
+ * @param  args
+ * @return exit code
+ */
+pub fn load_modules(path: &Path, args: &CommandArgs) -> i32 {
+	if args.read_only {
+		eprintln!("modules cannot be loaded in read-only mode");
+		return EXIT_USAGE;
+	}
+	match cmd::read_module_list(path) {
+		Ok(names) => {
+			let results = ModuleCommand::Load.run_batch(&names, args);
+			for result in &results {
+				println!("{}", result.describe(args.dry_run));
+			}
+			println!("{}", BatchResult::summary(&results));
+			if results.iter().all(|v| v.status == BatchStatus::Done) {
+				EXIT_SUCCESS
+			} else {
+				EXIT_FAILURE
+			}
+		}
+		Err(e) => {
+			eprintln!("{e}");
+			EXIT_FAILURE
+		}
+	}
+}
+
+/**
+ * Format the differences between two snapshots with optional colors.
+ *
+ * @param  diff
+ * @param  colored
+ * @return String
+ */
+pub fn format_snapshot_diff(diff: &SnapshotDiff, colored: bool) -> String {
+	let paint = |line: String, fg: &dyn color::Color| {
+		if colored {
+			format!("{}{line}{}", color::Fg(fg), color::Fg(color::Reset))
+		} else {
+			line
+		}
+	};
+	let mut lines = Vec::new();
+	for (name, version) in &diff.added {
+		lines.push(paint(format!("+ {name} {version}"), &color::Green));
+	}
+	for (name, version) in &diff.removed {
+		lines.push(paint(format!("- {name} {version}"), &color::Red));
+	}
+	for (name, old, new) in &diff.changed {
+		lines.push(paint(format!("~ {name} {old} -> {new}"), &color::Yellow));
+	}
+	lines.push(format!(
+		"{} added, {} removed, {} changed",
+		diff.added.len(),
+		diff.removed.len(),
+		diff.changed.len()
+	));
+	lines.join("\n")
+}
+
+/**
+ * Print the given configuration as TOML.
+ *
+ * @param  config
+ * @return exit code
+ */
+pub fn print_config(config: &Config) -> i32 {
+	match config.to_toml() {
+		Ok(contents) => {
+			print!("{contents}");
+			EXIT_SUCCESS
+		}
+		Err(e) => {
+			eprintln!("failed to serialize the configuration: {e}");
+			EXIT_FAILURE
+		}
+	}
+}
+
+/**
+ * Print the differences between the given snapshot files.
+ *
+ * @param  old
+ * @param  new
+ * @return exit code
+ */
+pub fn print_snapshot_diff(old: &Path, new: &Path) -> i32 {
+	match Snapshot::load(old).and_then(|old| Ok(old.diff(&Snapshot::load(new)?))) {
+		Ok(diff) => {
+			println!(
+				"{}",
+				format_snapshot_diff(&diff, termion::is_tty(&stdout()))
+			);
+			EXIT_SUCCESS
+		}
+		Err(e) => {
+			eprintln!("{e}");
+			EXIT_FAILURE
+		}
+	}
+}
+
+/**
+ * Print the Markdown report of the given module.
+ *
+ * Current values of the parameters are included if the module is loaded.
+ *
+ * @param  source
+ * @param  module_name
+ * @return exit code
+ */
+pub fn print_module_report(source: &dyn ModuleSource, module_name: &str) -> i32 {
+	match lkm::read_report(source, module_name) {
+		Some(report) => {
+			print!("{report}");
+			EXIT_SUCCESS
+		}
+		None => {
+			eprintln!("module '{module_name}' is not found");
+			EXIT_FAILURE
+		}
+	}
+}
+
+/**
+ * Get the module names from the arguments and from the input for '-'.
+ *
+ * Each line of the input is a module name and the empty lines are ignored.
+ *
+ * @param  names
+ * @param  input
+ * @return Vec
+ */
+pub fn read_module_names(names: &[String], input: impl BufRead) -> Vec<String> {
+	let mut input = Some(input);
+	let mut module_names = Vec::new();
+	for name in names {
+		match (name.as_str(), input.take()) {
+			("-", Some(input)) => module_names.extend(
+				input
+					.lines()
+					.map_while(Result::ok)
+					.map(|line| line.trim().to_string())
+					.filter(|line| !line.is_empty()),
+			),
+			("-", None) => {}
+			(name, stdin) => {
+				input = stdin;
+				module_names.push(name.to_string());
+			}
+		}
+	}
+	module_names
+}
+
+/**
+ * Print the information of the given modules with a section for each.
+ *
+ * Module names are read from stdin for '-'.
+ *
+ * @param  source
+ * @param  names
+ * @return exit code
+ */
+pub fn print_module_info(source: &dyn ModuleSource, names: &[String]) -> i32 {
+	/* Stdin is only locked if the names are read from it. */
+	let module_names = if names.iter().any(|v| v == "-") {
+		read_module_names(names, io::stdin().lock())
+	} else {
+		names.to_vec()
+	};
+	if module_names.is_empty() {
+		eprintln!("no module names are given");
+		return EXIT_USAGE;
+	}
+	let mut exit_code = EXIT_SUCCESS;
+	for (i, module_name) in module_names.iter().enumerate() {
+		match lkm::read_report(source, module_name) {
+			Some(report) => {
+				if i != 0 {
+					println!();
+				}
+				print!("{report}");
+			}
+			None => {
+				eprintln!("module '{module_name}' is not found");
+				exit_code = EXIT_FAILURE;
+			}
+		}
+	}
+	exit_code
+}
+
+/**
+ * Print the dependency graph of the loaded modules in DOT format.
+ *
+ * Graph only contains the given module and its transitive dependencies if
+ * the root module is given.
+ *
+ * @param  source
+ * @param  args
+ * @param  root
+ * @return exit code
+ */
+pub fn print_dependency_graph(
+	source: &dyn ModuleSource,
+	args: &ListArgs,
+	root: Option<&str>,
+) -> i32 {
+	let modules = match source.list_modules(args) {
+		Ok(v) => v,
+		Err(e) => {
+			eprintln!("{e}");
+			return EXIT_FAILURE;
+		}
+	};
+	let graph = DependencyGraph::new(
+		&modules
+			.iter()
+			.map(|v| (v.name.to_string(), v.dependents.clone()))
+			.collect::<Vec<(String, Vec<String>)>>(),
+	);
+	let nodes = match root {
+		Some(root) if modules.iter().any(|v| v.name == root) => {
+			graph.reachable(root, Relation::Depends)
+		}
+		Some(root) => {
+			eprintln!("module '{root}' is not loaded");
+			return EXIT_FAILURE;
+		}
+		None => modules.iter().map(|v| v.name.to_string()).collect(),
+	};
+	print!("{}", graph.to_dot(&nodes));
+	EXIT_SUCCESS
+}
+
+/**
+ * Get the version information of the binary.
+ *
+ * Build information is added in verbose mode for the bug reports.
+ *
+ * @param  verbose
+ * @return String
+ */
+pub fn version_info(verbose: bool) -> String {
+	let mut info =
+		format!("{} {}\n", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+	if verbose {
+		for (name, value) in [
+			("rustc", env!("KMON_RUSTC_VERSION")),
+			("target", env!("KMON_TARGET")),
+			("commit", env!("KMON_GIT_HASH")),
+			("build date", env!("KMON_BUILD_DATE")),
+		] {
+			info += &format!("{name}: {value}\n");
+		}
+	}
+	info
+}
+
+/**
+ * Generate the completion script of the given shell.
+ *
+ * @param  shell
+ * @return String
+ */
+pub fn generate_completions(shell: Shell) -> String {
+	let mut output = Vec::new();
+	clap_complete::generate(
+		shell,
+		&mut args::get_args(),
+		env!("CARGO_PKG_NAME"),
+		&mut output,
+	);
+	String::from_utf8_lossy(&output).to_string()
+}
+
+/**
+ * Print the completion script of the given shell.
+ *
+ * @param  shell
+ * @return exit code
+ */
+pub fn print_completions(shell: &str) -> i32 {
+	match shell.parse::<Shell>() {
+		Ok(shell) => {
+			print!("{}", generate_completions(shell));
+			EXIT_SUCCESS
+		}
+		Err(e) => {
+			eprintln!("{e}");
+			EXIT_FAILURE
+		}
+	}
+}
+
+/**
+ * Generate the man page from the command line arguments.
+ *
+ * Options of the visible subcommands are listed after the main options.
+ *
+ * @return Result
+ */
+pub fn generate_manpage() -> Result<String, String> {
+	let app = args::get_args();
+	let man = Man::new(app.clone());
+	let mut output = Vec::new();
+	man.render_title(&mut output)
+		.and_then(|_| man.render_name_section(&mut output))
+		.and_then(|_| man.render_synopsis_section(&mut output))
+		.and_then(|_| man.render_description_section(&mut output))
+		.and_then(|_| man.render_options_section(&mut output))
+		.map_err(|e| e.to_string())?;
+	for subcommand in app.get_subcommands().filter(|v| !v.is_hide_set()) {
+		let mut section = Vec::new();
+		Man::new(subcommand.clone())
+			.render_options_section(&mut section)
+			.map_err(|e| e.to_string())?;
+		output.extend(
+			String::from_utf8_lossy(&section)
+				.replacen(
+					".SH OPTIONS",
+					&format!(
+						".SH \"{} OPTIONS\"",
+						subcommand.get_name().to_uppercase()
+					),
+					1,
+				)
+				.as_bytes(),
+		);
+	}
+	man.render_subcommands_section(&mut output)
+		.and_then(|_| man.render_version_section(&mut output))
+		.and_then(|_| man.render_authors_section(&mut output))
+		.map_err(|e| e.to_string())?;
+	Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+/**
+ * Print the man page in roff format.
+ *
+ * @return exit code
+ */
+pub fn print_manpage() -> i32 {
+	match generate_manpage() {
+		Ok(manpage) => {
+			print!("{manpage}");
+			EXIT_SUCCESS
+		}
+		Err(e) => {
+			eprintln!("{e}");
+			EXIT_FAILURE
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::kernel::source::LocalLinuxSource;
+	#[test]
+	fn test_output() {
+		let modules = vec![
+			Module::parse("snd 98304 2 snd_hda_intel,snd_pcm, Live 0x0").unwrap(),
+			Module::parse("e1000e 294912 0 - Live 0x0 (OE)").unwrap(),
+		];
+		let output = OutputFormat::try_from("json").unwrap();
+		let value: serde_json::Value = serde_json::from_str(
+			&output.format_modules(&modules, SizeFormat::Human),
+		)
+		.unwrap();
+		assert_eq!("snd", value[0]["name"]);
+		assert_eq!(98304, value[0]["size"]);
+		assert_eq!(2, value[0]["refcount"]);
+		assert_eq!(json!(["snd_hda_intel", "snd_pcm"]), value[0]["dependents"]);
+		assert_eq!(json!([]), value[1]["dependents"]);
+		assert_eq!(
+			r#"{"error":"failed"}"#,
+			OutputFormat::Json.format_error("failed")
+		);
+		let output = OutputFormat::try_from("csv").unwrap();
+		assert_eq!(
+			"name,size,refcount,dependents\n\
+			snd,96.0 KiB,2,\"snd_hda_intel,snd_pcm\"\n\
+			e1000e,288.0 KiB,0,",
+			output.format_modules(&modules, SizeFormat::Human)
+		);
+		let args =
+			args::get_args().get_matches_from(["kmon", "--size-format", "raw"]);
+		let mut config = Config::default();
+		config.apply_args(&args).unwrap();
+		assert_eq!(
+			"name,size,refcount,dependents\n\
+			snd,98304,2,\"snd_hda_intel,snd_pcm\"\n\
+			e1000e,294912,0,",
+			output.format_modules(&modules, ListArgs::new(&config).size_format)
+		);
+		assert_eq!("\"a \"\"b\"\"\"", OutputFormat::escape_csv_field("a \"b\""));
+		assert!(OutputFormat::try_from("xml").is_err());
+		let diff = Snapshot::parse("snd 1.0\ne1000e 3.2.6-k")
+			.unwrap()
+			.diff(&Snapshot::parse("e1000e 3.3.0-k\nnouveau -").unwrap());
+		assert_eq!(
+			"+ nouveau -\n- snd 1.0\n~ e1000e 3.2.6-k -> 3.3.0-k\n\
+			1 added, 1 removed, 1 changed",
+			format_snapshot_diff(&diff, false)
+		);
+		assert!(format_snapshot_diff(&diff, true).contains("\x1b["));
+		assert_eq!(
+			"0 added, 0 removed, 0 changed",
+			format_snapshot_diff(&SnapshotDiff::default(), true)
+		);
+		for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::Elvish] {
+			assert!(generate_completions(shell).contains("kernel-log"));
+		}
+		assert_eq!(EXIT_FAILURE, print_completions("tcsh"));
+		assert_eq!(EXIT_SUCCESS, print_config(&Config::default()));
+		assert_eq!(EXIT_FAILURE, print_module_report(&LocalLinuxSource, "~"));
+		assert_eq!(
+			vec!["loop", "e1000e", "snd", "uinput"],
+			read_module_names(
+				&[
+					String::from("loop"),
+					String::from("-"),
+					String::from("uinput"),
+					String::from("-"),
+				],
+				"e1000e\n\n  snd \n".as_bytes()
+			)
+		);
+		assert_eq!(
+			EXIT_FAILURE,
+			print_module_info(&LocalLinuxSource, &[String::from("~")])
+		);
+		let path = std::env::temp_dir().join("kmon-test-output-load-file.txt");
+		std::fs::write(&path, "kmon_test_module\n").unwrap();
+		assert_eq!(EXIT_FAILURE, load_modules(&path, &CommandArgs::default()));
+		let args = CommandArgs {
+			dry_run: true,
+			..CommandArgs::default()
+		};
+		assert_eq!(EXIT_SUCCESS, load_modules(&path, &args));
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(EXIT_FAILURE, load_modules(&path, &args));
+		let args = CommandArgs {
+			read_only: true,
+			..CommandArgs::default()
+		};
+		assert_eq!(EXIT_USAGE, load_modules(&path, &args));
+		assert_eq!(
+			format!("kmon {}\n", env!("CARGO_PKG_VERSION")),
+			version_info(false)
+		);
+		let version = version_info(true);
+		assert_eq!(5, version.lines().count());
+		assert!(version.contains(&format!("target: {}", env!("KMON_TARGET"))));
+		let manpage = generate_manpage().unwrap();
+		assert!(manpage.starts_with(".ie"));
+		for value in [
+			"SORT OPTIONS",
+			"kernel\\-log",
+			"Set the source of the kernel activities",
+		] {
+			assert!(manpage.contains(value), "{value}");
+		}
+	}
+}
@@ -0,0 +1,135 @@
+use crate::app::Block;
+use crate::kernel::lkm::{KernelModules, ListFilter, SortType};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/* Name of the session state file */
+const STATE_FILE: &str = "state.toml";
+
+/* View state of the last session that is restored on startup */
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct State {
+	pub sort: Option<SortType>,
+	pub reverse: Option<bool>,
+	pub filter: Option<ListFilter>,
+	pub theme: Option<String>,
+	pub module: Option<String>,
+	pub block: Option<Block>,
+	pub bookmarks: BTreeSet<String>,
+	pub search_history: Vec<String>,
+	pub log_search_history: Vec<String>,
+}
+
+impl State {
+	/**
+	 * Create the state from the module list and the given theme.
+	 *
+	 * @param  kernel_modules
+	 * @param  theme
+	 * @return State
+	 */
+	pub fn capture(kernel_modules: &KernelModules, theme: Option<String>) -> Self {
+		Self {
+			sort: Some(kernel_modules.args.sort),
+			reverse: Some(kernel_modules.args.reverse),
+			filter: Some(kernel_modules.args.filter),
+			theme,
+			module: kernel_modules
+				.list
+				.get(kernel_modules.index)
+				.and_then(|module| module[0].split_whitespace().next())
+				.map(String::from),
+			bookmarks: kernel_modules.bookmarks.clone(),
+			..Self::default()
+		}
+	}
+
+	/**
+	 * Get the path of the state file.
+	 *
+	 * Uses '$XDG_STATE_HOME/kmon/state.toml' or '~/.local/state/kmon/state.toml'.
+	 *
+	 * @return PathBuf
+	 */
+	pub fn path() -> Option<PathBuf> {
+		env::var_os("XDG_STATE_HOME")
+			.filter(|v| !v.is_empty())
+			.map(PathBuf::from)
+			.or_else(|| {
+				env::var_os("HOME")
+					.map(|v| PathBuf::from(v).join(".local").join("state"))
+			})
+			.map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(STATE_FILE))
+	}
+
+	/**
+	 * Read the state from the given file.
+	 *
+	 * Default values are returned if the file is missing or invalid.
+	 *
+	 * @param  path
+	 * @return State
+	 */
+	pub fn load(path: &Path) -> Self {
+		fs::read_to_string(path)
+			.ok()
+			.and_then(|contents| toml::from_str(&contents).ok())
+			.unwrap_or_default()
+	}
+
+	/**
+	 * Write the state to the given file.
+	 *
+	 * @param  path
+	 * @return Result
+	 */
+	pub fn save(&self, path: &Path) -> Result<(), String> {
+		toml::to_string(self)
+			.map_err(|e| e.to_string())
+			.and_then(|contents| {
+				if let Some(dir) = path.parent() {
+					fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+				}
+				fs::write(path, contents).map_err(|e| e.to_string())
+			})
+			.map_err(|e| {
+				format!("failed to save the session state ({}): {e}", path.display())
+			})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	#[test]
+	fn test_state() {
+		let state = State {
+			sort: Some(SortType::Size),
+			reverse: Some(true),
+			filter: Some(ListFilter::InUse),
+			theme: Some(String::from("nord")),
+			module: Some(String::from("e1000e")),
+			block: Some(Block::ModuleInfo),
+			bookmarks: BTreeSet::from([String::from("snd"), String::from("e1000e")]),
+			search_history: vec![String::from("snd"), String::from("^nv")],
+			log_search_history: vec![String::from("usb")],
+		};
+		let path = env::temp_dir().join("kmon-test-state").join(STATE_FILE);
+		assert_eq!(Ok(()), state.save(&path));
+		assert!(fs::read_to_string(&path)
+			.unwrap()
+			.contains("filter = \"in-use\""));
+		assert!(fs::read_to_string(&path)
+			.unwrap()
+			.contains("block = \"module-info\""));
+		assert_eq!(state, State::load(&path));
+		fs::write(&path, "sort = 1\n").unwrap();
+		assert_eq!(State::default(), State::load(&path));
+		fs::remove_dir_all(path.parent().unwrap()).unwrap();
+		assert_eq!(State::default(), State::load(&path));
+	}
+}
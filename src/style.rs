@@ -1,6 +1,7 @@
-use clap::ArgMatches;
+use crate::config::Config;
 use colorsys::Rgb;
 use ratatui::style::{Color, Modifier, Style as TuiStyle};
+use ratatui::symbols::scrollbar;
 use ratatui::text::{Line, Span, Text};
 use std::collections::HashMap;
 
@@ -22,6 +23,9 @@ pub enum Symbol {
 	LeftBracket,
 	RightBracket,
 	HistoricSite,
+	Star,
+	UpArrow,
+	DownArrow,
 }
 
 /* Supported Unicode symbols */
@@ -55,7 +59,10 @@ impl Unicode<'_> {
 			Symbol::HighVoltage => &[" \u{26A1}", ""],
 			Symbol::LeftBracket => &["\u{2997}", "("],
 			Symbol::RightBracket => &["\u{2998}", ")"],
-			Symbol::HistoricSite => &[" \u{26EC} ", ""]
+			Symbol::HistoricSite => &[" \u{26EC} ", ""],
+			Symbol::Star => &["\u{2605}", "+"],
+			Symbol::UpArrow => &["\u{2191}", "^"],
+			Symbol::DownArrow => &["\u{2193}", "v"]
 			},
 			replace,
 		}
@@ -69,6 +76,207 @@ impl Unicode<'_> {
 	pub fn get(&self, symbol: Symbol) -> &str {
 		self.symbols[&symbol][self.replace as usize]
 	}
+
+	/**
+	 * Get the frame of the progress spinner for the given tick.
+	 *
+	 * @param  tick
+	 * @return str
+	 */
+	pub fn spinner(&self, tick: u64) -> &'static str {
+		let frames = if self.replace {
+			SPINNER_ASCII
+		} else {
+			SPINNER_UNICODE
+		};
+		frames[(tick % frames.len() as u64) as usize]
+	}
+
+	/**
+	 * Get the symbols of the vertical scrollbar.
+	 *
+	 * @return Set
+	 */
+	pub fn scrollbar(&self) -> scrollbar::Set {
+		if self.replace {
+			scrollbar::Set {
+				track: "|",
+				thumb: "#",
+				begin: "^",
+				end: "v",
+			}
+		} else {
+			scrollbar::VERTICAL
+		}
+	}
+
+	/**
+	 * Get a horizontal bar that fills the given fraction of the width.
+	 *
+	 * Non-zero fractions are shown with at least a partial block.
+	 *
+	 * @param  fraction
+	 * @param  width
+	 * @return String
+	 */
+	pub fn bar(&self, fraction: f64, width: usize) -> String {
+		let fraction = fraction.clamp(0., 1.);
+		if self.replace {
+			let blocks = (fraction * width as f64).round() as usize;
+			return "#".repeat(if fraction > 0. { blocks.max(1) } else { 0 });
+		}
+		let eighths = (fraction * (width * 8) as f64).round() as usize;
+		let eighths = if fraction > 0. { eighths.max(1) } else { 0 };
+		format!(
+			"{}{}",
+			"\u{2588}".repeat(eighths / 8),
+			BAR_EIGHTHS[eighths % 8]
+		)
+	}
+}
+
+/* Partial blocks of the size bars in eighths */
+const BAR_EIGHTHS: &[&str] = &[
+	"", "\u{258F}", "\u{258E}", "\u{258D}", "\u{258C}", "\u{258B}", "\u{258A}",
+	"\u{2589}",
+];
+
+/* Frames of the progress spinner */
+const SPINNER_UNICODE: &[&str] = &[
+	"\u{280B}", "\u{2819}", "\u{2839}", "\u{2838}", "\u{283C}", "\u{2834}",
+	"\u{2826}", "\u{2827}", "\u{2807}", "\u{280F}",
+];
+const SPINNER_ASCII: &[&str] = &["|", "/", "-", "\\"];
+
+/* Names of the available theme presets */
+pub const THEMES: &[&str] = &[
+	"default",
+	"dark",
+	"light",
+	"solarized",
+	"matrix",
+	"high-contrast",
+];
+
+/* Colors of the semantic roles in the terminal UI */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+	/* Selected block, selected row and the text */
+	pub accent: Color,
+	/* Borders and the rows that are not selected */
+	pub main: Color,
+	/* Block titles */
+	pub title: Color,
+	/* Kernel activity log (accent color is used if not set) */
+	pub log: Option<Color>,
+	/* Modifier of the selected row in addition to the accent color */
+	pub selected: Modifier,
+	/* Background and the modifier of all the text */
+	pub background: Color,
+	pub modifier: Modifier,
+}
+
+impl Default for Theme {
+	fn default() -> Self {
+		Self {
+			accent: Color::White,
+			main: Color::DarkGray,
+			title: Color::Reset,
+			log: None,
+			selected: Modifier::empty(),
+			background: Color::Reset,
+			modifier: Modifier::empty(),
+		}
+	}
+}
+
+impl Theme {
+	/**
+	 * Get the theme preset with the given name.
+	 *
+	 * @param  name
+	 * @return Result
+	 */
+	pub fn from_name(name: &str) -> Result<Self, String> {
+		match name.to_lowercase().as_str() {
+			"default" => Ok(Self::default()),
+			"dark" => Ok(Self {
+				accent: Color::LightCyan,
+				main: Color::DarkGray,
+				title: Color::White,
+				log: Some(Color::Gray),
+				..Self::default()
+			}),
+			"light" => Ok(Self {
+				accent: Color::Black,
+				main: Color::Gray,
+				title: Color::Black,
+				log: Some(Color::DarkGray),
+				..Self::default()
+			}),
+			"solarized" => Ok(Self {
+				accent: Color::Rgb(38, 139, 210),
+				main: Color::Rgb(88, 110, 117),
+				title: Color::Rgb(181, 137, 0),
+				log: Some(Color::Rgb(131, 148, 150)),
+				..Self::default()
+			}),
+			"matrix" => Ok(Self {
+				accent: Color::LightGreen,
+				main: Color::Green,
+				title: Color::LightGreen,
+				log: Some(Color::Green),
+				..Self::default()
+			}),
+			"high-contrast" => Ok(Self {
+				accent: Color::LightYellow,
+				main: Color::White,
+				title: Color::White,
+				log: Some(Color::White),
+				selected: Modifier::REVERSED | Modifier::BOLD,
+				background: Color::Black,
+				modifier: Modifier::BOLD,
+			}),
+			_ => Err(format!(
+				"unknown theme '{name}' (valid themes: {})",
+				THEMES.join(", ")
+			)),
+		}
+	}
+
+	/**
+	 * Get the name of the theme preset that is readable on the given background.
+	 *
+	 * Light theme is chosen if the luminance of the background color is high.
+	 *
+	 * @param  background
+	 * @return str
+	 */
+	pub fn for_background((r, g, b): (u8, u8, u8)) -> &'static str {
+		let luminance =
+			0.2126 * f64::from(r) + 0.7152 * f64::from(g) + 0.0722 * f64::from(b);
+		if luminance > 127.5 {
+			"light"
+		} else {
+			"default"
+		}
+	}
+
+	/**
+	 * Get the theme without colors that relies on reverse video for selection.
+	 *
+	 * @return Theme
+	 */
+	pub fn monochrome() -> Self {
+		Self {
+			accent: Color::Reset,
+			main: Color::Reset,
+			title: Color::Reset,
+			log: None,
+			selected: Modifier::REVERSED,
+			..Self::default()
+		}
+	}
 }
 
 /* Style properties */
@@ -77,45 +285,83 @@ pub struct Style {
 	pub default: TuiStyle,
 	pub bold: TuiStyle,
 	pub colored: TuiStyle,
+	pub log: TuiStyle,
+	pub selected: TuiStyle,
+	pub success: TuiStyle,
+	pub warning: TuiStyle,
+	pub error: TuiStyle,
 	pub unicode: Unicode<'static>,
 }
 
 impl Style {
 	/**
-	 * Create a new style instance from given arguments.
+	 * Create a new style instance from the given configuration.
 	 *
-	 * @param  args
+	 * The theme that is detected from the terminal background is used
+	 * unless a theme is chosen. All the colors are ignored in monochrome mode.
+	 *
+	 * @param  config
 	 * @return Style
 	 */
-	pub fn new(args: &ArgMatches) -> Self {
-		let mut default = TuiStyle::reset();
-		if let Ok(true) = args.try_contains_id("accent-color") {
-			default =
-				default.fg(Self::get_color(args, "accent-color", Color::White));
-		}
+	pub fn new(config: &Config) -> Self {
+		let theme = if config.monochrome {
+			Theme::monochrome()
+		} else {
+			config
+				.theme
+				.as_deref()
+				.or(config.detected_theme.as_deref())
+				.and_then(|name| Theme::from_name(name).ok())
+				.unwrap_or_default()
+		};
+		let color = |value: &Option<String>, default_color: Color| match value {
+			Some(color) if !config.monochrome => {
+				Self::get_color(color, default_color)
+			}
+			_ => default_color,
+		};
+		let accent_color = color(&config.accent_color, theme.accent);
+		let status_color = |color: Color| {
+			if config.monochrome {
+				Color::Reset
+			} else {
+				color
+			}
+		};
+		let base = TuiStyle::reset()
+			.bg(theme.background)
+			.add_modifier(theme.modifier);
 		Self {
-			default,
-			bold: TuiStyle::reset().add_modifier(Modifier::BOLD),
-			colored: TuiStyle::reset().fg(Self::get_color(
-				args,
-				"color",
-				Color::DarkGray,
-			)),
-			unicode: Unicode::new(
-				args.try_get_one::<bool>("unicode").ok().flatten() == Some(&false),
-			),
+			default: base.fg(accent_color),
+			bold: base.fg(theme.title).add_modifier(Modifier::BOLD),
+			colored: base.fg(color(&config.color, theme.main)),
+			log: base.fg(theme.log.unwrap_or(accent_color)),
+			selected: base.fg(accent_color).add_modifier(theme.selected),
+			success: base.fg(status_color(Color::Green)),
+			warning: base.fg(status_color(Color::Yellow)),
+			error: base.fg(status_color(Color::Red)),
+			unicode: Unicode::new(!config.unicode),
 		}
 	}
 
 	/**
-	 * Parse a color value from arguments.
+	 * Parse a color value from the given color name or hex value.
 	 *
-	 * @param  args
-	 * @param  arg_name
+	 * @param  value
 	 * @param  default_color
 	 * @return Color
 	 */
-	fn get_color(args: &ArgMatches, arg_name: &str, default_color: Color) -> Color {
+	fn get_color(value: &str, default_color: Color) -> Color {
+		Self::parse_color(value).unwrap_or(default_color)
+	}
+
+	/**
+	 * Parse a color from the given color name or hex value ('#rrggbb').
+	 *
+	 * @param  value
+	 * @return Result
+	 */
+	pub fn parse_color(value: &str) -> Result<Color, String> {
 		let colors = map![
 			"black" => Color::Black,
 			"red" => Color::Red,
@@ -134,19 +380,24 @@ impl Style {
 			"lightcyan" => Color::LightCyan,
 			"white" => Color::White
 		];
-		match args.try_get_one::<String>(arg_name) {
-			Ok(Some(v)) => *colors.get::<str>(&v.to_lowercase()).unwrap_or({
-				if let Ok(rgb) = Rgb::from_hex_str(&format!("#{v}")) {
-					Box::leak(Box::new(Color::Rgb(
-						rgb.red() as u8,
-						rgb.green() as u8,
-						rgb.blue() as u8,
-					)))
-				} else {
-					&default_color
-				}
-			}),
-			_ => default_color,
+		let hex = value.strip_prefix('#').unwrap_or(value);
+		match colors.get::<str>(&value.to_lowercase()) {
+			Some(color) => Ok(*color),
+			None if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+				Rgb::from_hex_str(&format!("#{hex}"))
+					.map(|rgb| {
+						Color::Rgb(
+							rgb.red() as u8,
+							rgb.green() as u8,
+							rgb.blue() as u8,
+						)
+					})
+					.map_err(|e| e.to_string())
+			}
+			None => Err(format!(
+				"invalid color '{value}' (expected a color name such as \
+				'red', 'lightblue' and 'darkgray' or a hex value such as '#1a2b3c')"
+			)),
 		}
 	}
 }
@@ -193,7 +444,7 @@ impl<'a> StyledText<'a> {
 	 */
 	pub fn stylize_data(
 		&mut self,
-		text: &'a str,
+		text: &str,
 		delimiter: &str,
 		style: Style,
 	) -> Text<'a> {
@@ -209,7 +460,7 @@ impl<'a> StyledText<'a> {
 			} else {
 				self.styled_text
 					.lines
-					.push(Line::from(Span::styled(line, style.default)))
+					.push(Line::from(Span::styled(line.to_string(), style.default)))
 			}
 		}
 		self.styled_text.clone()
@@ -227,16 +478,31 @@ impl<'a> StyledText<'a> {
 			self.styled_text.lines.len()
 		}
 	}
+
+	/**
+	 * Return the line count of styled text after wrapping it to the given width.
+	 *
+	 * @param  width
+	 * @return usize
+	 */
+	pub fn wrapped_lines(&self, width: u16) -> usize {
+		if width == 0 {
+			return self.lines();
+		}
+		self.get()
+			.lines
+			.iter()
+			.map(|line| line.width().max(1).div_ceil(width.into()))
+			.sum()
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use clap::ArgMatches;
 	#[test]
 	fn test_style() {
-		let args = ArgMatches::default();
-		let style = Style::new(&args);
+		let style = Style::new(&Config::default());
 		let mut styled_text = StyledText::default();
 		styled_text.set(
 			Text::styled("styled\ntext", style.colored),
@@ -247,17 +513,103 @@ mod tests {
 			styled_text.get()
 		);
 		assert_eq!(2, styled_text.lines());
+		assert_eq!(2, styled_text.wrapped_lines(0));
+		assert_eq!(3, styled_text.wrapped_lines(4));
 		assert_eq!("test", styled_text.raw_text);
+		assert_eq!(style.default, style.log);
+	}
+	#[test]
+	fn test_color() {
+		assert_eq!(Ok(Color::LightBlue), Style::parse_color("LightBlue"));
+		assert_eq!(Ok(Color::Rgb(26, 43, 60)), Style::parse_color("#1a2b3c"));
+		assert_eq!(Ok(Color::Rgb(26, 43, 60)), Style::parse_color("1a2b3c"));
+		assert!(Style::parse_color("notacolor")
+			.unwrap_err()
+			.contains("hex value"));
+		assert!(Style::parse_color("#12345").is_err());
+	}
+	#[test]
+	fn test_theme() {
+		for name in THEMES {
+			assert!(Theme::from_name(name).is_ok());
+		}
+		assert_eq!(Theme::default(), Theme::from_name("Default").unwrap());
+		assert!(Theme::from_name("neon").unwrap_err().contains("solarized"));
+		assert_eq!("light", Theme::for_background((255, 255, 255)));
+		assert_eq!("light", Theme::for_background((253, 246, 227)));
+		assert_eq!("default", Theme::for_background((0, 43, 54)));
+		assert_eq!("default", Theme::for_background((0, 0, 255)));
+		let config = Config {
+			theme: Some(String::from("matrix")),
+			color: Some(String::from("red")),
+			..Config::default()
+		};
+		let style = Style::new(&config);
+		assert_eq!(Some(Color::Red), style.colored.fg);
+		assert_eq!(Some(Color::LightGreen), style.default.fg);
+		assert_eq!(Some(Color::Green), style.log.fg);
+		assert_eq!(style.default, style.selected);
+		assert_eq!(Some(Color::Green), style.success.fg);
+		assert_eq!(Some(Color::Red), style.error.fg);
+		let style = Style::new(&Config {
+			monochrome: true,
+			..config
+		});
+		for style in [
+			style.default,
+			style.bold,
+			style.colored,
+			style.log,
+			style.success,
+			style.warning,
+			style.error,
+		] {
+			assert_eq!(Some(Color::Reset), style.fg);
+		}
+		assert!(style.selected.add_modifier.contains(Modifier::REVERSED));
+		let style = Style::new(&Config {
+			detected_theme: Some(String::from("light")),
+			..Config::default()
+		});
+		assert_eq!(Some(Color::Black), style.default.fg);
+		let style = Style::new(&Config {
+			theme: Some(String::from("matrix")),
+			detected_theme: Some(String::from("light")),
+			..Config::default()
+		});
+		assert_eq!(Some(Color::LightGreen), style.default.fg);
+		let style = Style::new(&Config {
+			theme: Some(String::from("high-contrast")),
+			..Config::default()
+		});
+		for style in [style.default, style.bold, style.colored, style.log] {
+			assert_eq!(Some(Color::Black), style.bg);
+			assert!(style.add_modifier.contains(Modifier::BOLD));
+			assert_ne!(Some(Color::DarkGray), style.fg);
+		}
+		assert!(style
+			.selected
+			.add_modifier
+			.contains(Modifier::REVERSED | Modifier::BOLD));
 	}
 	#[test]
 	fn test_unicode() {
 		let mut unicode = Unicode::new(true);
+		assert_eq!("##", unicode.bar(0.5, 4));
+		assert_eq!("#", unicode.bar(0.01, 4));
+		assert_eq!("", unicode.bar(0., 4));
+		assert_eq!("/", unicode.spinner(5));
+		assert_eq!("#", unicode.scrollbar().thumb);
 		for symbol in unicode.symbols.clone() {
 			if symbol.0 != Symbol::Blank {
 				assert!(symbol.1[1].len() < 2)
 			}
 		}
 		unicode.replace = false;
+		assert_eq!("\u{2588}\u{2588}\u{258C}", unicode.bar(0.625, 4));
+		assert_eq!("\u{2588}".repeat(4), unicode.bar(2., 4));
+		assert_eq!("\u{258F}", unicode.bar(0.001, 4));
+		assert_eq!(scrollbar::VERTICAL.thumb, unicode.scrollbar().thumb);
 		for symbol in unicode.symbols {
 			if symbol.0 != Symbol::None {
 				assert_ne!("", symbol.1[0]);
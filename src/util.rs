@@ -1,8 +1,18 @@
+use crate::date;
+use std::env;
 use std::error::Error;
-use std::io::{self, Write};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::fd::AsRawFd;
 use std::panic;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use termion::raw::IntoRawMode;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /* Macro for concise initialization of hashmap */
 macro_rules! map {
@@ -13,95 +23,1157 @@ macro_rules! map {
     }}
 }
 
-/* Array of the key bindings */
-pub const KEY_BINDINGS: &[(&str, &str)] = &[
-	("'?', f1", "help"),
-	("right/left, h/l", "switch between blocks"),
-	("up/down, k/j, alt-k/j", "scroll up/down [selected block]"),
-	("pgup/pgdown", "scroll up/down [kernel activities]"),
-	("</>", "scroll up/down [module information]"),
-	("alt-h/l", "scroll right/left [kernel activities]"),
-	("ctrl-t/b, home/end", "scroll to top/bottom [module list]"),
-	("alt-e/s", "expand/shrink the selected block"),
-	("ctrl-x", "change the block position"),
-	("ctrl-l/u, alt-c", "clear the kernel ring buffer"),
-	("d, alt-d", "show the dependent modules"),
-	("1..9", "jump to the dependent module"),
-	("\\, tab, backtab", "show the next kernel information"),
-	("/, s, enter", "search a kernel module"),
-	("+, i, insert", "load a kernel module"),
-	("-, u, backspace", "unload the kernel module"),
-	("x, b, delete", "blacklist the kernel module"),
-	("ctrl-r, alt-r", "reload the kernel module"),
-	("m, o", "show the options menu"),
-	("y/n", "execute/cancel the command"),
-	("c/v", "copy/paste"),
-	("r, f5", "refresh"),
-	("q, ctrl-c/d, esc", "quit"),
-];
+/**
+ * Format the given byte count with the binary unit and one decimal place.
+ *
+ * @param  bytes
+ * @return String
+ */
+pub fn format_bytes(bytes: u64) -> String {
+	const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
+	if bytes < 1024 {
+		return format!("{bytes} B");
+	}
+	let mut size = bytes as f64 / 1024.0;
+	let mut unit = 0;
+	while size >= 1024.0 && unit < UNITS.len() - 1 {
+		size /= 1024.0;
+		unit += 1;
+	}
+	format!("{size:.1} {}", UNITS[unit])
+}
+
+/**
+ * Format the given duration as an approximate value with the largest unit.
+ *
+ * @param  duration
+ * @return String
+ */
+pub fn format_duration(duration: Duration) -> String {
+	let secs = duration.as_secs();
+	match secs {
+		0..=59 => format!("~{secs}s"),
+		60..=3599 => format!("~{}m", secs / 60),
+		3600..=86399 => format!("~{}h", secs / 3600),
+		_ => format!("~{}d", secs / 86400),
+	}
+}
+
+/**
+ * Format the given Unix timestamp as an ISO 8601 date and time in UTC.
+ *
+ * @param  timestamp
+ * @return String
+ */
+pub fn format_utc_time(timestamp: u64) -> String {
+	let (days, secs) = (timestamp / 86400, timestamp % 86400);
+	let (year, month, day) = date::civil_date(days as i64);
+	format!(
+		"{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+		secs / 3600,
+		secs % 3600 / 60,
+		secs % 60
+	)
+}
+
+/**
+ * Check if the given executable exists in one of the directories of PATH.
+ *
+ * @param  name
+ * @return bool
+ */
+pub fn is_executable_in_path(name: &str) -> bool {
+	env::var_os("PATH").is_some_and(|paths| {
+		env::split_paths(&paths).any(|dir| dir.join(name).is_file())
+	})
+}
+
+/**
+ * Get the executables among the given ones that are not found in PATH.
+ *
+ * @param  names
+ * @return Vec
+ */
+pub fn missing_executables<'a>(names: &[&'a str]) -> Vec<&'a str> {
+	names
+		.iter()
+		.filter(|name| !is_executable_in_path(name))
+		.copied()
+		.collect()
+}
+
+/**
+ * Get the fuzzy match score of the text for the given pattern.
+ *
+ * Characters of the pattern must appear in the text in the same order
+ * (case-insensitive). Consecutive characters and the characters at the
+ * start of words are scored higher while the gaps lower the score.
+ *
+ * @param  pattern
+ * @param  text
+ * @return score (Option)
+ */
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+	let text = text.to_lowercase().chars().collect::<Vec<char>>();
+	let mut score = 0;
+	let mut last_index: Option<usize> = None;
+	let mut index = 0;
+	for c in pattern.to_lowercase().chars() {
+		index += text.get(index..)?.iter().position(|v| *v == c)?;
+		score += 1;
+		match last_index {
+			Some(last) if last + 1 == index => score += 5,
+			Some(last) => score -= (index - last - 1) as i64,
+			None => score -= index as i64,
+		}
+		if index == 0 || matches!(text[index - 1], '_' | '-') {
+			score += 3;
+		}
+		last_index = Some(index);
+		index += 1;
+	}
+	/* Prefer the shorter texts for the same matches. */
+	Some(score * 4 - (text.len() - index) as i64)
+}
+
+/**
+ * Get the indices of the characters that match the pattern in fuzzy search.
+ *
+ * Characters are matched in order and case-insensitively, with the same
+ * positions that are scored by 'fuzzy_score'.
+ *
+ * @param  pattern
+ * @param  text
+ * @return Vec (Option)
+ */
+pub fn fuzzy_indices(pattern: &str, text: &str) -> Option<Vec<usize>> {
+	let text = text.to_lowercase().chars().collect::<Vec<char>>();
+	let mut indices = Vec::new();
+	let mut index = 0;
+	for c in pattern.to_lowercase().chars() {
+		index += text.get(index..)?.iter().position(|v| *v == c)?;
+		indices.push(index);
+		index += 1;
+	}
+	Some(indices)
+}
+
+/**
+ * Check if the text matches the given shell wildcard pattern.
+ *
+ * '*' matches any number of characters and '?' matches a single character.
+ *
+ * @param  pattern
+ * @param  text
+ * @return bool
+ */
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+	let (pattern, text) = (pattern.as_bytes(), text.as_bytes());
+	let (mut p, mut t) = (0, 0);
+	let mut backtrack = None;
+	while t < text.len() {
+		match pattern.get(p) {
+			Some(b'*') => {
+				backtrack = Some((p, t));
+				p += 1;
+			}
+			Some(c) if *c == b'?' || *c == text[t] => {
+				p += 1;
+				t += 1;
+			}
+			_ => match backtrack {
+				Some((star, matched)) => {
+					backtrack = Some((star, matched + 1));
+					p = star + 1;
+					t = matched + 1;
+				}
+				None => return false,
+			},
+		}
+	}
+	pattern[p..].iter().all(|c| *c == b'*')
+}
+
+/**
+ * Get the longest common prefix of the given strings.
+ *
+ * @param  values
+ * @return String
+ */
+pub fn common_prefix(values: &[String]) -> String {
+	let Some(first) = values.first() else {
+		return String::new();
+	};
+	let mut prefix = first.as_str();
+	for value in &values[1..] {
+		while !value.starts_with(prefix) {
+			let mut chars = prefix.chars();
+			chars.next_back();
+			prefix = chars.as_str();
+		}
+	}
+	prefix.to_string()
+}
+
+/* Suffix of the text that is truncated for fitting into a column */
+pub const ELLIPSIS: &str = "...";
+
+/**
+ * Truncate the given text to fit into the given display width.
+ *
+ * Double-width (e.g. CJK) characters are counted as two columns and the
+ * combining characters are kept along with the preceding character.
+ * The ellipsis is appended to the text that is truncated.
+ *
+ * @param  text
+ * @param  width
+ * @return String
+ */
+pub fn truncate_width(text: &str, width: usize) -> String {
+	if text.width() <= width {
+		return text.to_string();
+	}
+	let Some(max_width) = width.checked_sub(ELLIPSIS.len()) else {
+		return ELLIPSIS[..width].to_string();
+	};
+	let mut truncated = String::new();
+	let mut truncated_width = 0;
+	for c in text.chars() {
+		truncated_width += c.width().unwrap_or_default();
+		if truncated_width > max_width {
+			break;
+		}
+		truncated.push(c);
+	}
+	truncated + ELLIPSIS
+}
+
+/* Default timeout of the executed commands */
+pub const CMD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/* Maximum number of characters of the logged command outputs */
+const LOG_OUTPUT_LIMIT: usize = 200;
+
+/* Log file of the executed commands and whether the read commands are logged */
+static COMMAND_LOG: OnceLock<(Mutex<File>, bool)> = OnceLock::new();
+
+/**
+ * Open the given file for appending the executed commands.
+ *
+ * Only the module commands are logged unless verbose is set.
+ *
+ * @param  path
+ * @param  verbose
+ * @return Result
+ */
+pub fn set_command_log(path: &Path, verbose: bool) -> Result<(), String> {
+	let file = OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(path)
+		.map_err(|e| {
+			format!("failed to open the command log ({}): {e}", path.display())
+		})?;
+	let _ = COMMAND_LOG.set((Mutex::new(file), verbose));
+	Ok(())
+}
+
+/**
+ * Format the log entry of an executed command.
+ *
+ * Output of the command is truncated and written on a single line.
+ *
+ * @param  timestamp
+ * @param  cmd
+ * @param  cmd_args
+ * @param  result
+ * @return String
+ */
+pub fn format_command_log(
+	timestamp: u64,
+	cmd: &str,
+	cmd_args: &[&str],
+	result: &Result<CmdOutput, CmdError>,
+) -> String {
+	let (status, output) = match result {
+		Ok(output) => (
+			match output.code {
+				Some(code) => format!("exit code: {code}"),
+				None => String::from("terminated"),
+			},
+			if output.stdout.is_empty() {
+				&output.stderr
+			} else {
+				&output.stdout
+			},
+		),
+		Err(e) => (String::from("failed"), &e.to_string()),
+	};
+	let mut output = output.replace('\n', "\\n");
+	if output.chars().count() > LOG_OUTPUT_LIMIT {
+		output = output.chars().take(LOG_OUTPUT_LIMIT).collect::<String>() + "...";
+	}
+	format!("[{timestamp}] {cmd} {cmd_args:?} ({status}) {output}")
+		.trim_end()
+		.to_string()
+}
+
+/**
+ * Append the executed command to the command log if it is set.
+ *
+ * @param cmd
+ * @param cmd_args
+ * @param result
+ * @param mutating
+ */
+fn log_command(
+	cmd: &str,
+	cmd_args: &[&str],
+	result: &Result<CmdOutput, CmdError>,
+	mutating: bool,
+) {
+	if let Some((file, verbose)) = COMMAND_LOG.get() {
+		if mutating || *verbose {
+			let timestamp = SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map(|v| v.as_secs())
+				.unwrap_or_default();
+			if let Ok(mut file) = file.lock() {
+				let _ = writeln!(
+					file,
+					"{}",
+					format_command_log(timestamp, cmd, cmd_args, result)
+				);
+			}
+		}
+	}
+}
+
+/* SSH target of the remote host that the commands are executed on */
+static REMOTE: OnceLock<String> = OnceLock::new();
+
+/**
+ * Execute the commands and read the kernel files on a remote host over SSH.
+ *
+ * @param target (e.g. 'user@host')
+ */
+pub fn set_remote(target: &str) {
+	let _ = REMOTE.set(target.to_string());
+}
+
+/**
+ * Get the SSH target of the remote host if it is set.
+ *
+ * @return target (Option)
+ */
+pub fn remote() -> Option<&'static str> {
+	REMOTE.get().map(String::as_str)
+}
+
+/**
+ * Quote the given value for using it in shell.
+ *
+ * @param  value
+ * @return String
+ */
+pub fn shell_quote(value: &str) -> String {
+	format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/**
+ * Get the 'ssh' arguments for executing the command on the given target.
+ *
+ * Arguments are quoted since they are joined into a shell command on the
+ * remote host, and password prompts are disabled.
+ *
+ * @param  target
+ * @param  cmd
+ * @param  cmd_args
+ * @return Vec
+ */
+pub fn remote_args(target: &str, cmd: &str, cmd_args: &[&str]) -> Vec<String> {
+	vec![
+		String::from("-o"),
+		String::from("BatchMode=yes"),
+		String::from("--"),
+		target.to_string(),
+		[cmd]
+			.iter()
+			.chain(cmd_args)
+			.map(|v| shell_quote(v))
+			.collect::<Vec<String>>()
+			.join(" "),
+	]
+}
+
+/**
+ * Read the contents of a file on the remote host or the local system.
+ *
+ * @param  path
+ * @return Result
+ */
+pub fn read_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
+	let path = path.as_ref();
+	match remote() {
+		Some(_) => {
+			exec_cmd("cat", &[&path.to_string_lossy()]).map_err(io::Error::other)
+		}
+		None => fs::read_to_string(path),
+	}
+}
+
+/**
+ * Get the paths of the entries in a directory on the remote host or the local system.
+ *
+ * @param  dir
+ * @return Result
+ */
+pub fn read_dir<P: AsRef<Path>>(dir: P) -> io::Result<Vec<PathBuf>> {
+	let dir = dir.as_ref();
+	match remote() {
+		Some(_) => exec_cmd(
+			"find",
+			&[&dir.to_string_lossy(), "-mindepth", "1", "-maxdepth", "1"],
+		)
+		.map(|output| output.lines().map(PathBuf::from).collect())
+		.map_err(io::Error::other),
+		None => fs::read_dir(dir).map(|entries| {
+			entries
+				.filter_map(Result::ok)
+				.map(|entry| entry.path())
+				.collect()
+		}),
+	}
+}
+
+/**
+ * Check if the path exists on the remote host or the local system.
+ *
+ * @param  path
+ * @return bool
+ */
+pub fn path_exists<P: AsRef<Path>>(path: P) -> bool {
+	let path = path.as_ref();
+	match remote() {
+		Some(_) => exec_cmd("test", &["-e", &path.to_string_lossy()]).is_ok(),
+		None => path.exists(),
+	}
+}
 
 /**
  * Execute a operating system command and return its output.
  *
+ * The command is executed on the remote host if it is set.
+ * Invalid UTF-8 sequences in the output are replaced with U+FFFD.
+ *
  * @param  cmd
  * @param  cmd_args
  * @return Result
  */
-pub fn exec_cmd(cmd: &str, cmd_args: &[&str]) -> Result<String, String> {
-	match Command::new(cmd).args(cmd_args).output() {
-		Ok(output) => {
-			if output.status.success() {
-				Ok(String::from_utf8(output.stdout)
-					.expect("not UTF-8")
-					.trim_end()
-					.to_string())
-			} else {
-				Err(String::from_utf8(output.stderr)
-					.expect("not UTF-8")
-					.trim_end()
-					.to_string())
+pub fn exec_cmd(cmd: &str, cmd_args: &[&str]) -> Result<String, CmdError> {
+	exec_cmd_timeout(cmd, cmd_args, CMD_TIMEOUT)
+}
+
+/**
+ * Execute a operating system command with a timeout and return its output.
+ *
+ * @param  cmd
+ * @param  cmd_args
+ * @param  timeout
+ * @return Result
+ */
+pub fn exec_cmd_timeout(
+	cmd: &str,
+	cmd_args: &[&str],
+	timeout: Duration,
+) -> Result<String, CmdError> {
+	let output = spawn_cmd(cmd, cmd_args, timeout, remote(), false);
+	log_command(cmd, cmd_args, &output, false);
+	output?.into_result()
+}
+
+/**
+ * Execute a operating system command and return its output.
+ *
+ * Unlike 'exec_cmd', the output is an error if it is not valid UTF-8.
+ * This is used for the values that would be changed silently otherwise.
+ *
+ * @param  cmd
+ * @param  cmd_args
+ * @return Result
+ */
+pub fn exec_cmd_utf8(cmd: &str, cmd_args: &[&str]) -> Result<String, CmdError> {
+	let output = spawn_cmd(cmd, cmd_args, CMD_TIMEOUT, remote(), true);
+	log_command(cmd, cmd_args, &output, false);
+	output?.into_result()
+}
+
+/* Output and exit status of an executed command */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CmdOutput {
+	pub stdout: String,
+	pub stderr: String,
+	pub code: Option<i32>,
+}
+
+impl CmdOutput {
+	/**
+	 * Check if the command exited successfully.
+	 *
+	 * @return bool
+	 */
+	pub fn success(&self) -> bool {
+		self.code == Some(0)
+	}
+
+	/**
+	 * Convert the output to the standard output or the exit status error.
+	 *
+	 * @return Result
+	 */
+	pub fn into_result(self) -> Result<String, CmdError> {
+		self.check().map(|v| v.stdout)
+	}
+
+	/**
+	 * Convert the output to the exit status error if the command failed.
+	 *
+	 * @return Result
+	 */
+	pub fn check(self) -> Result<Self, CmdError> {
+		if self.success() {
+			Ok(self)
+		} else {
+			Err(CmdError::Status {
+				code: self.code,
+				stderr: self.stderr,
+			})
+		}
+	}
+}
+
+/* Error of an operating system command that is executed */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CmdError {
+	/* Command could not be started */
+	Spawn {
+		program: String,
+		kind: ErrorKind,
+		message: String,
+	},
+	/* Command exited with a non-zero code or it is terminated by a signal */
+	Status {
+		code: Option<i32>,
+		stderr: String,
+	},
+	/* Command did not exit in the given duration */
+	Timeout {
+		cmd: String,
+		timeout: Duration,
+	},
+	/* Exit status of the command could not be read */
+	Wait {
+		message: String,
+	},
+	/* Output of the command is not valid UTF-8 */
+	Encoding {
+		cmd: String,
+		message: String,
+	},
+}
+
+impl CmdError {
+	/**
+	 * Check if the command is not found.
+	 *
+	 * @return bool
+	 */
+	pub fn is_not_found(&self) -> bool {
+		matches!(
+			self,
+			Self::Spawn {
+				kind: ErrorKind::NotFound,
+				..
+			}
+		)
+	}
+}
+
+impl fmt::Display for CmdError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Spawn {
+				program,
+				kind: ErrorKind::NotFound,
+				..
+			} => write!(f, "{program} not found"),
+			Self::Spawn { message, .. } | Self::Wait { message } => {
+				write!(f, "{message}")
+			}
+			Self::Status { stderr, .. } => write!(f, "{stderr}"),
+			Self::Timeout { cmd, timeout } => {
+				write!(f, "'{cmd}' timed out after {} ms", timeout.as_millis())
+			}
+			Self::Encoding { cmd, message } => {
+				write!(f, "output of '{cmd}' is not valid UTF-8: {message}")
 			}
 		}
-		Err(e) => Err(e.to_string()),
 	}
 }
 
+impl Error for CmdError {}
+
+impl From<CmdError> for String {
+	fn from(e: CmdError) -> Self {
+		e.to_string()
+	}
+}
+
+/**
+ * Execute a operating system command and return its output with exit code.
+ *
+ * This is used for the commands that modify the system, which are
+ * always written to the command log.
+ *
+ * @param  cmd
+ * @param  cmd_args
+ * @param  timeout
+ * @return Result
+ */
+pub fn exec_cmd_status(
+	cmd: &str,
+	cmd_args: &[&str],
+	timeout: Duration,
+) -> Result<CmdOutput, CmdError> {
+	let output = spawn_cmd(cmd, cmd_args, timeout, remote(), false);
+	log_command(cmd, cmd_args, &output, true);
+	output
+}
+
+/* Count of the commands that are spawned on the current thread */
+#[cfg(test)]
+thread_local! {
+	pub static SPAWN_COUNT: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/**
+ * Execute a operating system command on the local system and return its output.
+ *
+ * @param  cmd
+ * @param  cmd_args
+ * @return Result
+ */
+pub fn exec_local_cmd(cmd: &str, cmd_args: &[&str]) -> Result<String, CmdError> {
+	spawn_cmd(cmd, cmd_args, CMD_TIMEOUT, None, false)?.into_result()
+}
+
+/**
+ * Spawn a operating system command and wait for its output and exit code.
+ *
+ * The command is executed with 'ssh' if the remote target is given.
+ * It is killed if it does not exit in the given duration.
+ * Exit code is not available if the command is terminated by a signal.
+ * Invalid UTF-8 in the standard output is an error if 'strict' is set,
+ * otherwise it is replaced with U+FFFD like in the standard error.
+ *
+ * @param  cmd
+ * @param  cmd_args
+ * @param  timeout
+ * @param  remote
+ * @param  strict
+ * @return Result
+ */
+fn spawn_cmd(
+	cmd: &str,
+	cmd_args: &[&str],
+	timeout: Duration,
+	remote: Option<&str>,
+	strict: bool,
+) -> Result<CmdOutput, CmdError> {
+	#[cfg(test)]
+	SPAWN_COUNT.with(|v| v.set(v.get() + 1));
+	let mut command = match remote {
+		Some(target) => {
+			let mut command = Command::new("ssh");
+			command.args(remote_args(target, cmd, cmd_args));
+			command
+		}
+		None => {
+			let mut command = Command::new(cmd);
+			command.args(cmd_args);
+			command
+		}
+	};
+	let mut child = command
+		.stdin(Stdio::null())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.map_err(|e| CmdError::Spawn {
+			program: command.get_program().to_string_lossy().to_string(),
+			kind: e.kind(),
+			message: e.to_string(),
+		})?;
+	/* Read the outputs in the background for preventing the pipes from filling up. */
+	let read_output = |pipe: Option<Box<dyn Read + Send>>| {
+		thread::spawn(move || {
+			let mut output = Vec::new();
+			if let Some(mut pipe) = pipe {
+				let _ = pipe.read_to_end(&mut output);
+			}
+			output
+		})
+	};
+	let stdout = read_output(child.stdout.take().map(|v| Box::new(v) as _));
+	let stderr = read_output(child.stderr.take().map(|v| Box::new(v) as _));
+	let deadline = Instant::now() + timeout;
+	let status = loop {
+		match child.try_wait() {
+			Ok(Some(status)) => break status,
+			Ok(None) if Instant::now() < deadline => {
+				thread::sleep(Duration::from_millis(10))
+			}
+			Ok(None) => {
+				let _ = child.kill();
+				let _ = child.wait();
+				return Err(CmdError::Timeout {
+					cmd: cmd.to_string(),
+					timeout,
+				});
+			}
+			Err(e) => {
+				return Err(CmdError::Wait {
+					message: e.to_string(),
+				})
+			}
+		}
+	};
+	let read_string = |output: thread::JoinHandle<Vec<u8>>, strict: bool| {
+		let output = output.join().unwrap_or_default();
+		match String::from_utf8(output) {
+			Ok(v) => Ok(v.trim_end().to_string()),
+			Err(e) if strict => Err(CmdError::Encoding {
+				cmd: cmd.to_string(),
+				message: e.utf8_error().to_string(),
+			}),
+			Err(e) => {
+				Ok(String::from_utf8_lossy(e.as_bytes()).trim_end().to_string())
+			}
+		}
+	};
+	Ok(CmdOutput {
+		stdout: read_string(stdout, strict)?,
+		stderr: read_string(stderr, false)?,
+		code: status.code(),
+	})
+}
+
+/* Escape sequence for disabling the mouse reporting of the terminal */
+const MOUSE_RESET: &str = "\x1b[?1006l\x1b[?1015l\x1b[?1002l\x1b[?1000l";
+
+/**
+ * Restore the terminal by leaving the alternate screen and disabling the
+ * mouse reporting.
+ *
+ * @param  output
+ * @return Result
+ */
+pub fn restore_terminal(output: &mut impl Write) -> io::Result<()> {
+	write!(
+		output,
+		"{MOUSE_RESET}{}{}{}",
+		termion::clear::All,
+		termion::screen::ToMainScreen,
+		termion::cursor::Show
+	)?;
+	output.flush()
+}
+
 /**
  * Sets up the panic hook for the terminal.
  *
+ * Terminal is restored before the default panic message is printed.
+ * Raw mode is only disabled if the standard output is a terminal.
  * See <https://ratatui.rs/how-to/develop-apps/panic-hooks/#termion>
  *
  * @return Result
  */
 pub fn setup_panic_hook() -> Result<(), Box<dyn Error>> {
-	let raw_output = io::stdout().into_raw_mode()?;
-	raw_output.suspend_raw_mode()?;
+	let raw_output = match io::stdout().into_raw_mode() {
+		Ok(v) => {
+			v.suspend_raw_mode()?;
+			Some(v)
+		}
+		Err(_) => None,
+	};
 
 	let panic_hook = panic::take_hook();
 	panic::set_hook(Box::new(move |panic| {
-		let panic_cleanup = || -> Result<(), Box<dyn Error>> {
-			let mut output = io::stdout();
-			write!(
-				output,
-				"{}{}{}",
-				termion::clear::All,
-				termion::screen::ToMainScreen,
-				termion::cursor::Show
-			)?;
-			raw_output.suspend_raw_mode()?;
-			output.flush()?;
-			Ok(())
-		};
-		panic_cleanup().expect("failed to clean up for panic");
+		/* Errors are ignored for printing the panic message regardless. */
+		if let Some(raw_output) = &raw_output {
+			let _ = raw_output.suspend_raw_mode();
+			let _ = restore_terminal(&mut io::stdout());
+		}
 		panic_hook(panic);
 	}));
 
 	Ok(())
 }
 
+/* Query of the background color and the primary device attributes */
+const BACKGROUND_QUERY: &str = "\x1b]11;?\x1b\\\x1b[c";
+
+/* Duration of waiting for the responses of the terminal queries (e.g. over SSH) */
+const TERMINAL_QUERY_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/**
+ * Query the background color of the terminal with the OSC 11 sequence.
+ *
+ * The primary device attributes (which all the terminals respond to) are
+ * queried after the color, so reading is stopped as soon as a terminal
+ * that ignores the first query responds. It is also stopped after the
+ * timeout if the terminal does not respond at all.
+ *
+ * @return (u8, u8, u8) (Option)
+ */
+pub fn query_background_color() -> Option<(u8, u8, u8)> {
+	let stdin = io::stdin();
+	if !termion::is_tty(&stdin)
+		|| !termion::is_tty(&io::stdout())
+		|| env::var("TERM").is_ok_and(|v| v == "dumb")
+	{
+		return None;
+	}
+	/* Raw mode is disabled when the terminal is dropped. */
+	let mut terminal = io::stdout().into_raw_mode().ok()?;
+	write!(terminal, "{BACKGROUND_QUERY}").ok()?;
+	terminal.flush().ok()?;
+	let response = read_query_response(&stdin, TERMINAL_QUERY_TIMEOUT);
+	/* Discard the input that is received but not read yet (safe for a valid descriptor). */
+	unsafe { libc::tcflush(stdin.as_raw_fd(), libc::TCIFLUSH) };
+	parse_background_color(&response)
+}
+
+/**
+ * Read the responses of the terminal queries until the device attributes.
+ *
+ * The input that is left after the device attributes is discarded so
+ * that the responses are not read as key presses afterwards. The file
+ * descriptor is read directly since the buffered input would not be
+ * reported by poll.
+ *
+ * @param  input
+ * @param  timeout
+ * @return String
+ */
+fn read_query_response(input: &impl AsRawFd, timeout: Duration) -> String {
+	let mut response = String::new();
+	let deadline = Instant::now() + timeout;
+	let mut buffer = [0; 64];
+	while !is_attributes_response(&response) {
+		let timeout = deadline.saturating_duration_since(Instant::now());
+		if !is_readable(input, timeout) {
+			break;
+		}
+		match read_fd(input, &mut buffer) {
+			0 => break,
+			n => response += &String::from_utf8_lossy(&buffer[..n]),
+		}
+	}
+	while is_readable(input, Duration::ZERO) {
+		if read_fd(input, &mut buffer) == 0 {
+			break;
+		}
+	}
+	response
+}
+
+/**
+ * Read the available bytes from the file descriptor into the buffer.
+ *
+ * @param  input
+ * @param  buffer
+ * @return usize
+ */
+fn read_fd(input: &impl AsRawFd, buffer: &mut [u8]) -> usize {
+	/* Safe since the length of the buffer is given (errors are read as EOF). */
+	let n = unsafe {
+		libc::read(input.as_raw_fd(), buffer.as_mut_ptr().cast(), buffer.len())
+	};
+	usize::try_from(n).unwrap_or(0)
+}
+
+/**
+ * Check if the input can be read without blocking in the given duration.
+ *
+ * @param  input
+ * @param  timeout
+ * @return bool
+ */
+fn is_readable(input: &impl AsRawFd, timeout: Duration) -> bool {
+	let mut fd = libc::pollfd {
+		fd: input.as_raw_fd(),
+		events: libc::POLLIN,
+		revents: 0,
+	};
+	/* Safe since a single and valid file descriptor is given. */
+	unsafe { libc::poll(&mut fd, 1, timeout.as_millis() as libc::c_int) > 0 }
+}
+
+/**
+ * Check if the response contains the primary device attributes (e.g. '\x1b[?62;22c').
+ *
+ * @param  response
+ * @return bool
+ */
+fn is_attributes_response(response: &str) -> bool {
+	response.split("\x1b[?").nth(1).is_some_and(|v| {
+		v.chars()
+			.find(|c| !c.is_ascii_digit() && *c != ';')
+			.is_some_and(|c| c == 'c')
+	})
+}
+
+/**
+ * Parse the background color from the OSC 11 response of the terminal.
+ *
+ * Color components are given with 1 to 4 hexadecimal digits
+ * (e.g. 'rgb:ffff/ffff/ffff') and they are scaled to 8 bits.
+ *
+ * @param  response
+ * @return (u8, u8, u8) (Option)
+ */
+pub fn parse_background_color(response: &str) -> Option<(u8, u8, u8)> {
+	let (_, color) = response.split_once("\x1b]11;rgb:")?;
+	let color = color.split(['\x07', '\x1b']).next()?;
+	let components = color
+		.split('/')
+		.map(|v| {
+			let max = 16_u32.checked_pow(u32::try_from(v.len()).ok()?)? - 1;
+			let value = u32::from_str_radix(v, 16).ok()?;
+			(1..=4)
+				.contains(&v.len())
+				.then(|| (value * 255 / max) as u8)
+		})
+		.collect::<Option<Vec<u8>>>()?;
+	match components[..] {
+		[r, g, b] => Some((r, g, b)),
+		_ => None,
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use std::os::unix::net::UnixStream;
+	#[test]
+	fn test_format_bytes() {
+		assert_eq!("1023 B", format_bytes(1023));
+		assert_eq!("1.0 KiB", format_bytes(1024));
+		assert_eq!("96.0 KiB", format_bytes(98304));
+		assert_eq!("54.2 MiB", format_bytes(56885248));
+	}
+	#[test]
+	fn test_format_utc_time() {
+		assert_eq!("1970-01-01T00:00:00Z", format_utc_time(0));
+		assert_eq!("2000-02-29T12:30:45Z", format_utc_time(951827445));
+		assert_eq!("2026-10-14T23:59:59Z", format_utc_time(1792022399));
+	}
+	#[test]
+	fn test_format_duration() {
+		assert_eq!("~0s", format_duration(Duration::from_millis(500)));
+		assert_eq!("~59s", format_duration(Duration::from_secs(59)));
+		assert_eq!("~2m", format_duration(Duration::from_secs(150)));
+		assert_eq!("~3h", format_duration(Duration::from_secs(3 * 3600 + 59)));
+		assert_eq!("~12d", format_duration(Duration::from_secs(12 * 86400)));
+	}
+	#[test]
+	fn test_fuzzy_score() {
+		assert!(fuzzy_score("e100", "e1000e").is_some());
+		assert!(fuzzy_score("e100", "e1000") > fuzzy_score("e100", "e1000e"));
+		assert!(fuzzy_score("e100", "e100") > fuzzy_score("e100", "e1000"));
+		assert!(fuzzy_score("snd", "snd_hda") > fuzzy_score("snd", "asnd"));
+		assert!(fuzzy_score("hda", "snd_hda") > fuzzy_score("hda", "shdas"));
+		assert_eq!(None, fuzzy_score("e100", "ext4"));
+		assert_eq!(Some(-4), fuzzy_score("", "ext4"));
+		assert!(fuzzy_score("NVD", "nvidia_drm").is_some());
+	}
+	#[test]
+	fn test_fuzzy_indices() {
+		assert_eq!(Some(vec![0, 1, 2, 3]), fuzzy_indices("e100", "e1000e"));
+		assert_eq!(Some(vec![0, 4, 5]), fuzzy_indices("SHD", "snd_hda"));
+		assert_eq!(Some(vec![]), fuzzy_indices("", "ext4"));
+		assert_eq!(None, fuzzy_indices("e100", "ext4"));
+	}
+	#[test]
+	fn test_truncate_width() {
+		assert_eq!("e1000e", truncate_width("e1000e", 6));
+		assert_eq!("e1...", truncate_width("e1000e", 5));
+		assert_eq!("..", truncate_width("e1000e", 2));
+		assert_eq!("", truncate_width("e1000e", 0));
+		assert_eq!("日本語", truncate_width("日本語", 6));
+		assert_eq!("日...", truncate_width("日本語", 5));
+		assert_eq!("日本...", truncate_width("日本語テキスト", 8));
+		assert_eq!(
+			"e\u{301}\u{301}...",
+			truncate_width("e\u{301}\u{301}xyzw", 4)
+		);
+		assert_eq!("a\u{301}...", truncate_width("a\u{301}日本", 4));
+		for text in [
+			"日本語のカーネルログ",
+			"e\u{301}vdev_日本",
+			"ｅ１０００ｅ",
+			"ab",
+		] {
+			for width in 0..24 {
+				let truncated = truncate_width(text, width);
+				assert!(truncated.width() <= width, "{truncated:?} > {width}");
+				assert!(truncated.width() + 1 >= width.min(text.width()));
+			}
+		}
+	}
+	#[test]
+	fn test_glob_match() {
+		assert!(glob_match(
+			"pci:v00008086d*sv*",
+			"pci:v00008086d000015B8sv1028"
+		));
+		assert!(glob_match("usb:v?BDA*", "usb:v0BDAp8153"));
+		assert!(glob_match("*", ""));
+		assert!(!glob_match("pci:v00008086d*", "pci:v000010DEd00001C82"));
+		assert!(!glob_match("acpi*:PNP0C0A:*", "acpi:PNP0C0A"));
+	}
+	#[test]
+	fn test_command_log() {
+		assert_eq!(
+			"[1718000000] sh [\"-c\", \"modprobe snd\"] (exit code: 1) a\\nb",
+			format_command_log(
+				1718000000,
+				"sh",
+				&["-c", "modprobe snd"],
+				&Ok(CmdOutput {
+					stderr: String::from("a\nb"),
+					code: Some(1),
+					..CmdOutput::default()
+				})
+			)
+		);
+		assert_eq!(
+			"[0] x [] (failed) x not found",
+			format_command_log(
+				0,
+				"x",
+				&[],
+				&Err(CmdError::Spawn {
+					program: String::from("x"),
+					kind: ErrorKind::NotFound,
+					message: String::from("No such file or directory"),
+				})
+			)
+		);
+		assert_eq!(
+			"[0] x [] (terminated)",
+			format_command_log(0, "x", &[], &Ok(CmdOutput::default()))
+		);
+		assert!(format_command_log(
+			0,
+			"x",
+			&[],
+			&Ok(CmdOutput {
+				stdout: "x".repeat(500),
+				code: Some(0),
+				..CmdOutput::default()
+			})
+		)
+		.ends_with(&format!("{}...", "x".repeat(LOG_OUTPUT_LIMIT))));
+		assert!(set_command_log(Path::new("/nonexistent/kmon.log"), false).is_err());
+	}
+	#[test]
+	fn test_common_prefix() {
+		assert_eq!(
+			"snd_hda_",
+			common_prefix(&[
+				String::from("snd_hda_intel"),
+				String::from("snd_hda_codec"),
+				String::from("snd_hda_core"),
+			])
+		);
+		assert_eq!("e1000e", common_prefix(&[String::from("e1000e")]));
+		assert_eq!(
+			"",
+			common_prefix(&[String::from("ext4"), String::from("xfs")])
+		);
+		assert_eq!("", common_prefix(&[]));
+	}
+	#[test]
+	fn test_remote_args() {
+		assert_eq!("'it'\\''s'", shell_quote("it's"));
+		assert_eq!(
+			vec![
+				"-o",
+				"BatchMode=yes",
+				"--",
+				"admin@srv1",
+				"'modinfo' '-F' 'version' 'snd'"
+			],
+			remote_args("admin@srv1", "modinfo", &["-F", "version", "snd"])
+		);
+		assert_eq!(None, remote());
+	}
+	#[test]
+	fn test_read_file() {
+		let dir = std::env::temp_dir().join("kmon-test-read-file");
+		fs::create_dir_all(&dir).unwrap();
+		fs::write(dir.join("a"), "x\n").unwrap();
+		assert_eq!("x\n", read_file(dir.join("a")).unwrap());
+		assert!(read_file(dir.join("b")).is_err());
+		assert_eq!(vec![dir.join("a")], read_dir(&dir).unwrap());
+		assert!(path_exists(dir.join("a")));
+		fs::remove_dir_all(&dir).unwrap();
+		assert!(!path_exists(&dir));
+		assert!(read_dir(&dir).is_err());
+	}
+	#[test]
+	fn test_setup_panic_hook() {
+		let mut output = Vec::new();
+		restore_terminal(&mut output).unwrap();
+		let output = String::from_utf8(output).unwrap();
+		assert!(output.starts_with(MOUSE_RESET));
+		assert!(output.contains(&termion::screen::ToMainScreen.to_string()));
+		assert!(setup_panic_hook().is_ok());
+		let _ = panic::take_hook();
+	}
+	#[test]
+	fn test_parse_background_color() {
+		assert_eq!(
+			Some((255, 255, 255)),
+			parse_background_color("\x1b]11;rgb:ffff/ffff/ffff\x1b\\\x1b[?62;22c")
+		);
+		assert_eq!(
+			Some((0, 43, 54)),
+			parse_background_color("\x1b]11;rgb:0000/2b2b/3636\x07")
+		);
+		assert_eq!(
+			Some((255, 136, 0)),
+			parse_background_color("\x1b]11;rgb:f/88/000\x07")
+		);
+		assert_eq!(None, parse_background_color("\x1b]11;rgb:ffff/ffff\x07"));
+		assert_eq!(None, parse_background_color("\x1b]11;rgb:fffff/0/0\x07"));
+		assert_eq!(None, parse_background_color("\x1b[?62;22c"));
+		assert!(is_attributes_response("\x1b]11;rgb:f/f/f\x07\x1b[?62;22c"));
+		assert!(!is_attributes_response("\x1b]11;rgb:f/f/f\x07\x1b[?62;2"));
+		assert!(!is_attributes_response(""));
+		let (input, mut output) = UnixStream::pair().unwrap();
+		output
+			.write_all(b"\x1b]11;rgb:ffff/ffff/ffff\x07\x1b[?62;22c\x1b[?62;22c")
+			.unwrap();
+		let response = read_query_response(&input, Duration::from_secs(1));
+		assert_eq!(Some((255, 255, 255)), parse_background_color(&response));
+		assert!(!is_readable(&input, Duration::ZERO));
+		let start = Instant::now();
+		assert_eq!("", read_query_response(&input, Duration::from_millis(50)));
+		assert!(start.elapsed() >= Duration::from_millis(40));
+	}
 	#[test]
 	fn test_exec_cmd() {
 		assert_eq!("test", exec_cmd("printf", &["test"]).unwrap());
@@ -113,5 +1185,54 @@ mod tests {
 			"err",
 			exec_cmd("cat", &["-x"]).unwrap_or(String::from("err"))
 		);
+		assert_eq!("a\u{FFFD}b", exec_cmd("printf", &["a\\377b"]).unwrap());
+		assert!(matches!(
+			exec_cmd_utf8("printf", &["a\\377b"]),
+			Err(CmdError::Encoding { .. })
+		));
+		assert_eq!(Ok(String::from("ab")), exec_cmd_utf8("printf", &["ab"]));
+		assert_eq!(
+			CmdError::Status {
+				code: Some(1),
+				stderr: String::from("\u{FFFD}"),
+			},
+			exec_cmd("sh", &["-c", "printf '\\377' >&2; false"]).unwrap_err()
+		);
+		let output = exec_cmd_status(
+			"sh",
+			&["-c", "echo out; echo err >&2; exit 3"],
+			CMD_TIMEOUT,
+		)
+		.unwrap();
+		assert_eq!(
+			("out", "err"),
+			(output.stdout.as_str(), output.stderr.as_str())
+		);
+		assert_eq!(Some(3), output.code);
+		assert!(!output.success());
+		let now = Instant::now();
+		let error =
+			exec_cmd_timeout("sleep", &["5"], Duration::from_secs(1)).unwrap_err();
+		assert_eq!("'sleep' timed out after 1000 ms", error.to_string());
+		assert!(matches!(error, CmdError::Timeout { .. }));
+		assert!(now.elapsed() < Duration::from_secs(3));
+		assert_eq!(
+			100_000,
+			exec_cmd("sh", &["-c", "head -c 100000 /dev/zero | tr '\\0' x"])
+				.unwrap()
+				.len()
+		);
+		assert_eq!(
+			Err(String::from("kmon-nonexistent not found")),
+			exec_cmd("kmon-nonexistent", &[]).map_err(String::from)
+		);
+		assert!(exec_cmd("kmon-nonexistent", &[])
+			.unwrap_err()
+			.is_not_found());
+		assert!(!exec_cmd("false", &[]).unwrap_err().is_not_found());
+		assert_eq!(
+			vec!["kmon-nonexistent"],
+			missing_executables(&["sh", "kmon-nonexistent"])
+		);
 	}
 }